@@ -2,18 +2,45 @@ use super::ToolAdapter;
 use crate::scenario::Scenario;
 use crate::session::SessionRunner;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct ClaudeCodeAdapter;
 
+/// Environment variables that authenticate Claude Code without a stored login.
+const CREDENTIAL_ENV: &[&str] = &[
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_AUTH_TOKEN",
+    "CLAUDE_CODE_OAUTH_TOKEN",
+    "CLAUDE_CODE_USE_BEDROCK",
+    "CLAUDE_CODE_USE_VERTEX",
+];
+
+/// Whether Claude Code has credentials: a key or cloud provider in the
+/// environment, a stored login in `config_dir`, or an API key saved in
+/// `~/.claude.json`. On macOS the login lives in the keychain, which cannot
+/// be inspected, so it is assumed.
+fn has_credentials(env_is_set: impl Fn(&str) -> bool, config_dir: &Path, home: &Path) -> bool {
+    CREDENTIAL_ENV.iter().any(|name| env_is_set(name))
+        || cfg!(target_os = "macos")
+        || config_dir.join(".credentials.json").is_file()
+        || fs::read_to_string(home.join(".claude.json"))
+            .is_ok_and(|config| config.contains("\"primaryApiKey\""))
+}
+
 impl ToolAdapter for ClaudeCodeAdapter {
     fn is_available(&self) -> Result<super::ToolStatus, super::AdapterError> {
         let runner = SessionRunner::new();
         match runner.run_command("claude", &["--version"], Path::new("."), 10) {
-            Ok(_) => Ok(super::ToolStatus {
-                available: true,
-                authenticated: true,
-            }),
+            Ok(_) => {
+                let home = super::home_dir().unwrap_or_default();
+                let config_dir = std::env::var_os("CLAUDE_CONFIG_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| home.join(".claude"));
+                Ok(super::ToolStatus {
+                    available: true,
+                    authenticated: has_credentials(super::env_is_set, &config_dir, &home),
+                })
+            }
             Err(e) => Err(super::AdapterError::NotAvailable(format!(
                 "Claude Code tool not found: {}",
                 e
@@ -21,12 +48,27 @@ impl ToolAdapter for ClaudeCodeAdapter {
         }
     }
 
-    fn check_availability(&self) -> anyhow::Result<()> {
-        let runner = SessionRunner::new();
-        match runner.run_command("claude", &["--version"], Path::new("."), 10) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(anyhow::anyhow!("Claude Code tool not found: {}", e)),
-        }
+    fn auth_guidance(&self) -> Option<super::AuthGuidance> {
+        Some(super::AuthGuidance {
+            instructions: "Claude Code has no stored login; run `claude` and complete `/login`, \
+                 or set ANTHROPIC_API_KEY (CLAUDE_CODE_OAUTH_TOKEN from `claude setup-token` \
+                 also works)"
+                .to_string(),
+            // The login flow is the `/login` command of an interactive session
+            login_command: Some(vec!["claude".to_string()]),
+        })
+    }
+
+    fn detects_stalls(&self) -> bool {
+        true
+    }
+
+    fn accepts_extra_args(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> Option<String> {
+        super::command_version("claude")
     }
 
     fn run(
@@ -35,32 +77,70 @@ impl ToolAdapter for ClaudeCodeAdapter {
         cwd: &Path,
         model: Option<&str>,
         timeout_secs: u64,
+        options: &super::RunOptions,
     ) -> anyhow::Result<(String, i32, Option<f64>, Option<super::TokenUsage>)> {
-        let runner = SessionRunner::new();
+        let runner = SessionRunner::new()
+            .with_stall_timeout(options.stall_timeout.clone())
+            .with_progress(options.progress.clone())
+            .with_stream_capture(options.streams.clone())
+            .with_sandbox(options.sandbox.clone());
 
         let mut args = vec!["run"];
         if let Some(model) = model {
             args.push("--model");
             args.push(model);
         }
+        args.extend(options.extra_args.iter().map(String::as_str));
 
         let prompt_path = cwd.join("prompt.txt");
         fs::write(&prompt_path, &scenario.task.prompt)?;
 
-        let target_env = scenario
-            .target
-            .env
-            .as_ref()
-            .map(|vars| {
-                vars.iter()
-                    .map(|(k, v)| (k.clone(), v.clone()))
-                    .collect::<Vec<(String, String)>>()
-            })
-            .unwrap_or_default();
+        let mut env_vars = options.env.clone();
+        if let Some(target_env) = &scenario.target.env {
+            env_vars.extend(target_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
 
         let (output, exit_code) =
-            runner.run_command_with_env("claude", &args, cwd, timeout_secs, &target_env)?;
+            runner.run_command_with_env("claude", &args, cwd, timeout_secs, &env_vars)?;
+
+        // Keep private prompts out of the preserved fixture directory
+        if scenario.encrypted && !crate::scenario::bundle::reveal_prompts() {
+            let _ = fs::remove_file(&prompt_path);
+        }
 
         Ok((output, exit_code, None, None))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_credentials_from_env_and_stored_logins() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".claude");
+        fs::create_dir_all(&config_dir).unwrap();
+        let no_env = |_: &str| false;
+
+        assert!(has_credentials(
+            |name| name == "ANTHROPIC_API_KEY",
+            &config_dir,
+            dir.path()
+        ));
+        if !cfg!(target_os = "macos") {
+            assert!(!has_credentials(no_env, &config_dir, dir.path()));
+        }
+
+        fs::write(
+            dir.path().join(".claude.json"),
+            r#"{"primaryApiKey": "sk"}"#,
+        )
+        .unwrap();
+        assert!(has_credentials(no_env, &config_dir, dir.path()));
+        fs::remove_file(dir.path().join(".claude.json")).unwrap();
+
+        fs::write(config_dir.join(".credentials.json"), "{}").unwrap();
+        assert!(has_credentials(no_env, &config_dir, dir.path()));
+    }
+}
@@ -0,0 +1,495 @@
+//! Conformance harness for [`ToolAdapter`] implementations.
+//!
+//! Runs an adapter against a stub executable that stands in for the real tool
+//! and checks the behavior the runner relies on: consistent availability
+//! reporting, transcript capture and environment propagation, cost/token
+//! reporting, error propagation, failure classification, timeouts and, for
+//! adapters that enforce them, turn limits.
+//!
+//! Built for tests and with the `conformance` feature:
+//!
+//! ```ignore
+//! let report = run_conformance(&MyAdapter, &ConformanceOptions::new("mytool"), dir.path())?;
+//! report.assert_passed();
+//! ```
+
+use super::{RunOptions, ToolAdapter, TurnLimit};
+use crate::scenario::Scenario;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Line the stub prints so the transcript check can find it.
+const MARKER: &str = "llm-tool-test-conformance-marker";
+
+/// Variable passed through [`RunOptions::env`].
+const OPTIONS_ENV: &str = "LLM_TOOL_TEST_CONFORMANCE_OPTIONS";
+
+/// Variable passed through the scenario's `target.env`.
+const TARGET_ENV: &str = "LLM_TOOL_TEST_CONFORMANCE_TARGET";
+
+/// Timeout used by the timeout check; the stub sleeps well past it.
+const TIMEOUT_SECS: u64 = 1;
+
+/// How an adapter under test is exercised.
+pub struct ConformanceOptions<'a> {
+    /// Executable the adapter launches, replaced by a stub on `PATH`
+    pub binary: &'a str,
+    /// Output in the tool's native format that reports cost and/or tokens
+    pub usage_output: Option<&'a str>,
+    /// Whether the adapter must report a cost for `usage_output`
+    pub reports_cost: bool,
+    /// Whether the adapter must report token usage for `usage_output`
+    pub reports_tokens: bool,
+    /// Output line that starts a model turn, for adapters that stop the tool
+    /// at the turn limit by watching its output
+    pub turn_event: Option<&'a str>,
+}
+
+impl<'a> ConformanceOptions<'a> {
+    pub fn new(binary: &'a str) -> Self {
+        Self {
+            binary,
+            usage_output: None,
+            reports_cost: false,
+            reports_tokens: false,
+            turn_event: None,
+        }
+    }
+}
+
+/// Outcome of a single conformance check.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Outcome of all conformance checks for one adapter.
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    /// Panic with the list of failed checks unless every check passed.
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "adapter conformance failed:\n{}", self);
+    }
+
+    fn record(&mut self, name: &'static str, result: Result<(), String>) {
+        let (passed, detail) = match result {
+            Ok(()) => (true, String::new()),
+            Err(detail) => (false, detail),
+        };
+        self.checks.push(CheckResult {
+            name,
+            passed,
+            detail,
+        });
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            if check.passed {
+                writeln!(f, "PASS {}", check.name)?;
+            } else {
+                writeln!(f, "FAIL {}: {}", check.name, check.detail)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stub tool installed in a private `bin` directory.
+struct Stub {
+    bin_dir: PathBuf,
+    path: PathBuf,
+}
+
+impl Stub {
+    fn new(work_dir: &Path, binary: &str) -> anyhow::Result<Self> {
+        let bin_dir = work_dir.join("bin");
+        std::fs::create_dir_all(&bin_dir)?;
+        Ok(Self {
+            path: bin_dir.join(binary),
+            bin_dir,
+        })
+    }
+
+    /// Replace the stub's behavior for any invocation other than `--version`.
+    fn set_body(&self, body: &str) -> anyhow::Result<()> {
+        let script = format!(
+            "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then echo \"stub 1.0.0\"; exit 0; fi\n{}\n",
+            body
+        );
+        std::fs::write(&self.path, script)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+
+    /// Run options that put the stub first on `PATH`.
+    fn run_options(&self) -> RunOptions {
+        let path = std::env::var("PATH").unwrap_or_default();
+        RunOptions {
+            env: vec![
+                (
+                    "PATH".to_string(),
+                    format!("{}:{}", self.bin_dir.display(), path),
+                ),
+                (OPTIONS_ENV.to_string(), "from-options".to_string()),
+            ],
+            extra_args: Vec::new(),
+            turn_limit: None,
+            stall_timeout: None,
+            progress: None,
+            streams: None,
+            sandbox: None,
+        }
+    }
+}
+
+fn scenario(binary: &str) -> Scenario {
+    let yaml = format!(
+        "name: conformance\ndescription: Adapter conformance check\ntemplate_folder: conformance\ntarget:\n  binary: {}\n  env:\n    {}: from-target\ntask:\n  prompt: Reply with the conformance marker\nevaluation:\n  gates: []\n",
+        binary, TARGET_ENV
+    );
+    serde_yaml::from_str(&yaml).expect("conformance scenario is valid")
+}
+
+type RunOutput = (String, i32, Option<f64>, Option<super::TokenUsage>);
+
+/// Run every conformance check against `adapter`, using `work_dir` for the
+/// stub executable and the run directory.
+pub fn run_conformance(
+    adapter: &dyn ToolAdapter,
+    options: &ConformanceOptions,
+    work_dir: &Path,
+) -> anyhow::Result<ConformanceReport> {
+    let stub = Stub::new(work_dir, options.binary)?;
+    let cwd = work_dir.join("fixture");
+    std::fs::create_dir_all(&cwd)?;
+    let scenario = scenario(options.binary);
+    let run_options = stub.run_options();
+    let run = |timeout_secs: u64| -> anyhow::Result<RunOutput> {
+        adapter.run(
+            &scenario,
+            &cwd,
+            Some("stub-model"),
+            timeout_secs,
+            &run_options,
+        )
+    };
+    let mut report = ConformanceReport::default();
+
+    report.record("availability", check_availability(adapter));
+
+    stub.set_body(&format!(
+        "echo {}\necho \"options=${}\"\necho \"target=${}\"\nexit 0",
+        MARKER, OPTIONS_ENV, TARGET_ENV
+    ))?;
+    let transcript = run(30);
+    report.record("transcript", check_transcript(&transcript));
+
+    if let Some(usage) = options.usage_output {
+        stub.set_body(&format!(
+            "cat <<'LLM_TOOL_TEST_EOF'\n{}\nLLM_TOOL_TEST_EOF\nexit 0",
+            usage
+        ))?;
+        report.record("usage", check_usage(&run(30), options));
+    } else if let Ok((_, _, cost, _)) = &transcript {
+        report.record("usage", check_cost_value(*cost));
+    }
+
+    stub.set_body("echo 'conformance failure: something broke' >&2\nexit 3")?;
+    report.record("error_propagation", check_error_propagation(&run(30)));
+
+    report.record(
+        "failure_classification",
+        check_classification(adapter, transcript.as_ref().ok()),
+    );
+
+    stub.set_body("sleep 5\necho too-late\nexit 0")?;
+    let started = Instant::now();
+    let timed_out = run(TIMEOUT_SECS);
+    report.record("timeout", check_timeout(&timed_out, started.elapsed()));
+
+    if let Some(event) = options.turn_event {
+        stub.set_body(&format!(
+            "for i in 1 2 3 4 5 6; do\ncat <<'LLM_TOOL_TEST_EOF'\n{}\nLLM_TOOL_TEST_EOF\nsleep 1\ndone\necho too-late\nexit 0",
+            event
+        ))?;
+        let limit = TurnLimit::new(2);
+        let limited_options = RunOptions {
+            turn_limit: Some(limit.clone()),
+            ..run_options.clone()
+        };
+        let started = Instant::now();
+        let limited = adapter.run(&scenario, &cwd, Some("stub-model"), 30, &limited_options);
+        report.record(
+            "turn_limit",
+            check_turn_limit(adapter, &limit, &limited, started.elapsed()),
+        );
+    }
+
+    Ok(report)
+}
+
+fn check_availability(adapter: &dyn ToolAdapter) -> Result<(), String> {
+    let status = adapter.is_available();
+    let check = adapter.check_availability();
+    match (&status, &check) {
+        (Ok(s), Ok(())) if s.available && s.authenticated => Ok(()),
+        (Ok(s), Err(_)) if !s.available || !s.authenticated => Ok(()),
+        (Err(_), Err(e)) if !e.to_string().trim().is_empty() => Ok(()),
+        (Err(_), Err(_)) => Err("check_availability returned an empty error".to_string()),
+        _ => Err(format!(
+            "is_available ({:?}) and check_availability ({:?}) disagree",
+            status.as_ref().map(|s| s.available),
+            check.as_ref().map_err(|e| e.to_string())
+        )),
+    }
+}
+
+fn check_transcript(result: &anyhow::Result<RunOutput>) -> Result<(), String> {
+    let (output, exit_code, _, _) = result.as_ref().map_err(|e| format!("run failed: {}", e))?;
+    if *exit_code != 0 {
+        return Err(format!("successful run reported exit code {}", exit_code));
+    }
+    if !output.contains(MARKER) {
+        return Err("tool output missing from the returned transcript".to_string());
+    }
+    if !output.contains("options=from-options") {
+        return Err("RunOptions::env not passed to the tool".to_string());
+    }
+    if !output.contains("target=from-target") {
+        return Err("scenario target.env not passed to the tool".to_string());
+    }
+    Ok(())
+}
+
+fn check_cost_value(cost: Option<f64>) -> Result<(), String> {
+    match cost {
+        Some(c) if !c.is_finite() || c < 0.0 => Err(format!("invalid cost {}", c)),
+        _ => Ok(()),
+    }
+}
+
+fn check_usage(
+    result: &anyhow::Result<RunOutput>,
+    options: &ConformanceOptions,
+) -> Result<(), String> {
+    let (_, _, cost, tokens) = result.as_ref().map_err(|e| format!("run failed: {}", e))?;
+    check_cost_value(*cost)?;
+    if options.reports_cost && cost.is_none() {
+        return Err("no cost reported for usage output".to_string());
+    }
+    if options.reports_tokens {
+        match tokens {
+            None => return Err("no token usage reported for usage output".to_string()),
+            Some(t) if t.input == 0 && t.output == 0 => {
+                return Err("token usage reported as zero".to_string())
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_error_propagation(result: &anyhow::Result<RunOutput>) -> Result<(), String> {
+    match result {
+        Err(_) => Ok(()),
+        Ok((_, 0, _, _)) => Err("tool exited with 3 but adapter reported 0".to_string()),
+        Ok((output, _, _, _)) if !output.contains("conformance failure") => {
+            Err("tool error output missing from the returned transcript".to_string())
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+fn check_classification(
+    adapter: &dyn ToolAdapter,
+    success: Option<&RunOutput>,
+) -> Result<(), String> {
+    let throttled = "Error: 429 Too Many Requests";
+    if !adapter.is_rate_limited(throttled, 1) {
+        return Err("429 output not classified as rate limited".to_string());
+    }
+    if !adapter.is_transient_failure(throttled, 1) {
+        return Err("429 output not classified as transient".to_string());
+    }
+    if let Some((output, exit_code, _, _)) = success {
+        if adapter.is_transient_failure(output, *exit_code) {
+            return Err("successful run classified as transient failure".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn check_timeout(result: &anyhow::Result<RunOutput>, elapsed: Duration) -> Result<(), String> {
+    if elapsed > Duration::from_secs(TIMEOUT_SECS + 3) {
+        return Err(format!(
+            "run with a {}s timeout took {:.1}s",
+            TIMEOUT_SECS,
+            elapsed.as_secs_f64()
+        ));
+    }
+    match result {
+        Ok((output, 0, _, _)) if output.contains("too-late") => {
+            Err("run completed successfully despite the timeout".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_turn_limit(
+    adapter: &dyn ToolAdapter,
+    limit: &TurnLimit,
+    result: &anyhow::Result<RunOutput>,
+    elapsed: Duration,
+) -> Result<(), String> {
+    if !adapter.enforces_turn_limit() {
+        return Err("turn event given but enforces_turn_limit() is false".to_string());
+    }
+    if !limit.reached() {
+        return Err("tool was not stopped after exceeding 2 turns".to_string());
+    }
+    if let Ok((output, _, _, _)) = result {
+        if output.contains("too-late") {
+            return Err("tool ran to completion despite the turn limit".to_string());
+        }
+    }
+    if elapsed > Duration::from_secs(5) {
+        return Err(format!(
+            "turn-limited run took {:.1}s",
+            elapsed.as_secs_f64()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::aider::AiderAdapter;
+    use crate::adapter::claude_code::ClaudeCodeAdapter;
+    use crate::adapter::custom::CustomAdapter;
+    use crate::adapter::opencode::OpenCodeAdapter;
+
+    #[test]
+    fn opencode_adapter_conforms() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = ConformanceOptions {
+            usage_output: Some(
+                r#"{"type":"step_finish","part":{"tokens":{"input":120,"output":30}}}"#,
+            ),
+            reports_tokens: true,
+            turn_event: Some(r#"{"type":"step_start","part":{}}"#),
+            ..ConformanceOptions::new("opencode")
+        };
+        let report = run_conformance(&OpenCodeAdapter, &options, dir.path()).unwrap();
+        report.assert_passed();
+    }
+
+    #[test]
+    fn claude_code_adapter_conforms() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = ConformanceOptions::new("claude");
+        let report = run_conformance(&ClaudeCodeAdapter, &options, dir.path()).unwrap();
+        report.assert_passed();
+    }
+
+    #[test]
+    fn aider_adapter_conforms() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = ConformanceOptions {
+            usage_output: Some(
+                "Tokens: 2.3k sent, 156 received. Cost: $0.0093 message, $0.0093 session.",
+            ),
+            reports_cost: true,
+            reports_tokens: true,
+            ..ConformanceOptions::new("aider")
+        };
+        let report = run_conformance(&AiderAdapter, &options, dir.path()).unwrap();
+        report.assert_passed();
+    }
+
+    #[test]
+    fn custom_adapter_conforms() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = serde_yaml::from_str(
+            r#"
+command: "my-agent --model {model} {prompt}"
+cost_pattern: 'cost=\$([0-9.]+)'
+input_tokens_pattern: 'tokens_in=(\d+)'
+output_tokens_pattern: 'tokens_out=(\d+)'
+"#,
+        )
+        .unwrap();
+        let options = ConformanceOptions {
+            usage_output: Some("cost=$0.02 tokens_in=120 tokens_out=30"),
+            reports_cost: true,
+            reports_tokens: true,
+            ..ConformanceOptions::new("my-agent")
+        };
+        let adapter = CustomAdapter::new(&config).unwrap();
+        let report = run_conformance(&adapter, &options, dir.path()).unwrap();
+        report.assert_passed();
+    }
+
+    /// Adapter that drops the tool's exit code and environment.
+    struct LossyAdapter;
+
+    impl ToolAdapter for LossyAdapter {
+        fn is_available(&self) -> Result<crate::adapter::ToolStatus, crate::adapter::AdapterError> {
+            Ok(crate::adapter::ToolStatus {
+                available: true,
+                authenticated: true,
+            })
+        }
+
+        fn run(
+            &self,
+            _scenario: &Scenario,
+            cwd: &Path,
+            _model: Option<&str>,
+            timeout_secs: u64,
+            _options: &RunOptions,
+        ) -> anyhow::Result<RunOutput> {
+            let (output, _) = crate::session::SessionRunner::new().run_command(
+                "sh",
+                &["-c", "echo lossy"],
+                cwd,
+                timeout_secs,
+            )?;
+            Ok((output, 0, None, None))
+        }
+    }
+
+    #[test]
+    fn reports_failing_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let report =
+            run_conformance(&LossyAdapter, &ConformanceOptions::new("lossy"), dir.path()).unwrap();
+        assert!(!report.passed());
+        let failed: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(failed, vec!["transcript", "error_propagation"]);
+        assert!(report.to_string().contains("FAIL transcript:"));
+    }
+}
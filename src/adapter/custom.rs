@@ -0,0 +1,237 @@
+use super::ToolAdapter;
+use crate::scenario::{CustomToolConfig, Scenario};
+use crate::session::SessionRunner;
+use regex::Regex;
+use std::path::Path;
+
+/// Adapter for agents described by a scenario's `custom_tool` section.
+///
+/// The command template is split into arguments once; `{prompt}`, `{model}`
+/// and `{cwd}` are substituted per run, so prompts never pass through a shell.
+pub struct CustomAdapter {
+    argv: Vec<String>,
+    cost: Option<Regex>,
+    input_tokens: Option<Regex>,
+    output_tokens: Option<Regex>,
+    success_exit_codes: Vec<i32>,
+}
+
+impl CustomAdapter {
+    pub fn new(config: &CustomToolConfig) -> anyhow::Result<Self> {
+        config.validate()?;
+        let compile = |pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(|p| Regex::new(p).expect("validated pattern"))
+        };
+        Ok(Self {
+            argv: config.argv()?,
+            cost: compile(&config.cost_pattern),
+            input_tokens: compile(&config.input_tokens_pattern),
+            output_tokens: compile(&config.output_tokens_pattern),
+            success_exit_codes: config.success_exit_codes.clone(),
+        })
+    }
+
+    /// Build the adapter for `--tool custom` from the scenario.
+    pub fn for_scenario(scenario: &Scenario) -> anyhow::Result<Self> {
+        let config = scenario.custom_tool.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "--tool custom needs a custom_tool section in scenario '{}'",
+                scenario.name
+            )
+        })?;
+        Self::new(config)
+    }
+
+    fn program(&self) -> &str {
+        &self.argv[0]
+    }
+
+    /// The command line for one run, with placeholders filled in.
+    fn expand(&self, prompt: &str, model: &str, cwd: &Path) -> Vec<String> {
+        let cwd = cwd.display().to_string();
+        self.argv
+            .iter()
+            .map(|arg| {
+                arg.replace("{prompt}", prompt)
+                    .replace("{model}", model)
+                    .replace("{cwd}", &cwd)
+            })
+            .collect()
+    }
+
+    /// Map the agent's exit code onto the harness convention of 0 = success.
+    fn normalize_exit_code(&self, exit_code: i32) -> i32 {
+        if self.success_exit_codes.contains(&exit_code) {
+            0
+        } else if exit_code == 0 {
+            1
+        } else {
+            exit_code
+        }
+    }
+
+    fn parse_usage(&self, output: &str) -> (Option<f64>, Option<super::TokenUsage>) {
+        let cost = self
+            .cost
+            .as_ref()
+            .and_then(|re| sum_captures::<f64>(re, output));
+        let input = self
+            .input_tokens
+            .as_ref()
+            .and_then(|re| sum_captures::<usize>(re, output));
+        let output_tokens = self
+            .output_tokens
+            .as_ref()
+            .and_then(|re| sum_captures::<usize>(re, output));
+        let usage = (input.is_some() || output_tokens.is_some()).then(|| super::TokenUsage {
+            input: input.unwrap_or(0),
+            output: output_tokens.unwrap_or(0),
+        });
+        (cost, usage)
+    }
+}
+
+/// Sum of the first capture group over all matches, or `None` if nothing parsed.
+fn sum_captures<T>(regex: &Regex, output: &str) -> Option<T>
+where
+    T: std::str::FromStr + std::iter::Sum<T>,
+{
+    let values: Vec<T> = regex
+        .captures_iter(output)
+        .filter_map(|caps| caps.get(1)?.as_str().replace(',', "").parse().ok())
+        .collect();
+    (!values.is_empty()).then(|| values.into_iter().sum())
+}
+
+/// Whether `program` can be launched: absolute paths must exist, bare names
+/// must be on `PATH`. Relative paths resolve in the fixture directory at run
+/// time, so they are not checked here.
+fn program_exists(program: &str) -> bool {
+    let path = Path::new(program);
+    if path.is_absolute() {
+        return path.is_file();
+    }
+    if program.contains('/') {
+        return true;
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+impl ToolAdapter for CustomAdapter {
+    fn is_available(&self) -> Result<super::ToolStatus, super::AdapterError> {
+        if program_exists(self.program()) {
+            Ok(super::ToolStatus {
+                available: true,
+                authenticated: true,
+            })
+        } else {
+            Err(super::AdapterError::NotAvailable(format!(
+                "Custom tool not found: {}",
+                self.program()
+            )))
+        }
+    }
+
+    fn detects_stalls(&self) -> bool {
+        true
+    }
+
+    fn accepts_extra_args(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> Option<String> {
+        super::command_version(self.program())
+    }
+
+    fn run(
+        &self,
+        scenario: &Scenario,
+        cwd: &Path,
+        model: Option<&str>,
+        timeout_secs: u64,
+        options: &super::RunOptions,
+    ) -> anyhow::Result<(String, i32, Option<f64>, Option<super::TokenUsage>)> {
+        let runner = SessionRunner::new()
+            .with_stall_timeout(options.stall_timeout.clone())
+            .with_progress(options.progress.clone())
+            .with_stream_capture(options.streams.clone())
+            .with_sandbox(options.sandbox.clone());
+
+        let mut argv = self.expand(&scenario.task.prompt, model.unwrap_or_default(), cwd);
+        argv.extend(options.extra_args.iter().cloned());
+        let args: Vec<&str> = argv[1..].iter().map(String::as_str).collect();
+
+        let mut env_vars = options.env.clone();
+        if let Some(target_env) = &scenario.target.env {
+            env_vars.extend(target_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        let (output, exit_code) =
+            runner.run_command_with_env(&argv[0], &args, cwd, timeout_secs, &env_vars)?;
+        let (cost, token_usage) = self.parse_usage(&output);
+
+        Ok((
+            output,
+            self.normalize_exit_code(exit_code),
+            cost,
+            token_usage,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str) -> CustomToolConfig {
+        serde_yaml::from_str(&format!("command: {:?}", command)).unwrap()
+    }
+
+    #[test]
+    fn expands_placeholders_per_argument() {
+        let adapter =
+            CustomAdapter::new(&config("my-agent --model {model} --dir={cwd} {prompt}")).unwrap();
+        let argv = adapter.expand("fix it; rm -rf /", "gpt-4o", Path::new("/tmp/run"));
+        assert_eq!(
+            argv,
+            vec![
+                "my-agent",
+                "--model",
+                "gpt-4o",
+                "--dir=/tmp/run",
+                "fix it; rm -rf /"
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_exit_codes_and_sums_usage() {
+        let mut cfg = config("my-agent {prompt}");
+        cfg.cost_pattern = Some(r"cost: \$([0-9.]+)".to_string());
+        cfg.input_tokens_pattern = Some(r"in=([\d,]+)".to_string());
+        cfg.success_exit_codes = vec![0, 2];
+        let adapter = CustomAdapter::new(&cfg).unwrap();
+
+        assert_eq!(adapter.normalize_exit_code(2), 0);
+        assert_eq!(adapter.normalize_exit_code(3), 3);
+
+        let (cost, usage) = adapter.parse_usage("in=1,200 cost: $0.01\nin=300 cost: $0.02\n");
+        assert!((cost.unwrap() - 0.03).abs() < 1e-9);
+        let usage = usage.unwrap();
+        assert_eq!((usage.input, usage.output), (1_500, 0));
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        assert!(CustomAdapter::new(&config("agent 'unterminated")).is_err());
+        let mut cfg = config("agent");
+        cfg.cost_pattern = Some("(".to_string());
+        let err = CustomAdapter::new(&cfg).err().unwrap().to_string();
+        assert!(err.contains("custom_tool.cost_pattern"), "{}", err);
+    }
+}
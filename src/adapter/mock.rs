@@ -30,6 +30,7 @@ impl ToolAdapter for MockAdapter {
         _cwd: &Path,
         _model: Option<&str>,
         _timeout_secs: u64,
+        _options: &super::RunOptions,
     ) -> anyhow::Result<(String, i32, Option<f64>, Option<super::TokenUsage>)> {
         // Generate mock output without executing any commands
         let transcript = self.generate_transcript(scenario);
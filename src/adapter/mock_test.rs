@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::adapter::mock::MockAdapter;
-    use crate::adapter::ToolAdapter;
+    use crate::adapter::{RunOptions, ToolAdapter};
     use crate::scenario::Scenario;
 
     #[test]
@@ -49,7 +49,13 @@ evaluation:
         let scenario: Scenario = serde_yaml::from_str(scenario_yaml).unwrap();
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let result = adapter.run(&scenario, temp_dir.path(), Some("mock"), 30);
+        let result = adapter.run(
+            &scenario,
+            temp_dir.path(),
+            Some("mock"),
+            30,
+            &RunOptions::default(),
+        );
 
         assert!(result.is_ok());
         let (output, exit_code, _cost, _token_usage) = result.unwrap();
@@ -77,7 +83,13 @@ evaluation:
         let scenario: Scenario = serde_yaml::from_str(scenario_yaml).unwrap();
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let result = adapter.run(&scenario, temp_dir.path(), Some("mock"), 30);
+        let result = adapter.run(
+            &scenario,
+            temp_dir.path(),
+            Some("mock"),
+            30,
+            &RunOptions::default(),
+        );
 
         assert!(result.is_ok());
         let (output, exit_code, _cost, _token_usage) = result.unwrap();
@@ -103,7 +115,13 @@ evaluation:
         let scenario: Scenario = serde_yaml::from_str(scenario_yaml).unwrap();
 
         let temp_dir = tempfile::tempdir().unwrap();
-        let result = adapter.run(&scenario, temp_dir.path(), Some("mock"), 30);
+        let result = adapter.run(
+            &scenario,
+            temp_dir.path(),
+            Some("mock"),
+            30,
+            &RunOptions::default(),
+        );
 
         assert!(result.is_ok());
         let (_output, _exit_code, cost, token_usage) = result.unwrap();
@@ -1,12 +1,21 @@
+pub mod aider;
 pub mod claude_code;
+#[cfg(any(test, feature = "conformance"))]
+#[cfg_attr(not(test), allow(dead_code))]
+pub mod conformance;
+pub mod custom;
 pub mod mock;
+pub mod openai;
 pub mod opencode;
+pub mod plugin;
 
 #[cfg(test)]
 mod mock_test;
 
 use crate::scenario::Scenario;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 /// Error type for adapter operations.
 #[derive(Debug, thiserror::Error)]
@@ -14,10 +23,31 @@ pub enum AdapterError {
     #[error("Tool not available: {0}")]
     NotAvailable(String),
 
+    /// The tool ran past its timeout and was killed
+    #[error("{what} timed out after {secs} seconds")]
+    TimedOut { what: String, secs: u64 },
+
     #[error("Other error: {0}")]
     Other(#[from] anyhow::Error),
 }
 
+impl AdapterError {
+    /// A command killed after `secs`.
+    pub fn command_timed_out(secs: u64) -> Self {
+        Self::TimedOut {
+            what: "Command".to_string(),
+            secs,
+        }
+    }
+
+    /// Whether `error`, or an error it wraps, is [`AdapterError::TimedOut`].
+    pub fn is_timeout(error: &anyhow::Error) -> bool {
+        error
+            .chain()
+            .any(|e| matches!(e.downcast_ref::<Self>(), Some(Self::TimedOut { .. })))
+    }
+}
+
 /// Status of a tool's availability.
 #[derive(Debug, Clone)]
 pub struct ToolStatus {
@@ -25,6 +55,38 @@ pub struct ToolStatus {
     pub authenticated: bool,
 }
 
+/// How to log in to a tool that is installed but not authenticated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthGuidance {
+    /// Tool-specific steps shown when the availability preflight fails
+    pub instructions: String,
+    /// Interactive command that `doctor --fix` runs to start the login flow
+    pub login_command: Option<Vec<String>>,
+}
+
+impl AuthGuidance {
+    /// The preflight error for an unauthenticated tool.
+    pub fn into_error(self) -> anyhow::Error {
+        let mut message = format!("Tool not authenticated: {}", self.instructions);
+        if self.login_command.is_some() {
+            message.push_str(" (or run `llm-tool-test doctor --fix` to log in interactively)");
+        }
+        anyhow::anyhow!(message)
+    }
+}
+
+/// Whether the environment variable is set to a non-empty value.
+pub fn env_is_set(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|v| !v.is_empty())
+}
+
+/// The user's home directory from `HOME`.
+pub fn home_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|v| !v.is_empty())
+        .map(std::path::PathBuf::from)
+}
+
 /// Token usage statistics.
 #[derive(Debug, Clone)]
 pub struct TokenUsage {
@@ -32,21 +94,277 @@ pub struct TokenUsage {
     pub output: usize,
 }
 
+/// Per-invocation options passed to an adapter alongside the scenario.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Extra environment variables, applied after the adapter's own defaults
+    /// and before the scenario's `target.env`
+    pub env: Vec<(String, String)>,
+    /// Arguments appended to the tool's command line, from the matrix
+    /// entry's `extra_args`
+    pub extra_args: Vec<String>,
+    /// Turn budget from the scenario's `run.max_turns`
+    pub turn_limit: Option<TurnLimit>,
+    /// Idle-output budget from the scenario's `run.stall_timeout_secs`
+    pub stall_timeout: Option<StallTimeout>,
+    /// Live output counters sampled by the runner's heartbeat
+    pub progress: Option<Progress>,
+    /// Receives stdout and stderr separately when the tool's streams are
+    /// captured apart (see [`StreamCapture`])
+    pub streams: Option<StreamCapture>,
+    /// Container the tool runs in, from the scenario's `run.sandbox`
+    pub sandbox: Option<crate::sandbox::Sandbox>,
+}
+
+/// Turn budget enforced while a tool runs.
+///
+/// Adapters either pass the budget to the tool or watch its structured
+/// events (see [`crate::session::SessionRunner::with_turn_limit`]) and stop
+/// it once more than `max_turns` turns started. Clones share the reached flag.
+#[derive(Debug, Clone)]
+pub struct TurnLimit {
+    pub max_turns: usize,
+    reached: Arc<AtomicBool>,
+}
+
+impl TurnLimit {
+    pub fn new(max_turns: usize) -> Self {
+        Self {
+            max_turns,
+            reached: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Whether the tool was stopped for exceeding the budget.
+    pub fn reached(&self) -> bool {
+        self.reached.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_reached(&self) {
+        self.reached.store(true, Ordering::SeqCst);
+    }
+}
+
+/// No-output watchdog enforced while a tool runs.
+///
+/// A tool that produces no output for `secs` seconds is killed and the run
+/// is marked stalled, separating hangs from slow runs that keep progressing.
+/// Clones share the stalled flag.
+#[derive(Debug, Clone)]
+pub struct StallTimeout {
+    pub secs: u64,
+    stalled: Arc<AtomicBool>,
+}
+
+impl StallTimeout {
+    pub fn new(secs: u64) -> Self {
+        Self {
+            secs,
+            stalled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.secs)
+    }
+
+    /// Whether the tool was stopped for going silent.
+    pub fn stalled(&self) -> bool {
+        self.stalled.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_stalled(&self) {
+        self.stalled.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Live counters for a running tool, sampled for heartbeat events.
+///
+/// Adapters feed the tool's output as it arrives (see
+/// [`crate::session::SessionRunner::with_progress`]); complete lines matching
+/// the target's command pattern are counted as commands. Clones share counts.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    command_regex: Option<regex::Regex>,
+    state: Arc<Mutex<ProgressState>>,
+}
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    bytes: u64,
+    commands: usize,
+    line: Vec<u8>,
+}
+
+impl Progress {
+    /// Count commands with `command_pattern` (see
+    /// [`crate::transcript::TranscriptAnalyzer::resolve_command_pattern`]).
+    pub fn new(command_pattern: &str) -> Self {
+        Self {
+            command_regex: regex::Regex::new(command_pattern).ok(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Account for a chunk of tool output.
+    pub fn record(&self, bytes: &[u8]) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.bytes += bytes.len() as u64;
+        for &byte in bytes {
+            if byte != b'\n' {
+                state.line.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&state.line).into_owned();
+            if self
+                .command_regex
+                .as_ref()
+                .is_some_and(|re| re.is_match(&line))
+            {
+                state.commands += 1;
+            }
+            state.line.clear();
+        }
+    }
+
+    /// Bytes of output seen so far.
+    pub fn bytes(&self) -> u64 {
+        self.state.lock().map(|s| s.bytes).unwrap_or_default()
+    }
+
+    /// Target-tool commands seen so far.
+    pub fn commands(&self) -> usize {
+        self.state.lock().map(|s| s.commands).unwrap_or_default()
+    }
+}
+
+/// Tool output split into stdout and stderr.
+///
+/// A PTY merges both streams, so only runs on pipes fill the capture; set
+/// `require_pipes` to make [`crate::session::SessionRunner`] skip the PTY.
+/// Clones share the captured output.
+#[derive(Debug, Clone, Default)]
+pub struct StreamCapture {
+    pub require_pipes: bool,
+    captured: Arc<Mutex<Option<CapturedStreams>>>,
+}
+
+/// Separately captured output streams of one tool invocation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CapturedStreams {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl StreamCapture {
+    pub fn new(require_pipes: bool) -> Self {
+        Self {
+            require_pipes,
+            captured: Arc::default(),
+        }
+    }
+
+    pub fn set(&self, stdout: &[u8], stderr: &[u8]) {
+        if let Ok(mut captured) = self.captured.lock() {
+            *captured = Some(CapturedStreams {
+                stdout: String::from_utf8_lossy(stdout).into_owned(),
+                stderr: String::from_utf8_lossy(stderr).into_owned(),
+            });
+        }
+    }
+
+    /// Drop output from a previous invocation.
+    pub fn clear(&self) {
+        if let Ok(mut captured) = self.captured.lock() {
+            *captured = None;
+        }
+    }
+
+    /// Streams of the last invocation, if they were captured separately.
+    pub fn get(&self) -> Option<CapturedStreams> {
+        self.captured.lock().ok()?.clone()
+    }
+}
+
+/// Output fragments that indicate the provider throttled the request.
+const RATE_LIMIT_PATTERN: &str =
+    r"(?i)\b429\b|rate[ _-]?limit|too many requests|overloaded|quota exceeded";
+
+/// Output fragments that indicate a server-side or network failure worth retrying.
+const TRANSIENT_ERROR_PATTERN: &str = concat!(
+    r"(?i)internal server error|bad gateway|service unavailable|gateway timeout|\b50[0234]\b",
+    r"|connection (reset|refused|closed)|econnreset|etimedout|socket hang up|network error"
+);
+
+/// Trailing lines of tool output searched for a rate limit or transient failure.
+const ERROR_TAIL_LINES: usize = 5;
+
+/// The last few non-empty lines of `output`: where a tool reports the error
+/// it exited with. Earlier output is the agent's own work, where a 429 from a
+/// `curl` or a refused connection says nothing about the provider.
+fn error_tail(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(ERROR_TAIL_LINES)..].join("\n")
+}
+
+fn tail_matches(pattern: &str, output: &str) -> bool {
+    regex::Regex::new(pattern)
+        .map(|re| re.is_match(&error_tail(output)))
+        .unwrap_or(false)
+}
+
+/// Whether tool output ends in a 429-style rate limit failure.
+pub fn looks_rate_limited(output: &str) -> bool {
+    tail_matches(RATE_LIMIT_PATTERN, output)
+}
+
+/// Whether tool output ends in a transient provider failure (rate limit, 5xx, network reset).
+pub fn looks_transient(output: &str) -> bool {
+    looks_rate_limited(output) || tail_matches(TRANSIENT_ERROR_PATTERN, output)
+}
+
+/// First line of `<binary> --version`, if the command succeeds.
+pub fn command_version(binary: &str) -> Option<String> {
+    let (output, exit_code) = crate::session::SessionRunner::new()
+        .run_command(binary, &["--version"], Path::new("."), 10)
+        .ok()?;
+    if exit_code != 0 {
+        return None;
+    }
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
 /// Trait for tool adapters that execute LLM CLI tools.
 pub trait ToolAdapter: Send + Sync {
     /// Check if tool is installed and authenticated.
     fn is_available(&self) -> Result<ToolStatus, AdapterError>;
 
     /// Check if the tool is available and ready to use.
+    ///
+    /// An installed but unauthenticated tool fails with its [`AuthGuidance`].
     fn check_availability(&self) -> anyhow::Result<()> {
         match self.is_available() {
-            Ok(status) if status.available => Ok(()),
-            Ok(status) if !status.authenticated => Err(anyhow::anyhow!("Tool not authenticated")),
-            Ok(_) => Err(anyhow::anyhow!("Tool not available")),
+            Ok(status) if !status.available => Err(anyhow::anyhow!("Tool not available")),
+            Ok(status) if !status.authenticated => Err(self
+                .auth_guidance()
+                .map(AuthGuidance::into_error)
+                .unwrap_or_else(|| anyhow::anyhow!("Tool not authenticated"))),
+            Ok(_) => Ok(()),
             Err(e) => Err(e.into()),
         }
     }
 
+    /// How to authenticate the tool when `is_available` reports it is not.
+    fn auth_guidance(&self) -> Option<AuthGuidance> {
+        None
+    }
+
     /// Run the tool with the given scenario in the specified working directory.
     /// Returns the tool output, exit code, estimated cost in USD (if available), and token usage (if available).
     fn run(
@@ -55,5 +373,38 @@ pub trait ToolAdapter: Send + Sync {
         cwd: &Path,
         model: Option<&str>,
         timeout_secs: u64,
+        options: &RunOptions,
     ) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>)>;
+
+    /// Whether `run` enforces [`RunOptions::turn_limit`].
+    fn enforces_turn_limit(&self) -> bool {
+        false
+    }
+
+    /// Whether `run` enforces [`RunOptions::stall_timeout`].
+    fn detects_stalls(&self) -> bool {
+        false
+    }
+
+    /// Whether `run` passes [`RunOptions::extra_args`] to the tool.
+    fn accepts_extra_args(&self) -> bool {
+        false
+    }
+
+    /// Version string of the installed tool, recorded for reproduction.
+    fn version(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether a failed run was caused by provider rate limiting.
+    ///
+    /// Used by the scheduler to back off before the next launch of this tool.
+    fn is_rate_limited(&self, output: &str, exit_code: i32) -> bool {
+        exit_code != 0 && looks_rate_limited(output)
+    }
+
+    /// Whether a failed run is worth retrying (rate limits, 5xx, network resets).
+    fn is_transient_failure(&self, output: &str, exit_code: i32) -> bool {
+        exit_code != 0 && looks_transient(output)
+    }
 }
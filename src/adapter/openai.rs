@@ -0,0 +1,646 @@
+//! Adapter that drives an OpenAI-compatible chat completions endpoint.
+//!
+//! Instead of launching a CLI agent, the adapter runs a minimal agent loop
+//! itself: the model is offered a single `shell` tool, every tool call is
+//! executed with `sh -c` in the fixture directory, and the output is sent back
+//! until the model answers without tool calls (or `max_steps` is reached).
+//! This lets raw models be benchmarked on the same scenarios as full agents.
+//!
+//! The transcript lists each command on its own line followed by its output
+//! and `exit code: N`, so command extraction and `no_transcript_errors` work
+//! as for PTY-captured agents.
+
+use super::{AdapterError, AuthGuidance, RunOptions, TokenUsage, ToolAdapter, ToolStatus};
+use crate::config::{OpenAiConfig, DEFAULT_OPENAI_BASE_URL};
+use crate::exec::{Clock, CommandExecutor, SystemClock, SystemExecutor};
+use crate::scenario::Scenario;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Characters of command output sent back to the model per tool call.
+const MAX_TOOL_OUTPUT_CHARS: usize = 16_000;
+
+const SYSTEM_PROMPT: &str = "You are an autonomous agent working in a shell. \
+Use the `shell` tool to run commands in the current working directory until the task is done. \
+When you are finished, reply with a short summary and no tool calls.";
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: AssistantMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssistantMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ToolCall {
+    id: String,
+    #[serde(rename = "type", default = "function_type")]
+    kind: String,
+    function: FunctionCall,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    arguments: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShellArguments {
+    command: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    prompt_tokens: usize,
+    #[serde(default)]
+    completion_tokens: usize,
+}
+
+/// Adapter for OpenAI-compatible chat completions APIs (tool name `openai`).
+pub struct OpenAiAdapter {
+    config: OpenAiConfig,
+    executor: Arc<dyn CommandExecutor>,
+    clock: Arc<dyn Clock>,
+}
+
+impl OpenAiAdapter {
+    pub fn new(config: OpenAiConfig) -> Self {
+        Self {
+            config,
+            executor: Arc::new(SystemExecutor),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Run shell tool calls through `executor` and measure deadlines on `clock`.
+    #[cfg(test)]
+    fn with_runtime(mut self, executor: Arc<dyn CommandExecutor>, clock: Arc<dyn Clock>) -> Self {
+        self.executor = executor;
+        self.clock = clock;
+        self
+    }
+
+    fn api_key(&self) -> Option<String> {
+        std::env::var(self.config.api_key_env())
+            .ok()
+            .filter(|key| !key.is_empty())
+    }
+
+    fn cost(&self, usage: &Usage) -> Option<f64> {
+        let input = self.config.input_usd_per_mtok?;
+        let output = self.config.output_usd_per_mtok?;
+        Some(
+            (usage.prompt_tokens as f64 * input + usage.completion_tokens as f64 * output)
+                / 1_000_000.0,
+        )
+    }
+
+    /// POST one chat completion request, returning an error message for
+    /// non-success responses so the caller can put it in the transcript.
+    fn complete(
+        &self,
+        client: &reqwest::blocking::Client,
+        body: &Value,
+        timeout: Duration,
+    ) -> Result<ChatResponse, String> {
+        let mut request = client
+            .post(format!("{}/chat/completions", self.config.base_url()))
+            .timeout(timeout)
+            .json(body);
+        if let Some(key) = self.api_key() {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .map_err(|e| format!("API request failed: network error: {}", e))?;
+        let status = response.status();
+        let text = response.text().unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("API request failed: {} - {}", status, text.trim()));
+        }
+        serde_json::from_str(&text).map_err(|e| format!("Invalid API response: {}", e))
+    }
+}
+
+fn shell_tool() -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": "shell",
+            "description": "Run a shell command in the working directory and return its combined stdout and stderr.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string", "description": "Command to run with sh -c" }
+                },
+                "required": ["command"]
+            }
+        }
+    })
+}
+
+/// Run `command` with `sh -c`, returning combined output and exit code, or
+/// `None` if it was killed after `timeout`.
+fn run_shell(
+    executor: &dyn CommandExecutor,
+    command: &str,
+    cwd: &Path,
+    env: &[(String, String)],
+    timeout: Duration,
+) -> anyhow::Result<Option<(String, i32)>> {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .current_dir(cwd)
+        .envs(env.iter().map(|(k, v)| (k, v)));
+    let result = executor.run(cmd, None, Some(timeout))?;
+    if result.timed_out {
+        return Ok(None);
+    }
+    let exit_code = result.exit_code();
+    let mut output = result.output.stdout;
+    output.extend(result.output.stderr);
+    Ok(Some((
+        String::from_utf8_lossy(&output).into_owned(),
+        exit_code,
+    )))
+}
+
+fn truncate_for_model(output: &str) -> String {
+    if output.chars().count() <= MAX_TOOL_OUTPUT_CHARS {
+        return output.to_string();
+    }
+    let kept: String = output.chars().take(MAX_TOOL_OUTPUT_CHARS).collect();
+    format!("{}\n[output truncated]", kept)
+}
+
+impl ToolAdapter for OpenAiAdapter {
+    fn is_available(&self) -> Result<ToolStatus, AdapterError> {
+        // Local servers usually accept unauthenticated requests
+        Ok(ToolStatus {
+            available: true,
+            authenticated: self.api_key().is_some()
+                || self.config.base_url() != DEFAULT_OPENAI_BASE_URL,
+        })
+    }
+
+    fn auth_guidance(&self) -> Option<AuthGuidance> {
+        Some(AuthGuidance {
+            instructions: format!(
+                "{} is not set (required for {}); export it or point [openai] base_url at a local server",
+                self.config.api_key_env(),
+                self.config.base_url()
+            ),
+            login_command: None,
+        })
+    }
+
+    fn enforces_turn_limit(&self) -> bool {
+        true
+    }
+
+    fn detects_stalls(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> Option<String> {
+        Some(format!("openai-compatible {}", self.config.base_url()))
+    }
+
+    fn run(
+        &self,
+        scenario: &Scenario,
+        cwd: &Path,
+        model: Option<&str>,
+        timeout_secs: u64,
+        options: &RunOptions,
+    ) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>)> {
+        let model = match model.filter(|m| *m != "default") {
+            Some(model) => model.to_string(),
+            None => self.config.default_model.clone().ok_or_else(|| {
+                anyhow::anyhow!("No model given; pass --model or set openai.default_model")
+            })?,
+        };
+        let mut env = options.env.clone();
+        if let Some(target_env) = &scenario.target.env {
+            env.extend(target_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        let deadline = self.clock.now() + Duration::from_secs(timeout_secs);
+        let remaining = || deadline.saturating_duration_since(self.clock.now());
+        let timed_out = || anyhow::Error::from(AdapterError::command_timed_out(timeout_secs));
+        let client = reqwest::blocking::Client::new();
+        let mut messages = vec![
+            json!({ "role": "system", "content": SYSTEM_PROMPT }),
+            json!({ "role": "user", "content": scenario.task.prompt }),
+        ];
+        let mut transcript = String::new();
+        // Transcript bytes already passed to `options.progress`
+        let mut reported = 0;
+        let mut usage = Usage::default();
+        // Set once the model answers without tool calls or the API fails
+        let mut exit_code = None;
+
+        // One turn is one completion request
+        let turn_limit = options
+            .turn_limit
+            .as_ref()
+            .filter(|l| l.max_turns <= self.config.max_steps());
+        let steps = turn_limit.map_or(self.config.max_steps(), |l| l.max_turns);
+
+        for _ in 0..steps {
+            if remaining().is_zero() {
+                return Err(timed_out());
+            }
+            let body = json!({
+                "model": model,
+                "messages": messages,
+                "tools": [shell_tool()],
+            });
+            // A request that outlives the stall timeout counts as a stall
+            let stall = options.stall_timeout.as_ref();
+            let request_timeout = stall.map_or(remaining(), |s| remaining().min(s.duration()));
+            let requested = self.clock.now();
+            let response = match self.complete(&client, &body, request_timeout) {
+                Ok(response) => response,
+                Err(_) if remaining().is_zero() => return Err(timed_out()),
+                Err(_)
+                    if stall.is_some_and(|s| {
+                        self.clock.now().saturating_duration_since(requested) >= s.duration()
+                    }) =>
+                {
+                    if let Some(stall) = stall {
+                        stall.mark_stalled();
+                    }
+                    exit_code = Some(1);
+                    break;
+                }
+                Err(message) => {
+                    transcript.push_str(&message);
+                    transcript.push('\n');
+                    exit_code = Some(1);
+                    break;
+                }
+            };
+            if let Some(step_usage) = response.usage {
+                usage.prompt_tokens += step_usage.prompt_tokens;
+                usage.completion_tokens += step_usage.completion_tokens;
+            }
+            let Some(choice) = response.choices.into_iter().next() else {
+                transcript.push_str("Invalid API response: no choices\n");
+                exit_code = Some(1);
+                break;
+            };
+            let message = choice.message;
+            if let Some(content) = message.content.as_deref().filter(|c| !c.trim().is_empty()) {
+                transcript.push_str(content.trim_end());
+                transcript.push('\n');
+            }
+            let mut assistant = json!({ "role": "assistant", "content": message.content });
+            if !message.tool_calls.is_empty() {
+                assistant["tool_calls"] = serde_json::to_value(&message.tool_calls)?;
+            }
+            messages.push(assistant);
+            if message.tool_calls.is_empty() {
+                exit_code = Some(0);
+                break;
+            }
+
+            for call in &message.tool_calls {
+                let result = if call.function.name != "shell" {
+                    format!("Unknown tool: {}", call.function.name)
+                } else {
+                    match serde_json::from_str::<ShellArguments>(&call.function.arguments) {
+                        Err(e) => format!("Invalid arguments: {}", e),
+                        Ok(args) => {
+                            transcript.push_str(args.command.trim());
+                            transcript.push('\n');
+                            let limit = remaining()
+                                .min(Duration::from_secs(self.config.command_timeout_secs()));
+                            match run_shell(
+                                self.executor.as_ref(),
+                                &args.command,
+                                cwd,
+                                &env,
+                                limit,
+                            )? {
+                                None if remaining().is_zero() => return Err(timed_out()),
+                                None => {
+                                    let note =
+                                        format!("timed out after {} seconds", limit.as_secs());
+                                    transcript.push_str(&format!("{}\n", note));
+                                    note
+                                }
+                                Some((output, code)) => {
+                                    transcript.push_str(&output);
+                                    if !output.is_empty() && !output.ends_with('\n') {
+                                        transcript.push('\n');
+                                    }
+                                    transcript.push_str(&format!("exit code: {}\n", code));
+                                    format!("{}\nexit code: {}", truncate_for_model(&output), code)
+                                }
+                            }
+                        }
+                    }
+                };
+                messages.push(json!({
+                    "role": "tool",
+                    "tool_call_id": call.id,
+                    "content": result,
+                }));
+            }
+            if let Some(progress) = &options.progress {
+                progress.record(&transcript.as_bytes()[reported..]);
+                reported = transcript.len();
+            }
+        }
+        let exit_code = exit_code.unwrap_or_else(|| {
+            if let Some(limit) = turn_limit {
+                limit.mark_reached();
+                return 1;
+            }
+            transcript.push_str(&format!(
+                "Step limit of {} reached\n",
+                self.config.max_steps()
+            ));
+            1
+        });
+
+        let cost = self.cost(&usage);
+        let tokens =
+            (usage.prompt_tokens > 0 || usage.completion_tokens > 0).then_some(TokenUsage {
+                input: usage.prompt_tokens,
+                output: usage.completion_tokens,
+            });
+        Ok((transcript, exit_code, cost, tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::TurnLimit;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// Replies with each canned body in turn, repeating the last one.
+    struct Scripted {
+        bodies: Vec<Value>,
+        next: AtomicUsize,
+    }
+
+    impl Respond for Scripted {
+        fn respond(&self, _request: &Request) -> ResponseTemplate {
+            let i = self.next.fetch_add(1, Ordering::SeqCst);
+            ResponseTemplate::new(200).set_body_json(&self.bodies[i.min(self.bodies.len() - 1)])
+        }
+    }
+
+    fn tool_call(command: &str) -> Value {
+        json!({
+            "choices": [{ "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "shell", "arguments": json!({ "command": command }).to_string() }
+                }]
+            }}],
+            "usage": { "prompt_tokens": 100, "completion_tokens": 20 }
+        })
+    }
+
+    fn final_answer(text: &str) -> Value {
+        json!({
+            "choices": [{ "message": { "role": "assistant", "content": text } }],
+            "usage": { "prompt_tokens": 150, "completion_tokens": 10 }
+        })
+    }
+
+    fn scenario() -> Scenario {
+        serde_yaml::from_str(
+            "name: api\ndescription: API adapter\ntemplate_folder: api\ntarget:\n  binary: sh\n  env:\n    GREETING: hello\ntask:\n  prompt: Write a file\nevaluation:\n  gates: []\n",
+        )
+        .unwrap()
+    }
+
+    fn adapter(server: &MockServer, max_steps: Option<usize>) -> OpenAiAdapter {
+        OpenAiAdapter::new(OpenAiConfig {
+            base_url: Some(format!("{}/v1", server.uri())),
+            api_key_env: Some("LLM_TOOL_TEST_OPENAI_TEST_KEY".to_string()),
+            max_steps,
+            input_usd_per_mtok: Some(2.0),
+            output_usd_per_mtok: Some(10.0),
+            ..Default::default()
+        })
+    }
+
+    async fn run(
+        adapter: OpenAiAdapter,
+        cwd: &Path,
+        options: RunOptions,
+    ) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>)> {
+        let cwd = cwd.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            adapter.run(&scenario(), &cwd, Some("test-model"), 30, &options)
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn executes_shell_tool_calls_in_fixture() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(Scripted {
+                bodies: vec![
+                    tool_call("echo \"$GREETING\" > out.txt && cat out.txt"),
+                    final_answer("Wrote out.txt"),
+                ],
+                next: AtomicUsize::new(0),
+            })
+            .expect(2)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let (output, exit_code, cost, tokens) =
+            run(adapter(&server, None), dir.path(), RunOptions::default())
+                .await
+                .unwrap();
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+            "hello\n"
+        );
+        assert!(
+            output.contains("echo \"$GREETING\" > out.txt && cat out.txt\nhello\nexit code: 0\n")
+        );
+        assert!(output.ends_with("Wrote out.txt\n"));
+        let tokens = tokens.unwrap();
+        assert_eq!((tokens.input, tokens.output), (250, 30));
+        assert!((cost.unwrap() - 0.0008).abs() < 1e-9);
+
+        let requests = server.received_requests().await.unwrap();
+        let second: Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(second["model"], "test-model");
+        let messages = second["messages"].as_array().unwrap();
+        assert_eq!(messages[2]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(messages[3]["role"], "tool");
+        assert_eq!(messages[3]["content"], "hello\n\nexit code: 0");
+    }
+
+    #[tokio::test]
+    async fn stops_at_step_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(Scripted {
+                bodies: vec![tool_call("true")],
+                next: AtomicUsize::new(0),
+            })
+            .expect(3)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let (output, exit_code, _, _) =
+            run(adapter(&server, Some(3)), dir.path(), RunOptions::default())
+                .await
+                .unwrap();
+
+        assert_eq!(exit_code, 1);
+        assert!(output.ends_with("Step limit of 3 reached\n"));
+    }
+
+    #[tokio::test]
+    async fn enforces_scenario_turn_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(Scripted {
+                bodies: vec![tool_call("true")],
+                next: AtomicUsize::new(0),
+            })
+            .expect(2)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let limit = TurnLimit::new(2);
+        let options = RunOptions {
+            turn_limit: Some(limit.clone()),
+            ..Default::default()
+        };
+
+        let (output, exit_code, _, _) = run(adapter(&server, None), dir.path(), options)
+            .await
+            .unwrap();
+
+        assert_eq!(exit_code, 1);
+        assert!(limit.reached());
+        assert!(!output.contains("Step limit"));
+    }
+
+    #[tokio::test]
+    async fn reports_api_errors_as_failed_runs() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Rate limit reached"))
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let adapter = adapter(&server, None);
+        let classifier = OpenAiAdapter::new(adapter.config.clone());
+
+        let (output, exit_code, _, tokens) = run(adapter, dir.path(), RunOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(exit_code, 1);
+        assert!(output.contains("429"));
+        assert!(tokens.is_none());
+        assert!(classifier.is_rate_limited(&output, exit_code));
+    }
+
+    #[tokio::test]
+    async fn shell_timeouts_use_injected_executor_and_clock() {
+        use crate::exec::fake::{FakeClock, FakeExecutor, FakeResponse};
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(Scripted {
+                bodies: vec![tool_call("make build"), tool_call("make test")],
+                next: AtomicUsize::new(0),
+            })
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let clock = Arc::new(FakeClock::default());
+        let executor = Arc::new(
+            FakeExecutor::new([
+                FakeResponse::exit(2, "build failed\n"),
+                FakeResponse::slow(Duration::from_secs(3600)),
+            ])
+            .with_clock(clock.clone()),
+        );
+        let adapter = adapter(&server, None).with_runtime(executor.clone(), clock.clone());
+
+        let err = run(adapter, dir.path(), RunOptions::default())
+            .await
+            .unwrap_err();
+
+        // The second command used up the whole 30 second run without real waiting
+        assert!(err.to_string().contains("timed out after 30 seconds"));
+        assert_eq!(clock.elapsed(), Duration::from_secs(30));
+        let calls = executor.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].args, vec!["-c", "make build"]);
+        assert_eq!(calls[0].cwd.as_deref(), Some(dir.path()));
+        assert!(calls[0]
+            .env
+            .contains(&("GREETING".to_string(), "hello".to_string())));
+        assert_eq!(calls[1].timeout, Some(Duration::from_secs(30)));
+        let requests = server.received_requests().await.unwrap();
+        let second: Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(
+            second["messages"][3]["content"],
+            "build failed\n\nexit code: 2"
+        );
+    }
+
+    #[test]
+    fn requires_api_key_for_default_endpoint() {
+        let adapter = OpenAiAdapter::new(OpenAiConfig {
+            api_key_env: Some("LLM_TOOL_TEST_OPENAI_UNSET_KEY".to_string()),
+            ..Default::default()
+        });
+        let err = adapter.check_availability().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("LLM_TOOL_TEST_OPENAI_UNSET_KEY is not set"));
+    }
+}
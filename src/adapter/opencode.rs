@@ -1,11 +1,37 @@
 use super::ToolAdapter;
 use crate::scenario::Scenario;
 use crate::session::SessionRunner;
+use crate::transcript::analyzer::is_step_start_line;
 use serde_json::Value;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct OpenCodeAdapter;
 
+/// Provider API keys opencode picks up from the environment.
+const CREDENTIAL_ENV: &[&str] = &[
+    "ANTHROPIC_API_KEY",
+    "OPENAI_API_KEY",
+    "OPENROUTER_API_KEY",
+    "GEMINI_API_KEY",
+    "GOOGLE_GENERATIVE_AI_API_KEY",
+    "GROQ_API_KEY",
+    "XAI_API_KEY",
+    "DEEPSEEK_API_KEY",
+];
+
+/// Whether opencode has a provider key in the environment or a login stored
+/// by `opencode auth login` in `<data_dir>/opencode/auth.json`.
+///
+/// Runs point `XDG_CONFIG_HOME` at a fresh directory, so providers defined in
+/// the user's global config do not count.
+fn has_credentials(env_is_set: impl Fn(&str) -> bool, data_dir: &Path) -> bool {
+    CREDENTIAL_ENV.iter().any(|name| env_is_set(name))
+        || std::fs::read_to_string(data_dir.join("opencode/auth.json"))
+            .ok()
+            .and_then(|auth| serde_json::from_str::<serde_json::Map<String, Value>>(&auth).ok())
+            .is_some_and(|providers| !providers.is_empty())
+}
+
 fn extract_json_lines(output: &str) -> Vec<&str> {
     output
         .lines()
@@ -65,9 +91,14 @@ impl ToolAdapter for OpenCodeAdapter {
         let runner = SessionRunner::new();
         match runner.run_command("opencode", &["--version"], Path::new("."), 10) {
             Ok(_) => {
+                let data_dir = std::env::var_os("XDG_DATA_HOME")
+                    .filter(|v| !v.is_empty())
+                    .map(PathBuf::from)
+                    .or_else(|| super::home_dir().map(|h| h.join(".local/share")))
+                    .unwrap_or_default();
                 Ok(super::ToolStatus {
                     available: true,
-                    authenticated: true, // opencode doesn't require auth check
+                    authenticated: has_credentials(super::env_is_set, &data_dir),
                 })
             }
             Err(e) => Err(super::AdapterError::NotAvailable(format!(
@@ -77,12 +108,33 @@ impl ToolAdapter for OpenCodeAdapter {
         }
     }
 
-    fn check_availability(&self) -> anyhow::Result<()> {
-        let runner = SessionRunner::new();
-        match runner.run_command("opencode", &["--version"], Path::new("."), 10) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(anyhow::anyhow!("OpenCode tool not found: {}", e)),
-        }
+    fn auth_guidance(&self) -> Option<super::AuthGuidance> {
+        Some(super::AuthGuidance {
+            instructions: "OpenCode has no provider credentials; run `opencode auth login` \
+                 or set a provider API key such as ANTHROPIC_API_KEY or OPENAI_API_KEY"
+                .to_string(),
+            login_command: Some(vec![
+                "opencode".to_string(),
+                "auth".to_string(),
+                "login".to_string(),
+            ]),
+        })
+    }
+
+    fn version(&self) -> Option<String> {
+        super::command_version("opencode")
+    }
+
+    fn enforces_turn_limit(&self) -> bool {
+        true
+    }
+
+    fn detects_stalls(&self) -> bool {
+        true
+    }
+
+    fn accepts_extra_args(&self) -> bool {
+        true
     }
 
     fn run(
@@ -91,8 +143,15 @@ impl ToolAdapter for OpenCodeAdapter {
         cwd: &Path,
         model: Option<&str>,
         timeout_secs: u64,
+        options: &super::RunOptions,
     ) -> anyhow::Result<(String, i32, Option<f64>, Option<super::TokenUsage>)> {
-        let runner = SessionRunner::new();
+        // opencode has no turn limit of its own; stop it on the step events it streams
+        let runner = SessionRunner::new()
+            .with_turn_limit(options.turn_limit.clone(), is_step_start_line)
+            .with_stall_timeout(options.stall_timeout.clone())
+            .with_progress(options.progress.clone())
+            .with_stream_capture(options.streams.clone())
+            .with_sandbox(options.sandbox.clone());
 
         // Use 'opencode run' with JSON format for token extraction
         let mut args = vec!["run", "--format", "json"];
@@ -100,12 +159,14 @@ impl ToolAdapter for OpenCodeAdapter {
             args.push("--model");
             args.push(model);
         }
+        args.extend(options.extra_args.iter().map(String::as_str));
         args.push(&scenario.task.prompt);
 
         // Isolate opencode from global AGENTS.md by using a temp XDG_CONFIG_HOME
         // This ensures test results aren't skewed by global prompts/rules/tools
         // while still allowing authentication to work
-        // Use absolute path for XDG_CONFIG_HOME to ensure opencode respects it
+        // Use absolute path for XDG_CONFIG_HOME to ensure opencode respects it.
+        // An isolated HOME from the run options takes precedence over this default.
         let xdg_config_dir = cwd
             .canonicalize()
             .unwrap_or_else(|_| cwd.to_path_buf())
@@ -115,6 +176,7 @@ impl ToolAdapter for OpenCodeAdapter {
             "XDG_CONFIG_HOME".to_string(),
             xdg_config_dir.to_string_lossy().to_string(),
         )];
+        env_vars.extend(options.env.iter().cloned());
         if let Some(target_env) = &scenario.target.env {
             env_vars.extend(target_env.iter().map(|(k, v)| (k.clone(), v.clone())));
         }
@@ -126,3 +188,30 @@ impl ToolAdapter for OpenCodeAdapter {
         Ok((output, exit_code, None, token_usage))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_credentials_from_env_and_auth_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let no_env = |_: &str| false;
+        assert!(has_credentials(
+            |name| name == "OPENROUTER_API_KEY",
+            dir.path()
+        ));
+        assert!(!has_credentials(no_env, dir.path()));
+
+        std::fs::create_dir_all(dir.path().join("opencode")).unwrap();
+        std::fs::write(dir.path().join("opencode/auth.json"), "{}").unwrap();
+        assert!(!has_credentials(no_env, dir.path()));
+
+        std::fs::write(
+            dir.path().join("opencode/auth.json"),
+            r#"{"anthropic": {"type": "api", "key": "sk"}}"#,
+        )
+        .unwrap();
+        assert!(has_credentials(no_env, dir.path()));
+    }
+}
@@ -0,0 +1,442 @@
+//! Adapters implemented by external executables.
+//!
+//! A plugin is an executable named after the tool in the configured plugins
+//! directory (`<plugins_path>/<tool>`). It is invoked as `<plugin> <command>`
+//! with one JSON request on stdin and must print one JSON response on stdout:
+//!
+//! | Command   | Response                                                              |
+//! |-----------|-----------------------------------------------------------------------|
+//! | `check`   | `{"available": true, "authenticated": true, "message": "...", "login": ["mytool", "login"]}` |
+//! | `version` | `{"version": "mytool 1.2.3"}`                                         |
+//! | `run`     | `{"output": "...", "exit_code": 0, "cost_usd": 0.01, "tokens": {"input": 10, "output": 5}}` |
+//!
+//! A `run` response may add `"stderr"` with the agent's stderr when `output`
+//! holds only its stdout; the two are then recorded as separate streams.
+//!
+//! Every request carries `"protocol": 1` and `"command"`; `run` requests also
+//! carry the prompt, scenario, working directory, model, timeout and
+//! environment. The plugin process runs in the working directory with that
+//! environment applied, and is killed when the timeout expires.
+
+use super::{AdapterError, AuthGuidance, RunOptions, TokenUsage, ToolAdapter, ToolStatus};
+use crate::exec::{CommandExecutor, SystemExecutor};
+use crate::scenario::Scenario;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Version of the JSON contract sent in every request.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Timeout for `check` and `version` requests.
+const QUERY_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Serialize)]
+struct RunRequest<'a> {
+    protocol: u32,
+    command: &'static str,
+    prompt: &'a str,
+    scenario: &'a Scenario,
+    cwd: &'a Path,
+    model: Option<&'a str>,
+    timeout_secs: u64,
+    env: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    extra_args: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckResponse {
+    available: bool,
+    #[serde(default = "default_true")]
+    authenticated: bool,
+    #[serde(default)]
+    message: Option<String>,
+    /// Interactive login command for `doctor --fix`
+    #[serde(default)]
+    login: Option<Vec<String>>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionResponse {
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PluginTokens {
+    input: usize,
+    output: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunResponse {
+    output: String,
+    exit_code: i32,
+    #[serde(default)]
+    cost_usd: Option<f64>,
+    #[serde(default)]
+    tokens: Option<PluginTokens>,
+    #[serde(default)]
+    stderr: Option<String>,
+}
+
+/// Adapter backed by an external plugin executable.
+pub struct PluginAdapter {
+    name: String,
+    path: PathBuf,
+    executor: Arc<dyn CommandExecutor>,
+}
+
+/// Find the plugin for `tool` in `plugins_dir`, if one is installed.
+pub fn discover(plugins_dir: &Path, tool: &str) -> Option<PluginAdapter> {
+    if tool.is_empty() || tool.contains(['/', '\\']) || tool.starts_with('.') {
+        return None;
+    }
+    let path = plugins_dir.join(tool);
+    if !is_executable(&path) {
+        return None;
+    }
+    // Plugins run in the fixture directory, so a relative path would not resolve
+    let path = path.canonicalize().unwrap_or(path);
+    Some(PluginAdapter::new(tool, path))
+}
+
+/// Names of all plugins installed in `plugins_dir`, sorted.
+pub fn list(plugins_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(plugins_dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| is_executable(&e.path()))
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+impl PluginAdapter {
+    pub fn new(name: &str, path: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            path,
+            executor: Arc::new(SystemExecutor),
+        }
+    }
+
+    /// Launch the plugin through `executor` instead of spawning it directly.
+    #[cfg(test)]
+    fn with_executor(mut self, executor: Arc<dyn CommandExecutor>) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// Send `request` to the plugin and parse its JSON response.
+    fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        command: &str,
+        request: &serde_json::Value,
+        cwd: &Path,
+        env: &BTreeMap<String, String>,
+        timeout_secs: u64,
+    ) -> anyhow::Result<T> {
+        let mut cmd = Command::new(&self.path);
+        cmd.arg(command).current_dir(cwd).envs(env);
+        let request = serde_json::to_string(request)?;
+        let result = self
+            .executor
+            .run(
+                cmd,
+                Some(request.as_bytes()),
+                Some(Duration::from_secs(timeout_secs)),
+            )
+            .with_context(|| format!("Failed to launch plugin {}", self.path.display()))?;
+
+        if result.timed_out {
+            return Err(AdapterError::TimedOut {
+                what: format!("Plugin '{}' {}", self.name, command),
+                secs: timeout_secs,
+            }
+            .into());
+        }
+        let output = result.output;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Plugin '{}' {} failed ({}): {}",
+                self.name,
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let stdout = output.stdout;
+        serde_json::from_slice(&stdout).with_context(|| {
+            format!(
+                "Plugin '{}' returned an invalid {} response",
+                self.name, command
+            )
+        })
+    }
+
+    fn query<T: serde::de::DeserializeOwned>(&self, command: &'static str) -> anyhow::Result<T> {
+        let request = serde_json::json!({ "protocol": PROTOCOL_VERSION, "command": command });
+        self.call(
+            command,
+            &request,
+            Path::new("."),
+            &BTreeMap::new(),
+            QUERY_TIMEOUT_SECS,
+        )
+    }
+}
+
+impl ToolAdapter for PluginAdapter {
+    fn is_available(&self) -> Result<ToolStatus, AdapterError> {
+        let response: CheckResponse = self.query("check")?;
+        if !response.available {
+            if let Some(message) = response.message {
+                return Err(AdapterError::NotAvailable(message));
+            }
+        }
+        Ok(ToolStatus {
+            available: response.available,
+            authenticated: response.authenticated,
+        })
+    }
+
+    fn auth_guidance(&self) -> Option<AuthGuidance> {
+        let response: CheckResponse = self.query("check").ok()?;
+        Some(AuthGuidance {
+            instructions: response
+                .message
+                .unwrap_or_else(|| format!("plugin '{}' reports no credentials", self.name)),
+            login_command: response.login.filter(|command| !command.is_empty()),
+        })
+    }
+
+    fn accepts_extra_args(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> Option<String> {
+        self.query::<VersionResponse>("version").ok()?.version
+    }
+
+    fn run(
+        &self,
+        scenario: &Scenario,
+        cwd: &Path,
+        model: Option<&str>,
+        timeout_secs: u64,
+        options: &RunOptions,
+    ) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>)> {
+        let mut env: BTreeMap<String, String> = options.env.iter().cloned().collect();
+        if let Some(target_env) = &scenario.target.env {
+            env.extend(target_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+        let request = RunRequest {
+            protocol: PROTOCOL_VERSION,
+            command: "run",
+            prompt: &scenario.task.prompt,
+            scenario,
+            cwd: &cwd,
+            model,
+            timeout_secs,
+            env: env.clone(),
+            extra_args: &options.extra_args,
+        };
+        let response: RunResponse = self.call(
+            "run",
+            &serde_json::to_value(&request)?,
+            &cwd,
+            &env,
+            timeout_secs,
+        )?;
+        let tokens = response.tokens.map(|t| TokenUsage {
+            input: t.input,
+            output: t.output,
+        });
+        let mut output = response.output;
+        if let Some(stderr) = response.stderr {
+            if let Some(streams) = &options.streams {
+                streams.set(output.as_bytes(), stderr.as_bytes());
+            }
+            if !stderr.is_empty() && !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&stderr);
+        }
+        Ok((output, response.exit_code, response.cost_usd, tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::conformance::{run_conformance, ConformanceOptions};
+
+    /// Plugin that runs `echotool` and reports its output, with fixed usage.
+    const ECHO_PLUGIN: &str = r#"#!/bin/sh
+request=$(cat)
+case "$1" in
+  check) echo '{"available": true, "authenticated": true}' ;;
+  version) echo '{"version": "echotool-plugin 1.0"}' ;;
+  run)
+    out=$(echotool 2>&1)
+    code=$?
+    esc=$(printf '%s' "$out" | awk '{ gsub(/\\/, "\\\\"); gsub(/"/, "\\\""); printf "%s\\n", $0 }')
+    printf '{"output": "%s", "exit_code": %d, "cost_usd": 0.25, "tokens": {"input": 7, "output": 3}}\n' "$esc" "$code"
+    ;;
+  *) echo "unknown command $1" >&2; exit 2 ;;
+esac
+"#;
+
+    fn install(dir: &Path, name: &str, script: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, script).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn discovers_executable_plugins_only() {
+        let dir = tempfile::tempdir().unwrap();
+        install(dir.path(), "echotool", ECHO_PLUGIN);
+        std::fs::write(dir.path().join("README.md"), "not a plugin").unwrap();
+
+        assert!(discover(dir.path(), "echotool").is_some());
+        assert!(discover(dir.path(), "README.md").is_none());
+        assert!(discover(dir.path(), "../echotool").is_none());
+        assert_eq!(list(dir.path()), vec!["echotool"]);
+    }
+
+    #[test]
+    fn plugin_adapter_conforms() {
+        let plugins = tempfile::tempdir().unwrap();
+        let work = tempfile::tempdir().unwrap();
+        install(plugins.path(), "echotool", ECHO_PLUGIN);
+        let adapter = discover(plugins.path(), "echotool").unwrap();
+        assert_eq!(adapter.version().as_deref(), Some("echotool-plugin 1.0"));
+
+        let options = ConformanceOptions {
+            usage_output: Some("usage"),
+            reports_cost: true,
+            reports_tokens: true,
+            ..ConformanceOptions::new("echotool")
+        };
+        run_conformance(&adapter, &options, work.path())
+            .unwrap()
+            .assert_passed();
+    }
+
+    #[test]
+    fn records_separate_stderr() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = install(
+            dir.path(),
+            "split",
+            "#!/bin/sh\ncat >/dev/null\necho '{\"output\": \"narration\", \"exit_code\": 1, \"stderr\": \"boom\"}'\n",
+        );
+        let adapter = PluginAdapter::new("split", path);
+        let scenario: Scenario = serde_yaml::from_str(
+            "name: split\ndescription: d\ntemplate_folder: t\ntarget:\n  binary: split\ntask:\n  prompt: p\nevaluation:\n  gates: []\n",
+        )
+        .unwrap();
+        let streams = crate::adapter::StreamCapture::new(false);
+        let options = RunOptions {
+            streams: Some(streams.clone()),
+            ..Default::default()
+        };
+        let (output, exit_code, _, _) = adapter
+            .run(&scenario, dir.path(), None, 10, &options)
+            .unwrap();
+        assert_eq!(output, "narration\nboom");
+        assert_eq!(exit_code, 1);
+        let captured = streams.get().unwrap();
+        assert_eq!(captured.stdout, "narration");
+        assert_eq!(captured.stderr, "boom");
+    }
+
+    #[test]
+    fn unauthenticated_check_fails_with_login_guidance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = install(
+            dir.path(),
+            "locked",
+            "#!/bin/sh\ncat >/dev/null\necho '{\"available\": true, \"authenticated\": false, \"message\": \"run locked login\", \"login\": [\"locked\", \"login\"]}'\n",
+        );
+        let adapter = PluginAdapter::new("locked", path);
+        let guidance = adapter.auth_guidance().unwrap();
+        assert_eq!(
+            guidance.login_command,
+            Some(vec!["locked".to_string(), "login".to_string()])
+        );
+        let err = adapter.check_availability().unwrap_err().to_string();
+        assert!(err.contains("Tool not authenticated: run locked login"));
+        assert!(err.contains("doctor --fix"));
+    }
+
+    #[test]
+    fn reports_invalid_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = install(dir.path(), "broken", "#!/bin/sh\necho not json\n");
+        let adapter = PluginAdapter::new("broken", path);
+        let err = adapter.check_availability().unwrap_err();
+        assert!(err.to_string().contains("invalid check response"));
+    }
+
+    #[test]
+    fn reports_plugin_failures_and_timeouts() {
+        use crate::exec::fake::{FakeExecutor, FakeResponse};
+        let executor = Arc::new(FakeExecutor::new([
+            FakeResponse::exit(0, r#"{"version": "fake 1.0"}"#),
+            FakeResponse::exit(3, "").with_stderr("not logged in"),
+            FakeResponse::slow(Duration::from_secs(QUERY_TIMEOUT_SECS + 1)),
+        ]));
+        let adapter = PluginAdapter::new("fake", PathBuf::from("/no/such/plugin"))
+            .with_executor(executor.clone());
+
+        assert_eq!(adapter.version().as_deref(), Some("fake 1.0"));
+        let err = adapter.check_availability().unwrap_err().to_string();
+        assert!(err.contains("exit status: 3"), "{}", err);
+        assert!(err.contains("not logged in"), "{}", err);
+        let err = adapter.check_availability().unwrap_err().to_string();
+        assert!(err.contains("timed out after 10 seconds"), "{}", err);
+
+        let calls = executor.calls();
+        assert_eq!(calls[0].program, "/no/such/plugin");
+        assert_eq!(calls[0].args, vec!["version"]);
+        let request: serde_json::Value =
+            serde_json::from_str(calls[0].stdin.as_deref().unwrap()).unwrap();
+        assert_eq!(request["protocol"], PROTOCOL_VERSION);
+    }
+}
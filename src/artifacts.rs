@@ -0,0 +1,250 @@
+//! Crash-safe artifact writes.
+//!
+//! Reports, `metrics.json`, `run.json` and the other run artifacts are written
+//! with [`write_atomic`]: the content goes to a temporary sibling file that is
+//! then renamed over the target, so a crash mid-write leaves either the old
+//! file or the new one, never a truncated one. How hard writes are pushed to
+//! disk is set by `[artifacts] fsync` in the config file, or by
+//! `LLM_TOOL_TEST_FSYNC` for CI runners that may be torn down right after a run.
+//!
+//! Large transcripts and event logs can be stored zstd-compressed as
+//! `<name>.zst` ([`compress_if_larger`]); readers go through
+//! [`read_to_string`], which falls back to the compressed copy.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// Environment variable overriding `[artifacts] fsync`.
+pub const FSYNC_ENV: &str = "LLM_TOOL_TEST_FSYNC";
+
+/// How far artifact writes are flushed before they count as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsyncMode {
+    /// Leave flushing to the OS; survives process crashes but not power loss
+    #[default]
+    None,
+    /// fsync each file before renaming it into place and after each append
+    File,
+    /// Also fsync the containing directory so the rename itself is durable
+    Full,
+}
+
+impl FsyncMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" | "0" | "false" => Some(Self::None),
+            "file" => Some(Self::File),
+            "full" | "1" | "true" => Some(Self::Full),
+            _ => None,
+        }
+    }
+}
+
+static FSYNC_MODE: OnceLock<FsyncMode> = OnceLock::new();
+
+/// The fsync mode for this process, from `LLM_TOOL_TEST_FSYNC` or the config.
+pub fn fsync_mode() -> FsyncMode {
+    *FSYNC_MODE.get_or_init(|| match std::env::var(FSYNC_ENV) {
+        Ok(value) => FsyncMode::parse(&value).unwrap_or_else(|| {
+            eprintln!(
+                "Warning: ignoring {}={:?} (expected none, file or full)",
+                FSYNC_ENV, value
+            );
+            Config::load_or_default().artifacts.fsync
+        }),
+        Err(_) => Config::load_or_default().artifacts.fsync,
+    })
+}
+
+/// Write `contents` to `path` atomically, flushing per [`fsync_mode`].
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    write_atomic_with(path, contents.as_ref(), fsync_mode())
+}
+
+/// Temporary sibling of `path`, unique within and across processes.
+fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Write `contents` to `path` through a temporary file and a rename.
+pub fn write_atomic_with(path: &Path, contents: &[u8], mode: FsyncMode) -> std::io::Result<()> {
+    let tmp = temp_path(path);
+    let written = File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents)?;
+        if mode != FsyncMode::None {
+            file.sync_all()?;
+        }
+        Ok(())
+    });
+    if let Err(e) = written.and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    if mode == FsyncMode::Full {
+        sync_dir(path.parent().filter(|p| !p.as_os_str().is_empty()))?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: Option<&Path>) -> std::io::Result<()> {
+    File::open(dir.unwrap_or(Path::new(".")))?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: Option<&Path>) -> std::io::Result<()> {
+    // Directories cannot be opened for syncing here; the rename is as durable as it gets
+    Ok(())
+}
+
+/// Flush a file that was just appended to (`events.jsonl`, `results.jsonl`).
+///
+/// Appends cannot be made atomic by renaming; each record is one line, so a
+/// crash loses at most the line being written.
+pub fn sync_appended(file: &File) -> std::io::Result<()> {
+    if fsync_mode() != FsyncMode::None {
+        file.sync_data()?;
+    }
+    Ok(())
+}
+
+/// Suffix of artifacts stored zstd-compressed.
+pub const COMPRESSED_SUFFIX: &str = ".zst";
+
+/// zstd level for compressed artifacts; transcripts are highly repetitive, so
+/// higher levels buy little over the default.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Where the compressed copy of `path` lives.
+pub fn compressed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(COMPRESSED_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Read a text artifact, decompressing `<path>.zst` if the plain file is gone.
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let compressed = compressed_path(path);
+            if !compressed.exists() {
+                return Err(e);
+            }
+            let bytes = zstd::decode_all(File::open(&compressed)?)?;
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+        result => result,
+    }
+}
+
+/// Replace `path` with `<path>.zst` when it is larger than `threshold` bytes.
+///
+/// The compressed copy is written atomically before the original is removed,
+/// so one of the two always exists. Returns whether the file was compressed.
+pub fn compress_if_larger(path: &Path, threshold: u64) -> io::Result<bool> {
+    let size = match fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    if size <= threshold {
+        return Ok(false);
+    }
+    let compressed = zstd::encode_all(File::open(path)?, COMPRESSION_LEVEL)?;
+    write_atomic(&compressed_path(path), compressed)?;
+    fs::remove_file(path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn replaces_files_without_leaving_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.json");
+        for mode in [FsyncMode::None, FsyncMode::File, FsyncMode::Full] {
+            write_atomic_with(&path, format!("{:?}", mode).as_bytes(), mode).unwrap();
+            assert_eq!(fs::read_to_string(&path).unwrap(), format!("{:?}", mode));
+        }
+        assert_eq!(entries(dir.path()), vec!["metrics.json"]);
+    }
+
+    #[test]
+    fn failed_rename_removes_the_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // Renaming a file over a non-empty directory fails after the temp file is written
+        let target = dir.path().join("report.md");
+        fs::create_dir_all(target.join("child")).unwrap();
+
+        assert!(write_atomic_with(&target, b"new", FsyncMode::File).is_err());
+        assert!(target.join("child").is_dir());
+        assert_eq!(entries(dir.path()), vec!["report.md"]);
+    }
+
+    #[test]
+    fn compresses_only_above_the_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("events.jsonl");
+        let large = dir.path().join("transcript.raw.txt");
+        let transcript = "$ qipu list\nok\n".repeat(1000);
+        fs::write(&small, "{}\n").unwrap();
+        fs::write(&large, &transcript).unwrap();
+
+        assert!(!compress_if_larger(&small, 1024).unwrap());
+        assert!(compress_if_larger(&large, 1024).unwrap());
+        assert!(!compress_if_larger(&dir.path().join("missing.txt"), 0).unwrap());
+
+        assert_eq!(
+            entries(dir.path()),
+            vec!["events.jsonl", "transcript.raw.txt.zst"]
+        );
+        let stored = fs::metadata(compressed_path(&large)).unwrap().len();
+        assert!(stored < transcript.len() as u64 / 10);
+        assert_eq!(read_to_string(&large).unwrap(), transcript);
+        assert_eq!(read_to_string(&small).unwrap(), "{}\n");
+    }
+
+    #[test]
+    fn reading_a_missing_artifact_reports_the_plain_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = read_to_string(&dir.path().join("transcript.raw.txt")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn parses_fsync_modes() {
+        assert_eq!(FsyncMode::parse("FULL"), Some(FsyncMode::Full));
+        assert_eq!(FsyncMode::parse("file"), Some(FsyncMode::File));
+        assert_eq!(FsyncMode::parse("0"), Some(FsyncMode::None));
+        assert_eq!(FsyncMode::parse("sometimes"), None);
+        let config: Config = toml::from_str("[artifacts]\nfsync = \"full\"\n").unwrap();
+        assert_eq!(config.artifacts.fsync, FsyncMode::Full);
+    }
+}
@@ -1,4 +1,14 @@
-use clap::{Args, Parser, Subcommand};
+use crate::output::ToolModelConfig;
+use crate::results::costs::CostGroupBy;
+use crate::results::junit::RunReport;
+use crate::results::report::BadgeMetric;
+use crate::results::retention::KeepFixture;
+use crate::run::fault::FaultPhase;
+use crate::scaffold::ScaffoldTemplate;
+use crate::scenario::taxonomy::TaxonomyFilter;
+use crate::schema::SchemaKind;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -19,13 +29,22 @@ pub struct ToolModelArgs {
     pub model: Option<String>,
 }
 
+/// Output format for commands with machine-readable output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+// Parsed once per process; boxing the `Run` arguments would only obscure the match in main
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Run a test scenario
     Run {
-        /// Path to scenario file or name
+        /// Path, name or glob of scenario files (repeatable, e.g. 'fixtures/qipu/*.yaml')
         #[arg(long, short)]
-        scenario: Option<String>,
+        scenario: Vec<String>,
 
         /// Run all scenarios in fixtures directory
         #[arg(long)]
@@ -39,6 +58,21 @@ pub enum Commands {
         #[arg(long, default_value = "0")]
         tier: usize,
 
+        #[command(flatten)]
+        taxonomy: TaxonomyFilter,
+
+        /// Skip scenarios with any of these tags (repeatable)
+        #[arg(long)]
+        exclude_tags: Vec<String>,
+
+        /// Skip scenarios whose name, id, or path matches this glob (repeatable)
+        #[arg(long)]
+        exclude_scenario: Vec<String>,
+
+        /// Skip scenarios of this tier (repeatable)
+        #[arg(long)]
+        exclude_tier: Vec<usize>,
+
         /// Tool to test (e.g., claude-code, opencode)
         #[arg(long)]
         tool: Option<String>,
@@ -51,10 +85,23 @@ pub enum Commands {
         #[arg(long)]
         profile: Option<String>,
 
+        /// Replace every scenario's tool matrix, e.g. "opencode:gpt-4o,claude-code:sonnet"
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["tool", "model", "profile"])]
+        matrix: Vec<ToolModelConfig>,
+
+        /// Add tool:model cells to every scenario's tool matrix
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["matrix", "profile"])]
+        extend_matrix: Vec<ToolModelConfig>,
+
         /// Dry run (don't execute LLM calls)
         #[arg(long)]
         dry_run: bool,
 
+        /// With --dry-run, set up the fixture and run setup commands, then
+        /// leave the environment in place for inspection
+        #[arg(long, requires = "dry_run")]
+        materialize: bool,
+
         /// Disable caching
         #[arg(long)]
         no_cache: bool,
@@ -67,9 +114,38 @@ pub enum Commands {
         #[arg(long)]
         no_judge: bool,
 
-        /// Maximum execution time in seconds per command
-        #[arg(long, default_value = "300")]
-        timeout_secs: u64,
+        /// Call the judge even when a response for the same rubric,
+        /// transcript and judge model is cached
+        #[arg(long)]
+        no_judge_cache: bool,
+
+        /// Maximum run time in seconds, overriding scenario, suite and config timeouts
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+
+        /// Label to attach to recorded results (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+
+        /// Write a report when the run finishes, as <format>=<path> (formats: junit; repeatable)
+        #[arg(long = "report", value_name = "FORMAT=PATH")]
+        reports: Vec<RunReport>,
+
+        /// Run up to N scenario × tool/model runs concurrently
+        #[arg(long, short = 'j', default_value = "1", value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: u16,
+
+        /// Run each scenario × tool/model cell N times and report its pass rate and score variance
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u16).range(1..))]
+        repeats: u16,
+
+        /// Which runs keep their fixture directory [default: from config, else on-failure]
+        #[arg(long, value_enum)]
+        keep_fixture: Option<KeepFixture>,
+
+        /// Force a failure in one pipeline phase (for testing error handling)
+        #[arg(long, value_enum, hide = true)]
+        inject_fault: Option<FaultPhase>,
     },
     /// List available scenarios
     Scenarios {
@@ -80,6 +156,17 @@ pub enum Commands {
         /// Filter scenarios by tier (0=smoke, 1=quick, 2=standard, 3=comprehensive)
         #[arg(long, default_value = "0")]
         tier: usize,
+
+        #[command(flatten)]
+        taxonomy: TaxonomyFilter,
+
+        /// Append each scenario's recent pass/fail history and mean score
+        #[arg(long)]
+        with_history: bool,
+
+        /// Output format (JSON includes calibrated difficulty estimates)
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
     },
     /// Show details of a scenario
     Show {
@@ -93,4 +180,303 @@ pub enum Commands {
         #[arg(long)]
         older_than: Option<String>,
     },
+    /// Report which target tool subcommands are exercised by scenarios
+    Coverage {
+        /// Target binary (defaults to the binary shared by all scenarios)
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// File listing subcommands, one per line (instead of parsing `<binary> --help`)
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Validate scenario files and report diagnostics
+    Validate {
+        /// Scenario paths or names (defaults to all scenarios in fixtures directory)
+        paths: Vec<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Create a scenario YAML, its template fixture folder and optionally a rubric stub
+    New {
+        /// Scenario name, also used for the template folder and rubric file
+        name: String,
+
+        /// Starting point for the scenario
+        #[arg(long, value_enum, default_value = "basic")]
+        template: ScaffoldTemplate,
+
+        /// Binary of the tool under test
+        #[arg(long, default_value = "mytool")]
+        binary: String,
+
+        /// Fixtures directory to write into (defaults to the configured one)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+    /// Score externally produced artifacts with a scenario's gates, judge and evaluators
+    Score {
+        /// Scenario whose evaluation is applied (path or name)
+        #[arg(long)]
+        scenario: String,
+
+        /// Directory holding the final state the agent left behind
+        #[arg(long)]
+        fixture: PathBuf,
+
+        /// Transcript of the agent session (plain text)
+        #[arg(long)]
+        transcript: PathBuf,
+
+        /// Tool name passed to script gates and evaluators
+        #[arg(long, default_value = "external")]
+        tool: String,
+
+        /// Model name passed to script gates and evaluators
+        #[arg(long, default_value = "default")]
+        model: String,
+
+        /// Skip the LLM-as-judge evaluation
+        #[arg(long)]
+        no_judge: bool,
+
+        /// Call the judge even when a response for the same rubric,
+        /// transcript and judge model is cached
+        #[arg(long)]
+        no_judge_cache: bool,
+
+        /// Also write the outcome and metrics as JSON to this file
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    /// Check that agent tools are installed and authenticated
+    Doctor {
+        /// Tools to check (default: aider, claude-code, opencode, openai and installed plugins)
+        #[arg(long, value_delimiter = ',')]
+        tool: Vec<String>,
+
+        /// Launch the interactive login flow of installed tools that are not authenticated
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Summarize recorded results per scenario and per tool/model suite
+    Stats {
+        /// Flag runs whose duration, command count or cost deviate from their history
+        #[arg(long)]
+        anomalies: bool,
+
+        /// Standard deviations from the historical mean that count as anomalous
+        #[arg(long, default_value = "3.0")]
+        sigma: f64,
+
+        /// Only include results newer than this (e.g., "7d", "12h")
+        #[arg(long)]
+        since: Option<String>,
+
+        #[command(flatten)]
+        taxonomy: TaxonomyFilter,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Summarize spend (agent + judge cost) from recorded results
+    Costs {
+        /// Only include results newer than this (e.g., "7d", "12h")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Group spend by this dimension
+        #[arg(long, value_enum, default_value = "tool")]
+        group_by: CostGroupBy,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Compare the latest results of two tools or models scenario by scenario
+    Compare {
+        /// Reference side, as tool[:model]
+        #[arg(long, value_name = "TOOL[:MODEL]")]
+        baseline: ToolModelConfig,
+
+        /// Side judged against the baseline, as tool[:model]
+        #[arg(long, value_name = "TOOL[:MODEL]")]
+        candidate: ToolModelConfig,
+
+        /// Only include results newer than this (e.g., "7d", "12h")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Show a refreshing overview of the latest suite results
+    Dashboard {
+        /// Seconds between refreshes
+        #[arg(long, default_value = "10")]
+        interval: u64,
+
+        /// Render once and exit
+        #[arg(long)]
+        once: bool,
+    },
+    /// Generate suite-level reports from recorded results
+    Report {
+        /// Write an SVG badge for the latest suite to this path
+        #[arg(long)]
+        badge: Option<PathBuf>,
+
+        /// Value shown on the badge
+        #[arg(long, value_enum, default_value = "pass-rate")]
+        metric: BadgeMetric,
+
+        /// Only include results for this tool
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Only include results for this model
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Badge label (defaults to the metric name, prefixed with the tool)
+        #[arg(long)]
+        badge_label: Option<String>,
+
+        /// Write a Markdown summary of the latest suite to this path
+        #[arg(long)]
+        summary: Option<PathBuf>,
+
+        /// Write a skill x tool/model capability matrix to this path (`.json` for JSON, otherwise Markdown)
+        #[arg(long)]
+        capabilities: Option<PathBuf>,
+
+        /// Write a static HTML site (index plus one page per run) to this directory
+        #[arg(long, value_name = "DIR")]
+        html: Option<PathBuf>,
+
+        /// Only include results carrying this label (e.g. one matrix run)
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Only include results newer than this (e.g., "7d", "12h")
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Manage recorded results
+    Results {
+        #[command(subcommand)]
+        command: ResultsCommand,
+    },
+    /// Create and open encrypted scenario bundles for private benchmarks
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommand,
+    },
+    /// Print JSON Schemas for scenario, result and event formats
+    Schema {
+        /// Schema to print
+        #[arg(value_enum)]
+        kind: Option<SchemaKind>,
+
+        /// Write schema files to this directory instead of printing
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ResultsCommand {
+    /// Move historical results from one scenario id to another (e.g. after a rename)
+    Relink {
+        /// Scenario id currently recorded in results
+        #[arg(long)]
+        from: String,
+
+        /// Scenario id to record instead
+        #[arg(long)]
+        to: String,
+    },
+    /// Remove or archive run directories not referenced by any result record
+    Gc {
+        /// Move orphaned directories here instead of deleting them
+        #[arg(long)]
+        archive: Option<PathBuf>,
+
+        /// Only collect directories older than this (protects runs in progress)
+        #[arg(long, default_value = "1h")]
+        older_than: String,
+
+        /// List what would be collected without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Estimate scenario difficulty from pass rates across tools/models and store it
+    Calibrate {
+        /// Only include results newer than this (e.g., "7d", "12h")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Export result records as JSONL, or into a DuckDB database
+    Export {
+        /// Strip scenario names, messages, paths and --redact strings, keeping metrics
+        #[arg(long)]
+        anonymize: bool,
+
+        /// String to remove from exported fields, case-insensitive (repeatable; implies --anonymize)
+        #[arg(long)]
+        redact: Vec<String>,
+
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+
+        /// Write runs, gates and efficiency tables into this DuckDB database (needs the duckdb CLI)
+        #[arg(long, conflicts_with = "out")]
+        duckdb: Option<PathBuf>,
+
+        /// Only include results newer than this (e.g., "7d", "12h")
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BundleCommand {
+    /// Print a new random key for LLM_TOOL_TEST_SCENARIO_KEY
+    Keygen,
+    /// Encrypt scenario files to `<file>.enc` with the key from LLM_TOOL_TEST_SCENARIO_KEY
+    Encrypt {
+        /// Scenario YAML files to encrypt
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Delete the plaintext files after encrypting
+        #[arg(long)]
+        remove_plaintext: bool,
+    },
+    /// Decrypt a bundle with the key from LLM_TOOL_TEST_SCENARIO_KEY
+    Decrypt {
+        /// Encrypted scenario file (`*.yaml.enc`)
+        path: PathBuf,
+
+        /// Write the plaintext here instead of printing it
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
 }
@@ -1,29 +1,105 @@
+use crate::cli::OutputFormat;
 use crate::config::Config;
 use crate::evaluation::ScoreTier;
 use crate::output;
+use crate::results::compare::{compare_runs, render_comparison};
+use crate::results::costs::{summarize_costs, CostGroupBy};
+use crate::results::junit::{JunitReport, RunReport};
+use crate::results::retention::KeepFixture;
 use crate::results::{Cache, ResultsDB};
 use crate::run;
+use crate::run::fault::FaultPhase;
 use crate::scenario::load;
+use crate::scenario::taxonomy::TaxonomyFilter;
 use crate::utils::resolve_fixtures_path;
 use chrono::{Duration, Utc};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 pub struct ScenarioSelection {
-    pub scenario: Option<String>,
+    /// `--scenario` values: paths, names or globs
+    pub scenarios: Vec<String>,
     pub all: bool,
     pub tags: Vec<String>,
     pub tier: usize,
+    pub taxonomy: TaxonomyFilter,
+    pub exclude_tags: Vec<String>,
+    /// `--exclude-scenario` globs, matched against name, id, and path
+    pub exclude_scenarios: Vec<String>,
+    pub exclude_tiers: Vec<usize>,
+}
+
+impl ScenarioSelection {
+    /// Drop the scenarios matched by any `--exclude-*` filter, reporting how many were skipped.
+    fn apply_exclusions(
+        &self,
+        scenarios: Vec<(String, PathBuf)>,
+    ) -> anyhow::Result<Vec<(String, PathBuf)>> {
+        let patterns = self
+            .exclude_scenarios
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .map_err(|e| anyhow::anyhow!("Invalid --exclude-scenario glob '{}': {}", p, e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if self.exclude_tags.is_empty() && patterns.is_empty() && self.exclude_tiers.is_empty() {
+            return Ok(scenarios);
+        }
+
+        let fixtures_dir = resolve_fixtures_path("");
+        let total = scenarios.len();
+        let mut kept = Vec::new();
+        for (name, path) in scenarios {
+            let s = load(&path)?;
+            let relative = path.strip_prefix(&fixtures_dir).unwrap_or(&path);
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let excluded = s.tags.iter().any(|tag| self.exclude_tags.contains(tag))
+                || self.exclude_tiers.contains(&s.tier)
+                || patterns.iter().any(|pattern| {
+                    pattern.matches(&s.name)
+                        || pattern.matches(s.stable_id())
+                        || pattern.matches(&stem)
+                        || pattern.matches_path(&path)
+                        || pattern.matches_path(relative)
+                });
+            if !excluded {
+                kept.push((name, path));
+            }
+        }
+        if kept.len() < total {
+            println!("Excluded {} of {} scenarios", total - kept.len(), total);
+        }
+        Ok(kept)
+    }
 }
 
 pub struct ExecutionConfig {
     pub tool: Option<String>,
     pub model: Option<String>,
     pub profile: Option<String>,
+    /// `--matrix` cells replacing each scenario's tool matrix
+    pub matrix: Vec<output::ToolModelConfig>,
+    /// `--extend-matrix` cells added to each scenario's tool matrix
+    pub extend_matrix: Vec<output::ToolModelConfig>,
     pub dry_run: bool,
+    pub materialize: bool,
     pub no_cache: bool,
-    pub timeout_secs: u64,
+    pub timeout_secs: Option<u64>,
     pub judge_model: Option<String>,
     pub no_judge: bool,
+    /// `--no-judge-cache`: call the judge even on a cached response
+    pub no_judge_cache: bool,
+    pub labels: Vec<String>,
+    pub inject_fault: Option<FaultPhase>,
+    /// Runs executed concurrently (1 = serial)
+    pub jobs: usize,
+    /// Runs of each scenario × tool/model cell (`--repeats`, 1 = single run)
+    pub repeats: u32,
+    /// `--keep-fixture`, overriding `[retention] keep_fixture`
+    pub keep_fixture: Option<KeepFixture>,
+    /// Reports written once all runs finished (`--report`)
+    pub reports: Vec<RunReport>,
 }
 
 pub struct ExecutionContext<'a> {
@@ -40,12 +116,55 @@ fn resolve_scenario_path(path: &str) -> PathBuf {
     } else {
         let fixtures_dir = resolve_fixtures_path("");
         let fixture_path = fixtures_dir.join(path);
+        let yaml_path = fixtures_dir.join(format!("{}.yaml", path));
+        let bundle_path = crate::scenario::bundle::bundle_path(&yaml_path);
         if fixture_path.exists() {
             fixture_path
+        } else if !yaml_path.exists() && bundle_path.exists() {
+            bundle_path
         } else {
-            fixtures_dir.join(format!("{}.yaml", path))
+            yaml_path
+        }
+    }
+}
+
+/// Expand `--scenario` values into scenario files, in order and without duplicates.
+///
+/// Values with glob characters are matched against the working directory,
+/// then against the fixtures directory; other values resolve like a single
+/// `--scenario` path or name.
+fn resolve_scenario_patterns(patterns: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        if !pattern.contains(['*', '?', '[']) {
+            paths.push(resolve_scenario_path(pattern));
+            continue;
+        }
+        let fixtures_pattern = resolve_fixtures_path("").join(pattern);
+        let mut matched = glob_scenarios(pattern)?;
+        if matched.is_empty() && Path::new(pattern).is_relative() {
+            matched = glob_scenarios(&fixtures_pattern.to_string_lossy())?;
+        }
+        if matched.is_empty() {
+            anyhow::bail!("No scenario files match '{}'", pattern);
         }
+        paths.extend(matched);
     }
+
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())));
+    Ok(paths)
+}
+
+/// Scenario files matching a glob, sorted.
+fn glob_scenarios(pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let mut matched: Vec<PathBuf> = glob::glob(pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid scenario glob '{}': {}", pattern, e))?
+        .flatten()
+        .filter(|path| path.is_file() && crate::scenario::is_scenario_path(path))
+        .collect();
+    matched.sort();
+    Ok(matched)
 }
 
 fn find_scenarios(dir: &Path, scenarios: &mut Vec<(String, PathBuf)>) {
@@ -53,11 +172,9 @@ fn find_scenarios(dir: &Path, scenarios: &mut Vec<(String, PathBuf)>) {
         for entry in entries.flatten() {
             let path = entry.path();
             if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext == "yaml" {
-                        if let Ok(s) = load(&path) {
-                            scenarios.push((s.name, path));
-                        }
+                if crate::scenario::is_scenario_path(&path) {
+                    if let Ok(s) = load(&path) {
+                        scenarios.push((s.name, path));
                     }
                 }
             } else if path.is_dir() {
@@ -67,6 +184,19 @@ fn find_scenarios(dir: &Path, scenarios: &mut Vec<(String, PathBuf)>) {
     }
 }
 
+/// Load every scenario in the fixtures directory, skipping files that fail to parse.
+fn load_fixture_scenarios() -> Vec<crate::scenario::Scenario> {
+    let mut scenarios = Vec::new();
+    let fixtures_dir = resolve_fixtures_path("");
+    if fixtures_dir.exists() {
+        find_scenarios(&fixtures_dir, &mut scenarios);
+    }
+    scenarios
+        .iter()
+        .filter_map(|(_, path)| load(path).ok())
+        .collect()
+}
+
 pub fn handle_run_command(
     selection: &ScenarioSelection,
     exec_config: &ExecutionConfig,
@@ -102,113 +232,513 @@ pub fn handle_run_command(
 
             let tier_match = s.tier <= selection.tier;
 
-            if tags_match && tier_match {
+            if tags_match && tier_match && selection.taxonomy.matches(&s) {
                 filtered_scenarios.push((name, path));
             }
         }
         filtered_scenarios
-    } else if let Some(path) = &selection.scenario {
-        let resolved_path = resolve_scenario_path(path);
-        let s = load(&resolved_path)?;
-        vec![(s.name, resolved_path)]
+    } else if !selection.scenarios.is_empty() {
+        let mut scenarios = Vec::new();
+        for path in resolve_scenario_patterns(&selection.scenarios)? {
+            let s = load(&path)?;
+            scenarios.push((s.name, path));
+        }
+        if scenarios.len() > 1 {
+            println!("Running {} scenarios", scenarios.len());
+        }
+        scenarios
     } else {
         println!("No scenario specified. Use --scenario <path> or --all");
         return Ok(());
     };
+    let scenarios_to_run = selection.apply_exclusions(scenarios_to_run)?;
 
-    for (name, path) in scenarios_to_run {
-        let s = load(&path)?;
-        println!("Loaded scenario: {}", name);
+    let limiter = run::rate_limit::RateLimiter::new(config.rate_limits.clone());
+    let retry = &config.retry;
 
-        let matrix = crate::build_tool_matrix(
-            &exec_config.tool,
-            &exec_config.model,
-            &exec_config.profile,
+    let mut junit = exec_config
+        .reports
+        .iter()
+        .any(|r| matches!(r, RunReport::Junit(_)))
+        .then(JunitReport::new);
+
+    let mut skipped = Vec::new();
+    if exec_config.jobs > 1 {
+        run_scenarios_parallel(
+            scenarios_to_run,
+            exec_config,
+            ctx,
             config,
-            &s.tool_matrix,
+            &limiter,
+            junit.as_mut(),
+            &mut skipped,
         )?;
+        output::print_unavailable_summary(&skipped);
+        return write_run_reports(&exec_config.reports, junit.as_ref());
+    }
 
-        if matrix.len() > 1 {
-            println!("Matrix run: {} tool×model combinations", matrix.len());
+    for (name, path) in scenarios_to_run {
+        let s = load(&path)?;
+        println!("Loaded scenario: {}", name);
+        if s.id.is_none() {
+            eprintln!(
+                "Warning: scenario '{}' has no stable `id`; renaming it will orphan its result history",
+                name
+            );
         }
 
+        let matrix = effective_matrix(&s, exec_config, config)?;
+        let runs = repeated_runs(&matrix, exec_config.repeats);
+
+        let estimator = run::progress::DurationEstimator::load(ctx.results_db);
+        let estimates: Vec<Option<f64>> = runs
+            .iter()
+            .map(|(c, _)| estimator.estimate(s.stable_id(), &c.tool, &c.model))
+            .collect();
         let mut results = Vec::new();
 
-        for config in &matrix {
-            println!("\n=== Running: {} / {} ===", config.tool, config.model);
+        for (i, &(config, repeat_index)) in runs.iter().enumerate() {
+            match repeat_index {
+                Some(index) => println!(
+                    "\n=== Running: {} / {} (repeat {}/{}) ===",
+                    config.tool, config.model, index, exec_config.repeats
+                ),
+                None => println!("\n=== Running: {} / {} ===", config.tool, config.model),
+            }
+            if runs.len() > 1 {
+                if let Some(eta) = run::progress::total_estimate(&estimates[i..]) {
+                    println!(
+                        "Matrix ETA: ~{} for {} remaining run(s)",
+                        run::progress::format_duration(eta),
+                        runs.len() - i
+                    );
+                }
+            }
 
             let result = run::run_single_scenario(
                 &s,
                 &path,
-                &config.tool,
-                &config.model,
+                config,
                 exec_config.dry_run,
+                exec_config.materialize,
                 exec_config.no_cache,
                 exec_config.timeout_secs,
                 exec_config.no_judge,
+                exec_config.no_judge_cache,
                 ctx.base_dir,
                 ctx.results_db,
                 ctx.cache,
+                &exec_config.labels,
+                &limiter,
+                retry,
+                exec_config.inject_fault,
+                matrix.len() > 1,
+                repeat_index,
             );
 
             results.push((config.clone(), result));
         }
 
-        if matrix.len() > 1 {
-            output::print_matrix_summary(&results);
-        }
+        finish_scenario(
+            &s,
+            &results,
+            exec_config,
+            ctx,
+            config,
+            junit.as_mut(),
+            &mut skipped,
+        );
     }
 
+    output::print_unavailable_summary(&skipped);
+    write_run_reports(&exec_config.reports, junit.as_ref())
+}
+
+fn write_run_reports(reports: &[RunReport], junit: Option<&JunitReport>) -> anyhow::Result<()> {
+    for report in reports {
+        match (report, junit) {
+            (RunReport::Junit(path), Some(junit)) => {
+                junit.write(path)?;
+                println!("JUnit report written to {}", path.display());
+            }
+            (RunReport::Junit(_), None) => {}
+        }
+    }
     Ok(())
 }
 
-pub fn handle_list_command(
-    tags: &[String],
-    tier: &usize,
-    _results_db: &ResultsDB,
-) -> anyhow::Result<()> {
-    let mut scenarios = Vec::new();
+/// Each matrix cell `repeats` times in a row, with its 1-based repeat index
+/// when repeating.
+fn repeated_runs(
+    matrix: &[output::ToolModelConfig],
+    repeats: u32,
+) -> Vec<(&output::ToolModelConfig, Option<u32>)> {
+    matrix
+        .iter()
+        .flat_map(|cell| {
+            (1..=repeats.max(1)).map(move |index| (cell, (repeats > 1).then_some(index)))
+        })
+        .collect()
+}
 
-    fn find_scenarios(
-        dir: &std::path::Path,
-        scenarios: &mut Vec<(PathBuf, String, usize, String, Vec<String>)>,
-    ) {
-        if let Ok(entries) = std::fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(ext) = path.extension() {
-                        if ext == "yaml" {
-                            if let Ok(s) = load(&path) {
-                                scenarios.push((path, s.name, s.tier, s.description, s.tags));
-                            }
-                        }
-                    }
-                } else if path.is_dir() {
-                    find_scenarios(&path, scenarios);
+/// The scenario's tool matrix with `--matrix`/`--extend-matrix` applied,
+/// logged when it has several cells or came from the command line.
+fn effective_matrix(
+    s: &crate::scenario::Scenario,
+    exec_config: &ExecutionConfig,
+    config: &Config,
+) -> anyhow::Result<Vec<output::ToolModelConfig>> {
+    let (matrix, source) = if !exec_config.matrix.is_empty() {
+        let mut matrix = Vec::new();
+        for cell in &exec_config.matrix {
+            if !matrix.contains(cell) {
+                matrix.push(cell.clone());
+            }
+        }
+        (matrix, "--matrix")
+    } else {
+        let mut matrix = crate::build_tool_matrix(
+            &exec_config.tool,
+            &exec_config.model,
+            &exec_config.profile,
+            config,
+            &s.tool_matrix,
+        )?;
+        let source = if exec_config.profile.is_some() {
+            "profile"
+        } else if s.tool_matrix.is_some() {
+            "scenario tool_matrix"
+        } else if exec_config.tool.is_none()
+            && exec_config.model.is_none()
+            && !config.default_matrix.is_empty()
+        {
+            "config default_matrix"
+        } else {
+            "--tool/--model"
+        };
+        if exec_config.extend_matrix.is_empty() {
+            (matrix, source)
+        } else {
+            for cell in &exec_config.extend_matrix {
+                if !matrix.contains(cell) {
+                    matrix.push(cell.clone());
                 }
             }
+            (matrix, "extended with --extend-matrix")
+        }
+    };
+
+    let from_cli = !exec_config.matrix.is_empty() || !exec_config.extend_matrix.is_empty();
+    if from_cli {
+        for cell in exec_config.matrix.iter().chain(&exec_config.extend_matrix) {
+            if cell.model != "default" {
+                crate::validate_cli_model(config, &cell.tool, &cell.model)?;
+            }
         }
     }
+    if matrix.len() > 1 || from_cli {
+        let cells: Vec<String> = matrix.iter().map(|c| c.to_string()).collect();
+        println!(
+            "Matrix run: {} tool×model combinations ({}): {}",
+            matrix.len(),
+            source,
+            cells.join(", ")
+        );
+    }
+    Ok(matrix)
+}
 
-    let fixtures_dir = resolve_fixtures_path("");
-    if fixtures_dir.exists() {
-        find_scenarios(&fixtures_dir, &mut scenarios);
+/// Report a scenario's matrix results, tear down fixtures and apply the
+/// retention policy.
+///
+/// Cells skipped for an unavailable tool are collected in `skipped` for the
+/// end-of-run summary.
+#[allow(clippy::too_many_arguments)]
+fn finish_scenario(
+    s: &crate::scenario::Scenario,
+    results: &[(
+        output::ToolModelConfig,
+        anyhow::Result<crate::results::ResultRecord>,
+    )],
+    exec_config: &ExecutionConfig,
+    ctx: &ExecutionContext,
+    config: &Config,
+    junit: Option<&mut JunitReport>,
+    skipped: &mut Vec<crate::results::ResultRecord>,
+) {
+    skipped.extend(
+        results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .filter(|r| r.outcome == crate::results::Outcome::SkippedUnavailable)
+            .cloned(),
+    );
+    if let Some(junit) = junit {
+        for (cell, result) in results {
+            junit.add_run(s.stable_id(), &cell.tool, &cell.model, result);
+        }
+    }
+
+    if exec_config.repeats > 1 {
+        output::print_repeat_summary(results);
+    } else if results.len() > 1 {
+        output::print_matrix_summary(results);
+    } else if let Some((_, Err(e))) = results.first() {
+        eprintln!("Error: {:#}", e);
     }
 
-    let filtered_scenarios: Vec<_> = scenarios
+    if !exec_config.dry_run {
+        let keep = exec_config
+            .keep_fixture
+            .unwrap_or(config.retention.keep_fixture);
+        tear_down_fixtures(ctx, results, keep);
+        apply_retention_policy(ctx, config, s.stable_id());
+    }
+}
+
+/// Run every scenario × tool/model combination on `exec_config.jobs` threads.
+///
+/// Each run has its own fixture and results directory; records go through
+/// the shared `ResultsDB` and cache, and launches through the shared rate
+/// limiter. Runs print as they progress, prefixed start/finish lines keep
+/// them apart, and the per-scenario summaries follow once all have finished.
+fn run_scenarios_parallel(
+    scenarios: Vec<(String, PathBuf)>,
+    exec_config: &ExecutionConfig,
+    ctx: &ExecutionContext,
+    config: &Config,
+    limiter: &run::rate_limit::RateLimiter,
+    mut junit: Option<&mut JunitReport>,
+    skipped: &mut Vec<crate::results::ResultRecord>,
+) -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    // Load everything up front so a broken scenario fails before any run starts
+    let mut loaded = Vec::new();
+    for (name, path) in scenarios {
+        let s = load(&path)?;
+        if s.id.is_none() {
+            eprintln!(
+                "Warning: scenario '{}' has no stable `id`; renaming it will orphan its result history",
+                name
+            );
+        }
+        let matrix = effective_matrix(&s, exec_config, config)?;
+        loaded.push((s, path, matrix));
+    }
+    let cells: Vec<(usize, &output::ToolModelConfig, Option<u32>)> = loaded
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (_, _, matrix))| {
+            repeated_runs(matrix, exec_config.repeats)
+                .into_iter()
+                .map(move |(cell, repeat_index)| (i, cell, repeat_index))
+        })
+        .collect();
+    let jobs = exec_config.jobs.min(cells.len()).max(1);
+    println!("Running {} run(s) on {} parallel jobs", cells.len(), jobs);
+
+    let next = AtomicUsize::new(0);
+    let finished: Mutex<Vec<Option<anyhow::Result<crate::results::ResultRecord>>>> =
+        Mutex::new(cells.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(&(scenario_index, cell, repeat_index)) = cells.get(index) else {
+                    break;
+                };
+                let (s, path, _) = &loaded[scenario_index];
+                let mut label = format!(
+                    "[{}/{}] {} / {} / {}",
+                    index + 1,
+                    cells.len(),
+                    s.name,
+                    cell.tool,
+                    cell.model
+                );
+                if let Some(repeat) = repeat_index {
+                    label.push_str(&format!(" (repeat {}/{})", repeat, exec_config.repeats));
+                }
+                println!("\n=== Starting {} ===", label);
+                let result = run::run_single_scenario(
+                    s,
+                    path,
+                    cell,
+                    exec_config.dry_run,
+                    exec_config.materialize,
+                    exec_config.no_cache,
+                    exec_config.timeout_secs,
+                    exec_config.no_judge,
+                    exec_config.no_judge_cache,
+                    ctx.base_dir,
+                    ctx.results_db,
+                    ctx.cache,
+                    &exec_config.labels,
+                    limiter,
+                    &config.retry,
+                    exec_config.inject_fault,
+                    loaded[scenario_index].2.len() > 1,
+                    repeat_index,
+                );
+                match &result {
+                    Ok(record) => {
+                        println!("=== Finished {}: {} ===", label, record.outcome_summary())
+                    }
+                    Err(e) => println!("=== Finished {}: Error: {} ===", label, e),
+                }
+                finished.lock().unwrap_or_else(|p| p.into_inner())[index] = Some(result);
+            });
+        }
+    });
+
+    let mut finished = finished
+        .into_inner()
+        .unwrap_or_else(|p| p.into_inner())
+        .into_iter();
+    for (s, _, matrix) in &loaded {
+        let results: Vec<_> = repeated_runs(matrix, exec_config.repeats)
+            .into_iter()
+            .map(|(cell, _)| {
+                let result = finished
+                    .next()
+                    .flatten()
+                    .unwrap_or_else(|| Err(anyhow::anyhow!("run did not complete")));
+                (cell.clone(), result)
+            })
+            .collect();
+        println!("\n=== Results: {} ===", s.name);
+        finish_scenario(
+            s,
+            &results,
+            exec_config,
+            ctx,
+            config,
+            junit.as_deref_mut(),
+            skipped,
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove the fixtures of this matrix run's runs that `keep` does not retain.
+///
+/// Failures are reported but do not fail the run that triggered them.
+fn tear_down_fixtures(
+    ctx: &ExecutionContext,
+    results: &[(
+        output::ToolModelConfig,
+        anyhow::Result<crate::results::ResultRecord>,
+    )],
+    keep: KeepFixture,
+) {
+    use crate::results::gc::format_bytes;
+
+    let records: Vec<_> = results
         .iter()
-        .filter(|(_, _, scenario_tier, _, scenario_tags)| {
-            let tier_match = scenario_tier <= tier;
+        .filter_map(|(_, result)| result.as_ref().ok())
+        .cloned()
+        .collect();
+    match crate::results::retention::tear_down_fixtures(ctx.base_dir, &records, keep) {
+        Ok(summary) if !summary.pruned.is_empty() => println!(
+            "Fixture cleanup: removed {} fixture(s), reclaimed {}",
+            summary.pruned.len(),
+            format_bytes(summary.reclaimed)
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: failed to remove fixtures: {:#}", e),
+    }
+}
+
+/// Prune old run artifacts of `scenario_id` per `[retention]`.
+///
+/// Failures are reported but do not fail the run that triggered them.
+fn apply_retention_policy(ctx: &ExecutionContext, config: &Config, scenario_id: &str) {
+    use crate::results::gc::format_bytes;
+    use crate::results::retention::apply_retention;
+
+    if config.retention.keep_last.is_none() {
+        return;
+    }
+    let applied = ctx.results_db.load_all().and_then(|mut records| {
+        records.retain(|r| r.scenario_id == scenario_id);
+        apply_retention(ctx.base_dir, &records, &config.retention)
+    });
+    match applied {
+        Ok(summary) if !summary.pruned.is_empty() => println!(
+            "Retention: pruned artifacts of {} older run(s), reclaimed {}",
+            summary.pruned.len(),
+            format_bytes(summary.reclaimed)
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: failed to apply retention policy: {:#}", e),
+    }
+}
+
+pub fn handle_list_command(
+    tags: &[String],
+    tier: &usize,
+    taxonomy: &TaxonomyFilter,
+    with_history: bool,
+    format: OutputFormat,
+    results_db: &ResultsDB,
+) -> anyhow::Result<()> {
+    use crate::results::stats::{scenario_histories, HISTORY_LEN};
+
+    let filtered_scenarios: Vec<_> = load_fixture_scenarios()
+        .into_iter()
+        .filter(|s| {
+            let tier_match = s.tier <= *tier;
             let tags_match = if tags.is_empty() {
                 true
             } else {
-                tags.iter().all(|tag| scenario_tags.contains(tag))
+                tags.iter().all(|tag| s.tags.contains(tag))
             };
-            tier_match && tags_match
+            tier_match && tags_match && taxonomy.matches(s)
         })
         .collect();
 
+    let histories = if with_history {
+        Some(scenario_histories(&results_db.load_all()?, HISTORY_LEN))
+    } else {
+        None
+    };
+
+    if format == OutputFormat::Json {
+        let calibration = results_db.load_calibration()?;
+        let listing: Vec<_> = filtered_scenarios
+            .iter()
+            .map(|s| {
+                let estimate = calibration
+                    .as_ref()
+                    .and_then(|c| c.difficulty_of(s.stable_id()));
+                let mut entry = serde_json::json!({
+                    "id": s.stable_id(),
+                    "name": s.name,
+                    "description": s.description,
+                    "tier": s.tier,
+                    "weight": s.weight,
+                    "tags": s.tags,
+                    "category": s.category,
+                    "difficulty": s.difficulty,
+                    "skills": s.skills,
+                    "estimated_difficulty": estimate,
+                });
+                if let Some(histories) = &histories {
+                    entry["history"] = serde_json::to_value(
+                        histories.get(s.stable_id()).cloned().unwrap_or_default(),
+                    )
+                    .unwrap_or_default();
+                }
+                entry
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&listing)?);
+        return Ok(());
+    }
+
     let tier_label = match *tier {
         0 => "smoke",
         1 => "quick",
@@ -217,18 +747,365 @@ pub fn handle_list_command(
         _ => "unknown",
     };
     println!("Available scenarios (tier {}):", tier_label);
-    for (_path, name, _scenario_tier, description, scenario_tags) in &filtered_scenarios {
-        let tags_str = if scenario_tags.is_empty() {
+    for s in &filtered_scenarios {
+        let tags_str = if s.tags.is_empty() {
             String::new()
         } else {
-            format!(" [{}]", scenario_tags.join(", "))
+            format!(" [{}]", s.tags.join(", "))
         };
-        println!("  [{}] {}{} - {}", tier_label, name, tags_str, description);
+        let metadata: Vec<&str> = s
+            .category
+            .iter()
+            .chain(s.difficulty.iter())
+            .chain(s.skills.iter())
+            .map(String::as_str)
+            .collect();
+        let metadata_str = if metadata.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", metadata.join(", "))
+        };
+        let history_str = match &histories {
+            Some(histories) => match histories.get(s.stable_id()) {
+                Some(h) => format!(
+                    " {} (mean score {})",
+                    h.sparkline(),
+                    h.mean_score
+                        .map_or_else(|| "-".to_string(), |m| format!("{:.2}", m))
+                ),
+                None => " (no runs)".to_string(),
+            },
+            None => String::new(),
+        };
+        println!(
+            "  [{}] {}{}{} - {}{}",
+            tier_label, s.name, tags_str, metadata_str, s.description, history_str
+        );
     }
 
     Ok(())
 }
 
+pub fn handle_coverage_command(
+    binary: Option<&str>,
+    manifest: Option<&Path>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    use crate::scenario::coverage::{compute_coverage, load_manifest, parse_help_subcommands};
+
+    let mut scenarios = load_fixture_scenarios();
+
+    let binary = match binary {
+        Some(binary) => binary.to_string(),
+        None => {
+            let binaries: std::collections::BTreeSet<_> =
+                scenarios.iter().map(|s| s.target.binary.as_str()).collect();
+            match binaries.len() {
+                0 => anyhow::bail!("No scenarios found; pass --binary to choose a target"),
+                1 => binaries.into_iter().next().unwrap_or_default().to_string(),
+                _ => anyhow::bail!(
+                    "Scenarios target several binaries ({}); pass --binary to choose one",
+                    binaries.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            }
+        }
+    };
+    scenarios.retain(|s| s.target.binary == binary);
+
+    let subcommands = match manifest {
+        Some(path) => load_manifest(path)?,
+        None => {
+            let output = std::process::Command::new(&binary)
+                .arg("--help")
+                .output()
+                .map_err(|e| anyhow::anyhow!("Failed to run '{} --help': {}", binary, e))?;
+            let help = String::from_utf8_lossy(&output.stdout);
+            let subcommands = parse_help_subcommands(&help);
+            if subcommands.is_empty() {
+                anyhow::bail!(
+                    "No subcommands found in '{} --help'; pass --manifest instead",
+                    binary
+                );
+            }
+            subcommands
+        }
+    };
+
+    let report = compute_coverage(&binary, &subcommands, &scenarios)?;
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Text => {
+            println!(
+                "Coverage for '{}': {}/{} subcommands exercised by {} scenario(s)",
+                binary,
+                report.covered.len(),
+                subcommands.len(),
+                scenarios.len()
+            );
+            for command in &report.covered {
+                println!(
+                    "  [x] {:<20} {}",
+                    command.name,
+                    command.scenarios.join(", ")
+                );
+            }
+            for name in &report.uncovered {
+                println!("  [ ] {}", name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_validate_command(paths: &[String], format: OutputFormat) -> anyhow::Result<()> {
+    use crate::scenario::validate::{looks_like_scenario, validate_scenario_file, Severity};
+
+    fn find_yaml_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    find_yaml_files(&path, files);
+                } else if path.extension().is_some_and(|ext| ext == "yaml")
+                    && path
+                        .file_name()
+                        .is_some_and(|name| name != crate::scenario::gatesets::GATESETS_FILE)
+                {
+                    let content = std::fs::read_to_string(&path).unwrap_or_default();
+                    if looks_like_scenario(&content) {
+                        files.push(path);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    if paths.is_empty() {
+        let fixtures_dir = resolve_fixtures_path("");
+        find_yaml_files(&fixtures_dir, &mut files);
+        files.sort();
+    } else {
+        files.extend(paths.iter().map(|p| resolve_scenario_path(p)));
+    }
+
+    let diagnostics: Vec<_> = files
+        .iter()
+        .flat_map(|path| validate_scenario_file(path))
+        .collect();
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "valid": errors == 0,
+                "files_checked": files.len(),
+                "diagnostics": diagnostics,
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Text => {
+            for d in &diagnostics {
+                let severity = match d.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                println!(
+                    "{}:{}:{}: {}: {}",
+                    d.file, d.line, d.column, severity, d.message
+                );
+            }
+            println!(
+                "{} scenario(s) checked, {} error(s), {} warning(s)",
+                files.len(),
+                errors,
+                warnings
+            );
+        }
+    }
+
+    if errors > 0 {
+        anyhow::bail!("Validation failed with {} error(s)", errors);
+    }
+    Ok(())
+}
+
+/// Evaluate a fixture and transcript produced outside this harness.
+///
+/// Nothing is recorded in the results database. The fixture's parent stands
+/// in for the run directory, bounding `workdir`s and passed to scripts as
+/// the results directory. Fails unless the outcome is a pass.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_score_command(
+    scenario: &str,
+    fixture: &Path,
+    transcript: &Path,
+    tool: &str,
+    model: &str,
+    no_judge: bool,
+    judge_cache: Option<&Cache>,
+    out: Option<&Path>,
+) -> anyhow::Result<()> {
+    use crate::results::Outcome;
+    use anyhow::Context;
+
+    let s = load(resolve_scenario_path(scenario))?;
+    let fixture = fixture
+        .canonicalize()
+        .with_context(|| format!("Fixture directory {} not found", fixture.display()))?;
+    anyhow::ensure!(
+        fixture.is_dir(),
+        "Fixture {} is not a directory",
+        fixture.display()
+    );
+    let transcript = transcript
+        .canonicalize()
+        .with_context(|| format!("Transcript {} not found", transcript.display()))?;
+    let results_dir = fixture.parent().unwrap_or(&fixture).to_path_buf();
+
+    let script_runner = crate::script_runner::ScriptRunner::new(
+        fixture.clone(),
+        results_dir.clone(),
+        s.name.clone(),
+        tool.to_string(),
+        model.to_string(),
+        Some(transcript),
+        None,
+        s.target.env.clone().unwrap_or_default(),
+    )
+    .with_executor(crate::sandbox::executor_for(&s, [results_dir]));
+    let metrics = crate::evaluation::evaluate(
+        &s,
+        &fixture,
+        no_judge,
+        judge_cache,
+        Some(&script_runner),
+        crate::evaluation::LiveGateResults::new(),
+    )?;
+    let (outcome, detail) =
+        run::execution::determine_outcome(&metrics, s.evaluation.judge.as_ref());
+
+    println!("\n--- Score ---");
+    println!("Scenario: {}", s.name);
+    println!("Outcome: {}", outcome.describe(detail.as_deref()));
+    println!("Gates: {}/{}", metrics.gates_passed, metrics.gates_total);
+    if let Some(score) = metrics.judge_score {
+        println!("Judge score: {:.2}", score);
+    }
+    if let Some(score) = metrics.composite_score {
+        println!("Composite score: {:.2}", score);
+    }
+    println!(
+        "Commands: {} ({} errors)",
+        metrics.efficiency.total_commands, metrics.efficiency.error_count
+    );
+
+    if let Some(path) = out {
+        let report = serde_json::json!({
+            "scenario_id": s.stable_id(),
+            "outcome": outcome,
+            "outcome_detail": detail,
+            "metrics": metrics,
+        });
+        crate::artifacts::write_atomic(path, serde_json::to_string_pretty(&report)?)?;
+        eprintln!("Wrote score to {}", path.display());
+    }
+
+    if outcome != Outcome::Pass {
+        anyhow::bail!(
+            "Scenario '{}' scored {}",
+            s.name,
+            outcome.describe(detail.as_deref())
+        );
+    }
+    Ok(())
+}
+
+/// Built-in tools checked by `doctor` when no `--tool` is given.
+const DOCTOR_TOOLS: &[&str] = &["aider", "claude-code", "opencode", "openai"];
+
+pub fn handle_doctor_command(config: &Config, tools: &[String], fix: bool) -> anyhow::Result<()> {
+    let tools: Vec<String> = if tools.is_empty() {
+        DOCTOR_TOOLS
+            .iter()
+            .map(|t| t.to_string())
+            .chain(crate::adapter::plugin::list(Path::new(
+                config.get_plugins_path(),
+            )))
+            .collect()
+    } else {
+        tools.to_vec()
+    };
+
+    let mut unauthenticated = Vec::new();
+    for tool in &tools {
+        let adapter = run::execution::create_adapter(tool, config)?;
+        let status = match adapter.is_available() {
+            Ok(status) if status.available => status,
+            Ok(_) => {
+                println!("-  {}: not installed", tool);
+                continue;
+            }
+            Err(e) => {
+                println!("-  {}: {}", tool, e);
+                continue;
+            }
+        };
+        if status.authenticated {
+            match adapter.version() {
+                Some(version) => println!("✓  {}: ready ({})", tool, version),
+                None => println!("✓  {}: ready", tool),
+            }
+            continue;
+        }
+
+        println!("✗  {}: not authenticated", tool);
+        let guidance = adapter.auth_guidance();
+        if let Some(guidance) = &guidance {
+            println!("   {}", guidance.instructions);
+        }
+        match guidance.and_then(|g| g.login_command) {
+            Some(command) if fix => {
+                println!("   Launching `{}`...", command.join(" "));
+                let launched = std::process::Command::new(&command[0])
+                    .args(&command[1..])
+                    .status();
+                if let Err(e) = launched {
+                    println!("   Failed to launch `{}`: {}", command[0], e);
+                }
+                if adapter.is_available().is_ok_and(|s| s.authenticated) {
+                    println!("✓  {}: authenticated", tool);
+                    continue;
+                }
+                println!("✗  {}: still not authenticated", tool);
+            }
+            Some(command) => {
+                println!(
+                    "   Run `llm-tool-test doctor --fix --tool {}` to launch `{}`",
+                    tool,
+                    command.join(" ")
+                );
+            }
+            None => {}
+        }
+        unauthenticated.push(tool.as_str());
+    }
+
+    if !unauthenticated.is_empty() {
+        anyhow::bail!(
+            "{} installed tool(s) need authentication: {}",
+            unauthenticated.len(),
+            unauthenticated.join(", ")
+        );
+    }
+    Ok(())
+}
+
 pub fn handle_show_command(name: &str, results_db: &ResultsDB) -> anyhow::Result<()> {
     let record = results_db.load_by_id(name)?;
     match record {
@@ -241,7 +1118,7 @@ pub fn handle_show_command(name: &str, results_db: &ResultsDB) -> anyhow::Result
             if let Some(cost) = r.cost_usd {
                 println!("Cost: ${:.4}", cost);
             }
-            println!("Outcome: {}", r.outcome);
+            println!("Outcome: {}", r.outcome_summary());
             println!(
                 "Gates: {}/{}",
                 r.metrics.gates_passed, r.metrics.gates_total
@@ -265,6 +1142,175 @@ pub fn handle_show_command(name: &str, results_db: &ResultsDB) -> anyhow::Result
     Ok(())
 }
 
+pub fn handle_relink_command(
+    results_db: &ResultsDB,
+    cache: &Cache,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<()> {
+    if from == to {
+        anyhow::bail!("--from and --to are the same scenario id");
+    }
+
+    let records = results_db.relink_scenario(from, to)?;
+    let cached = cache.relink_scenario(from, to)?;
+    if records == 0 && cached == 0 {
+        println!("No results found for scenario '{}'", from);
+    } else {
+        println!(
+            "Relinked {} result record(s) and {} cache entry(ies) from '{}' to '{}'",
+            records, cached, from, to
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_calibrate_command(
+    results_db: &ResultsDB,
+    since: &Option<String>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    use crate::results::calibration::calibrate;
+
+    let mut records = results_db.load_all()?;
+    if let Some(duration_str) = since {
+        let cutoff = Utc::now() - parse_duration(duration_str)?;
+        records.retain(|r| r.timestamp >= cutoff);
+    }
+    let calibration = calibrate(&records);
+    results_db.save_calibration(&calibration)?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&calibration)?),
+        OutputFormat::Text => {
+            if calibration.scenarios.is_empty() {
+                println!("No results to calibrate");
+                return Ok(());
+            }
+            println!("Scenario difficulty (logits, easiest first):");
+            for d in &calibration.scenarios {
+                println!(
+                    "  {:>6.2}  {} (pass rate {:.0}%, {} runs, {} tool/models)",
+                    d.difficulty,
+                    d.scenario,
+                    d.pass_rate * 100.0,
+                    d.runs,
+                    d.tool_models
+                );
+            }
+            println!("\nTool/model ability:");
+            for a in &calibration.abilities {
+                println!(
+                    "  {:>6.2}  {} / {} ({} runs)",
+                    a.ability, a.tool, a.model, a.runs
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_export_command(
+    results_db: &ResultsDB,
+    anonymize: bool,
+    redact: &[String],
+    out: Option<&Path>,
+    duckdb: Option<&Path>,
+    since: &Option<String>,
+) -> anyhow::Result<()> {
+    use crate::results::export::{anonymize_record, Redactor};
+
+    let mut records = results_db.load_all()?;
+    if let Some(duration_str) = since {
+        let cutoff = Utc::now() - parse_duration(duration_str)?;
+        records.retain(|r| r.timestamp >= cutoff);
+    }
+    if anonymize || !redact.is_empty() {
+        let redactor = Redactor::new(redact)?;
+        records = records
+            .iter()
+            .map(|r| anonymize_record(r, &redactor))
+            .collect();
+    }
+
+    if let Some(path) = duckdb {
+        crate::results::duckdb::write_database(path, &records)?;
+        eprintln!("Exported {} record(s) to {}", records.len(), path.display());
+        return Ok(());
+    }
+
+    let mut content = String::new();
+    for record in &records {
+        content.push_str(&serde_json::to_string(record)?);
+        content.push('\n');
+    }
+    match out {
+        Some(path) => {
+            crate::artifacts::write_atomic(path, content)?;
+            eprintln!("Exported {} record(s) to {}", records.len(), path.display());
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+pub fn handle_gc_command(
+    results_db: &ResultsDB,
+    cache: &Cache,
+    base_dir: &Path,
+    archive: Option<&Path>,
+    older_than: &str,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    use crate::results::gc::{dir_size, find_orphaned_run_dirs, format_bytes};
+
+    let mut records = results_db.load_all()?;
+    records.extend(cache.load_all());
+    let cutoff = Utc::now() - parse_duration(older_than)?;
+    let orphans = find_orphaned_run_dirs(base_dir, &records, Some(cutoff))?;
+
+    if let Some(archive) = archive {
+        if !dry_run {
+            std::fs::create_dir_all(archive)?;
+        }
+    }
+
+    let mut reclaimed = 0;
+    for dir in &orphans {
+        let size = dir_size(dir);
+        let name = dir.file_name().unwrap_or_default();
+        if dry_run {
+            println!("Would collect {} ({})", dir.display(), format_bytes(size));
+        } else if let Some(archive) = archive {
+            let target = archive.join(name);
+            if std::fs::rename(dir, &target).is_err() {
+                // Rename fails across filesystems; fall back to copy + delete
+                run::utils::copy_dir_recursive_with_exclusions(dir, &target, &[])?;
+                std::fs::remove_dir_all(dir)?;
+            }
+            println!("Archived {} -> {}", dir.display(), target.display());
+        } else {
+            std::fs::remove_dir_all(dir)?;
+            println!("Removed {}", dir.display());
+        }
+        reclaimed += size;
+    }
+
+    let verb = match (dry_run, archive.is_some()) {
+        (true, _) => "Would reclaim",
+        (false, true) => "Archived",
+        (false, false) => "Reclaimed",
+    };
+    println!(
+        "{} {} across {} orphaned run director{}",
+        verb,
+        format_bytes(reclaimed),
+        orphans.len(),
+        if orphans.len() == 1 { "y" } else { "ies" }
+    );
+    Ok(())
+}
+
 pub fn handle_clean_command(
     cache: &Cache,
     older_than: &Option<String>,
@@ -341,6 +1387,377 @@ pub fn handle_clean_command(
     Ok(())
 }
 
+pub fn handle_stats_command(
+    results_db: &ResultsDB,
+    anomalies: bool,
+    sigma: f64,
+    since: &Option<String>,
+    taxonomy: &TaxonomyFilter,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    use crate::results::stats::{compute_stats, find_anomalies, MIN_HISTORY};
+
+    let scenarios = load_fixture_scenarios();
+    let mut records = results_db.load_all()?;
+    if !taxonomy.is_empty() {
+        let selected: std::collections::HashSet<&str> = scenarios
+            .iter()
+            .filter(|s| taxonomy.matches(s))
+            .map(|s| s.stable_id())
+            .collect();
+        records.retain(|r| selected.contains(r.scenario_id.as_str()));
+    }
+    let cutoff = match since {
+        Some(duration_str) => Some(Utc::now() - parse_duration(duration_str)?),
+        None => None,
+    };
+
+    if anomalies {
+        // Judge recent runs against their full history, then report only the window
+        let mut found = find_anomalies(&records, sigma);
+        if let Some(cutoff) = cutoff {
+            found.retain(|a| a.timestamp >= cutoff);
+        }
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&found)?),
+            OutputFormat::Text => {
+                if found.is_empty() {
+                    println!(
+                        "No anomalies beyond {:.1}σ (runs need {} earlier runs of the same scenario/tool/model)",
+                        sigma, MIN_HISTORY
+                    );
+                }
+                for a in &found {
+                    println!(
+                        "{} {} [{}/{}] {}: {:.2} vs mean {:.2} ± {:.2} ({:+.1}σ)",
+                        a.timestamp.format("%Y-%m-%d %H:%M"),
+                        a.scenario,
+                        a.tool,
+                        a.model,
+                        a.metric,
+                        a.value,
+                        a.history_mean,
+                        a.history_stddev,
+                        a.sigma
+                    );
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(cutoff) = cutoff {
+        records.retain(|r| r.timestamp >= cutoff);
+    }
+    let weights: HashMap<String, f64> = scenarios
+        .iter()
+        .map(|s| (s.stable_id().to_string(), s.weight))
+        .collect();
+    let stats = compute_stats(&records, &weights);
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Text => {
+            if records.is_empty() {
+                println!("No results recorded");
+                return Ok(());
+            }
+            let fmt_opt = |v: Option<f64>, precision: usize| {
+                v.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v))
+            };
+
+            println!("Suites (latest run per scenario)");
+            for s in &stats.suites {
+                println!(
+                    "  {:<30} {:>3} scenarios  pass {:>5.1}%  score {}",
+                    format!("{}/{}", s.tool, s.model),
+                    s.scenarios,
+                    s.pass_rate * 100.0,
+                    fmt_opt(s.mean_score, 2)
+                );
+            }
+
+            println!("\nScenarios");
+            for s in &stats.scenarios {
+                println!(
+                    "  {:<30} {:<30} {:>4} runs  pass {:>5.1}%  {:>7.1}s  {:>5.1} cmds  cost {}  score {}",
+                    s.scenario,
+                    format!("{}/{}", s.tool, s.model),
+                    s.runs,
+                    s.pass_rate * 100.0,
+                    s.mean_duration_secs,
+                    s.mean_commands,
+                    fmt_opt(s.mean_cost_usd, 4),
+                    fmt_opt(s.mean_score, 2)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_costs_command(
+    results_db: &ResultsDB,
+    config: &Config,
+    since: &Option<String>,
+    group_by: CostGroupBy,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let mut records = results_db.load_all()?;
+    if let Some(duration_str) = since {
+        let cutoff = Utc::now() - parse_duration(duration_str)?;
+        records.retain(|r| r.timestamp >= cutoff);
+    }
+
+    let report = summarize_costs(&records, group_by, config.budgets.as_ref());
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Text => {
+            if records.is_empty() {
+                println!("No results recorded");
+                return Ok(());
+            }
+
+            let width = report
+                .rows
+                .iter()
+                .map(|r| r.key.len())
+                .max()
+                .unwrap_or(0)
+                .max("total".len());
+            println!(
+                "{:<width$}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                "", "runs", "agent", "judge", "total", "budget"
+            );
+            for row in report.rows.iter().chain(std::iter::once(&report.total)) {
+                let budget = match (row.budget_usd, row.remaining_usd) {
+                    (Some(budget), Some(remaining)) if row.over_budget() => {
+                        format!("${:.4} (over by ${:.4})", budget, -remaining)
+                    }
+                    (Some(budget), _) => format!("${:.4}", budget),
+                    _ => "-".to_string(),
+                };
+                println!(
+                    "{:<width$}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                    row.key,
+                    row.runs,
+                    format!("${:.4}", row.agent_usd),
+                    format!("${:.4}", row.judge_usd),
+                    format!("${:.4}", row.total_usd),
+                    budget
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_compare_command(
+    results_db: &ResultsDB,
+    baseline: &output::ToolModelConfig,
+    candidate: &output::ToolModelConfig,
+    since: &Option<String>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let mut records = results_db.load_all()?;
+    if let Some(duration_str) = since {
+        let cutoff = Utc::now() - parse_duration(duration_str)?;
+        records.retain(|r| r.timestamp >= cutoff);
+    }
+
+    let comparison = compare_runs(
+        &records,
+        (&baseline.tool, &baseline.model),
+        (&candidate.tool, &candidate.model),
+    );
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&comparison)?),
+        OutputFormat::Text => print!("{}", render_comparison(&comparison)),
+    }
+    Ok(())
+}
+
+pub fn handle_dashboard_command(
+    results_db: &ResultsDB,
+    interval: u64,
+    once: bool,
+) -> anyhow::Result<()> {
+    use crate::results::dashboard::{build_dashboard, render_dashboard};
+
+    let tiers: HashMap<String, usize> = load_fixture_scenarios()
+        .into_iter()
+        .map(|s| (s.stable_id().to_string(), s.tier))
+        .collect();
+
+    loop {
+        let records = results_db.load_all()?;
+        let now = chrono::Local::now();
+        let text = render_dashboard(&build_dashboard(&records, &tiers, now.date_naive()));
+        if once {
+            print!("{}", text);
+            return Ok(());
+        }
+
+        // Clear the screen and move the cursor home before redrawing
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "llm-tool-test dashboard - {} (refresh every {}s, Ctrl-C to exit)\n",
+            now.format("%Y-%m-%d %H:%M:%S"),
+            interval
+        );
+        print!("{}", text);
+        std::io::Write::flush(&mut std::io::stdout())?;
+        std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
+    }
+}
+
+/// Outputs and filters for `report`.
+pub struct ReportOptions<'a> {
+    pub badge: Option<&'a Path>,
+    pub metric: crate::results::report::BadgeMetric,
+    pub badge_label: Option<&'a str>,
+    pub summary: Option<&'a Path>,
+    pub capabilities: Option<&'a Path>,
+    pub html: Option<&'a Path>,
+    pub tool: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub label: Option<&'a str>,
+    pub since: Option<&'a str>,
+}
+
+fn write_report_file(path: &Path, content: &str) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::artifacts::write_atomic(path, content)?;
+    Ok(())
+}
+
+pub fn handle_report_command(
+    results_db: &ResultsDB,
+    options: &ReportOptions,
+) -> anyhow::Result<()> {
+    use crate::results::report::{
+        badge_value, compute_capabilities, render_badge, render_capabilities, render_summary,
+    };
+
+    if options.badge.is_none()
+        && options.summary.is_none()
+        && options.capabilities.is_none()
+        && options.html.is_none()
+    {
+        anyhow::bail!(
+            "Nothing to report; pass --badge <FILE>, --summary <FILE>, --capabilities <FILE> and/or --html <DIR>"
+        );
+    }
+
+    let cutoff = match options.since {
+        Some(duration_str) => Some(Utc::now() - parse_duration(duration_str)?),
+        None => None,
+    };
+    let mut records = results_db.load_all()?;
+    records.retain(|r| {
+        options.tool.is_none_or(|t| r.tool == t)
+            && options.model.is_none_or(|m| r.model == m)
+            && options
+                .label
+                .is_none_or(|l| r.labels.iter().any(|rl| rl == l))
+            && cutoff.is_none_or(|c| r.timestamp >= c)
+    });
+    let scenarios = load_fixture_scenarios();
+    let weights: HashMap<String, f64> = scenarios
+        .iter()
+        .map(|s| (s.stable_id().to_string(), s.weight))
+        .collect();
+
+    if let Some(badge) = options.badge {
+        let value = badge_value(&records, &weights, options.metric);
+        let label = match (options.badge_label, options.tool) {
+            (Some(label), _) => label.to_string(),
+            (None, Some(tool)) => format!("{} {}", tool, options.metric.label()),
+            (None, None) => options.metric.label().to_string(),
+        };
+        write_report_file(badge, &render_badge(&label, value, options.metric))?;
+        println!("Wrote badge to {}", badge.display());
+    }
+
+    if let Some(summary) = options.summary {
+        write_report_file(summary, &render_summary(&records, &weights))?;
+        println!("Wrote summary to {}", summary.display());
+    }
+
+    if let Some(path) = options.capabilities {
+        let skills: HashMap<String, Vec<String>> = scenarios
+            .iter()
+            .filter(|s| !s.skills.is_empty())
+            .map(|s| (s.stable_id().to_string(), s.skills.clone()))
+            .collect();
+        let matrix = compute_capabilities(&records, &skills);
+        let content = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(&matrix)?
+        } else {
+            render_capabilities(&matrix)
+        };
+        write_report_file(path, &content)?;
+        println!("Wrote capability matrix to {}", path.display());
+    }
+
+    if let Some(dir) = options.html {
+        let runs = crate::results::html::write_site(dir, &records, &weights)?;
+        println!(
+            "Wrote HTML report for {} run(s) to {}",
+            runs,
+            dir.join("index.html").display()
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_bundle_keygen_command() {
+    println!("{}", crate::scenario::bundle::generate_key());
+}
+
+pub fn handle_bundle_encrypt_command(
+    paths: &[PathBuf],
+    remove_plaintext: bool,
+) -> anyhow::Result<()> {
+    use crate::scenario::bundle::{bundle_path, encrypt, is_bundle, key_from_env};
+
+    let key = key_from_env()?;
+    for path in paths {
+        let plaintext = std::fs::read_to_string(path)?;
+        if is_bundle(&plaintext) {
+            anyhow::bail!("{} is already encrypted", path.display());
+        }
+        // Refuse to encrypt something that would not load as a scenario
+        serde_yaml::from_str::<crate::scenario::Scenario>(&plaintext)
+            .map_err(|e| anyhow::anyhow!("{} is not a valid scenario: {}", path.display(), e))?;
+
+        let out = bundle_path(path);
+        std::fs::write(&out, encrypt(&plaintext, &key)?)?;
+        if remove_plaintext {
+            std::fs::remove_file(path)?;
+        }
+        println!("Encrypted {} -> {}", path.display(), out.display());
+    }
+    Ok(())
+}
+
+pub fn handle_bundle_decrypt_command(path: &Path, out: Option<&Path>) -> anyhow::Result<()> {
+    use crate::scenario::bundle::{decrypt, key_from_env};
+
+    let plaintext = decrypt(&std::fs::read_to_string(path)?, &key_from_env()?)?;
+    match out {
+        Some(out) => {
+            std::fs::write(out, plaintext)?;
+            println!("Decrypted {} -> {}", path.display(), out.display());
+        }
+        None => print!("{}", plaintext),
+    }
+    Ok(())
+}
+
 fn parse_duration(s: &str) -> anyhow::Result<Duration> {
     let re = regex::Regex::new(r"^(\d+)([dhm])$")?;
     let caps = re.captures(s).ok_or_else(|| {
@@ -1,7 +1,9 @@
+use crate::artifacts::FsyncMode;
+use crate::results::retention::KeepFixture;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Configuration for a specific LLM tool.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +28,364 @@ pub struct ProfileConfig {
     pub models: Vec<String>,
 }
 
+/// Spending budgets in USD, compared against actual spend by `costs`.
+///
+/// Keys of the per-group maps are tool names, model names, scenario names
+/// or run labels respectively.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BudgetConfig {
+    /// Budget for all spend in the reported window
+    #[serde(default)]
+    pub total_usd: Option<f64>,
+    #[serde(default)]
+    pub tools: HashMap<String, f64>,
+    #[serde(default)]
+    pub models: HashMap<String, f64>,
+    #[serde(default)]
+    pub scenarios: HashMap<String, f64>,
+    #[serde(default)]
+    pub labels: HashMap<String, f64>,
+}
+
+/// Rate limits applied when launching tool runs.
+///
+/// Top-level values apply to every tool; entries under `tools` override
+/// them for a single tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Maximum runs of one tool in flight at once (unlimited if unset)
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    /// Minimum delay between consecutive launches of one tool
+    #[serde(default)]
+    pub launch_delay_secs: f64,
+    /// Initial pause after a rate-limited run; doubles on each consecutive hit
+    #[serde(default = "default_backoff_initial_secs")]
+    pub backoff_initial_secs: f64,
+    /// Upper bound for the backoff pause
+    #[serde(default = "default_backoff_max_secs")]
+    pub backoff_max_secs: f64,
+    /// Per-tool overrides
+    #[serde(default)]
+    pub tools: HashMap<String, ToolRateLimit>,
+}
+
+/// Per-tool override of [`RateLimitConfig`] values.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ToolRateLimit {
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    #[serde(default)]
+    pub launch_delay_secs: Option<f64>,
+}
+
+fn default_backoff_initial_secs() -> f64 {
+    30.0
+}
+
+fn default_backoff_max_secs() -> f64 {
+    600.0
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: None,
+            launch_delay_secs: 0.0,
+            backoff_initial_secs: default_backoff_initial_secs(),
+            backoff_max_secs: default_backoff_max_secs(),
+            tools: HashMap::new(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Concurrency limit for `tool`, if any.
+    pub fn max_concurrent_for(&self, tool: &str) -> Option<usize> {
+        self.tools
+            .get(tool)
+            .and_then(|t| t.max_concurrent)
+            .or(self.max_concurrent)
+    }
+
+    /// Minimum delay between launches of `tool`, in seconds.
+    pub fn launch_delay_for(&self, tool: &str) -> f64 {
+        self.tools
+            .get(tool)
+            .and_then(|t| t.launch_delay_secs)
+            .unwrap_or(self.launch_delay_secs)
+    }
+}
+
+/// Retry policy for tool invocations that fail with transient provider errors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0, the default, disables retrying).
+    /// A retry runs in the working directory the failed attempt left behind.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each subsequent retry
+    #[serde(default = "default_retry_initial_delay_secs")]
+    pub initial_delay_secs: f64,
+    /// Upper bound for the retry delay
+    #[serde(default = "default_retry_max_delay_secs")]
+    pub max_delay_secs: f64,
+}
+
+fn default_max_retries() -> u32 {
+    0
+}
+
+fn default_retry_initial_delay_secs() -> f64 {
+    5.0
+}
+
+fn default_retry_max_delay_secs() -> f64 {
+    60.0
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            initial_delay_secs: default_retry_initial_delay_secs(),
+            max_delay_secs: default_retry_max_delay_secs(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before retry number `retry` (1-based).
+    pub fn delay_for(&self, retry: u32) -> std::time::Duration {
+        let factor = 2f64.powi(retry.saturating_sub(1).min(30) as i32);
+        let secs = (self.initial_delay_secs * factor).min(self.max_delay_secs);
+        std::time::Duration::from_secs_f64(secs.max(0.0))
+    }
+}
+
+/// Language and template source for generated reports and judge prompts.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReportConfig {
+    /// Language code selecting `<templates_dir>/locales/<language>.toml` (default `en`)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Directory holding report templates and locale files
+    /// (default `<fixtures_path>/report-templates`)
+    #[serde(default)]
+    pub templates_dir: Option<String>,
+}
+
+impl ReportConfig {
+    pub fn language(&self) -> &str {
+        self.language
+            .as_deref()
+            .unwrap_or(crate::locale::DEFAULT_LANGUAGE)
+    }
+
+    pub fn templates_dir(&self) -> PathBuf {
+        match &self.templates_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => crate::utils::resolve_fixtures_path("report-templates"),
+        }
+    }
+}
+
+/// Handling of captured tool output.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TranscriptConfig {
+    /// Also keep the unnormalized output, ANSI escapes and carriage returns
+    /// intact, in `transcript.terminal.txt`
+    #[serde(default)]
+    pub keep_terminal_output: bool,
+    /// Store `transcript.raw.txt` and `events.jsonl` zstd-compressed (as
+    /// `<name>.zst`) once a run finishes, when they exceed this many bytes
+    #[serde(default)]
+    pub compress_above_bytes: Option<u64>,
+}
+
+/// Durability of run artifacts.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ArtifactsConfig {
+    /// How far writes are flushed to disk: `none`, `file` or `full`
+    #[serde(default)]
+    pub fsync: FsyncMode,
+}
+
+/// Which runs keep their full artifacts once newer runs exist.
+///
+/// Result records are never removed; pruned runs keep `metrics.json`, `judge.json`,
+/// `evaluation.md`, `report.md` and `repro.sh` but lose `artifacts/`, the
+/// fixture and the isolated home.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Keep full artifacts for this many most recent runs per scenario
+    /// (unset disables retention)
+    #[serde(default)]
+    pub keep_last: Option<usize>,
+    /// Also keep full artifacts of every run that did not pass
+    #[serde(default = "default_keep_failed")]
+    pub keep_failed: bool,
+    /// Which runs keep `fixture/` and `home/` once the matrix run finishes
+    /// (`on-failure`, `always` or `never`); `--keep-fixture` overrides it
+    #[serde(default)]
+    pub keep_fixture: KeepFixture,
+}
+
+fn default_keep_failed() -> bool {
+    true
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            keep_last: None,
+            keep_failed: default_keep_failed(),
+            keep_fixture: KeepFixture::default(),
+        }
+    }
+}
+
+/// Run timeouts for scenarios that set none themselves.
+///
+/// Consulted after `--timeout-secs`, the scenario's `run.timeout_secs` and
+/// the nearest `suite.yaml`; see [`crate::run::timeout`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TimeoutConfig {
+    /// Timeout for scenarios whose tier has no entry in `tiers`
+    #[serde(default)]
+    pub default_secs: Option<u64>,
+    /// Timeout per scenario tier: the first entry applies to tier 0, the
+    /// second to tier 1, and so on
+    #[serde(default)]
+    pub tiers: Vec<u64>,
+}
+
+pub const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Endpoint settings for the `openai` adapter, which drives an
+/// OpenAI-compatible chat completions API directly.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenAiConfig {
+    /// API base URL including the version prefix (default `https://api.openai.com/v1`)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key (default `OPENAI_API_KEY`)
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Model used when a run does not name one (`--model default`)
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Maximum chat completion requests per run (default 50)
+    #[serde(default)]
+    pub max_steps: Option<usize>,
+    /// Timeout for a single shell tool call (default 120)
+    #[serde(default)]
+    pub command_timeout_secs: Option<u64>,
+    /// Price per million input tokens, for cost estimates
+    #[serde(default)]
+    pub input_usd_per_mtok: Option<f64>,
+    /// Price per million output tokens, for cost estimates
+    #[serde(default)]
+    pub output_usd_per_mtok: Option<f64>,
+}
+
+impl OpenAiConfig {
+    pub fn base_url(&self) -> &str {
+        self.base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_OPENAI_BASE_URL)
+            .trim_end_matches('/')
+    }
+
+    pub fn api_key_env(&self) -> &str {
+        self.api_key_env.as_deref().unwrap_or("OPENAI_API_KEY")
+    }
+
+    pub fn max_steps(&self) -> usize {
+        self.max_steps.unwrap_or(50)
+    }
+
+    pub fn command_timeout_secs(&self) -> u64 {
+        self.command_timeout_secs.unwrap_or(120)
+    }
+}
+
+/// How the LLM-as-judge is run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JudgeBackend {
+    /// Prompt the `opencode` CLI, which reads the transcript and rubric files
+    #[default]
+    Cli,
+    /// Call an OpenAI-compatible chat completions endpoint directly
+    Api,
+}
+
+/// Model used by the `api` judge backend when none is configured.
+pub const DEFAULT_JUDGE_MODEL: &str = "gpt-4o-mini";
+
+/// Settings of the LLM-as-judge (`[judge]`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct JudgeBackendConfig {
+    /// `cli` (default) or `api`
+    #[serde(default)]
+    pub backend: JudgeBackend,
+    /// API base URL including the version prefix (default `https://api.openai.com/v1`)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key (default `OPENAI_API_KEY`)
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Model asked to judge (default `gpt-4o-mini`)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Timeout for the judge request (default 120)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Times a reply holding no judge response JSON is answered by asking
+    /// for JSON only, before the judge fails (default 1)
+    #[serde(default)]
+    pub parse_retries: Option<u32>,
+    /// Price per million input tokens, for the `api` backend's judge cost
+    #[serde(default)]
+    pub input_usd_per_mtok: Option<f64>,
+    /// Price per million output tokens, for the `api` backend's judge cost
+    #[serde(default)]
+    pub output_usd_per_mtok: Option<f64>,
+}
+
+impl JudgeBackendConfig {
+    pub fn base_url(&self) -> &str {
+        self.base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_OPENAI_BASE_URL)
+            .trim_end_matches('/')
+    }
+
+    pub fn api_key_env(&self) -> &str {
+        self.api_key_env.as_deref().unwrap_or("OPENAI_API_KEY")
+    }
+
+    pub fn model(&self) -> &str {
+        self.model.as_deref().unwrap_or(DEFAULT_JUDGE_MODEL)
+    }
+
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout_secs.unwrap_or(120)
+    }
+
+    pub fn parse_retries(&self) -> u32 {
+        self.parse_retries.unwrap_or(1)
+    }
+
+    /// Cost of the given token usage, if both prices are configured.
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+        let input = self.input_usd_per_mtok?;
+        let output = self.output_usd_per_mtok?;
+        Some((input_tokens as f64 * input + output_tokens as f64 * output) / 1_000_000.0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     /// Tool configurations
@@ -34,10 +394,46 @@ pub struct Config {
     /// Profile configurations for test matrices
     #[serde(default)]
     pub profiles: HashMap<String, ProfileConfig>,
+    /// Tool matrix for scenarios without their own `tool_matrix`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_matrix: Vec<crate::scenario::ToolConfig>,
     #[serde(default)]
     pub fixtures_path: Option<String>,
     #[serde(default)]
     pub results_path: Option<String>,
+    /// Directory searched for external adapter plugins
+    #[serde(default)]
+    pub plugins_path: Option<String>,
+    /// Spending budgets for the cost report
+    #[serde(default)]
+    pub budgets: Option<BudgetConfig>,
+    /// Rate limits for launching tool runs
+    #[serde(default)]
+    pub rate_limits: RateLimitConfig,
+    /// Retry policy for transient tool failures
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Localization of generated reports and judge prompts
+    #[serde(default)]
+    pub report: ReportConfig,
+    /// Endpoint for the `openai` adapter
+    #[serde(default)]
+    pub openai: OpenAiConfig,
+    /// How the LLM-as-judge is run
+    #[serde(default)]
+    pub judge: JudgeBackendConfig,
+    /// Transcript normalization settings
+    #[serde(default)]
+    pub transcript: TranscriptConfig,
+    /// Artifact write durability
+    #[serde(default)]
+    pub artifacts: ArtifactsConfig,
+    /// Automatic pruning of old run artifacts
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    /// Default run timeouts
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
 }
 
 impl Config {
@@ -77,6 +473,12 @@ impl Config {
             .unwrap_or("llm-tool-test-results")
     }
 
+    pub fn get_plugins_path(&self) -> &str {
+        self.plugins_path
+            .as_deref()
+            .unwrap_or("llm-tool-test-plugins")
+    }
+
     /// Get a tool configuration by name.
     pub fn get_tool(&self, name: &str) -> Option<&ToolConfig> {
         self.tools.get(name)
@@ -1,14 +1,476 @@
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::path::Path;
 
+/// Computes the hex-encoded SHA-256 digest of a file.
+pub fn file_sha256(path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read file '{}'", path.display()))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Reads `(width, height)` from the header of a PNG, GIF, BMP or JPEG image.
+pub fn image_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read file '{}'", path.display()))?;
+    parse_image_dimensions(&bytes)
+        .with_context(|| format!("Unrecognized image format in '{}'", path.display()))
+}
+
+fn parse_image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be_u16 = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let le_u16 = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let be_u32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let le_i32 = |at: usize| Some(i32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.get(12..16) == Some(b"IHDR") {
+        return Some((be_u32(16)?, be_u32(20)?));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some((le_u16(6)? as u32, le_u16(8)? as u32));
+    }
+    if bytes.starts_with(b"BM") {
+        return Some((le_i32(18)?.unsigned_abs(), le_i32(22)?.unsigned_abs()));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        // Walk JPEG segments until a start-of-frame marker
+        let mut offset = 2;
+        while offset + 4 <= bytes.len() {
+            if bytes[offset] != 0xFF {
+                return None;
+            }
+            let marker = bytes[offset + 1];
+            let length = be_u16(offset + 2)? as usize;
+            let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_sof {
+                return Some((be_u16(offset + 7)? as u32, be_u16(offset + 5)? as u32));
+            }
+            offset += 2 + length;
+        }
+    }
+    None
+}
+
+/// Reads an entry from a zip, tar or gzipped tar archive.
+///
+/// The format is detected from the file header. Entry names are compared
+/// ignoring a leading `./` and trailing `/`. Returns `None` if the entry is
+/// missing.
+pub fn read_archive_entry(archive: &Path, entry: &str) -> Result<Option<Vec<u8>>> {
+    use std::io::Read;
+
+    let bytes = std::fs::read(archive)
+        .with_context(|| format!("Failed to read archive '{}'", archive.display()))?;
+    let wanted = normalize_archive_entry(entry);
+
+    if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+            .with_context(|| format!("Failed to open zip archive '{}'", archive.display()))?;
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i)?;
+            if normalize_archive_entry(file.name()) == wanted {
+                let mut content = Vec::new();
+                file.read_to_end(&mut content)?;
+                return Ok(Some(content));
+            }
+        }
+        return Ok(None);
+    }
+
+    let reader: Box<dyn Read> = if bytes.starts_with(&[0x1f, 0x8b]) {
+        Box::new(flate2::read::GzDecoder::new(std::io::Cursor::new(bytes)))
+    } else {
+        Box::new(std::io::Cursor::new(bytes))
+    };
+    let mut tar = tar::Archive::new(reader);
+    for file in tar
+        .entries()
+        .with_context(|| format!("Failed to open tar archive '{}'", archive.display()))?
+    {
+        let mut file = file?;
+        let name = file.path()?.to_string_lossy().to_string();
+        if normalize_archive_entry(&name) == wanted {
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            return Ok(Some(content));
+        }
+    }
+    Ok(None)
+}
+
+fn normalize_archive_entry(name: &str) -> &str {
+    name.trim_start_matches("./").trim_end_matches('/')
+}
+
+/// Parse CSV `content` into records of fields, following RFC 4180: fields
+/// may be quoted with `"`, a doubled `""` inside quotes is a literal quote,
+/// and quoted fields may span lines. Blank lines are skipped and a leading
+/// byte order mark is ignored.
+pub fn parse_csv(content: &str, delimiter: char) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    // Whether the current record has any content, to skip blank lines
+    let mut started = false;
+    let mut line = 1;
+    let mut chars = content
+        .strip_prefix('\u{feff}')
+        .unwrap_or(content)
+        .chars()
+        .peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => {
+                in_quotes = true;
+                started = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if started {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                started = false;
+                line += 1;
+            }
+            _ if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                started = true;
+            }
+            _ => {
+                field.push(c);
+                started = true;
+            }
+        }
+    }
+    if in_quotes {
+        anyhow::bail!("unterminated quoted field starting before line {}", line);
+    }
+    if started {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Structural differences between two JSON documents, as `path: detail`
+/// lines in document order. Object key order is ignored and numbers compare
+/// by value, so `1` equals `1.0`.
+pub fn json_differences(expected: &serde_json::Value, actual: &serde_json::Value) -> Vec<String> {
+    let mut differences = Vec::new();
+    collect_json_differences("$", expected, actual, &mut differences);
+    differences
+}
+
+fn collect_json_differences(
+    path: &str,
+    expected: &serde_json::Value,
+    actual: &serde_json::Value,
+    out: &mut Vec<String>,
+) {
+    use serde_json::Value;
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let child = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(found) => collect_json_differences(&child, value, found, out),
+                    None => out.push(format!("{}: missing, expected {}", child, value)),
+                }
+            }
+            for (key, value) in actual {
+                if !expected.contains_key(key) {
+                    out.push(format!("{}.{}: unexpected {}", path, key, value));
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for (index, (value, found)) in expected.iter().zip(actual).enumerate() {
+                collect_json_differences(&format!("{}[{}]", path, index), value, found, out);
+            }
+            if expected.len() != actual.len() {
+                out.push(format!(
+                    "{}: expected {} element(s), found {}",
+                    path,
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+        }
+        (Value::Number(a), Value::Number(b)) if a.as_f64() == b.as_f64() => {}
+        _ if expected == actual => {}
+        _ => out.push(format!("{}: expected {}, found {}", path, expected, actual)),
+    }
+}
+
+/// What a Markdown line is, as far as structure checks care.
+#[derive(Debug, Clone, Copy)]
+enum MarkdownLine<'a> {
+    /// An ATX heading with its level and text
+    Heading(usize, &'a str),
+    /// The opening line of a fenced code block, with its info string
+    FenceOpen(&'a str),
+    /// Any other line, including the contents and closing line of a fence
+    Other,
+}
+
+/// Classify each line of `content`, skipping headings inside fenced code
+/// blocks (```` ``` ```` or `~~~`).
+fn markdown_lines(content: &str) -> Vec<(&str, MarkdownLine<'_>)> {
+    let mut fence: Option<&str> = None;
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let kind = if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                MarkdownLine::Other
+            } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+                MarkdownLine::FenceOpen(trimmed.trim_start_matches(['`', '~']).trim())
+            } else {
+                markdown_heading(line)
+                    .map(|(level, text)| MarkdownLine::Heading(level, text))
+                    .unwrap_or(MarkdownLine::Other)
+            };
+            (line, kind)
+        })
+        .collect()
+}
+
+/// Level and text of an ATX heading line (`## Text ##`), if it is one.
+fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    // An optional closing sequence of `#`s is not part of the text
+    let text = rest.trim();
+    let stripped = text.trim_end_matches('#');
+    let text = if stripped.is_empty() || stripped.ends_with([' ', '\t']) {
+        stripped.trim_end()
+    } else {
+        text
+    };
+    Some((level, text))
+}
+
+/// Text of every Markdown section whose heading matches `heading`,
+/// case-insensitively and ignoring leading `#`s in `heading`.
+///
+/// A section runs from the line after its ATX heading to the next heading of
+/// the same or a higher level, so it includes its subsections. Lines inside
+/// fenced code blocks are never headings.
+pub fn markdown_sections(content: &str, heading: &str) -> Vec<String> {
+    let wanted = heading.trim().trim_start_matches('#').trim().to_lowercase();
+    let mut sections = Vec::new();
+    // Level and collected lines of the matching section being read
+    let mut current: Option<(usize, Vec<&str>)> = None;
+
+    for (line, kind) in markdown_lines(content) {
+        if let MarkdownLine::Heading(level, text) = kind {
+            if current.as_ref().is_some_and(|(open, _)| level <= *open) {
+                let (_, lines) = current.take().expect("section is open");
+                sections.push(lines.join("\n"));
+            }
+            if current.is_none() && text.to_lowercase() == wanted {
+                current = Some((level, Vec::new()));
+                continue;
+            }
+        }
+        if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    if let Some((_, lines)) = current {
+        sections.push(lines.join("\n"));
+    }
+    sections
+}
+
+/// Headings and code block languages of a Markdown document.
+#[derive(Debug, Default)]
+pub struct MarkdownOutline {
+    /// Level and text of every ATX heading, in document order
+    pub headings: Vec<(usize, String)>,
+    /// Language of every fenced code block (first word of its info string,
+    /// lowercased); empty for blocks without one
+    pub code_languages: Vec<String>,
+}
+
+/// Outline of `content` for structure checks.
+pub fn markdown_outline(content: &str) -> MarkdownOutline {
+    let mut outline = MarkdownOutline::default();
+    for (_, kind) in markdown_lines(content) {
+        match kind {
+            MarkdownLine::Heading(level, text) => outline.headings.push((level, text.to_string())),
+            MarkdownLine::FenceOpen(info) => outline.code_languages.push(
+                info.split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase(),
+            ),
+            MarkdownLine::Other => {}
+        }
+    }
+    outline
+}
+
+/// Lists running processes as `(pid, command line)` pairs using `ps`.
+pub fn list_process_command_lines() -> Result<Vec<(u32, String)>> {
+    let output = std::process::Command::new("ps")
+        .args(["-eo", "pid=,args="])
+        .output()
+        .context("Failed to run 'ps'")?;
+    if !output.status.success() {
+        anyhow::bail!("'ps' exited with status {}", output.status);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, args) = line.trim_start().split_once(char::is_whitespace)?;
+            Some((pid.parse().ok()?, args.trim().to_string()))
+        })
+        .collect())
+}
+
+/// Patterns for well-known credential formats, paired with a human-readable label.
+const SECRET_PATTERNS: &[(&str, &str)] = &[
+    (r"sk-[A-Za-z0-9_-]{20,}", "API key (sk-...)"),
+    (r"AKIA[0-9A-Z]{16}", "AWS access key"),
+    (r"gh[pousr]_[A-Za-z0-9]{36,}", "GitHub token"),
+    (r"github_pat_[A-Za-z0-9_]{22,}", "GitHub token"),
+    (r"xox[abprs]-[A-Za-z0-9-]{10,}", "Slack token"),
+    (r"AIza[0-9A-Za-z_-]{35}", "Google API key"),
+    (
+        r"-----BEGIN ([A-Z]+ )?PRIVATE KEY-----",
+        "private key block",
+    ),
+];
+
+/// Files larger than this are skipped by the secret scanner.
+const MAX_SCAN_BYTES: u64 = 1024 * 1024;
+
+/// Collects values of environment variables whose names suggest credentials.
+///
+/// Short values are skipped to avoid flagging unrelated matches.
+pub fn sensitive_env_values() -> Vec<(String, String)> {
+    let name_regex = Regex::new(r"(?i)(KEY|TOKEN|SECRET|PASSWORD|PASSPHRASE|CREDENTIAL)")
+        .expect("valid sensitive env regex");
+    std::env::vars()
+        .filter(|(name, value)| name_regex.is_match(name) && value.len() >= 8)
+        .collect()
+}
+
+/// Scans the fixture for leaked secrets.
+///
+/// Returns `(relative_path, reason)` pairs for every file containing a known
+/// credential pattern or the value of one of `sensitive_values`. The reason
+/// never includes the secret itself.
+pub fn find_secret_leaks(
+    env_root: &Path,
+    ignore: &[String],
+    sensitive_values: &[(String, String)],
+) -> Result<Vec<(String, String)>> {
+    let patterns: Vec<(Regex, &str)> = SECRET_PATTERNS
+        .iter()
+        .map(|(pattern, label)| (Regex::new(pattern).expect("valid secret pattern"), *label))
+        .collect();
+
+    let mut files = Vec::new();
+    collect_files(env_root, env_root, ignore, &mut files)?;
+    files.sort();
+
+    let mut leaks = Vec::new();
+    for relative in files {
+        let full_path = env_root.join(&relative);
+        let Ok(bytes) = std::fs::read(&full_path) else {
+            continue;
+        };
+        let content = String::from_utf8_lossy(&bytes);
+
+        for (regex, label) in &patterns {
+            if regex.is_match(&content) {
+                leaks.push((relative.clone(), label.to_string()));
+            }
+        }
+        for (name, value) in sensitive_values {
+            if content.contains(value.as_str()) {
+                leaks.push((relative.clone(), format!("value of ${}", name)));
+            }
+        }
+    }
+
+    Ok(leaks)
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    ignore: &[String],
+    files: &mut Vec<String>,
+) -> Result<()> {
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {}", dir.display()))?
+    {
+        let entry = entry?;
+        let path = entry.path();
+        let relative_path = path.strip_prefix(root).unwrap_or(&path);
+        // Compared by component, so ignoring `vendor` keeps `vendors.txt`
+        if relative_path == Path::new(".git")
+            || ignore
+                .iter()
+                .any(|prefix| relative_path.starts_with(prefix.trim_start_matches("./")))
+        {
+            continue;
+        }
+        let relative = relative_path.to_string_lossy().to_string();
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, ignore, files)?;
+        } else if file_type.is_file() && entry.metadata()?.len() <= MAX_SCAN_BYTES {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
 /// Checks if the transcript has no errors.
 pub fn no_transcript_errors(
-    env_root: &Path,
+    transcript_path: &Path,
     target_binary: &str,
     command_pattern: Option<&str>,
 ) -> Result<bool> {
-    let transcript_path = env_root.join("transcript.raw.txt");
-    let content = std::fs::read_to_string(&transcript_path)
+    let content = crate::artifacts::read_to_string(transcript_path)
         .context("Failed to read transcript file (missing or unreadable)")?;
     let metrics = crate::transcript::TranscriptAnalyzer::analyze_with_exit_codes_for_target(
         &content,
@@ -18,17 +480,59 @@ pub fn no_transcript_errors(
     Ok(metrics.error_count == 0)
 }
 
-/// Computes efficiency metrics from the transcript.
+/// Counts how many milestones the transcript hits in sequence.
+///
+/// Each milestone regex is searched for after the end of the previous match;
+/// counting stops at the first milestone that is not found.
+pub fn count_transcript_milestones(transcript_path: &Path, milestones: &[String]) -> Result<usize> {
+    let content = crate::artifacts::read_to_string(transcript_path)
+        .context("Failed to read transcript file (missing or unreadable)")?;
+
+    let mut position = 0;
+    let mut reached = 0;
+    for milestone in milestones {
+        let regex = Regex::new(&format!("(?m){}", milestone))
+            .with_context(|| format!("Invalid milestone pattern '{}'", milestone))?;
+        match regex.find_at(&content, position) {
+            Some(found) => {
+                position = found.end();
+                reached += 1;
+            }
+            None => break,
+        }
+    }
+    Ok(reached)
+}
+
+/// Computes efficiency metrics from the transcript. `required_paths` are the
+/// files the task refers to, used to spot edits it never asked for.
 pub fn compute_efficiency_metrics(
-    env_root: &Path,
+    transcript_path: &Path,
     target_binary: &str,
     command_pattern: Option<&str>,
+    required_paths: &[String],
 ) -> Result<crate::transcript::EfficiencyMetrics> {
-    let transcript_path = env_root.join("transcript.raw.txt");
-    let content = std::fs::read_to_string(&transcript_path)
+    let content = crate::artifacts::read_to_string(transcript_path)
         .context("Failed to read transcript file for efficiency metrics")?;
+    let mut metrics = crate::transcript::TranscriptAnalyzer::analyze_with_exit_codes_for_target(
+        &content,
+        target_binary,
+        command_pattern,
+    );
+    metrics.wasted_actions =
+        crate::transcript::TranscriptAnalyzer::count_wasted_actions(&content, required_paths);
+    Ok(metrics)
+}
+
+pub fn compute_conversation_metrics(
+    transcript_path: &Path,
+    target_binary: &str,
+    command_pattern: Option<&str>,
+) -> Result<crate::transcript::ConversationMetrics> {
+    let content = crate::artifacts::read_to_string(transcript_path)
+        .context("Failed to read transcript file for conversation metrics")?;
     Ok(
-        crate::transcript::TranscriptAnalyzer::analyze_with_exit_codes_for_target(
+        crate::transcript::TranscriptAnalyzer::analyze_conversation_for_target(
             &content,
             target_binary,
             command_pattern,
@@ -36,32 +540,103 @@ pub fn compute_efficiency_metrics(
     )
 }
 
-/// Computes a composite score from judge score, gates, and efficiency metrics.
-pub fn compute_composite_score(
-    judge_score: Option<f64>,
-    gates_passed: usize,
-    gates_total: usize,
+/// Deductions `penalties` imposes for the errors and retries in `efficiency`.
+pub fn compute_score_penalties(
     efficiency: &crate::transcript::EfficiencyMetrics,
-    weights: Option<&crate::scenario::CompositeConfig>,
-) -> f64 {
-    let (judge_weight, gates_weight, efficiency_weight) = match weights {
-        Some(w) => (w.judge_weight, w.gate_weight, w.interaction_weight),
-        None => (0.55, 0.35, 0.10), // Default weights
+    penalties: &crate::scenario::PenaltyConfig,
+) -> crate::evaluation::ScorePenalties {
+    let errors = efficiency.error_count as f64 * penalties.per_error.max(0.0);
+    let retries = efficiency.retry_count as f64 * penalties.per_retry.max(0.0);
+    let total = match penalties.max_total {
+        Some(max) => (errors + retries).min(max.max(0.0)),
+        None => errors + retries,
     };
+    crate::evaluation::ScorePenalties {
+        errors,
+        retries,
+        total,
+    }
+}
 
-    let judge_component = judge_score.unwrap_or(0.0);
+/// Itemizes the composite score: each weighted component with its raw value,
+/// then the penalties `weights` configures.
+///
+/// The gate component is summed gate credit: pass/fail gates contribute 1.0
+/// or 0.0, soft-score gates their score.
+///
+/// Components without a value (no judge score, no gates) are left out and
+/// listed in `excluded`; their weight is spread over the remaining
+/// components in proportion, so a run without a judge is not scored as if
+/// the judge had given 0.0.
+pub fn compute_score_breakdown(
+    judge_score: Option<f64>,
+    gate_credit: f64,
+    gates_total: usize,
+    efficiency: &crate::transcript::EfficiencyMetrics,
+    weights: &crate::scenario::CompositeConfig,
+) -> crate::evaluation::ScoreBreakdown {
+    use crate::evaluation::{ScoreBreakdown, ScoreComponent};
 
-    let gates_component = if gates_total > 0 {
-        gates_passed as f64 / gates_total as f64
+    let candidates = [
+        (
+            "judge",
+            weights.judge_weight,
+            judge_score.map(|score| (score, format!("judge score {:.2}", score))),
+        ),
+        (
+            "gates",
+            weights.gate_weight,
+            (gates_total > 0).then(|| {
+                (
+                    gate_credit / gates_total as f64,
+                    format!("{:.2} of {} gate credit", gate_credit, gates_total),
+                )
+            }),
+        ),
+        (
+            "interaction",
+            weights.interaction_weight,
+            Some((
+                efficiency.first_try_success_rate,
+                "first-try success rate".to_string(),
+            )),
+        ),
+    ];
+    let total_weight: f64 = candidates.iter().map(|(_, weight, _)| weight).sum();
+    let available_weight: f64 = candidates
+        .iter()
+        .filter(|(_, _, value)| value.is_some())
+        .map(|(_, weight, _)| weight)
+        .sum();
+    let scale = if available_weight > 0.0 {
+        total_weight / available_weight
     } else {
         0.0
     };
 
-    let efficiency_component = efficiency.first_try_success_rate;
-
-    let composite = (judge_weight * judge_component)
-        + (gates_weight * gates_component)
-        + (efficiency_weight * efficiency_component);
+    let mut components = Vec::new();
+    let mut excluded = Vec::new();
+    for (name, weight, value) in candidates {
+        match value {
+            Some((raw, source)) => {
+                components.push(ScoreComponent::new(name, weight * scale, raw, source))
+            }
+            None => excluded.push(name.to_string()),
+        }
+    }
+    let weighted_sum = components.iter().map(|c| c.contribution).sum::<f64>();
+    let penalties = weights
+        .penalties
+        .as_ref()
+        .map(|penalties| compute_score_penalties(efficiency, penalties));
+    let deduction = penalties.as_ref().map_or(0.0, |p| p.total);
 
-    composite.clamp(0.0, 1.0)
+    ScoreBreakdown {
+        components,
+        excluded,
+        weighted_sum,
+        penalties,
+        evaluators: Vec::new(),
+        score: (weighted_sum.clamp(0.0, 1.0) - deduction).clamp(0.0, 1.0),
+    }
 }
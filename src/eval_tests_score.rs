@@ -1,8 +1,25 @@
 use crate::eval_helpers::*;
 use crate::evaluation::*;
 
+/// Composite score with the default weights, as recorded for a run.
+fn composite_score(
+    judge_score: Option<f64>,
+    gate_credit: f64,
+    gates_total: usize,
+    efficiency: &crate::transcript::EfficiencyMetrics,
+) -> f64 {
+    compute_score_breakdown(
+        judge_score,
+        gate_credit,
+        gates_total,
+        efficiency,
+        &crate::scenario::CompositeConfig::default(),
+    )
+    .score
+}
+
 #[test]
-fn test_compute_composite_score_with_judge() {
+fn test_composite_score_with_judge() {
     let efficiency = crate::transcript::EfficiencyMetrics {
         total_commands: 5,
         unique_commands: 3,
@@ -11,16 +28,37 @@ fn test_compute_composite_score_with_judge() {
         help_invocations: 0,
         first_try_success_rate: 0.8,
         iteration_ratio: 1.5,
+        wasted_actions: 0,
     };
 
-    let composite = compute_composite_score(Some(0.9), 3, 3, &efficiency, None);
+    let composite = composite_score(Some(0.9), 3.0, 3, &efficiency);
 
     let expected = (0.55 * 0.9) + (0.35 * 1.0) + (0.10 * 0.8);
     assert!((composite - expected).abs() < 0.001);
 }
 
 #[test]
-fn test_compute_composite_score_without_judge() {
+fn test_composite_score_with_partial_gate_credit() {
+    let efficiency = crate::transcript::EfficiencyMetrics {
+        total_commands: 5,
+        unique_commands: 3,
+        error_count: 0,
+        retry_count: 1,
+        help_invocations: 0,
+        first_try_success_rate: 0.8,
+        iteration_ratio: 1.5,
+        wasted_actions: 0,
+    };
+
+    // One passing gate plus one soft gate scoring 0.5
+    let composite = composite_score(Some(0.9), 1.5, 2, &efficiency);
+
+    let expected = (0.55 * 0.9) + (0.35 * 0.75) + (0.10 * 0.8);
+    assert!((composite - expected).abs() < 0.001);
+}
+
+#[test]
+fn test_composite_score_without_judge() {
     let efficiency = crate::transcript::EfficiencyMetrics {
         total_commands: 5,
         unique_commands: 3,
@@ -29,16 +67,35 @@ fn test_compute_composite_score_without_judge() {
         help_invocations: 0,
         first_try_success_rate: 0.8,
         iteration_ratio: 1.5,
+        wasted_actions: 0,
     };
 
-    let composite = compute_composite_score(None, 3, 3, &efficiency, None);
+    let composite = composite_score(None, 3.0, 3, &efficiency);
 
-    let expected = (0.55 * 0.0) + (0.35 * 1.0) + (0.10 * 0.8);
+    // The judge's weight is spread over gates and interaction, not scored as 0.0
+    let expected = ((0.35 * 1.0) + (0.10 * 0.8)) / 0.45;
     assert!((composite - expected).abs() < 0.001);
+
+    let breakdown = compute_score_breakdown(
+        None,
+        3.0,
+        3,
+        &efficiency,
+        &crate::scenario::CompositeConfig::default(),
+    );
+    assert_eq!(breakdown.excluded, vec!["judge"]);
+    let names: Vec<_> = breakdown
+        .components
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["gates", "interaction"]);
+    let weights: f64 = breakdown.components.iter().map(|c| c.weight).sum();
+    assert!((weights - 1.0).abs() < 1e-9);
 }
 
 #[test]
-fn test_compute_composite_score_empty_store() {
+fn test_composite_score_empty_store() {
     let efficiency = crate::transcript::EfficiencyMetrics {
         total_commands: 0,
         unique_commands: 0,
@@ -47,15 +104,16 @@ fn test_compute_composite_score_empty_store() {
         help_invocations: 0,
         first_try_success_rate: 0.0,
         iteration_ratio: 0.0,
+        wasted_actions: 0,
     };
 
-    let composite = compute_composite_score(None, 0, 0, &efficiency, None);
+    let composite = composite_score(None, 0.0, 0, &efficiency);
 
     assert_eq!(composite, 0.0);
 }
 
 #[test]
-fn test_compute_composite_score_clamped() {
+fn test_composite_score_clamped() {
     let efficiency = crate::transcript::EfficiencyMetrics {
         total_commands: 5,
         unique_commands: 3,
@@ -64,14 +122,81 @@ fn test_compute_composite_score_clamped() {
         help_invocations: 0,
         first_try_success_rate: 1.5,
         iteration_ratio: 1.5,
+        wasted_actions: 0,
     };
 
-    let composite = compute_composite_score(Some(1.5), 3, 3, &efficiency, None);
+    let composite = composite_score(Some(1.5), 3.0, 3, &efficiency);
 
     assert!(composite <= 1.0);
     assert!(composite >= 0.0);
 }
 
+#[test]
+fn test_compute_score_penalties_capped() {
+    let efficiency = crate::transcript::EfficiencyMetrics {
+        total_commands: 10,
+        unique_commands: 7,
+        error_count: 4,
+        retry_count: 3,
+        help_invocations: 0,
+        first_try_success_rate: 0.5,
+        iteration_ratio: 1.4,
+        wasted_actions: 0,
+    };
+    let mut penalties = crate::scenario::PenaltyConfig {
+        per_error: 0.02,
+        per_retry: 0.01,
+        max_total: None,
+    };
+
+    let uncapped = compute_score_penalties(&efficiency, &penalties);
+    assert!((uncapped.errors - 0.08).abs() < 1e-9);
+    assert!((uncapped.retries - 0.03).abs() < 1e-9);
+    assert!((uncapped.total - 0.11).abs() < 1e-9);
+
+    penalties.max_total = Some(0.1);
+    let capped = compute_score_penalties(&efficiency, &penalties);
+    assert!((capped.errors - 0.08).abs() < 1e-9);
+    assert!((capped.total - 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn test_score_breakdown_itemizes_composite() {
+    let efficiency = crate::transcript::EfficiencyMetrics {
+        total_commands: 10,
+        unique_commands: 8,
+        error_count: 3,
+        retry_count: 2,
+        help_invocations: 0,
+        first_try_success_rate: 0.5,
+        iteration_ratio: 1.25,
+        wasted_actions: 0,
+    };
+    let weights = crate::scenario::CompositeConfig {
+        penalties: Some(crate::scenario::PenaltyConfig {
+            per_error: 0.02,
+            per_retry: 0.0,
+            max_total: None,
+        }),
+        ..Default::default()
+    };
+
+    let breakdown = compute_score_breakdown(Some(0.8), 1.5, 2, &efficiency, &weights);
+
+    let names: Vec<_> = breakdown
+        .components
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["judge", "gates", "interaction"]);
+    assert!(breakdown.excluded.is_empty());
+    assert_eq!(breakdown.components[1].raw, 0.75);
+    let unpenalized = composite_score(Some(0.8), 1.5, 2, &efficiency);
+    assert!((breakdown.weighted_sum - unpenalized).abs() < 1e-9);
+    // Three errors at 0.02 each come off the recorded score
+    assert!((breakdown.score - (unpenalized - 0.06)).abs() < 1e-9);
+}
+
 #[test]
 fn test_score_tier_excellent() {
     assert_eq!(ScoreTier::from_score(0.95), ScoreTier::Excellent);
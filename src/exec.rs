@@ -0,0 +1,435 @@
+//! Process execution and time behind injectable traits.
+//!
+//! Evaluation gates, the script runner, and the adapters that spawn processes
+//! go through a [`CommandExecutor`]; retry backoff and adapter deadlines go
+//! through a [`Clock`]. Production code uses [`SystemExecutor`] and
+//! [`SystemClock`]; unit tests substitute the fakes in [`fake`] to simulate
+//! exit codes, timeouts, and slow commands without spawning real processes.
+
+use std::io::{Read, Write};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use wait_timeout::ChildExt;
+
+/// Output of a finished (or killed) command.
+#[derive(Debug, Clone)]
+pub struct ExecOutput {
+    /// Exit status and captured stdout/stderr
+    pub output: Output,
+    /// Whether the command was killed after its timeout
+    pub timed_out: bool,
+}
+
+impl ExecOutput {
+    /// Exit code, or -1 if the command timed out or was killed by a signal.
+    pub fn exit_code(&self) -> i32 {
+        if self.timed_out {
+            return -1;
+        }
+        self.output.status.code().unwrap_or(-1)
+    }
+}
+
+/// Runs commands to completion.
+pub trait CommandExecutor: Send + Sync + std::fmt::Debug {
+    /// Run `command`, writing `stdin` to it and killing it once `timeout` elapses.
+    ///
+    /// Stdout and stderr are always captured. Without `stdin` the command
+    /// reads from `/dev/null`; without `timeout` it may run indefinitely.
+    fn run(
+        &self,
+        command: Command,
+        stdin: Option<&[u8]>,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<ExecOutput>;
+
+    /// Whether commands run in a container, whose image supplies its own
+    /// `PATH` and `HOME`; host values would replace them.
+    fn runs_in_container(&self) -> bool {
+        false
+    }
+}
+
+/// Spawns real processes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemExecutor;
+
+impl CommandExecutor for SystemExecutor {
+    fn run(
+        &self,
+        mut command: Command,
+        stdin: Option<&[u8]>,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<ExecOutput> {
+        let mut child = command
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Read concurrently so large outputs cannot fill the pipe and stall the child
+        let mut stdout = child.stdout.take();
+        let mut stderr = child.stderr.take();
+        let stdout_thread = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(out) = stdout.as_mut() {
+                let _ = out.read_to_end(&mut buf);
+            }
+            buf
+        });
+        let stderr_thread = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(err) = stderr.as_mut() {
+                let _ = err.read_to_end(&mut buf);
+            }
+            buf
+        });
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            // A command may exit without reading its input
+            let _ = pipe.write_all(input);
+        }
+
+        let (status, timed_out) = match timeout {
+            None => (child.wait()?, false),
+            Some(timeout) => match child.wait_timeout(timeout) {
+                Ok(Some(status)) => (status, false),
+                Ok(None) => {
+                    let _ = child.kill();
+                    (child.wait()?, true)
+                }
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(e);
+                }
+            },
+        };
+        Ok(ExecOutput {
+            output: Output {
+                status,
+                stdout: stdout_thread.join().unwrap_or_default(),
+                stderr: stderr_thread.join().unwrap_or_default(),
+            },
+            timed_out,
+        })
+    }
+}
+
+/// Source of the current time and of delays.
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real monotonic clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Test doubles for [`CommandExecutor`] and [`Clock`].
+#[cfg(test)]
+pub mod fake {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::os::unix::process::ExitStatusExt;
+    use std::path::PathBuf;
+    use std::process::ExitStatus;
+    use std::sync::{Arc, Mutex};
+
+    /// A scripted response from [`FakeExecutor`].
+    #[derive(Debug, Clone)]
+    pub enum FakeResponse {
+        /// Exit with `code` after `duration`, printing `stdout` and `stderr`
+        Exit {
+            code: i32,
+            stdout: String,
+            stderr: String,
+            duration: Duration,
+        },
+        /// Fail to spawn, as when the program does not exist
+        SpawnError(std::io::ErrorKind),
+    }
+
+    impl FakeResponse {
+        pub fn exit(code: i32, stdout: &str) -> Self {
+            Self::Exit {
+                code,
+                stdout: stdout.to_string(),
+                stderr: String::new(),
+                duration: Duration::ZERO,
+            }
+        }
+
+        /// A command that runs for `duration` before exiting successfully.
+        pub fn slow(duration: Duration) -> Self {
+            Self::Exit {
+                code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                duration,
+            }
+        }
+
+        pub fn with_stderr(mut self, text: &str) -> Self {
+            if let Self::Exit { stderr, .. } = &mut self {
+                *stderr = text.to_string();
+            }
+            self
+        }
+    }
+
+    /// A command [`FakeExecutor`] was asked to run.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct FakeCall {
+        pub program: String,
+        pub args: Vec<String>,
+        pub cwd: Option<PathBuf>,
+        pub env: Vec<(String, String)>,
+        pub stdin: Option<String>,
+        pub timeout: Option<Duration>,
+    }
+
+    /// Replays scripted responses in order, repeating the last one, and
+    /// records every call. A response that runs longer than the call's
+    /// timeout is reported as timed out; time advances on `clock` if one is
+    /// attached.
+    #[derive(Debug, Default)]
+    pub struct FakeExecutor {
+        responses: Mutex<VecDeque<FakeResponse>>,
+        calls: Mutex<Vec<FakeCall>>,
+        clock: Option<Arc<FakeClock>>,
+    }
+
+    impl FakeExecutor {
+        pub fn new(responses: impl IntoIterator<Item = FakeResponse>) -> Self {
+            Self {
+                responses: Mutex::new(responses.into_iter().collect()),
+                ..Default::default()
+            }
+        }
+
+        pub fn with_clock(mut self, clock: Arc<FakeClock>) -> Self {
+            self.clock = Some(clock);
+            self
+        }
+
+        pub fn calls(&self) -> Vec<FakeCall> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn next_response(&self) -> FakeResponse {
+            let mut responses = self.responses.lock().unwrap();
+            match responses.len() {
+                0 => FakeResponse::exit(0, ""),
+                1 => responses[0].clone(),
+                _ => responses.pop_front().unwrap(),
+            }
+        }
+    }
+
+    fn lossy(s: &std::ffi::OsStr) -> String {
+        s.to_string_lossy().into_owned()
+    }
+
+    impl CommandExecutor for FakeExecutor {
+        fn run(
+            &self,
+            command: Command,
+            stdin: Option<&[u8]>,
+            timeout: Option<Duration>,
+        ) -> std::io::Result<ExecOutput> {
+            self.calls.lock().unwrap().push(FakeCall {
+                program: lossy(command.get_program()),
+                args: command.get_args().map(lossy).collect(),
+                cwd: command.get_current_dir().map(PathBuf::from),
+                env: command
+                    .get_envs()
+                    .filter_map(|(k, v)| Some((lossy(k), lossy(v?))))
+                    .collect(),
+                stdin: stdin.map(|s| String::from_utf8_lossy(s).into_owned()),
+                timeout,
+            });
+
+            let (code, stdout, stderr, duration) = match self.next_response() {
+                FakeResponse::SpawnError(kind) => {
+                    return Err(std::io::Error::new(kind, "fake spawn failure"))
+                }
+                FakeResponse::Exit {
+                    code,
+                    stdout,
+                    stderr,
+                    duration,
+                } => (code, stdout, stderr, duration),
+            };
+            let timed_out = timeout.is_some_and(|t| duration > t);
+            if let Some(clock) = &self.clock {
+                clock.advance(timeout.filter(|_| timed_out).unwrap_or(duration));
+            }
+            let status = if timed_out {
+                // Killed by SIGKILL
+                ExitStatus::from_raw(9)
+            } else {
+                ExitStatus::from_raw((code & 0xff) << 8)
+            };
+            Ok(ExecOutput {
+                output: Output {
+                    status,
+                    stdout: stdout.into_bytes(),
+                    stderr: stderr.into_bytes(),
+                },
+                timed_out,
+            })
+        }
+    }
+
+    /// A clock that only moves when slept on or advanced.
+    #[derive(Debug)]
+    pub struct FakeClock {
+        start: Instant,
+        elapsed: Mutex<Duration>,
+        sleeps: Mutex<Vec<Duration>>,
+    }
+
+    impl Default for FakeClock {
+        fn default() -> Self {
+            Self {
+                start: Instant::now(),
+                elapsed: Mutex::new(Duration::ZERO),
+                sleeps: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl FakeClock {
+        pub fn advance(&self, duration: Duration) {
+            *self.elapsed.lock().unwrap() += duration;
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            *self.elapsed.lock().unwrap()
+        }
+
+        /// Every delay passed to `sleep`, in order.
+        pub fn sleeps(&self) -> Vec<Duration> {
+            self.sleeps.lock().unwrap().clone()
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.sleeps.lock().unwrap().push(duration);
+            self.advance(duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::*;
+    use super::*;
+    use std::sync::Arc;
+
+    fn sh(command: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+
+    #[test]
+    fn system_executor_captures_output_and_exit_code() {
+        let result = SystemExecutor
+            .run(sh("cat; echo err >&2; exit 3"), Some(b"input"), None)
+            .unwrap();
+        assert_eq!(result.exit_code(), 3);
+        assert!(!result.timed_out);
+        assert_eq!(result.output.stdout, b"input");
+        assert_eq!(result.output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn system_executor_kills_on_timeout() {
+        let result = SystemExecutor
+            .run(sh("sleep 5"), None, Some(Duration::from_millis(100)))
+            .unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code(), -1);
+    }
+
+    #[test]
+    fn fake_executor_replays_responses_and_records_calls() {
+        let executor = FakeExecutor::new([
+            FakeResponse::exit(2, "first").with_stderr("oops"),
+            FakeResponse::exit(0, "rest"),
+        ]);
+        let mut command = sh("make test");
+        command.current_dir("/work").env("KEY", "value");
+        let first = executor.run(command, None, None).unwrap();
+        assert_eq!(first.exit_code(), 2);
+        assert!(!first.output.status.success());
+        assert_eq!(first.output.stderr, b"oops");
+        for _ in 0..2 {
+            let rest = executor.run(sh("true"), Some(b"in"), None).unwrap();
+            assert_eq!(rest.output.stdout, b"rest");
+            assert!(rest.output.status.success());
+        }
+
+        let calls = executor.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].program, "sh");
+        assert_eq!(calls[0].args, vec!["-c", "make test"]);
+        assert_eq!(calls[0].cwd, Some("/work".into()));
+        assert_eq!(calls[0].env, vec![("KEY".to_string(), "value".to_string())]);
+        assert_eq!(calls[1].stdin.as_deref(), Some("in"));
+    }
+
+    #[test]
+    fn fake_executor_simulates_slow_commands_and_timeouts() {
+        let clock = Arc::new(FakeClock::default());
+        let executor = FakeExecutor::new([FakeResponse::slow(Duration::from_secs(30))])
+            .with_clock(clock.clone());
+        let start = clock.now();
+
+        let finished = executor
+            .run(sh("slow"), None, Some(Duration::from_secs(60)))
+            .unwrap();
+        assert!(!finished.timed_out);
+        assert_eq!(clock.now() - start, Duration::from_secs(30));
+
+        let killed = executor
+            .run(sh("slow"), None, Some(Duration::from_secs(10)))
+            .unwrap();
+        assert!(killed.timed_out);
+        assert_eq!(killed.exit_code(), -1);
+        assert_eq!(clock.elapsed(), Duration::from_secs(40));
+    }
+
+    #[test]
+    fn fake_clock_records_sleeps_without_blocking() {
+        let clock = FakeClock::default();
+        let start = clock.now();
+        clock.sleep(Duration::from_secs(3600));
+        clock.sleep(Duration::from_secs(1));
+        assert_eq!(clock.now() - start, Duration::from_secs(3601));
+        assert_eq!(
+            clock.sleeps(),
+            vec![Duration::from_secs(3600), Duration::from_secs(1)]
+        );
+    }
+}
@@ -1,10 +1,22 @@
+use crate::paths::confine;
 use crate::run::utils::copy_dir_recursive;
 use crate::utils::resolve_fixtures_path;
+use anyhow::Context;
 use std::fs;
 use std::path::PathBuf;
 
+/// Subdirectories created inside an isolated HOME, keyed by the XDG variable pointing at them.
+const XDG_DIRS: &[(&str, &str)] = &[
+    ("XDG_CONFIG_HOME", ".config"),
+    ("XDG_DATA_HOME", ".local/share"),
+    ("XDG_STATE_HOME", ".local/state"),
+    ("XDG_CACHE_HOME", ".cache"),
+];
+
 pub struct TestEnv {
     pub root: PathBuf,
+    /// Isolated HOME directory for the tool under test, if isolation is enabled
+    pub home: Option<PathBuf>,
 }
 
 impl TestEnv {
@@ -13,7 +25,7 @@ impl TestEnv {
             fs::remove_dir_all(&root)?;
         }
         fs::create_dir_all(&root)?;
-        Ok(Self { root })
+        Ok(Self { root, home: None })
     }
 
     pub fn setup_fixture(&self, fixture_name: &str) -> anyhow::Result<()> {
@@ -22,7 +34,111 @@ impl TestEnv {
         if !fixture_src.exists() {
             anyhow::bail!("Fixture not found: {:?}", fixture_src);
         }
+        let fixture_src = confine(&fixture_src, &templates_base)
+            .with_context(|| format!("Invalid template_folder '{}'", fixture_name))?;
         copy_dir_recursive(&fixture_src, &self.root)?;
         Ok(())
     }
+
+    /// Create a fresh HOME directory for the run, optionally seeded from a template folder.
+    pub fn setup_isolated_home(
+        &mut self,
+        home: PathBuf,
+        home_template: Option<&str>,
+    ) -> anyhow::Result<()> {
+        if home.exists() {
+            fs::remove_dir_all(&home)?;
+        }
+        fs::create_dir_all(&home)?;
+        for (_, subdir) in XDG_DIRS {
+            fs::create_dir_all(home.join(subdir))?;
+        }
+
+        if let Some(template) = home_template {
+            let template_src = resolve_fixtures_path("templates").join(template);
+            if !template_src.exists() {
+                anyhow::bail!("Home template not found: {:?}", template_src);
+            }
+            let template_src = confine(&template_src, &resolve_fixtures_path("templates"))
+                .with_context(|| format!("Invalid home_template '{}'", template))?;
+            copy_dir_recursive(&template_src, &home)?;
+        }
+
+        self.home = Some(home.canonicalize().unwrap_or(home));
+        Ok(())
+    }
+
+    /// Environment variables pointing HOME and XDG directories at the isolated HOME.
+    ///
+    /// Returns an empty list when isolation is disabled.
+    pub fn isolation_env(&self) -> Vec<(String, String)> {
+        let Some(home) = &self.home else {
+            return Vec::new();
+        };
+
+        let mut vars = vec![("HOME".to_string(), home.to_string_lossy().to_string())];
+        for (var, subdir) in XDG_DIRS {
+            vars.push((
+                var.to_string(),
+                home.join(subdir).to_string_lossy().to_string(),
+            ));
+        }
+        vars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn isolation_env_is_empty_without_isolated_home() {
+        let dir = tempdir().unwrap();
+        let env = TestEnv::new(dir.path().join("fixture")).unwrap();
+        assert!(env.isolation_env().is_empty());
+    }
+
+    #[test]
+    fn isolated_home_sets_home_and_xdg_vars() {
+        let dir = tempdir().unwrap();
+        let mut env = TestEnv::new(dir.path().join("fixture")).unwrap();
+        env.setup_isolated_home(dir.path().join("home"), None)
+            .unwrap();
+
+        let vars = env.isolation_env();
+        let home = env.home.clone().unwrap();
+        assert!(vars.contains(&("HOME".to_string(), home.to_string_lossy().to_string())));
+        let config_home = vars
+            .iter()
+            .find(|(k, _)| k == "XDG_CONFIG_HOME")
+            .map(|(_, v)| PathBuf::from(v))
+            .unwrap();
+        assert!(config_home.starts_with(&home));
+        assert!(config_home.is_dir());
+    }
+
+    #[test]
+    fn home_template_outside_templates_dir_fails() {
+        let dir = tempdir().unwrap();
+        let mut env = TestEnv::new(dir.path().join("fixture")).unwrap();
+        let outside = tempdir().unwrap();
+        let result = env.setup_isolated_home(
+            dir.path().join("home"),
+            Some(outside.path().to_str().unwrap()),
+        );
+        let err = format!("{:#}", result.unwrap_err());
+        assert!(err.contains("Invalid home_template"), "{}", err);
+    }
+
+    #[test]
+    fn isolated_home_missing_template_fails() {
+        let dir = tempdir().unwrap();
+        let mut env = TestEnv::new(dir.path().join("fixture")).unwrap();
+        let result = env.setup_isolated_home(
+            dir.path().join("home"),
+            Some("definitely-missing-home-template"),
+        );
+        assert!(result.is_err());
+    }
 }
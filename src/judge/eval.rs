@@ -1,203 +1,279 @@
-//! Evaluation execution for LLM-as-judge.
+//! Direct API backend for LLM-as-judge.
 //!
-//! This module provides functionality for running LLM-based evaluations
-//! using OpenAI API or custom API endpoints.
+//! With `[judge] backend = "api"` the task, rubric criteria and transcript
+//! are sent to an OpenAI-compatible chat completions endpoint in a single
+//! request, so judging needs no agent CLI. The default `cli` backend instead
+//! asks `opencode` to read the transcript and rubric files itself.
+//!
+//! Both backends parse the judge's reply with [`judge_with_retries`], which
+//! finds the judge response JSON even when the model wraps it in prose or a
+//! code fence, and otherwise asks the model again for JSON only.
 
-#[cfg(test)]
+use crate::config::{JudgeBackendConfig, DEFAULT_OPENAI_BASE_URL};
 use crate::judge::types::{JudgeResponse, Rubric};
-#[cfg(test)]
+use crate::locale::Locale;
 use anyhow::{Context, Result};
+use std::time::Duration;
 
-/// Run LLM-as-judge evaluation via OpenAI API.
-///
-/// This is a test-only implementation for future use. The production
-/// evaluation system uses CLI-based judge execution instead.
-///
-/// # Arguments
-///
-/// * `model` - OpenAI model to use (e.g., "gpt-4o-mini")
-/// * `transcript_summary` - Summary of the LLM tool interaction
-/// * `store_export` - JSON export of the qipu store state
-/// * `task_description` - Description of the task that was performed
-/// * `rubric` - Evaluation rubric with criteria
-///
-/// # Returns
-///
-/// * `Ok(JudgeResponse)` - Parsed judge evaluation
-/// * `Err` - API error or response parsing error
-///
-/// # Environment Variables
-///
-/// Requires either `OPENAI_API_KEY` or `LLM_TOOL_TEST_API_KEY` to be set.
-#[cfg(test)]
-pub async fn run_judge(
-    model: &str,
-    transcript_summary: &str,
-    store_export: &str,
-    task_description: &str,
-    rubric: &Rubric,
-) -> Result<JudgeResponse> {
-    let api_key = std::env::var("OPENAI_API_KEY")
-        .or_else(|_| std::env::var("LLM_TOOL_TEST_API_KEY"))
-        .context("OPENAI_API_KEY or LLM_TOOL_TEST_API_KEY environment variable must be set")?;
-
-    let client = reqwest::Client::new();
-
-    let prompt = build_judge_prompt(transcript_summary, store_export, task_description, rubric);
-
-    let request_body = serde_json::json!({
-        "model": model,
-        "messages": [
-            {
-                "role": "system",
-                "content": "You are an expert evaluator. Analyze the provided transcript and store state against the given rubric. Return your evaluation as JSON only."
-            },
-            {
-                "role": "user",
-                "content": prompt
-            }
-        ],
-        "response_format": { "type": "json_object" },
-        "temperature": 0.3,
-        "max_tokens": 2000,
-    });
-
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&request_body)
-        .send()
-        .await
-        .context("Failed to call OpenAI API")?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        anyhow::bail!("OpenAI API request failed: {} - {}", status, error_text);
-    }
+/// Characters of transcript sent to the judge; longer transcripts keep their end.
+const MAX_TRANSCRIPT_CHARS: usize = 100_000;
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .context("Failed to parse OpenAI API response")?;
+/// Characters of an unreadable judge reply quoted in errors.
+const MAX_QUOTED_REPLY_CHARS: usize = 500;
 
-    let content = response_json
-        .get("choices")
-        .and_then(|c| c.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|c| c.get("message"))
-        .and_then(|m| m.get("content"))
-        .and_then(|c| c.as_str())
-        .context("Invalid OpenAI API response format")?;
+const SYSTEM_PROMPT: &str = "You are an expert evaluator. Analyze the provided transcript \
+against the given rubric. Return your evaluation as JSON only.";
 
-    let judge_response: JudgeResponse = serde_json::from_str(content)
-        .with_context(|| format!("Failed to parse judge response JSON: {}", content))?;
-
-    Ok(judge_response)
-}
-
-#[cfg(test)]
+/// Build the `judge.api_prompt` message, with the rubric criteria and the
+/// transcript inlined.
 pub fn build_judge_prompt(
-    transcript_summary: &str,
-    store_export: &str,
-    task_description: &str,
+    locale: &Locale,
+    task: &str,
+    transcript: &str,
     rubric: &Rubric,
 ) -> String {
-    let criteria_text = rubric
+    let criteria = rubric
         .criteria
         .iter()
         .map(|c| format!("- {}: {} (weight: {:.2})", c.id, c.description, c.weight))
         .collect::<Vec<_>>()
         .join("\n");
+    locale.format(
+        "judge.api_prompt",
+        &[
+            ("task", &task),
+            ("criteria", &criteria),
+            ("transcript", &truncate_transcript(transcript)),
+        ],
+    )
+}
 
+fn truncate_transcript(transcript: &str) -> String {
+    let count = transcript.chars().count();
+    if count <= MAX_TRANSCRIPT_CHARS {
+        return transcript.to_string();
+    }
+    let omitted = count - MAX_TRANSCRIPT_CHARS;
+    let kept: String = transcript.chars().skip(omitted).collect();
     format!(
-        r#"Evaluate the following LLM tool interaction.
-
-# Task
-{}
-
-# Transcript Summary
-{}
-
-# Store State (JSON)
-{}
-
-# Evaluation Criteria
-{}
-
-# Required Response Format
-Return JSON with this exact structure:
-{{
-  "scores": {{
-    "criterion_id": <score_0_to_1>,
-    ...
-  }},
-  "weighted_score": <weighted_average_0_to_1>,
-  "confidence": <confidence_0_to_1>,
-  "issues": ["issue1", "issue2", ...],
-  "highlights": ["good_practice1", "good_practice2", ...]
-}}
-
-Provide JSON only, no additional text."#,
-        task_description, transcript_summary, store_export, criteria_text
+        "[transcript truncated: first {} characters omitted]\n{}",
+        omitted, kept
     )
 }
 
-/// Run judge with a custom API client for testing.
+/// A judge reply that held no judge response, and the `judge.retry_prompt`
+/// message asking for JSON only.
+pub struct JudgeRetry<'a> {
+    pub reply: &'a str,
+    pub feedback: &'a str,
+}
+
+/// Get a judge response from `ask`, which sends the judge prompt (followed
+/// by a [`JudgeRetry`] when given) and returns the model's reply.
 ///
-/// Test helper that allows specifying a custom API base URL and key,
-/// useful for mocking in tests.
-#[cfg(test)]
-pub async fn run_judge_with_client(
-    model: &str,
-    transcript_summary: &str,
-    store_export: &str,
-    task_description: &str,
-    rubric: &Rubric,
-    api_base: &str,
-    api_key: &str,
+/// A reply without a judge response is answered up to `retries` times
+/// before failing. Errors from `ask` itself, such as a failed request, are
+/// returned at once.
+pub fn judge_with_retries(
+    locale: &Locale,
+    retries: u32,
+    mut ask: impl FnMut(Option<JudgeRetry<'_>>) -> Result<String>,
 ) -> Result<JudgeResponse> {
-    use reqwest::Client;
-
-    let client = Client::new();
-    let prompt = build_judge_prompt(transcript_summary, store_export, task_description, rubric);
-
-    let request_body = serde_json::json!({
-        "model": model,
-        "messages": [
-            {
-                "role": "system",
-                "content": "You are an expert evaluator. Analyze the provided transcript and store state against the given rubric. Return your evaluation as JSON only."
-            },
-            {
-                "role": "user",
-                "content": prompt
+    let mut reply = ask(None)?;
+    let mut attempt = 0;
+    loop {
+        let error = match parse_judge_response(&reply) {
+            Ok(response) => return Ok(response),
+            Err(e) => e,
+        };
+        if attempt == retries {
+            return Err(error).with_context(|| {
+                format!(
+                    "Failed to parse judge response JSON after {} attempt(s)",
+                    attempt + 1
+                )
+            });
+        }
+        attempt += 1;
+        println!(
+            "Judge reply held no valid JSON ({:#}); asking again ({}/{})",
+            error, attempt, retries
+        );
+        let feedback = locale.format("judge.retry_prompt", &[("error", &format!("{:#}", error))]);
+        reply = ask(Some(JudgeRetry {
+            reply: &reply,
+            feedback: &feedback,
+        }))?;
+    }
+}
+
+/// Parse a judge reply: the whole reply as JSON, else the first JSON object
+/// in it that is a judge response, such as one inside a Markdown code fence
+/// or after a sentence of explanation.
+pub fn parse_judge_response(reply: &str) -> Result<JudgeResponse> {
+    if let Ok(response) = serde_json::from_str(reply.trim()) {
+        return Ok(response);
+    }
+    let mut first_error = None;
+    for object in json_objects(reply) {
+        match serde_json::from_str(object) {
+            Ok(response) => return Ok(response),
+            Err(e) => {
+                first_error.get_or_insert(e);
             }
-        ],
+        }
+    }
+    match first_error {
+        Some(e) => Err(e).with_context(|| {
+            format!(
+                "JSON in judge reply is not a judge response: {}",
+                quote_reply(reply)
+            )
+        }),
+        None => anyhow::bail!("Judge reply holds no JSON object: {}", quote_reply(reply)),
+    }
+}
+
+/// Top-level JSON objects embedded in `text`, in order. A `{` that does not
+/// open a valid object, like one in prose, is skipped.
+fn json_objects(text: &str) -> Vec<&str> {
+    let mut objects = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = text[from..].find('{') {
+        let start = from + offset;
+        match object_end(&text[start..])
+            .map(|len| &text[start..start + len])
+            .filter(|object| serde_json::from_str::<serde_json::Value>(object).is_ok())
+        {
+            Some(object) => {
+                objects.push(object);
+                from = start + object.len();
+            }
+            None => from = start + 1,
+        }
+    }
+    objects
+}
+
+/// Length of the balanced `{...}` at the start of `text`, skipping braces
+/// inside JSON strings.
+fn object_end(text: &str) -> Option<usize> {
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    for (index, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `reply` for an error message, shortened to its start.
+fn quote_reply(reply: &str) -> String {
+    let reply = reply.trim();
+    match reply.char_indices().nth(MAX_QUOTED_REPLY_CHARS) {
+        Some((index, _)) => format!("{}...", &reply[..index]),
+        None => reply.to_string(),
+    }
+}
+
+/// Send `prompt` to the configured endpoint and parse the judge's verdict,
+/// continuing the conversation with `judge.retry_prompt` while the reply
+/// holds no judge response.
+///
+/// The API key is read from `api_key_env`. It may be unset for endpoints
+/// other than the OpenAI default, such as a local server without auth.
+///
+/// Returns the verdict and its cost in USD: the token usage the endpoint
+/// reported over all requests, at the configured prices.
+pub fn run_judge(
+    config: &JudgeBackendConfig,
+    locale: &Locale,
+    prompt: &str,
+) -> Result<(JudgeResponse, Option<f64>)> {
+    let api_key = std::env::var(config.api_key_env())
+        .ok()
+        .filter(|key| !key.is_empty());
+    if api_key.is_none() && config.base_url() == DEFAULT_OPENAI_BASE_URL {
+        anyhow::bail!(
+            "{} environment variable must be set for the api judge backend",
+            config.api_key_env()
+        );
+    }
+
+    let client = reqwest::blocking::Client::new();
+    let mut usage: Option<(u64, u64)> = None;
+    let response = judge_with_retries(locale, config.parse_retries(), |retry| {
+        let mut messages = vec![
+            serde_json::json!({ "role": "system", "content": SYSTEM_PROMPT }),
+            serde_json::json!({ "role": "user", "content": prompt }),
+        ];
+        if let Some(retry) = retry {
+            messages.push(serde_json::json!({ "role": "assistant", "content": retry.reply }));
+            messages.push(serde_json::json!({ "role": "user", "content": retry.feedback }));
+        }
+        let (reply, reply_usage) =
+            request_completion(config, &client, api_key.as_deref(), &messages)?;
+        if let Some((input, output)) = reply_usage {
+            let total = usage.get_or_insert((0, 0));
+            total.0 += input;
+            total.1 += output;
+        }
+        Ok(reply)
+    })?;
+    let cost = usage.and_then(|(input, output)| config.cost(input, output));
+    Ok((response, cost))
+}
+
+/// Send one chat completion request and return the reply's content, with
+/// the prompt and completion tokens if the endpoint reported them.
+fn request_completion(
+    config: &JudgeBackendConfig,
+    client: &reqwest::blocking::Client,
+    api_key: Option<&str>,
+    messages: &[serde_json::Value],
+) -> Result<(String, Option<(u64, u64)>)> {
+    let body = serde_json::json!({
+        "model": config.model(),
+        "messages": messages,
         "response_format": { "type": "json_object" },
         "temperature": 0.3,
         "max_tokens": 2000,
     });
 
-    let response = client
-        .post(format!("{}/v1/chat/completions", api_base))
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&request_body)
-        .send()
-        .await
-        .context("Failed to call OpenAI API")?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        anyhow::bail!("OpenAI API request failed: {} - {}", status, error_text);
+    let mut request = client
+        .post(format!("{}/chat/completions", config.base_url()))
+        .timeout(Duration::from_secs(config.timeout_secs()))
+        .json(&body);
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
     }
+    let response = request.send().context("Failed to call OpenAI API")?;
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .context("Failed to parse OpenAI API response")?;
+    let status = response.status();
+    let text = response.text().unwrap_or_default();
+    if !status.is_success() {
+        anyhow::bail!("OpenAI API request failed: {} - {}", status, text.trim());
+    }
 
+    let response_json: serde_json::Value =
+        serde_json::from_str(&text).context("Failed to parse OpenAI API response")?;
     let content = response_json
         .get("choices")
         .and_then(|c| c.as_array())
@@ -206,9 +282,11 @@ pub async fn run_judge_with_client(
         .and_then(|m| m.get("content"))
         .and_then(|c| c.as_str())
         .context("Invalid OpenAI API response format")?;
-
-    let judge_response: JudgeResponse = serde_json::from_str(content)
-        .with_context(|| format!("Failed to parse judge response JSON: {}", content))?;
-
-    Ok(judge_response)
+    let usage = response_json.get("usage").and_then(|u| {
+        Some((
+            u.get("prompt_tokens")?.as_u64()?,
+            u.get("completion_tokens")?.as_u64()?,
+        ))
+    });
+    Ok((content.to_string(), usage))
 }
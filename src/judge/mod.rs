@@ -1,8 +1,8 @@
 //! Judge module for LLM-as-judge evaluation.
 //!
 //! This module provides rubric-based evaluation functionality for assessing
-//! LLM tool performance. Currently supports loading rubrics from YAML files
-//! with future support for direct LLM API-based evaluation.
+//! LLM tool performance: loading rubrics from YAML files and judging
+//! transcripts through an OpenAI-compatible API (see [`eval`]).
 //!
 //! # Example
 //!
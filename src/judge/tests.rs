@@ -1,10 +1,36 @@
 //! Tests for judge module.
 
-use super::eval::{build_judge_prompt, run_judge_with_client};
+use super::eval::{build_judge_prompt, judge_with_retries, parse_judge_response, run_judge};
 use super::rubric::load_rubric;
-use super::types::{Criterion, OutputFormat, Rubric};
+use super::types::{Criterion, JudgeResponse, OutputFormat, Rubric};
+use crate::config::JudgeBackendConfig;
+use crate::locale::Locale;
 use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
 
+/// Judge against the mock server with API key `test-key`, off the async
+/// runtime since the client blocks.
+async fn judge_with(server: &MockServer) -> anyhow::Result<JudgeResponse> {
+    judge_with_config(server, JudgeBackendConfig::default())
+        .await
+        .map(|(response, _)| response)
+}
+
+/// [`judge_with`] on top of `config`, also returning the judge cost.
+async fn judge_with_config(
+    server: &MockServer,
+    config: JudgeBackendConfig,
+) -> anyhow::Result<(JudgeResponse, Option<f64>)> {
+    std::env::set_var("LLM_TOOL_TEST_JUDGE_KEY", "test-key");
+    let config = JudgeBackendConfig {
+        base_url: Some(format!("{}/v1", server.uri())),
+        api_key_env: Some("LLM_TOOL_TEST_JUDGE_KEY".to_string()),
+        ..config
+    };
+    tokio::task::spawn_blocking(move || run_judge(&config, &Locale::default(), "prompt"))
+        .await
+        .unwrap()
+}
+
 #[test]
 fn test_build_judge_prompt() {
     let rubric = Rubric {
@@ -19,11 +45,18 @@ fn test_build_judge_prompt() {
         },
     };
 
-    let prompt = build_judge_prompt("Test task", "Test transcript", "{}", &rubric);
+    let prompt = build_judge_prompt(&Locale::default(), "Test task", "Test transcript", &rubric);
+
+    assert!(prompt.contains("Task: Test task"));
+    assert!(prompt.contains("Transcript:\nTest transcript"));
+    assert!(prompt.contains("- test_criterion: Test description (weight: 1.00)"));
+    assert!(prompt.contains("\"weighted_score\""));
 
-    assert!(prompt.contains("Test task"));
-    assert!(prompt.contains("Test transcript"));
-    assert!(prompt.contains("test_criterion"));
+    // Long transcripts keep their end, where the outcome is
+    let long = format!("{}the end", "x".repeat(200_000));
+    let prompt = build_judge_prompt(&Locale::default(), "Test task", &long, &rubric);
+    assert!(prompt.contains("[transcript truncated: first 100007 characters omitted]"));
+    assert!(prompt.contains("the end\n"));
 }
 
 #[test]
@@ -79,26 +112,14 @@ output:
     assert!(result.unwrap_err().to_string().contains("must sum to 1.0"));
 }
 
-#[tokio::test]
-async fn test_run_judge_missing_api_key() {
-    use super::eval::run_judge;
-
-    let rubric = Rubric {
-        criteria: vec![Criterion {
-            id: "test".to_string(),
-            weight: 1.0,
-            description: "Test".to_string(),
-        }],
-        output: OutputFormat {
-            format: "json".to_string(),
-            require_fields: vec![],
-        },
+#[test]
+fn test_run_judge_missing_api_key() {
+    let config = JudgeBackendConfig {
+        api_key_env: Some("LLM_TOOL_TEST_JUDGE_UNSET_KEY".to_string()),
+        ..Default::default()
     };
 
-    std::env::remove_var("OPENAI_API_KEY");
-    std::env::remove_var("LLM_TOOL_TEST_API_KEY");
-
-    let result = run_judge("gpt-4o-mini", "transcript", "{}", "task", &rubric).await;
+    let result = run_judge(&config, &Locale::default(), "prompt");
 
     assert!(result.is_err());
     assert!(result
@@ -110,19 +131,6 @@ async fn test_run_judge_missing_api_key() {
 #[tokio::test]
 async fn test_run_judge_success() {
     let mock_server = MockServer::start().await;
-    let api_url = mock_server.uri();
-
-    let rubric = Rubric {
-        criteria: vec![Criterion {
-            id: "test_criterion".to_string(),
-            weight: 1.0,
-            description: "Test criterion".to_string(),
-        }],
-        output: OutputFormat {
-            format: "json".to_string(),
-            require_fields: vec![],
-        },
-    };
 
     let mock_response = serde_json::json!({
         "choices": [{
@@ -145,40 +153,23 @@ async fn test_run_judge_success() {
         .mount(&mock_server)
         .await;
 
-    let result = run_judge_with_client(
-        "gpt-4o-mini",
-        "transcript summary",
-        r#"{"notes": []}"#,
-        "task description",
-        &rubric,
-        &api_url,
-        "test-key",
-    )
-    .await;
+    let result = judge_with(&mock_server).await;
 
     assert!(result.is_ok());
     let response = result.unwrap();
     assert_eq!(response.scores.get("test_criterion"), Some(&0.85));
     assert_eq!(response.weighted_score, 0.85);
     assert_eq!(response.confidence, 0.9);
+
+    let requests = mock_server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["model"], "gpt-4o-mini");
+    assert_eq!(body["messages"][1]["content"], "prompt");
 }
 
 #[tokio::test]
 async fn test_run_judge_api_error() {
     let mock_server = MockServer::start().await;
-    let api_url = mock_server.uri();
-
-    let rubric = Rubric {
-        criteria: vec![Criterion {
-            id: "test".to_string(),
-            weight: 1.0,
-            description: "Test".to_string(),
-        }],
-        output: OutputFormat {
-            format: "json".to_string(),
-            require_fields: vec![],
-        },
-    };
 
     Mock::given(matchers::method("POST"))
         .and(matchers::path("/v1/chat/completions"))
@@ -188,16 +179,7 @@ async fn test_run_judge_api_error() {
         .mount(&mock_server)
         .await;
 
-    let result = run_judge_with_client(
-        "gpt-4o-mini",
-        "transcript",
-        "{}",
-        "task",
-        &rubric,
-        &api_url,
-        "test-key",
-    )
-    .await;
+    let result = judge_with(&mock_server).await;
 
     assert!(result.is_err());
     let err_msg = result.unwrap_err().to_string();
@@ -208,19 +190,6 @@ async fn test_run_judge_api_error() {
 #[tokio::test]
 async fn test_run_judge_invalid_response_format() {
     let mock_server = MockServer::start().await;
-    let api_url = mock_server.uri();
-
-    let rubric = Rubric {
-        criteria: vec![Criterion {
-            id: "test".to_string(),
-            weight: 1.0,
-            description: "Test".to_string(),
-        }],
-        output: OutputFormat {
-            format: "json".to_string(),
-            require_fields: vec![],
-        },
-    };
 
     Mock::given(matchers::method("POST"))
         .and(matchers::path("/v1/chat/completions"))
@@ -230,16 +199,7 @@ async fn test_run_judge_invalid_response_format() {
         .mount(&mock_server)
         .await;
 
-    let result = run_judge_with_client(
-        "gpt-4o-mini",
-        "transcript",
-        "{}",
-        "task",
-        &rubric,
-        &api_url,
-        "test-key",
-    )
-    .await;
+    let result = judge_with(&mock_server).await;
 
     assert!(result.is_err());
     let err_msg = result.unwrap_err().to_string();
@@ -249,19 +209,6 @@ async fn test_run_judge_invalid_response_format() {
 #[tokio::test]
 async fn test_run_judge_missing_content_field() {
     let mock_server = MockServer::start().await;
-    let api_url = mock_server.uri();
-
-    let rubric = Rubric {
-        criteria: vec![Criterion {
-            id: "test".to_string(),
-            weight: 1.0,
-            description: "Test".to_string(),
-        }],
-        output: OutputFormat {
-            format: "json".to_string(),
-            require_fields: vec![],
-        },
-    };
 
     Mock::given(matchers::method("POST"))
         .and(matchers::path("/v1/chat/completions"))
@@ -271,18 +218,149 @@ async fn test_run_judge_missing_content_field() {
         .mount(&mock_server)
         .await;
 
-    let result = run_judge_with_client(
-        "gpt-4o-mini",
-        "transcript",
-        "{}",
-        "task",
-        &rubric,
-        &api_url,
-        "test-key",
-    )
-    .await;
+    let result = judge_with(&mock_server).await;
 
     assert!(result.is_err());
     let err_msg = result.unwrap_err().to_string();
     assert!(err_msg.contains("Invalid OpenAI API response format"));
 }
+
+const VERDICT: &str = r#"{"scores": {"quality": 0.8}, "weighted_score": 0.8, "confidence": 0.9, "issues": ["a {brace} in a string"], "highlights": []}"#;
+
+#[test]
+fn test_parse_judge_response_finds_json_in_prose() {
+    let fenced = format!(
+        "Here is my evaluation of {{task}}:\n\n```json\n{}\n```\nLet me know if you need more.",
+        VERDICT
+    );
+    let response = parse_judge_response(&fenced).unwrap();
+    assert_eq!(response.weighted_score, 0.8);
+    assert_eq!(response.issues, ["a {brace} in a string"]);
+
+    // Other JSON objects before the verdict are passed over
+    let response = parse_judge_response(&format!("{{\"step\": 1}} then {}", VERDICT)).unwrap();
+    assert_eq!(response.confidence, 0.9);
+
+    let err = parse_judge_response("I cannot evaluate this.").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Judge reply holds no JSON object: I cannot evaluate this."
+    );
+    let err = parse_judge_response(r#"Result: {"score": 0.8}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("JSON in judge reply is not a judge response"));
+}
+
+#[test]
+fn test_judge_with_retries_asks_for_json_only() {
+    let mut retries = Vec::new();
+    let response = judge_with_retries(&Locale::default(), 2, |retry| {
+        Ok(match retry {
+            None => "Sure! The agent did well.".to_string(),
+            Some(retry) => {
+                retries.push((retry.reply.to_string(), retry.feedback.to_string()));
+                VERDICT.to_string()
+            }
+        })
+    })
+    .unwrap();
+    assert_eq!(response.weighted_score, 0.8);
+    assert_eq!(retries.len(), 1);
+    assert_eq!(retries[0].0, "Sure! The agent did well.");
+    assert!(retries[0]
+        .1
+        .contains("could not be read as the evaluation JSON: Judge reply holds no JSON object"));
+
+    let mut calls = 0;
+    let err = judge_with_retries(&Locale::default(), 1, |_| {
+        calls += 1;
+        Ok("no".to_string())
+    })
+    .unwrap_err();
+    assert_eq!(calls, 2);
+    assert_eq!(
+        err.to_string(),
+        "Failed to parse judge response JSON after 2 attempt(s)"
+    );
+
+    // Failures to reach the judge are not retried
+    let mut calls = 0;
+    let err = judge_with_retries(&Locale::default(), 3, |_| {
+        calls += 1;
+        anyhow::bail!("connection refused")
+    })
+    .unwrap_err();
+    assert_eq!((calls, err.to_string().as_str()), (1, "connection refused"));
+}
+
+#[tokio::test]
+async fn test_run_judge_retries_with_conversation() {
+    let mock_server = MockServer::start().await;
+    let reply = |content: &str| {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{"message": {"content": content}}]
+        }))
+    };
+
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(reply("The transcript looks good overall."))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(reply(VERDICT))
+        .mount(&mock_server)
+        .await;
+
+    let response = judge_with(&mock_server).await.unwrap();
+    assert_eq!(response.weighted_score, 0.8);
+
+    let requests = mock_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    let messages = body["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 4);
+    assert_eq!(messages[2]["role"], "assistant");
+    assert_eq!(messages[2]["content"], "The transcript looks good overall.");
+    assert!(messages[3]["content"]
+        .as_str()
+        .unwrap()
+        .starts_with("Your previous reply could not be read as the evaluation JSON"));
+}
+
+#[tokio::test]
+async fn test_run_judge_costs_usage_over_all_requests() {
+    let mock_server = MockServer::start().await;
+    let reply = |content: &str| {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{"message": {"content": content}}],
+            "usage": {"prompt_tokens": 1000, "completion_tokens": 100}
+        }))
+    };
+    Mock::given(matchers::method("POST"))
+        .respond_with(reply("Looks good."))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .respond_with(reply(VERDICT))
+        .mount(&mock_server)
+        .await;
+
+    let priced = JudgeBackendConfig {
+        input_usd_per_mtok: Some(1.0),
+        output_usd_per_mtok: Some(10.0),
+        ..Default::default()
+    };
+    let (_, cost) = judge_with_config(&mock_server, priced).await.unwrap();
+    assert!((cost.unwrap() - 0.004).abs() < 1e-12);
+
+    // Without prices the cost is unknown
+    let (_, cost) = judge_with_config(&mock_server, JudgeBackendConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(cost, None);
+}
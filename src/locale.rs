@@ -0,0 +1,340 @@
+//! Localization of generated reports and judge prompts.
+//!
+//! Messages are looked up by dotted key (`report.title`). English is built
+//! in; other languages are loaded from `<templates_dir>/locales/<language>.toml`,
+//! where each table level adds a key segment:
+//!
+//! ```toml
+//! [report]
+//! title = "Testlauf-Bericht"
+//! ```
+//!
+//! Keys missing from a locale file fall back to English.
+
+use crate::config::ReportConfig;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// Language whose messages are built in.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Built-in English messages.
+const ENGLISH: &[(&str, &str)] = &[
+    ("report.title", "Test Run Report"),
+    ("report.scenario", "Scenario"),
+    ("report.execution", "Execution"),
+    ("report.setup_commands", "Setup Commands"),
+    ("report.evaluation_metrics", "Evaluation Metrics"),
+    ("report.gate_details", "Gate Details"),
+    ("report.efficiency", "Efficiency"),
+    ("field.id", "ID"),
+    ("field.scenario", "Scenario"),
+    ("field.tool", "Tool"),
+    ("field.model", "Model"),
+    ("field.timestamp", "Timestamp"),
+    ("field.duration", "Duration"),
+    ("field.cost", "Cost"),
+    ("field.setup", "Setup"),
+    ("field.token_usage", "Token Usage"),
+    ("field.outcome", "Outcome"),
+    ("field.gates_passed", "Gates Passed"),
+    ("field.composite_score", "Composite Score"),
+    ("field.score_penalties", "Score Penalties"),
+    ("field.weighted_sum", "Weighted Sum"),
+    ("field.excluded", "Excluded"),
+    ("field.component", "Component"),
+    ("field.weight", "Weight"),
+    ("field.value", "Value"),
+    ("field.contribution", "Contribution"),
+    ("field.source", "Source"),
+    ("field.judge", "Judge"),
+    ("field.rubric", "Rubric"),
+    ("field.threshold", "Threshold"),
+    ("field.verdict", "Verdict"),
+    ("field.aggregate", "Aggregate"),
+    ("field.disagreement", "Disagreement"),
+    ("field.total_commands", "Total Commands"),
+    ("field.unique_commands", "Unique Commands"),
+    ("field.error_count", "Error Count"),
+    ("field.first_try_success_rate", "First Try Success Rate"),
+    ("field.iteration_ratio", "Iteration Ratio"),
+    ("field.wasted_actions", "Wasted Actions"),
+    ("value.success", "Success"),
+    ("value.failed", "Failed"),
+    ("value.token_usage", "{input} input, {output} output"),
+    ("value.gate_score", "score {score}"),
+    ("value.pass", "Pass"),
+    ("value.fail", "Fail"),
+    (
+        "value.judge_aggregate",
+        "{method} score {score} against threshold {threshold}: {verdict}",
+    ),
+    (
+        "value.judge_disagreement",
+        "spread {spread}, {passing}/{scored} judges passed",
+    ),
+    (
+        "value.excluded_components",
+        "{components} (weight spread over the other components)",
+    ),
+    (
+        "value.score_penalties",
+        "-{total} (errors -{errors}, retries -{retries})",
+    ),
+    ("evaluation.title", "Evaluation"),
+    ("evaluation.summary", "Summary"),
+    ("evaluation.judge_score", "Judge Score"),
+    ("evaluation.metrics", "Metrics"),
+    ("evaluation.judge_feedback", "Judge Feedback"),
+    ("evaluation.judge_ensemble", "Judge Ensemble"),
+    ("evaluation.score_breakdown", "Score Breakdown"),
+    (
+        "evaluation.evaluators_unweighted",
+        "Custom evaluator scores are listed below and do not feed the composite score.",
+    ),
+    ("evaluation.custom_evaluators", "Custom Evaluator Results"),
+    ("evaluation.evaluator_failed", "Failed"),
+    ("evaluation.score", "Score"),
+    ("evaluation.human_review", "Human Review"),
+    ("evaluation.human_score", "Human Score"),
+    ("evaluation.human_notes", "Further Human Notes"),
+    ("evaluation.links", "Links"),
+    ("link.transcript", "Transcript"),
+    ("link.metrics", "Metrics"),
+    ("link.score_breakdown", "Score Breakdown"),
+    ("link.events", "Events"),
+    ("link.fixture", "Fixture"),
+    (
+        "judge.prompt",
+        r#"Evaluate this LLM tool interaction.
+
+Task: {task}
+
+Files to review:
+- @{transcript} - The interaction transcript
+
+Use the rubric at {rubric} for evaluation.
+
+Return evaluation as JSON with this structure:
+{
+  "scores": {
+    "criterion_id": <score_0_to_1>,
+    ...
+  },
+  "weighted_score": <weighted_average_0_to_1>,
+  "confidence": <confidence_0_to_1>,
+  "issues": ["issue1", "issue2", ...],
+  "highlights": ["good_practice1", "good_practice2", ...]
+}
+
+Provide JSON only, no additional text."#,
+    ),
+    (
+        "judge.api_prompt",
+        r#"Evaluate this LLM tool interaction.
+
+Task: {task}
+
+Rubric criteria (id: description, weight):
+{criteria}
+
+Transcript:
+{transcript}
+
+Return evaluation as JSON with this structure:
+{
+  "scores": {
+    "criterion_id": <score_0_to_1>,
+    ...
+  },
+  "weighted_score": <weighted_average_0_to_1>,
+  "confidence": <confidence_0_to_1>,
+  "issues": ["issue1", "issue2", ...],
+  "highlights": ["good_practice1", "good_practice2", ...]
+}
+
+Provide JSON only, no additional text."#,
+    ),
+    (
+        "judge.retry_prompt",
+        r#"Your previous reply could not be read as the evaluation JSON: {error}
+
+Reply again with only the JSON object described above, without any text, Markdown or code fences around it."#,
+    ),
+];
+
+/// Message catalog for one language.
+#[derive(Debug, Clone)]
+pub struct Locale {
+    messages: HashMap<String, String>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            messages: ENGLISH
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Flatten nested TOML tables into dotted keys.
+fn flatten(prefix: &str, value: &toml::Value, out: &mut HashMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, out);
+            }
+        }
+        toml::Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        other => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
+
+/// Path of the locale file for `language` under `templates_dir`.
+pub fn locale_path(templates_dir: &Path, language: &str) -> PathBuf {
+    templates_dir
+        .join("locales")
+        .join(format!("{}.toml", language))
+}
+
+impl Locale {
+    /// Load `language`, overlaying `<templates_dir>/locales/<language>.toml` on English.
+    ///
+    /// English needs no file; any other language must have one.
+    pub fn load(language: &str, templates_dir: &Path) -> anyhow::Result<Self> {
+        let mut locale = Self::default();
+        let path = locale_path(templates_dir, language);
+        if !path.exists() {
+            if language == DEFAULT_LANGUAGE {
+                return Ok(locale);
+            }
+            anyhow::bail!(
+                "No locale file for language '{}' (expected {})",
+                language,
+                path.display()
+            );
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read locale file {}", path.display()))?;
+        let value: toml::Value = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse locale file {}", path.display()))?;
+        let mut messages = HashMap::new();
+        flatten("", &value, &mut messages);
+        for key in messages.keys() {
+            if !locale.messages.contains_key(key) {
+                eprintln!(
+                    "Warning: unknown message key '{}' in {}",
+                    key,
+                    path.display()
+                );
+            }
+        }
+        locale.messages.extend(messages);
+        Ok(locale)
+    }
+
+    /// Load the locale selected by the `[report]` config section.
+    pub fn from_config(config: &ReportConfig) -> anyhow::Result<Self> {
+        Self::load(config.language(), &config.templates_dir())
+    }
+
+    /// Message for `key`, or the key itself if unknown.
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// Message for `key` with `{name}` placeholders replaced by `args`.
+    ///
+    /// Substitution is a single pass, so placeholder-like text inside
+    /// argument values is left alone; unknown `{...}` text is kept verbatim.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let text = self.text(key);
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let tail = &rest[start..];
+            let arg = tail.find('}').and_then(|end| {
+                let name = &tail[1..end];
+                args.iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, value)| (end, value))
+            });
+            match arg {
+                Some((end, value)) => {
+                    out.push_str(&value.to_string());
+                    rest = &tail[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = &tail[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_is_built_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let locale = Locale::load("en", dir.path()).unwrap();
+        assert_eq!(locale.text("report.title"), "Test Run Report");
+        assert_eq!(
+            locale.format("value.token_usage", &[("input", &10), ("output", &5)]),
+            "10 input, 5 output"
+        );
+        assert!(Locale::load("de", dir.path()).is_err());
+    }
+
+    #[test]
+    fn locale_file_overrides_and_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("locales")).unwrap();
+        std::fs::write(
+            locale_path(dir.path(), "de"),
+            "[report]\ntitle = \"Testlauf-Bericht\"\n\n[field]\ntool = \"Werkzeug\"\n",
+        )
+        .unwrap();
+
+        let locale = Locale::load("de", dir.path()).unwrap();
+        assert_eq!(locale.text("report.title"), "Testlauf-Bericht");
+        assert_eq!(locale.text("field.tool"), "Werkzeug");
+        assert_eq!(locale.text("field.model"), "Model");
+    }
+
+    #[test]
+    fn judge_prompt_keeps_json_braces() {
+        let prompt = Locale::default().format(
+            "judge.prompt",
+            &[
+                ("task", &"Do it"),
+                ("transcript", &"t.txt"),
+                ("rubric", &"r.yaml"),
+            ],
+        );
+        assert!(prompt.contains("Task: Do it"));
+        assert!(prompt.contains("- @t.txt - The interaction transcript"));
+        assert!(prompt.contains("\"scores\": {"));
+    }
+}
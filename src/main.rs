@@ -3,6 +3,7 @@
 //! Evaluates LLM tools against test scenarios with automatic judging.
 
 mod adapter;
+mod artifacts;
 mod cli;
 mod commands;
 mod config;
@@ -10,20 +11,29 @@ mod eval_helpers;
 #[cfg(test)]
 mod eval_tests_score;
 mod evaluation;
+mod exec;
 mod fixture;
 mod judge;
+mod locale;
 mod output;
+mod paths;
 mod results;
 mod run;
+mod sandbox;
+mod scaffold;
 mod scenario;
+mod schema;
 mod script_runner;
 mod session;
 mod transcript;
 mod utils;
+mod wasm_gate;
 
 use clap::Parser;
+use cli::BundleCommand;
 use cli::Cli;
 use cli::Commands;
+use cli::ResultsCommand;
 use results::{Cache, ResultsDB};
 use scenario::ToolConfig as ScenarioToolConfig;
 
@@ -37,28 +47,30 @@ pub fn build_tool_matrix(
 ) -> anyhow::Result<Vec<output::ToolModelConfig>> {
     // If profile is specified, expand from config
     if let Some(profile_name) = cli_profile {
-        let matrix = config.build_profile_matrix(profile_name).map_err(|e| anyhow::anyhow!(e))?;
+        let matrix = config
+            .build_profile_matrix(profile_name)
+            .map_err(|e| anyhow::anyhow!(e))?;
         return Ok(matrix
             .into_iter()
-            .map(|(tool, model)| output::ToolModelConfig { tool, model })
+            .map(|(tool, model)| output::ToolModelConfig {
+                tool,
+                model,
+                ..Default::default()
+            })
             .collect());
     }
 
     // If scenario has a tool_matrix, use it (deprecated but still supported)
     if let Some(scenario_matrix) = scenario_matrix {
-        let mut matrix = Vec::new();
-        for config in scenario_matrix {
-            let models = if config.models.is_empty() {
-                vec!["default".to_string()]
-            } else {
-                config.models.clone()
-            };
-            for model in models {
-                matrix.push(output::ToolModelConfig {
-                    tool: config.tool.clone(),
-                    model,
-                });
-            }
+        return Ok(expand_matrix(scenario_matrix));
+    }
+
+    // Otherwise the config's default matrix, unless a tool or model was given
+    if cli_tool.is_none() && cli_model.is_none() && !config.default_matrix.is_empty() {
+        let matrix = expand_matrix(&config.default_matrix);
+        for cell in matrix.iter().filter(|c| c.model != "default") {
+            validate_cli_model(config, &cell.tool, &cell.model)
+                .map_err(|e| anyhow::anyhow!("default_matrix: {}", e))?;
         }
         return Ok(matrix);
     }
@@ -66,23 +78,51 @@ pub fn build_tool_matrix(
     // Single tool/model mode - default to "opencode" if no tool specified
     let tool = cli_tool.as_deref().unwrap_or("opencode");
 
-    // Validate that the tool supports the model if tool is configured
     if let Some(model) = cli_model {
-        if let Err(e) = config.validate_tool_model(tool, model) {
-            // Only error if the tool exists in config and doesn't support the model
-            // If tool not in config, we allow any model (backwards compatibility)
-            if config.get_tool(tool).is_some() {
-                return Err(anyhow::anyhow!(e));
-            }
-        }
+        validate_cli_model(config, tool, model)?;
     }
 
     Ok(vec![output::ToolModelConfig {
         tool: tool.to_string(),
         model: cli_model.as_deref().unwrap_or("default").to_string(),
+        ..Default::default()
     }])
 }
 
+/// One cell per tool and model; a tool without models runs its default model.
+/// Every cell of an entry carries the entry's `env` and `extra_args`.
+fn expand_matrix(entries: &[ScenarioToolConfig]) -> Vec<output::ToolModelConfig> {
+    let mut matrix = Vec::new();
+    for config in entries {
+        let models = if config.models.is_empty() {
+            vec!["default".to_string()]
+        } else {
+            config.models.clone()
+        };
+        for model in models {
+            matrix.push(output::ToolModelConfig {
+                tool: config.tool.clone(),
+                model,
+                env: config.env.clone(),
+                extra_args: config.extra_args.clone(),
+            });
+        }
+    }
+    matrix
+}
+
+/// Check a model given on the command line against the tool's configured models.
+pub fn validate_cli_model(config: &config::Config, tool: &str, model: &str) -> anyhow::Result<()> {
+    if let Err(e) = config.validate_tool_model(tool, model) {
+        // Only error if the tool exists in config and doesn't support the model
+        // If tool not in config, we allow any model (backwards compatibility)
+        if config.get_tool(tool).is_some() {
+            return Err(anyhow::anyhow!(e));
+        }
+    }
+    Ok(())
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
@@ -104,14 +144,28 @@ fn main() -> anyhow::Result<()> {
             all,
             tags,
             tier,
+            taxonomy,
+            exclude_tags,
+            exclude_scenario,
+            exclude_tier,
             tool,
             model,
             profile,
+            matrix,
+            extend_matrix,
             dry_run,
+            materialize,
             no_cache,
             judge_model,
             no_judge,
+            no_judge_cache,
             timeout_secs,
+            labels,
+            reports,
+            jobs,
+            repeats,
+            keep_fixture,
+            inject_fault,
         } => {
             // Safety check: only run tests when explicitly enabled
             if std::env::var("LLM_TOOL_TEST_ENABLED").as_deref() != Ok("1") {
@@ -133,21 +187,35 @@ fn main() -> anyhow::Result<()> {
             }
 
             let selection = commands::ScenarioSelection {
-                scenario: scenario.clone(),
+                scenarios: scenario.clone(),
                 all: *all,
                 tags: tags.clone(),
                 tier: *tier,
+                taxonomy: taxonomy.clone(),
+                exclude_tags: exclude_tags.clone(),
+                exclude_scenarios: exclude_scenario.clone(),
+                exclude_tiers: exclude_tier.clone(),
             };
 
             let exec_config = commands::ExecutionConfig {
                 tool: tool.clone(),
                 model: model.clone(),
                 profile: profile.clone(),
+                matrix: matrix.clone(),
+                extend_matrix: extend_matrix.clone(),
                 dry_run: *dry_run,
+                materialize: *materialize,
                 no_cache: *no_cache,
                 timeout_secs: *timeout_secs,
                 judge_model: judge_model.clone(),
                 no_judge: *no_judge,
+                no_judge_cache: *no_judge_cache,
+                labels: labels.clone(),
+                inject_fault: *inject_fault,
+                jobs: usize::from(*jobs),
+                repeats: u32::from(*repeats),
+                keep_fixture: *keep_fixture,
+                reports: reports.clone(),
             };
 
             let ctx = commands::ExecutionContext {
@@ -156,14 +224,27 @@ fn main() -> anyhow::Result<()> {
                 cache: &cache,
             };
 
-            if selection.scenario.is_some() || selection.all {
+            if !selection.scenarios.is_empty() || selection.all {
                 commands::handle_run_command(&selection, &exec_config, &ctx, &config)?;
             } else {
                 println!("No scenario specified. Use --scenario <path> or --all");
             }
         }
-        Commands::Scenarios { tags, tier } => {
-            commands::handle_list_command(tags, tier, &results_db)?;
+        Commands::Scenarios {
+            tags,
+            tier,
+            taxonomy,
+            with_history,
+            format,
+        } => {
+            commands::handle_list_command(
+                tags,
+                tier,
+                taxonomy,
+                *with_history,
+                *format,
+                &results_db,
+            )?;
         }
         Commands::Show { name } => {
             commands::handle_show_command(name, &results_db)?;
@@ -171,6 +252,164 @@ fn main() -> anyhow::Result<()> {
         Commands::Clean { older_than } => {
             commands::handle_clean_command(&cache, older_than, &base_dir)?;
         }
+        Commands::Coverage {
+            binary,
+            manifest,
+            format,
+        } => {
+            commands::handle_coverage_command(binary.as_deref(), manifest.as_deref(), *format)?;
+        }
+        Commands::Validate { paths, format } => {
+            commands::handle_validate_command(paths, *format)?;
+        }
+        Commands::New {
+            name,
+            template,
+            binary,
+            dir,
+            force,
+        } => {
+            scaffold::handle_new_command(&config, name, *template, binary, dir.as_deref(), *force)?;
+        }
+        Commands::Score {
+            scenario,
+            fixture,
+            transcript,
+            tool,
+            model,
+            no_judge,
+            no_judge_cache,
+            out,
+        } => {
+            commands::handle_score_command(
+                scenario,
+                fixture,
+                transcript,
+                tool,
+                model,
+                *no_judge,
+                (!*no_judge_cache).then_some(&cache),
+                out.as_deref(),
+            )?;
+        }
+        Commands::Doctor { tool, fix } => {
+            commands::handle_doctor_command(&config, tool, *fix)?;
+        }
+        Commands::Stats {
+            anomalies,
+            sigma,
+            since,
+            taxonomy,
+            format,
+        } => {
+            commands::handle_stats_command(
+                &results_db,
+                *anomalies,
+                *sigma,
+                since,
+                taxonomy,
+                *format,
+            )?;
+        }
+        Commands::Costs {
+            since,
+            group_by,
+            format,
+        } => {
+            commands::handle_costs_command(&results_db, &config, since, *group_by, *format)?;
+        }
+        Commands::Compare {
+            baseline,
+            candidate,
+            since,
+            format,
+        } => {
+            commands::handle_compare_command(&results_db, baseline, candidate, since, *format)?;
+        }
+        Commands::Dashboard { interval, once } => {
+            commands::handle_dashboard_command(&results_db, *interval, *once)?;
+        }
+        Commands::Report {
+            badge,
+            metric,
+            tool,
+            model,
+            badge_label,
+            summary,
+            capabilities,
+            html,
+            label,
+            since,
+        } => {
+            commands::handle_report_command(
+                &results_db,
+                &commands::ReportOptions {
+                    badge: badge.as_deref(),
+                    metric: *metric,
+                    badge_label: badge_label.as_deref(),
+                    summary: summary.as_deref(),
+                    capabilities: capabilities.as_deref(),
+                    html: html.as_deref(),
+                    tool: tool.as_deref(),
+                    model: model.as_deref(),
+                    label: label.as_deref(),
+                    since: since.as_deref(),
+                },
+            )?;
+        }
+        Commands::Results { command } => match command {
+            ResultsCommand::Relink { from, to } => {
+                commands::handle_relink_command(&results_db, &cache, from, to)?;
+            }
+            ResultsCommand::Gc {
+                archive,
+                older_than,
+                dry_run,
+            } => {
+                commands::handle_gc_command(
+                    &results_db,
+                    &cache,
+                    &base_dir,
+                    archive.as_deref(),
+                    older_than,
+                    *dry_run,
+                )?;
+            }
+            ResultsCommand::Calibrate { since, format } => {
+                commands::handle_calibrate_command(&results_db, since, *format)?;
+            }
+            ResultsCommand::Export {
+                anonymize,
+                redact,
+                out,
+                duckdb,
+                since,
+            } => {
+                commands::handle_export_command(
+                    &results_db,
+                    *anonymize,
+                    redact,
+                    out.as_deref(),
+                    duckdb.as_deref(),
+                    since,
+                )?;
+            }
+        },
+        Commands::Bundle { command } => match command {
+            BundleCommand::Keygen => commands::handle_bundle_keygen_command(),
+            BundleCommand::Encrypt {
+                paths,
+                remove_plaintext,
+            } => {
+                commands::handle_bundle_encrypt_command(paths, *remove_plaintext)?;
+            }
+            BundleCommand::Decrypt { path, out } => {
+                commands::handle_bundle_decrypt_command(path, out.as_deref())?;
+            }
+        },
+        Commands::Schema { kind, out_dir } => {
+            schema::handle_schema_command(*kind, out_dir.as_deref())?;
+        }
     }
     Ok(())
 }
@@ -225,10 +464,12 @@ mod tests {
             ScenarioToolConfig {
                 tool: "opencode".to_string(),
                 models: vec!["gpt-4o".to_string(), "claude-sonnet".to_string()],
+                ..Default::default()
             },
             ScenarioToolConfig {
                 tool: "claude-code".to_string(),
                 models: vec!["default".to_string()],
+                ..Default::default()
             },
         ];
 
@@ -248,10 +489,12 @@ mod tests {
             ScenarioToolConfig {
                 tool: "opencode".to_string(),
                 models: vec![],
+                ..Default::default()
             },
             ScenarioToolConfig {
                 tool: "claude-code".to_string(),
                 models: vec![],
+                ..Default::default()
             },
         ];
 
@@ -298,7 +541,8 @@ mod tests {
             },
         );
 
-        let result = build_tool_matrix(&None, &None, &Some("standard".to_string()), &config, &None).unwrap();
+        let result =
+            build_tool_matrix(&None, &None, &Some("standard".to_string()), &config, &None).unwrap();
 
         assert_eq!(result.len(), 1);
         assert_matrix_contains(&result, "opencode", "gpt-4o");
@@ -336,7 +580,8 @@ mod tests {
             },
         );
 
-        let result = build_tool_matrix(&None, &None, &Some("full".to_string()), &config, &None).unwrap();
+        let result =
+            build_tool_matrix(&None, &None, &Some("full".to_string()), &config, &None).unwrap();
 
         assert_eq!(result.len(), 4);
         assert_matrix_contains(&result, "opencode", "gpt-4o");
@@ -391,4 +636,43 @@ mod tests {
         assert_eq!(result[0].tool, "unknown-tool");
         assert_eq!(result[0].model, "any-model");
     }
+
+    #[test]
+    fn test_build_tool_matrix_config_default_matrix() {
+        let config: config::Config = toml::from_str(
+            "[[default_matrix]]\ntool = \"opencode\"\nmodels = [\"gpt-4o\", \"claude-sonnet\"]\n\n[[default_matrix]]\ntool = \"claude-code\"\n",
+        )
+        .unwrap();
+
+        let result = build_tool_matrix(&None, &None, &None, &config, &None).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_matrix_contains(&result, "opencode", "gpt-4o");
+        assert_matrix_contains(&result, "claude-code", "default");
+
+        // A scenario's own matrix and an explicit tool both take precedence
+        let scenario_matrix = vec![ScenarioToolConfig {
+            tool: "mock".to_string(),
+            models: vec![],
+            ..Default::default()
+        }];
+        let result =
+            build_tool_matrix(&None, &None, &None, &config, &Some(scenario_matrix)).unwrap();
+        assert_eq!(result, vec!["mock".parse().unwrap()]);
+        let result =
+            build_tool_matrix(&Some("aider".to_string()), &None, &None, &config, &None).unwrap();
+        assert_eq!(result, vec!["aider".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_tool_model_config_parses_cli_cells() {
+        let cell: ToolModelConfig = "ollama:llama3:8b".parse().unwrap();
+        assert_eq!(
+            (cell.tool.as_str(), cell.model.as_str()),
+            ("ollama", "llama3:8b")
+        );
+        let cell: ToolModelConfig = "claude-code".parse().unwrap();
+        assert_eq!(cell.to_string(), "claude-code/default");
+        assert!(":gpt-4o".parse::<ToolModelConfig>().is_err());
+        assert!("opencode:".parse::<ToolModelConfig>().is_err());
+    }
 }
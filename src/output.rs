@@ -1,10 +1,92 @@
 use crate::evaluation::ScoreTier;
 use crate::results::ResultRecord;
+use std::io::Write;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct ToolModelConfig {
     pub tool: String,
     pub model: String,
+    /// Environment variables from the matrix entry's `env`
+    pub env: std::collections::BTreeMap<String, String>,
+    /// Tool arguments from the matrix entry's `extra_args`
+    pub extra_args: Vec<String>,
+}
+
+impl std::str::FromStr for ToolModelConfig {
+    type Err = String;
+
+    /// Parse `tool:model`, or a bare `tool` for its default model.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (tool, model) = value.split_once(':').unwrap_or((value, "default"));
+        let (tool, model) = (tool.trim(), model.trim());
+        if tool.is_empty() || model.is_empty() {
+            return Err(format!("expected tool:model, got '{}'", value));
+        }
+        Ok(Self {
+            tool: tool.to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+impl std::fmt::Display for ToolModelConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.tool, self.model)
+    }
+}
+
+/// Summarize matrix cells skipped because their tool is unavailable, one
+/// line per tool with the adapter's diagnostic.
+pub fn print_unavailable_summary(skipped: &[ResultRecord]) {
+    let mut by_tool: std::collections::BTreeMap<&str, (usize, &str)> =
+        std::collections::BTreeMap::new();
+    for record in skipped {
+        let entry = by_tool
+            .entry(&record.tool)
+            .or_insert((0, record.outcome_detail.as_deref().unwrap_or_default()));
+        entry.0 += 1;
+    }
+    if by_tool.is_empty() {
+        return;
+    }
+    println!("\nSkipped {} run(s) with unavailable tools:", skipped.len());
+    for (tool, (count, diagnostic)) in by_tool {
+        println!("  {} ({} run(s)): {}", tool, count, diagnostic);
+    }
+}
+
+/// Pass rate and score variance per cell of a `run --repeats` run, in
+/// matrix order; cells that both passed and failed are marked flaky.
+pub fn print_repeat_summary(results: &[(ToolModelConfig, anyhow::Result<ResultRecord>)]) {
+    let mut cells: Vec<(&ToolModelConfig, Vec<Option<&ResultRecord>>)> = Vec::new();
+    for (cell, result) in results {
+        let run = result.as_ref().ok();
+        match cells.iter_mut().find(|(c, _)| *c == cell) {
+            Some((_, runs)) => runs.push(run),
+            None => cells.push((cell, vec![run])),
+        }
+    }
+
+    println!("\n--- Repeat Summary ---");
+    for (cell, runs) in cells {
+        let stats = crate::results::stats::repeat_stats(&runs);
+        let score = match (stats.mean_score, stats.score_variance) {
+            (Some(mean), Some(variance)) => {
+                format!(", score {:.2} (variance {:.4})", mean, variance)
+            }
+            _ => String::new(),
+        };
+        println!(
+            "{}: {}/{} passed ({:.0}%){}{}",
+            cell,
+            stats.passed,
+            stats.runs,
+            stats.pass_rate * 100.0,
+            score,
+            if stats.is_flaky() { " FLAKY" } else { "" }
+        );
+    }
 }
 
 pub fn print_matrix_summary(results: &[(ToolModelConfig, anyhow::Result<ResultRecord>)]) {
@@ -15,7 +97,7 @@ pub fn print_matrix_summary(results: &[(ToolModelConfig, anyhow::Result<ResultRe
 
     for (config, result) in results {
         let outcome = match result {
-            Ok(record) => record.outcome.clone(),
+            Ok(record) => record.outcome_summary(),
             Err(e) => format!("Error: {}", e),
         };
 
@@ -60,38 +142,61 @@ pub fn print_matrix_summary(results: &[(ToolModelConfig, anyhow::Result<ResultRe
 }
 
 pub fn print_result_summary(record: &ResultRecord) {
-    println!("\n--- Result Summary ---");
-    println!("ID: {}", record.id);
-    println!("Scenario: {}", record.scenario_id);
-    println!("Tool: {}", record.tool);
-    println!("Outcome: {}", record.outcome);
-    println!(
+    // One locked write, so summaries of parallel runs do not interleave
+    let mut out = std::io::stdout().lock();
+    let _ = write_result_summary(&mut out, record);
+}
+
+fn write_result_summary(out: &mut impl Write, record: &ResultRecord) -> std::io::Result<()> {
+    writeln!(out, "\n--- Result Summary ---")?;
+    writeln!(out, "ID: {}", record.id)?;
+    writeln!(out, "Scenario: {}", record.scenario_id)?;
+    writeln!(out, "Tool: {}", record.tool)?;
+    writeln!(out, "Outcome: {}", record.outcome_summary())?;
+    writeln!(
+        out,
         "Gates: {}/{}",
         record.metrics.gates_passed, record.metrics.gates_total
-    );
-    println!("Duration: {:.2}s", record.duration_secs);
-    println!(
+    )?;
+    writeln!(out, "Duration: {:.2}s", record.duration_secs)?;
+    if let Some(phases) = &record.phase_durations {
+        let parts: Vec<String> = phases
+            .phases()
+            .iter()
+            .map(|(name, secs)| format!("{} {:.2}s", name, secs))
+            .collect();
+        writeln!(out, "Phases: {}", parts.join(", "))?;
+    }
+    writeln!(
+        out,
         "Commands: {} ({} unique, {} errors, {} help, {} retries)",
         record.metrics.efficiency.total_commands,
         record.metrics.efficiency.unique_commands,
         record.metrics.efficiency.error_count,
         record.metrics.efficiency.help_invocations,
         record.metrics.efficiency.retry_count
-    );
-    println!(
-        "First-try success: {:.0}%, iteration ratio: {:.2}",
+    )?;
+    writeln!(
+        out,
+        "First-try success: {:.0}%, iteration ratio: {:.2}, wasted actions: {}",
         record.metrics.efficiency.first_try_success_rate * 100.0,
-        record.metrics.efficiency.iteration_ratio
-    );
+        record.metrics.efficiency.iteration_ratio,
+        record.metrics.efficiency.wasted_actions
+    )?;
     if let Some(score) = record.judge_score {
         let tier = ScoreTier::from_score(score);
-        println!("Judge Score: {:.2} ({})", score, tier);
+        writeln!(out, "Judge Score: {:.2} ({})", score, tier)?;
     }
     if let Some(composite_score) = record.metrics.composite_score {
         let composite_tier = ScoreTier::from_score(composite_score);
-        println!(
+        writeln!(
+            out,
             "Composite Score: {:.2} ({})",
             composite_score, composite_tier
-        );
+        )?;
+    }
+    for error in &record.metrics.component_errors {
+        writeln!(out, "Errored: {} ({})", error.component, error.message)?;
     }
+    Ok(())
 }
@@ -0,0 +1,104 @@
+//! Confinement of scenario- and agent-controlled paths.
+//!
+//! Gate paths, workdirs and fixture names are joined onto a trusted root.
+//! `Path::join` alone lets `../` segments, absolute paths and symlinks written
+//! by the agent reach arbitrary host files; [`confine`] resolves the result
+//! and rejects anything that ends up outside the root.
+
+use anyhow::Context;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolve `path`, following symlinks of every component that exists, and
+/// check that it lies inside `root`.
+///
+/// Components past the first missing one are applied lexically, so paths to
+/// files that do not exist yet are checked too. Returns the resolved path.
+pub fn confine(path: &Path, root: &Path) -> anyhow::Result<PathBuf> {
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", root.display()))?;
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    let mut exists = true;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                if exists && resolved.symlink_metadata().is_ok() {
+                    resolved = resolved.canonicalize().with_context(|| {
+                        format!("Failed to resolve {} (broken symlink?)", resolved.display())
+                    })?;
+                } else {
+                    exists = false;
+                }
+            }
+        }
+    }
+
+    if !resolved.starts_with(&root) {
+        anyhow::bail!(
+            "{} resolves to {}, outside {}",
+            path.display(),
+            resolved.display(),
+            root.display()
+        );
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn accepts_paths_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+
+        let resolved = confine(&dir.path().join("a/./b/../b/new.txt"), dir.path()).unwrap();
+        assert_eq!(
+            resolved,
+            dir.path().canonicalize().unwrap().join("a/b/new.txt")
+        );
+        assert!(confine(dir.path(), dir.path()).is_ok());
+    }
+
+    #[test]
+    fn rejects_parent_segments_and_absolute_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        fs::create_dir_all(&root).unwrap();
+
+        assert!(confine(&root.join("../outside.txt"), &root).is_err());
+        assert!(confine(&root.join("missing/../../outside.txt"), &root).is_err());
+        assert!(confine(Path::new("/etc/passwd"), &root).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_leaving_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), root.join("up")).unwrap();
+        std::os::unix::fs::symlink("link.txt", root.join("inner.txt")).unwrap();
+
+        let err = confine(&root.join("link.txt"), &root).unwrap_err();
+        assert!(err.to_string().contains("outside"));
+        assert!(confine(&root.join("up/secret.txt"), &root).is_err());
+        assert!(confine(&root.join("inner.txt"), &root).is_err());
+    }
+}
@@ -6,7 +6,19 @@
 //! # Submodules
 //!
 //! - `cache` - File-based result caching
+//! - `calibration` - Scenario difficulty estimates fitted from pass rates
+//! - `compare` - A/B comparison of two tools or models
+//! - `costs` - Spend aggregation and budget comparison
+//! - `dashboard` - Operational overview of the latest suite
 //! - `db` - JSONL results database
+//! - `duckdb` - Export into a DuckDB database for SQL analysis
+//! - `export` - Record export and anonymization
+//! - `gc` - Orphaned artifact directory detection
+//! - `html` - Static HTML site with per-run pages
+//! - `junit` - JUnit XML output for CI
+//! - `report` - Suite-level reports such as SVG badges
+//! - `retention` - Artifact pruning for old passing runs
+//! - `stats` - Aggregate statistics and anomaly detection
 //! - `types` - Result data structures
 //! - `utils` - Utility functions for result handling
 //!
@@ -21,7 +33,19 @@
 //! ```
 
 pub mod cache;
+pub mod calibration;
+pub mod compare;
+pub mod costs;
+pub mod dashboard;
 pub mod db;
+pub mod duckdb;
+pub mod export;
+pub mod gc;
+pub mod html;
+pub mod junit;
+pub mod report;
+pub mod retention;
+pub mod stats;
 pub mod types;
 pub mod utils;
 
@@ -2,7 +2,8 @@
 //!
 //! Provides persistent caching of test results keyed by scenario,
 /// prompt, and tool configuration to avoid redundant test runs.
-use crate::results::types::{CacheKey, ResultRecord};
+use crate::judge::JudgeResponse;
+use crate::results::types::{CacheKey, JudgeCacheKey, ResultRecord};
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
@@ -83,11 +84,70 @@ impl Cache {
     pub fn put(&self, key: &CacheKey, record: &ResultRecord) -> Result<()> {
         let cache_file = self.cache_dir.join(key.as_string());
         let content = serde_json::to_string_pretty(record)?;
-        std::fs::write(&cache_file, content)?;
+        crate::artifacts::write_atomic(&cache_file, content)?;
         Ok(())
     }
 
-    /// Clear all cached results.
+    /// Retrieve a cached judge response by key.
+    pub fn get_judge(&self, key: &JudgeCacheKey) -> Option<JudgeResponse> {
+        let content = std::fs::read_to_string(self.judge_file(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store a judge response in the cache.
+    pub fn put_judge(&self, key: &JudgeCacheKey, response: &JudgeResponse) -> Result<()> {
+        let cache_file = self.judge_file(key);
+        if let Some(parent) = cache_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(response)?;
+        crate::artifacts::write_atomic(&cache_file, content)?;
+        Ok(())
+    }
+
+    /// Judge responses live in a `judge` subdirectory, apart from result
+    /// records.
+    fn judge_file(&self, key: &JudgeCacheKey) -> PathBuf {
+        self.cache_dir
+            .join("judge")
+            .join(format!("{}.json", key.as_string()))
+    }
+
+    /// Move cached results of scenario `from` to scenario id `to`.
+    ///
+    /// Returns the number of cache entries changed.
+    pub fn relink_scenario(&self, from: &str, to: &str) -> Result<usize> {
+        let mut changed = 0;
+        for entry in std::fs::read_dir(&self.cache_dir)? {
+            let path = entry?.path();
+            let Some(mut record) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<ResultRecord>(&content).ok())
+            else {
+                continue;
+            };
+            if record.scenario_id == from {
+                record.scenario_id = to.to_string();
+                crate::artifacts::write_atomic(&path, serde_json::to_string_pretty(&record)?)?;
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Load all cached result records, skipping unreadable entries.
+    pub fn load_all(&self) -> Vec<ResultRecord> {
+        let Ok(entries) = std::fs::read_dir(&self.cache_dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect()
+    }
+
+    /// Clear all cached results and judge responses.
     ///
     /// # Returns
     ///
@@ -100,6 +160,42 @@ impl Cache {
                 std::fs::remove_file(path)?;
             }
         }
+        let judge_dir = self.cache_dir.join("judge");
+        if judge_dir.is_dir() {
+            std::fs::remove_dir_all(judge_dir)?;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn judge_responses_are_cached_apart_from_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let key = JudgeCacheKey::compute("criteria: []", "transcript", "gpt-4o-mini");
+        assert!(cache.get_judge(&key).is_none());
+
+        let response = JudgeResponse {
+            scores: [("task".to_string(), 0.8)].into_iter().collect(),
+            weighted_score: 0.8,
+            confidence: 0.9,
+            issues: vec![],
+            highlights: vec!["tidy".to_string()],
+        };
+        cache.put_judge(&key, &response).unwrap();
+        let cached = cache.get_judge(&key).unwrap();
+        assert_eq!(cached.weighted_score, 0.8);
+        assert_eq!(cached.highlights, ["tidy"]);
+
+        let other_model = JudgeCacheKey::compute("criteria: []", "transcript", "gpt-4o");
+        assert!(cache.get_judge(&other_model).is_none());
+        assert!(cache.load_all().is_empty());
+
+        cache.clear().unwrap();
+        assert!(cache.get_judge(&key).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+//! Difficulty calibration from recorded results.
+//!
+//! Fits a one-parameter logistic (Rasch) model to every recorded run:
+//! each tool/model has an ability `θ`, each scenario a difficulty `b`, and a
+//! run passes with probability `1 / (1 + e^-(θ - b))`. Both are on the same
+//! logit scale, so a scenario with difficulty 1.0 is passed half the time by a
+//! tool/model of ability 1.0. A standard normal prior on both keeps estimates
+//! finite for scenarios that every (or no) tool/model passes.
+//!
+//! Unlike a raw pass rate, the estimate accounts for which tool/models
+//! attempted a scenario: passing only with strong models makes it harder.
+
+use crate::results::types::ResultRecord;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Variance of the normal prior on abilities and difficulties.
+const PRIOR_VARIANCE: f64 = 1.0;
+const MAX_ITERATIONS: usize = 200;
+const TOLERANCE: f64 = 1e-6;
+
+/// Estimated difficulty of one scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyEstimate {
+    pub scenario: String,
+    /// Difficulty on the logit scale; 0 is average, higher is harder
+    pub difficulty: f64,
+    /// Fraction of recorded runs that passed
+    pub pass_rate: f64,
+    pub runs: usize,
+    /// Distinct tool/model combinations that ran the scenario
+    pub tool_models: usize,
+}
+
+/// Estimated ability of one tool/model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityEstimate {
+    pub tool: String,
+    pub model: String,
+    /// Ability on the same logit scale as scenario difficulty
+    pub ability: f64,
+    pub runs: usize,
+}
+
+/// Result of a calibration pass, stored next to `results.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Calibration {
+    pub calibrated_at: DateTime<Utc>,
+    /// Scenarios sorted from easiest to hardest
+    pub scenarios: Vec<DifficultyEstimate>,
+    /// Tool/models sorted from strongest to weakest
+    pub abilities: Vec<AbilityEstimate>,
+}
+
+impl Calibration {
+    /// The estimate for `scenario`, if it has recorded runs.
+    pub fn difficulty_of(&self, scenario: &str) -> Option<&DifficultyEstimate> {
+        self.scenarios.iter().find(|d| d.scenario == scenario)
+    }
+}
+
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// One Newton step on a parameter with a normal prior.
+///
+/// `residual` is the summed `y - p` seen from the parameter's side and
+/// `information` the summed `p(1 - p)`.
+fn newton_step(value: f64, residual: f64, information: f64) -> f64 {
+    let gradient = residual - value / PRIOR_VARIANCE;
+    let curvature = information + 1.0 / PRIOR_VARIANCE;
+    value + gradient / curvature
+}
+
+/// Fit scenario difficulties and tool/model abilities to `records`.
+///
+/// Runs where the tool never ran (dry runs, `setup_invalid`) are ignored; a run counts as passed when all its gates passed.
+pub fn calibrate(records: &[ResultRecord]) -> Calibration {
+    let mut scenario_index: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut subject_index: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    let mut observations = Vec::new();
+    for record in records.iter().filter(|r| r.outcome.tool_ran()) {
+        let next = scenario_index.len();
+        let item = *scenario_index
+            .entry(record.scenario_id.as_str())
+            .or_insert(next);
+        let next = subject_index.len();
+        let subject = *subject_index
+            .entry((record.tool.as_str(), record.model.as_str()))
+            .or_insert(next);
+        let passed = if record.passed() { 1.0 } else { 0.0 };
+        observations.push((subject, item, passed));
+    }
+
+    let mut abilities = vec![0.0; subject_index.len()];
+    let mut difficulties = vec![0.0; scenario_index.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut residual = vec![0.0; difficulties.len()];
+        let mut information = vec![0.0; difficulties.len()];
+        for &(subject, item, passed) in &observations {
+            let p = logistic(abilities[subject] - difficulties[item]);
+            // Seen from the difficulty, a pass is evidence for a lower value
+            residual[item] += p - passed;
+            information[item] += p * (1.0 - p);
+        }
+        let mut change: f64 = 0.0;
+        for (item, difficulty) in difficulties.iter_mut().enumerate() {
+            let updated = newton_step(*difficulty, residual[item], information[item]);
+            change = change.max((updated - *difficulty).abs());
+            *difficulty = updated;
+        }
+
+        let mut residual = vec![0.0; abilities.len()];
+        let mut information = vec![0.0; abilities.len()];
+        for &(subject, item, passed) in &observations {
+            let p = logistic(abilities[subject] - difficulties[item]);
+            residual[subject] += passed - p;
+            information[subject] += p * (1.0 - p);
+        }
+        for (subject, ability) in abilities.iter_mut().enumerate() {
+            let updated = newton_step(*ability, residual[subject], information[subject]);
+            change = change.max((updated - *ability).abs());
+            *ability = updated;
+        }
+
+        if change < TOLERANCE {
+            break;
+        }
+    }
+
+    let mut scenarios: Vec<DifficultyEstimate> = scenario_index
+        .iter()
+        .map(|(&scenario, &item)| {
+            let runs: Vec<_> = observations.iter().filter(|o| o.1 == item).collect();
+            let mut tool_models: Vec<usize> = runs.iter().map(|o| o.0).collect();
+            tool_models.sort_unstable();
+            tool_models.dedup();
+            DifficultyEstimate {
+                scenario: scenario.to_string(),
+                difficulty: difficulties[item],
+                pass_rate: runs.iter().map(|o| o.2).sum::<f64>() / runs.len() as f64,
+                runs: runs.len(),
+                tool_models: tool_models.len(),
+            }
+        })
+        .collect();
+    scenarios.sort_by(|a, b| a.difficulty.total_cmp(&b.difficulty));
+
+    let mut abilities: Vec<AbilityEstimate> = subject_index
+        .iter()
+        .map(|(&(tool, model), &subject)| AbilityEstimate {
+            tool: tool.to_string(),
+            model: model.to_string(),
+            ability: abilities[subject],
+            runs: observations.iter().filter(|o| o.0 == subject).count(),
+        })
+        .collect();
+    abilities.sort_by(|a, b| b.ability.total_cmp(&a.ability));
+
+    Calibration {
+        calibrated_at: Utc::now(),
+        scenarios,
+        abilities,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_tool;
+    use crate::results::types::Outcome;
+
+    fn run(scenario: &str, tool: &str, passed: bool) -> ResultRecord {
+        let mut record = create_test_record_with_tool("run", scenario, tool);
+        record.gates_passed = passed;
+        record.outcome = if passed { Outcome::Pass } else { Outcome::Fail };
+        record
+    }
+
+    #[test]
+    fn harder_scenarios_get_higher_difficulty() {
+        let records = vec![
+            run("easy", "strong", true),
+            run("easy", "weak", true),
+            run("medium", "strong", true),
+            run("medium", "weak", false),
+            run("hard", "strong", false),
+            run("hard", "weak", false),
+        ];
+        let calibration = calibrate(&records);
+
+        let order: Vec<_> = calibration
+            .scenarios
+            .iter()
+            .map(|d| d.scenario.as_str())
+            .collect();
+        assert_eq!(order, vec!["easy", "medium", "hard"]);
+        assert_eq!(calibration.abilities[0].tool, "strong");
+        let medium = calibration.difficulty_of("medium").unwrap();
+        assert_eq!(medium.pass_rate, 0.5);
+        assert_eq!((medium.runs, medium.tool_models), (2, 2));
+        assert!(calibration
+            .scenarios
+            .iter()
+            .all(|d| d.difficulty.is_finite()));
+    }
+
+    #[test]
+    fn accounts_for_who_attempted_a_scenario() {
+        // Both scenarios pass half the time, but only the weak tool fails `b`
+        let mut records = vec![
+            run("a", "strong", true),
+            run("a", "strong", false),
+            run("b", "weak", true),
+            run("b", "weak", false),
+        ];
+        // Establish that `strong` is stronger on other scenarios
+        for scenario in ["c", "d", "e"] {
+            records.push(run(scenario, "strong", true));
+            records.push(run(scenario, "weak", false));
+        }
+        let calibration = calibrate(&records);
+
+        let a = calibration.difficulty_of("a").unwrap().difficulty;
+        let b = calibration.difficulty_of("b").unwrap().difficulty;
+        assert!(a > b, "a={} b={}", a, b);
+    }
+
+    #[test]
+    fn ignores_runs_where_the_tool_never_ran() {
+        let mut dry = run("dry", "tool", true);
+        dry.outcome = Outcome::DryRun;
+        let mut invalid = run("invalid", "tool", false);
+        invalid.outcome = Outcome::SetupInvalid;
+        let calibration = calibrate(&[dry, invalid]);
+        assert!(calibration.scenarios.is_empty());
+        assert!(calibration.abilities.is_empty());
+    }
+}
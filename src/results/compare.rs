@@ -0,0 +1,313 @@
+//! A/B comparison of two tool/model combinations (`compare` command).
+//!
+//! Pairs the latest run of each scenario for the baseline and the candidate
+//! and decides each scenario from the candidate's point of view: passing
+//! where the baseline fails is a win, the reverse a loss, and with equal
+//! pass status a composite score difference above [`SCORE_TIE_MARGIN`]
+//! decides. Duration and cost are reported but never decide.
+
+use crate::results::stats::latest_runs;
+use crate::results::types::ResultRecord;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Composite score difference below which two runs with the same pass status tie.
+pub const SCORE_TIE_MARGIN: f64 = 0.05;
+
+/// One side of a scenario comparison.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSide {
+    pub run_id: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub composite_score: Option<f64>,
+    pub duration_secs: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl RunSide {
+    fn new(r: &ResultRecord) -> Self {
+        Self {
+            run_id: r.id.clone(),
+            passed: r.passed(),
+            composite_score: r.metrics.composite_score,
+            duration_secs: r.duration_secs,
+            cost_usd: r.cost_usd,
+        }
+    }
+}
+
+/// Result of a scenario for the candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Win,
+    Loss,
+    Tie,
+    /// Only one side has run the scenario
+    Unpaired,
+}
+
+/// Baseline and candidate runs of one scenario.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScenarioComparison {
+    pub scenario: String,
+    pub baseline: Option<RunSide>,
+    pub candidate: Option<RunSide>,
+    pub verdict: Verdict,
+}
+
+/// Per-scenario comparison with win/loss/tie totals.
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    /// Baseline as `tool/model`
+    pub baseline: String,
+    /// Candidate as `tool/model`
+    pub candidate: String,
+    pub scenarios: Vec<ScenarioComparison>,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+    pub unpaired: usize,
+}
+
+fn verdict(baseline: &RunSide, candidate: &RunSide) -> Verdict {
+    match (baseline.passed, candidate.passed) {
+        (false, true) => return Verdict::Win,
+        (true, false) => return Verdict::Loss,
+        _ => {}
+    }
+    match (baseline.composite_score, candidate.composite_score) {
+        (Some(b), Some(c)) if c - b > SCORE_TIE_MARGIN => Verdict::Win,
+        (Some(b), Some(c)) if b - c > SCORE_TIE_MARGIN => Verdict::Loss,
+        _ => Verdict::Tie,
+    }
+}
+
+/// Compare the latest runs of `baseline` and `candidate`, each a `(tool, model)`.
+///
+/// Runs in which the tool never ran (dry runs, invalid setups, unavailable
+/// tools) are ignored.
+pub fn compare_runs(
+    records: &[ResultRecord],
+    baseline: (&str, &str),
+    candidate: (&str, &str),
+) -> Comparison {
+    let mut pairs: BTreeMap<&str, (Option<RunSide>, Option<RunSide>)> = BTreeMap::new();
+    for r in latest_runs(records) {
+        let key = (r.tool.as_str(), r.model.as_str());
+        if key == baseline {
+            pairs.entry(&r.scenario_id).or_default().0 = Some(RunSide::new(r));
+        } else if key == candidate {
+            pairs.entry(&r.scenario_id).or_default().1 = Some(RunSide::new(r));
+        }
+    }
+
+    let mut comparison = Comparison {
+        baseline: format!("{}/{}", baseline.0, baseline.1),
+        candidate: format!("{}/{}", candidate.0, candidate.1),
+        scenarios: Vec::new(),
+        wins: 0,
+        losses: 0,
+        ties: 0,
+        unpaired: 0,
+    };
+    for (scenario, (baseline, candidate)) in pairs {
+        let verdict = match (&baseline, &candidate) {
+            (Some(b), Some(c)) => verdict(b, c),
+            _ => Verdict::Unpaired,
+        };
+        match verdict {
+            Verdict::Win => comparison.wins += 1,
+            Verdict::Loss => comparison.losses += 1,
+            Verdict::Tie => comparison.ties += 1,
+            Verdict::Unpaired => comparison.unpaired += 1,
+        }
+        comparison.scenarios.push(ScenarioComparison {
+            scenario: scenario.to_string(),
+            baseline,
+            candidate,
+            verdict,
+        });
+    }
+    comparison
+}
+
+fn side_cell(side: &Option<RunSide>) -> String {
+    match side {
+        Some(s) => format!(
+            "{} {}",
+            if s.passed { "PASS" } else { "FAIL" },
+            s.composite_score
+                .map_or_else(|| "-".to_string(), |v| format!("{:.2}", v))
+        ),
+        None => "-".to_string(),
+    }
+}
+
+/// Signed difference `candidate - baseline`, or `-` if either is missing.
+fn delta(baseline: Option<f64>, candidate: Option<f64>, precision: usize, unit: &str) -> String {
+    match (baseline, candidate) {
+        (Some(b), Some(c)) => format!("{:+.*}{}", precision, c - b, unit),
+        _ => "-".to_string(),
+    }
+}
+
+/// Render the comparison as a text table with a summary line.
+pub fn render_comparison(comparison: &Comparison) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Baseline:  {}\nCandidate: {}\n",
+        comparison.baseline, comparison.candidate
+    );
+    if comparison.scenarios.is_empty() {
+        let _ = writeln!(out, "No recorded runs for either side");
+        return out;
+    }
+
+    let width = comparison
+        .scenarios
+        .iter()
+        .map(|s| s.scenario.len())
+        .max()
+        .unwrap_or(0)
+        .max("Scenario".len());
+    let _ = writeln!(
+        out,
+        "{:<width$}  {:<10}  {:<10}  {:>7}  {:>9}  {:>9}  Result",
+        "Scenario", "Baseline", "Candidate", "Δscore", "Δduration", "Δcost"
+    );
+    for s in &comparison.scenarios {
+        let (b, c) = (s.baseline.as_ref(), s.candidate.as_ref());
+        let _ = writeln!(
+            out,
+            "{:<width$}  {:<10}  {:<10}  {:>7}  {:>9}  {:>9}  {}",
+            s.scenario,
+            side_cell(&s.baseline),
+            side_cell(&s.candidate),
+            delta(
+                b.and_then(|r| r.composite_score),
+                c.and_then(|r| r.composite_score),
+                2,
+                ""
+            ),
+            delta(
+                b.map(|r| r.duration_secs),
+                c.map(|r| r.duration_secs),
+                1,
+                "s"
+            ),
+            delta(
+                b.and_then(|r| r.cost_usd),
+                c.and_then(|r| r.cost_usd),
+                4,
+                ""
+            ),
+            match s.verdict {
+                Verdict::Win => "win",
+                Verdict::Loss => "loss",
+                Verdict::Tie => "tie",
+                Verdict::Unpaired if b.is_none() => "candidate only",
+                Verdict::Unpaired => "baseline only",
+            }
+        );
+    }
+    let _ = writeln!(
+        out,
+        "\nCandidate vs baseline: {} win(s), {} loss(es), {} tie(s) over {} shared scenario(s)",
+        comparison.wins,
+        comparison.losses,
+        comparison.ties,
+        comparison.wins + comparison.losses + comparison.ties
+    );
+    if comparison.unpaired > 0 {
+        let _ = writeln!(
+            out,
+            "{} scenario(s) were run by only one side and are not counted",
+            comparison.unpaired
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_tool;
+    use crate::results::types::Outcome;
+
+    fn run(id: &str, scenario: &str, tool: &str, passed: bool, score: f64) -> ResultRecord {
+        let mut r = create_test_record_with_tool(id, scenario, tool);
+        r.gates_passed = passed;
+        r.outcome = if passed { Outcome::Pass } else { Outcome::Fail };
+        r.metrics.composite_score = Some(score);
+        r
+    }
+
+    #[test]
+    fn decides_by_pass_status_then_score() {
+        let mut skipped = run("run-9", "d", "claude-code", true, 1.0);
+        skipped.outcome = Outcome::SkippedUnavailable;
+        let records = vec![
+            run("run-1", "a", "opencode", false, 0.4),
+            run("run-2", "a", "claude-code", true, 0.9),
+            run("run-3", "b", "opencode", true, 0.9),
+            run("run-4", "b", "claude-code", true, 0.7),
+            run("run-5", "c", "opencode", true, 0.80),
+            run("run-6", "c", "claude-code", true, 0.82),
+            run("run-7", "d", "opencode", true, 0.9),
+            skipped,
+        ];
+        let comparison = compare_runs(&records, ("opencode", "gpt-4o"), ("claude-code", "gpt-4o"));
+
+        let verdicts: Vec<_> = comparison
+            .scenarios
+            .iter()
+            .map(|s| (s.scenario.as_str(), s.verdict))
+            .collect();
+        assert_eq!(
+            verdicts,
+            vec![
+                ("a", Verdict::Win),
+                ("b", Verdict::Loss),
+                ("c", Verdict::Tie),
+                ("d", Verdict::Unpaired),
+            ]
+        );
+        assert_eq!(
+            (
+                comparison.wins,
+                comparison.losses,
+                comparison.ties,
+                comparison.unpaired
+            ),
+            (1, 1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn renders_deltas_and_summary() {
+        let mut baseline = run("run-1", "capture", "opencode", false, 0.5);
+        baseline.duration_secs = 40.0;
+        let mut candidate = run("run-2", "capture", "claude-code", true, 0.75);
+        candidate.duration_secs = 30.0;
+        candidate.cost_usd = Some(0.04);
+        let comparison = compare_runs(
+            &[baseline, candidate],
+            ("opencode", "gpt-4o"),
+            ("claude-code", "gpt-4o"),
+        );
+
+        let text = render_comparison(&comparison);
+        assert!(text.contains("Baseline:  opencode/gpt-4o"));
+        assert!(text.contains("FAIL 0.50"));
+        assert!(text.contains("+0.25"));
+        assert!(text.contains("-10.0s"));
+        assert!(text.contains("+0.0300"));
+        assert!(text.contains("win"));
+        assert!(text.contains("1 win(s), 0 loss(es), 0 tie(s) over 1 shared scenario(s)"));
+    }
+}
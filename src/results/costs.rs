@@ -0,0 +1,196 @@
+//! Spend aggregation for recorded results.
+//!
+//! Sums agent and judge cost per tool, model, scenario or label and
+//! compares the totals against configured budgets.
+
+use crate::config::BudgetConfig;
+use crate::results::types::ResultRecord;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Group key used for records without any label.
+pub const UNLABELED: &str = "(none)";
+
+/// Dimension to group spend by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CostGroupBy {
+    Tool,
+    Model,
+    Scenario,
+    Label,
+}
+
+impl CostGroupBy {
+    /// Group keys a record contributes to. Records with several labels count in each.
+    fn keys(self, record: &ResultRecord) -> Vec<String> {
+        match self {
+            CostGroupBy::Tool => vec![record.tool.clone()],
+            CostGroupBy::Model => vec![record.model.clone()],
+            CostGroupBy::Scenario => vec![record.scenario_id.clone()],
+            CostGroupBy::Label if record.labels.is_empty() => vec![UNLABELED.to_string()],
+            CostGroupBy::Label => record.labels.clone(),
+        }
+    }
+
+    fn budgets(self, budgets: &BudgetConfig) -> &HashMap<String, f64> {
+        match self {
+            CostGroupBy::Tool => &budgets.tools,
+            CostGroupBy::Model => &budgets.models,
+            CostGroupBy::Scenario => &budgets.scenarios,
+            CostGroupBy::Label => &budgets.labels,
+        }
+    }
+}
+
+/// Spend for one group (or the overall total).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CostRow {
+    pub key: String,
+    pub runs: usize,
+    pub agent_usd: f64,
+    pub judge_usd: f64,
+    pub total_usd: f64,
+    /// Configured budget, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_usd: Option<f64>,
+    /// Budget minus actual spend; negative when over budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_usd: Option<f64>,
+}
+
+impl CostRow {
+    fn add(&mut self, record: &ResultRecord) {
+        let agent = record.cost_usd.unwrap_or(0.0);
+        let judge = record.judge_cost_usd.unwrap_or(0.0);
+        self.runs += 1;
+        self.agent_usd += agent;
+        self.judge_usd += judge;
+        self.total_usd += agent + judge;
+    }
+
+    fn with_budget(mut self, budget: Option<f64>) -> Self {
+        self.budget_usd = budget;
+        self.remaining_usd = budget.map(|b| b - self.total_usd);
+        self
+    }
+
+    /// Whether spend exceeds the configured budget.
+    pub fn over_budget(&self) -> bool {
+        self.remaining_usd.is_some_and(|r| r < 0.0)
+    }
+}
+
+/// Cost report: per-group rows sorted by spend (highest first) plus an overall total.
+#[derive(Debug, Clone, Serialize)]
+pub struct CostReport {
+    pub rows: Vec<CostRow>,
+    pub total: CostRow,
+}
+
+/// Aggregate spend across `records` grouped by `group_by`.
+pub fn summarize_costs(
+    records: &[ResultRecord],
+    group_by: CostGroupBy,
+    budgets: Option<&BudgetConfig>,
+) -> CostReport {
+    let mut groups: BTreeMap<String, CostRow> = BTreeMap::new();
+    let mut total = CostRow {
+        key: "total".to_string(),
+        ..Default::default()
+    };
+
+    for record in records {
+        total.add(record);
+        for key in group_by.keys(record) {
+            groups
+                .entry(key.clone())
+                .or_insert_with(|| CostRow {
+                    key,
+                    ..Default::default()
+                })
+                .add(record);
+        }
+    }
+
+    let group_budgets = budgets.map(|b| group_by.budgets(b));
+    let mut rows: Vec<CostRow> = groups
+        .into_values()
+        .map(|row| {
+            let budget = group_budgets.and_then(|b| b.get(&row.key).copied());
+            row.with_budget(budget)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.total_usd.total_cmp(&a.total_usd));
+
+    CostReport {
+        rows,
+        total: total.with_budget(budgets.and_then(|b| b.total_usd)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_tool;
+
+    fn record(tool: &str, agent: Option<f64>, judge: Option<f64>) -> ResultRecord {
+        let mut r = create_test_record_with_tool("run", "scenario", tool);
+        r.cost_usd = agent;
+        r.judge_cost_usd = judge;
+        r
+    }
+
+    #[test]
+    fn groups_by_tool_and_sorts_by_spend() {
+        let records = vec![
+            record("opencode", Some(0.10), Some(0.02)),
+            record("claude-code", Some(0.50), None),
+            record("opencode", None, None),
+        ];
+        let report = summarize_costs(&records, CostGroupBy::Tool, None);
+
+        assert_eq!(report.rows.len(), 2);
+        assert_eq!(report.rows[0].key, "claude-code");
+        assert_eq!(report.rows[1].runs, 2);
+        assert!((report.rows[1].judge_usd - 0.02).abs() < 1e-9);
+        assert!((report.rows[1].total_usd - 0.12).abs() < 1e-9);
+        assert_eq!(report.total.runs, 3);
+        assert!((report.total.total_usd - 0.62).abs() < 1e-9);
+        assert_eq!(report.total.budget_usd, None);
+    }
+
+    #[test]
+    fn label_grouping_counts_each_label() {
+        let mut a = record("opencode", Some(1.0), None);
+        a.labels = vec!["nightly".to_string(), "ci".to_string()];
+        let b = record("opencode", Some(2.0), None);
+        let report = summarize_costs(&[a, b], CostGroupBy::Label, None);
+
+        let keys: Vec<_> = report.rows.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0], UNLABELED);
+        assert!(keys.contains(&"nightly") && keys.contains(&"ci"));
+        assert!((report.total.total_usd - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compares_against_budgets() {
+        let budgets = BudgetConfig {
+            total_usd: Some(1.0),
+            tools: HashMap::from([("opencode".to_string(), 0.5)]),
+            ..Default::default()
+        };
+        let records = vec![
+            record("opencode", Some(0.75), None),
+            record("claude-code", Some(0.10), None),
+        ];
+        let report = summarize_costs(&records, CostGroupBy::Tool, Some(&budgets));
+
+        assert!(report.rows[0].over_budget());
+        assert!((report.rows[0].remaining_usd.unwrap() + 0.25).abs() < 1e-9);
+        assert_eq!(report.rows[1].budget_usd, None);
+        assert!(!report.rows[1].over_budget());
+        assert!(!report.total.over_budget());
+    }
+}
@@ -0,0 +1,282 @@
+//! Operational overview of the latest suite results.
+//!
+//! Builds the data behind `llm-tool-test dashboard`: pass rates by tier,
+//! regressions against each run's previous result, today's spend and the
+//! slowest scenarios. Rendering is plain text so the view works in any terminal.
+
+use crate::results::types::ResultRecord;
+use chrono::{Local, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Relative composite score drop that counts as a regression.
+const SCORE_DROP_THRESHOLD: f64 = 0.15;
+
+/// Number of entries shown in the regression and slowest lists.
+const TOP_N: usize = 5;
+
+/// Pass rate for one scenario tier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TierPassRate {
+    /// Scenario tier, or `None` for scenarios no longer in the fixtures directory
+    pub tier: Option<usize>,
+    pub passed: usize,
+    pub total: usize,
+}
+
+/// A scenario/tool/model whose latest run is worse than the one before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub scenario: String,
+    pub tool: String,
+    pub model: String,
+    pub reason: String,
+    /// Sort key: 1.0 for a newly failing run, otherwise the relative score drop
+    severity: f64,
+}
+
+/// Snapshot shown by the dashboard.
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub runs: usize,
+    pub tiers: Vec<TierPassRate>,
+    pub regressions: Vec<Regression>,
+    pub spend_today_usd: f64,
+    /// (scenario, tool, model, duration_secs), slowest first
+    pub slowest: Vec<(String, String, String, f64)>,
+}
+
+fn record_key(r: &ResultRecord) -> (String, String, String) {
+    (r.scenario_id.clone(), r.tool.clone(), r.model.clone())
+}
+
+fn regression(latest: &ResultRecord, previous: &ResultRecord) -> Option<Regression> {
+    let (reason, severity) = if previous.passed() && !latest.passed() {
+        (
+            format!(
+                "now failing ({}/{} gates)",
+                latest.metrics.gates_passed, latest.metrics.gates_total
+            ),
+            1.0,
+        )
+    } else {
+        let before = previous.metrics.composite_score?;
+        let after = latest.metrics.composite_score?;
+        if before <= 0.0 {
+            return None;
+        }
+        let drop = (before - after) / before;
+        if drop <= SCORE_DROP_THRESHOLD {
+            return None;
+        }
+        (format!("score {:.2} -> {:.2}", before, after), drop)
+    };
+    Some(Regression {
+        scenario: latest.scenario_id.clone(),
+        tool: latest.tool.clone(),
+        model: latest.model.clone(),
+        reason,
+        severity,
+    })
+}
+
+/// Build the dashboard from all recorded results.
+///
+/// The latest suite is the most recent run of each scenario/tool/model; its
+/// baseline is the run before that. Records in which the tool never ran are
+/// left out of both. `tiers` maps scenario names to their tier.
+pub fn build_dashboard(
+    records: &[ResultRecord],
+    tiers: &HashMap<String, usize>,
+    today: NaiveDate,
+) -> Dashboard {
+    let mut history: HashMap<(String, String, String), Vec<&ResultRecord>> = HashMap::new();
+    for record in records.iter().filter(|r| r.outcome.tool_ran()) {
+        history.entry(record_key(record)).or_default().push(record);
+    }
+
+    let mut tier_rates: BTreeMap<Option<usize>, (usize, usize)> = BTreeMap::new();
+    let mut regressions = Vec::new();
+    let mut slowest = Vec::new();
+    for runs in history.values_mut() {
+        runs.sort_by_key(|r| r.timestamp);
+        let latest = runs[runs.len() - 1];
+
+        let rate = tier_rates
+            .entry(tiers.get(&latest.scenario_id).copied())
+            .or_default();
+        rate.0 += usize::from(latest.passed());
+        rate.1 += 1;
+
+        if let Some(previous) = runs.len().checked_sub(2).map(|i| runs[i]) {
+            regressions.extend(regression(latest, previous));
+        }
+        slowest.push((
+            latest.scenario_id.clone(),
+            latest.tool.clone(),
+            latest.model.clone(),
+            latest.duration_secs,
+        ));
+    }
+
+    regressions.sort_by(|a, b| {
+        b.severity
+            .total_cmp(&a.severity)
+            .then_with(|| a.scenario.cmp(&b.scenario))
+    });
+    regressions.truncate(TOP_N);
+    slowest.sort_by(|a, b| b.3.total_cmp(&a.3).then_with(|| a.0.cmp(&b.0)));
+    slowest.truncate(TOP_N);
+
+    let spend_today_usd = records
+        .iter()
+        .filter(|r| r.timestamp.with_timezone(&Local).date_naive() == today)
+        .map(|r| r.cost_usd.unwrap_or(0.0) + r.judge_cost_usd.unwrap_or(0.0))
+        .sum();
+
+    Dashboard {
+        runs: history.len(),
+        tiers: tier_rates
+            .into_iter()
+            .map(|(tier, (passed, total))| TierPassRate {
+                tier,
+                passed,
+                total,
+            })
+            .collect(),
+        regressions,
+        spend_today_usd,
+        slowest,
+    }
+}
+
+/// Render the dashboard as plain text.
+pub fn render_dashboard(dashboard: &Dashboard) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Latest suite: {} scenario/tool/model combination(s)",
+        dashboard.runs
+    );
+    let _ = writeln!(out, "Spend today: ${:.4}", dashboard.spend_today_usd);
+
+    let _ = writeln!(out, "\nPass rate by tier");
+    for rate in &dashboard.tiers {
+        let label = rate
+            .tier
+            .map_or_else(|| "unknown".to_string(), |t| t.to_string());
+        let pct = if rate.total > 0 {
+            rate.passed as f64 / rate.total as f64 * 100.0
+        } else {
+            0.0
+        };
+        let _ = writeln!(
+            out,
+            "  tier {:<8} {:>3}/{:<3} {:>5.1}%",
+            label, rate.passed, rate.total, pct
+        );
+    }
+
+    let _ = writeln!(out, "\nTop regressions vs previous run");
+    if dashboard.regressions.is_empty() {
+        let _ = writeln!(out, "  none");
+    }
+    for r in &dashboard.regressions {
+        let _ = writeln!(
+            out,
+            "  {} [{}/{}]: {}",
+            r.scenario, r.tool, r.model, r.reason
+        );
+    }
+
+    let _ = writeln!(out, "\nSlowest scenarios");
+    for (scenario, tool, model, secs) in &dashboard.slowest {
+        let _ = writeln!(out, "  {:>8.1}s  {} [{}/{}]", secs, scenario, tool, model);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_scenario;
+    use crate::results::Outcome;
+    use chrono::{Duration, Utc};
+
+    fn record(scenario: &str, age_hours: i64, passed: bool, score: f64) -> ResultRecord {
+        let mut r = create_test_record_with_scenario("run", scenario);
+        r.timestamp = Utc::now() - Duration::hours(age_hours);
+        r.gates_passed = passed;
+        r.outcome = if passed { Outcome::Pass } else { Outcome::Fail };
+        r.metrics.composite_score = Some(score);
+        r
+    }
+
+    #[test]
+    fn uses_latest_run_per_scenario_for_pass_rates() {
+        let mut skipped = record("c", 0, false, 0.0);
+        skipped.outcome = Outcome::SkippedUnavailable;
+        let records = vec![
+            record("a", 2, false, 0.2),
+            record("a", 1, true, 0.9),
+            record("b", 1, false, 0.3),
+            record("c", 1, true, 0.9),
+            skipped,
+        ];
+        let tiers = HashMap::from([("a".to_string(), 0), ("b".to_string(), 0)]);
+        let dashboard = build_dashboard(&records, &tiers, Local::now().date_naive());
+
+        assert_eq!(dashboard.runs, 3);
+        assert_eq!(
+            dashboard.tiers,
+            vec![
+                TierPassRate {
+                    tier: None,
+                    passed: 1,
+                    total: 1
+                },
+                TierPassRate {
+                    tier: Some(0),
+                    passed: 1,
+                    total: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_new_failures_above_score_drops() {
+        let records = vec![
+            record("dropped", 2, true, 0.9),
+            record("dropped", 1, true, 0.6),
+            record("broken", 2, true, 0.9),
+            record("broken", 1, false, 0.8),
+            record("steady", 2, true, 0.9),
+            record("steady", 1, true, 0.85),
+        ];
+        let dashboard = build_dashboard(&records, &HashMap::new(), Local::now().date_naive());
+
+        let names: Vec<_> = dashboard
+            .regressions
+            .iter()
+            .map(|r| r.scenario.as_str())
+            .collect();
+        assert_eq!(names, vec!["broken", "dropped"]);
+        assert!(dashboard.regressions[1].reason.contains("0.90 -> 0.60"));
+    }
+
+    #[test]
+    fn sums_spend_for_today_only() {
+        let mut old = record("a", 72, true, 0.9);
+        old.cost_usd = Some(5.0);
+        let mut recent = record("a", 0, true, 0.9);
+        recent.cost_usd = Some(0.25);
+        recent.judge_cost_usd = Some(0.05);
+        let dashboard = build_dashboard(&[old, recent], &HashMap::new(), Local::now().date_naive());
+
+        assert!((dashboard.spend_today_usd - 0.30).abs() < 1e-9);
+        let text = render_dashboard(&dashboard);
+        assert!(text.contains("Spend today: $0.3000"));
+        assert!(text.contains("Slowest scenarios"));
+    }
+}
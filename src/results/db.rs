@@ -3,16 +3,20 @@
 //! Provides persistent append-only storage of test results
 //! in JSON Lines format for easy querying and analysis.
 
+use crate::artifacts::FsyncMode;
+use crate::results::calibration::Calibration;
 use crate::results::types::ResultRecord;
 use anyhow::{Context, Result};
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// JSONL-based results database.
 ///
 /// Stores test results as JSON Lines in a `results.jsonl` file,
-/// providing append-only writes and full/ID-based loading.
+/// providing append-only writes and full/ID-based loading. Appends are
+/// serialized, so parallel runs can share one database.
 ///
 /// # Example
 ///
@@ -29,12 +33,13 @@ use std::path::{Path, PathBuf};
 /// let all_results = db.load_all().unwrap();
 ///
 /// // Load specific result by ID
-/// if let Some(record) = db.load_by_id("run-20250130-120000").unwrap() {
+/// if let Some(record) = db.load_by_id("run-20250130-120000-123456789-3f2a9c01").unwrap() {
 ///     println!("Found result: {}", record.scenario_id);
 /// }
 /// ```
 pub struct ResultsDB {
     results_path: PathBuf,
+    append_lock: Mutex<()>,
 }
 
 impl ResultsDB {
@@ -53,6 +58,7 @@ impl ResultsDB {
         std::fs::create_dir_all(base_dir).ok();
         Self {
             results_path: base_dir.join("results.jsonl"),
+            append_lock: Mutex::new(()),
         }
     }
 
@@ -67,14 +73,22 @@ impl ResultsDB {
     /// * `Ok(())` - On success
     /// * `Err` - IO or serialization error
     pub fn append(&self, record: &ResultRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _guard = self
+            .append_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&self.results_path)
             .context("Failed to open results.jsonl")?;
-
-        let line = serde_json::to_string(record)?;
-        writeln!(file, "{}", line).context("Failed to write to results.jsonl")?;
+        // One write per record, so the line is never split by another writer
+        file.write_all(line.as_bytes())
+            .context("Failed to write to results.jsonl")?;
+        crate::artifacts::sync_appended(&file).context("Failed to sync results.jsonl")?;
         Ok(())
     }
 
@@ -103,6 +117,39 @@ impl ResultsDB {
         Ok(records)
     }
 
+    /// Replace the database contents with `records`.
+    ///
+    /// Writes to a temporary file first so a failure never leaves a truncated database.
+    pub fn replace_all(&self, records: &[ResultRecord]) -> Result<()> {
+        let mut content = String::new();
+        for record in records {
+            content.push_str(&serde_json::to_string(record)?);
+            content.push('\n');
+        }
+        crate::artifacts::write_atomic_with(
+            &self.results_path,
+            content.as_bytes(),
+            crate::artifacts::fsync_mode().max(FsyncMode::File),
+        )
+        .context("Failed to replace results.jsonl")
+    }
+
+    /// Move all records of scenario `from` to scenario id `to`.
+    ///
+    /// Returns the number of records changed.
+    pub fn relink_scenario(&self, from: &str, to: &str) -> Result<usize> {
+        let mut records = self.load_all()?;
+        let mut changed = 0;
+        for record in records.iter_mut().filter(|r| r.scenario_id == from) {
+            record.scenario_id = to.to_string();
+            changed += 1;
+        }
+        if changed > 0 {
+            self.replace_all(&records)?;
+        }
+        Ok(changed)
+    }
+
     /// Load a specific result record by ID.
     ///
     /// # Arguments
@@ -118,11 +165,36 @@ impl ResultsDB {
         let records = self.load_all()?;
         Ok(records.into_iter().find(|r| r.id == id))
     }
+
+    fn calibration_path(&self) -> PathBuf {
+        self.results_path.with_file_name("difficulty.json")
+    }
+
+    /// Store a difficulty calibration in `difficulty.json`, replacing the previous one.
+    pub fn save_calibration(&self, calibration: &Calibration) -> Result<()> {
+        let content = serde_json::to_string_pretty(calibration)?;
+        crate::artifacts::write_atomic(&self.calibration_path(), content)
+            .context("Failed to write difficulty.json")
+    }
+
+    /// Load the stored difficulty calibration, if `results calibrate` has been run.
+    pub fn load_calibration(&self) -> Result<Option<Calibration>> {
+        let path = self.calibration_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).context("Failed to read difficulty.json")?;
+        let calibration =
+            serde_json::from_str(&content).context("Failed to parse difficulty.json")?;
+        Ok(Some(calibration))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::results::test_helpers::{create_test_record, TestDb};
+    use crate::results::test_helpers::{
+        create_test_record, create_test_record_with_scenario, TestDb,
+    };
 
     #[test]
     fn test_results_db_append_and_load_all() {
@@ -140,6 +212,40 @@ mod tests {
         assert_eq!(loaded[1].id, "run-2");
     }
 
+    #[test]
+    fn test_results_db_concurrent_appends_keep_lines_whole() {
+        let test_db = TestDb::new();
+
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let db = &test_db.db;
+                scope.spawn(move || {
+                    for i in 0..20 {
+                        db.append(&create_test_record(&format!("run-{}-{}", thread, i)))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        let loaded = test_db.db.load_all().unwrap();
+        assert_eq!(loaded.len(), 160);
+    }
+
+    #[test]
+    fn test_results_db_calibration_round_trip() {
+        let test_db = TestDb::new();
+        assert!(test_db.db.load_calibration().unwrap().is_none());
+
+        let records = vec![create_test_record_with_scenario("run-1", "scenario-a")];
+        let calibration = crate::results::calibration::calibrate(&records);
+        test_db.db.save_calibration(&calibration).unwrap();
+
+        let loaded = test_db.db.load_calibration().unwrap().unwrap();
+        assert_eq!(loaded.scenarios, calibration.scenarios);
+        assert!(loaded.difficulty_of("scenario-a").is_some());
+    }
+
     #[test]
     fn test_results_db_load_empty() {
         let test_db = TestDb::new();
@@ -165,4 +271,23 @@ mod tests {
         let not_found = test_db.db.load_by_id("run-3").unwrap();
         assert!(not_found.is_none());
     }
+
+    #[test]
+    fn test_results_db_relink_scenario() {
+        let test_db = TestDb::new();
+        test_db
+            .db
+            .append(&create_test_record_with_scenario("run-1", "old"))
+            .unwrap();
+        test_db
+            .db
+            .append(&create_test_record_with_scenario("run-2", "other"))
+            .unwrap();
+
+        assert_eq!(test_db.db.relink_scenario("old", "new").unwrap(), 1);
+        let loaded = test_db.db.load_all().unwrap();
+        assert_eq!(loaded[0].scenario_id, "new");
+        assert_eq!(loaded[1].scenario_id, "other");
+        assert_eq!(test_db.db.relink_scenario("old", "new").unwrap(), 0);
+    }
 }
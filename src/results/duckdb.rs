@@ -0,0 +1,283 @@
+//! Export of result records into a DuckDB database.
+//!
+//! Records are written as three tables an analyst can query directly:
+//!
+//! - `runs` - one row per record, with the full record as JSON in `record_json`
+//! - `gates` - one row per gate result, keyed by `run_id` and `gate_index`
+//! - `efficiency` - one row per record with its efficiency metrics
+//!
+//! The database is written by piping a generated SQL script into the DuckDB
+//! CLI (`duckdb`, or the binary named by [`DUCKDB_BIN_ENV`]), so no native
+//! DuckDB library is linked into the harness. Existing tables of the same
+//! name are replaced; other tables in the file are left alone.
+
+use crate::results::types::ResultRecord;
+use anyhow::Context;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Environment variable naming the DuckDB CLI binary to run.
+pub const DUCKDB_BIN_ENV: &str = "LLM_TOOL_TEST_DUCKDB";
+
+/// Rows per `INSERT` statement, keeping statements a manageable size.
+const INSERT_BATCH: usize = 500;
+
+const SCHEMA: &str = "\
+CREATE OR REPLACE TABLE runs (
+    id VARCHAR PRIMARY KEY,
+    scenario_id VARCHAR,
+    scenario_hash VARCHAR,
+    tool VARCHAR,
+    model VARCHAR,
+    timestamp TIMESTAMPTZ,
+    duration_secs DOUBLE,
+    cost_usd DOUBLE,
+    judge_cost_usd DOUBLE,
+    outcome VARCHAR,
+    outcome_detail VARCHAR,
+    gates_passed BOOLEAN,
+    gates_passed_count INTEGER,
+    gates_total INTEGER,
+    judge_score DOUBLE,
+    composite_score DOUBLE,
+    labels VARCHAR[],
+    repeat_index INTEGER,
+    transcript_path VARCHAR,
+    record_json VARCHAR
+);
+CREATE OR REPLACE TABLE gates (
+    run_id VARCHAR,
+    gate_index INTEGER,
+    gate_type VARCHAR,
+    passed BOOLEAN,
+    score DOUBLE,
+    message VARCHAR,
+    expected VARCHAR,
+    actual VARCHAR,
+    path VARCHAR,
+    command VARCHAR,
+    exit_code INTEGER,
+    duration_secs DOUBLE,
+    warning BOOLEAN,
+    attempts INTEGER
+);
+CREATE OR REPLACE TABLE efficiency (
+    run_id VARCHAR PRIMARY KEY,
+    total_commands INTEGER,
+    unique_commands INTEGER,
+    error_count INTEGER,
+    retry_count INTEGER,
+    help_invocations INTEGER,
+    first_try_success_rate DOUBLE,
+    iteration_ratio DOUBLE,
+    wasted_actions INTEGER
+);
+";
+
+/// Quote `s` as a SQL string literal.
+fn str_lit(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn opt_str_lit(s: Option<&str>) -> String {
+    s.map(str_lit).unwrap_or_else(|| "NULL".to_string())
+}
+
+/// Non-finite values have no SQL literal and are written as NULL.
+fn opt_f64_lit(v: Option<f64>) -> String {
+    match v {
+        Some(v) if v.is_finite() => v.to_string(),
+        _ => "NULL".to_string(),
+    }
+}
+
+fn opt_int_lit<T: ToString>(v: Option<T>) -> String {
+    v.map(|v| v.to_string())
+        .unwrap_or_else(|| "NULL".to_string())
+}
+
+fn labels_lit(labels: &[String]) -> String {
+    let items: Vec<String> = labels.iter().map(|l| str_lit(l)).collect();
+    format!("[{}]::VARCHAR[]", items.join(", "))
+}
+
+/// Append `INSERT INTO <table> VALUES ...` statements for `rows`.
+fn push_inserts(script: &mut String, table: &str, rows: &[String]) {
+    for batch in rows.chunks(INSERT_BATCH) {
+        let _ = writeln!(
+            script,
+            "INSERT INTO {} VALUES\n    {};",
+            table,
+            batch.join(",\n    ")
+        );
+    }
+}
+
+/// Build the SQL script that creates and fills the export tables.
+pub fn build_script(records: &[ResultRecord]) -> anyhow::Result<String> {
+    let mut runs = Vec::with_capacity(records.len());
+    let mut gates = Vec::new();
+    let mut efficiency = Vec::with_capacity(records.len());
+
+    for r in records {
+        let m = &r.metrics;
+        runs.push(format!(
+            "({}, {}, {}, {}, {}, {}::TIMESTAMPTZ, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {})",
+            str_lit(&r.id),
+            str_lit(&r.scenario_id),
+            str_lit(&r.scenario_hash),
+            str_lit(&r.tool),
+            str_lit(&r.model),
+            str_lit(&r.timestamp.to_rfc3339()),
+            opt_f64_lit(Some(r.duration_secs)),
+            opt_f64_lit(r.cost_usd),
+            opt_f64_lit(r.judge_cost_usd),
+            str_lit(serde_json::to_value(r.outcome)?.as_str().unwrap_or_default()),
+            opt_str_lit(r.outcome_detail.as_deref()),
+            r.gates_passed,
+            m.gates_passed,
+            m.gates_total,
+            opt_f64_lit(r.judge_score),
+            opt_f64_lit(m.composite_score),
+            labels_lit(&r.labels),
+            opt_int_lit(r.repeat_index),
+            str_lit(&r.transcript_path),
+            str_lit(&serde_json::to_string(r)?),
+        ));
+
+        for (i, g) in m.details.iter().enumerate() {
+            gates.push(format!(
+                "({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {})",
+                str_lit(&r.id),
+                i,
+                str_lit(&g.gate_type),
+                g.passed,
+                opt_f64_lit(g.score),
+                str_lit(&g.message),
+                opt_str_lit(g.expected.as_deref()),
+                opt_str_lit(g.actual.as_deref()),
+                opt_str_lit(g.path.as_deref()),
+                opt_str_lit(g.command.as_deref()),
+                opt_int_lit(g.exit_code),
+                opt_f64_lit(g.duration_secs),
+                g.warning,
+                opt_int_lit(g.attempts),
+            ));
+        }
+
+        let e = &m.efficiency;
+        efficiency.push(format!(
+            "({}, {}, {}, {}, {}, {}, {}, {}, {})",
+            str_lit(&r.id),
+            e.total_commands,
+            e.unique_commands,
+            e.error_count,
+            e.retry_count,
+            e.help_invocations,
+            opt_f64_lit(Some(e.first_try_success_rate)),
+            opt_f64_lit(Some(e.iteration_ratio)),
+            e.wasted_actions,
+        ));
+    }
+
+    let mut script = String::from("BEGIN TRANSACTION;\n");
+    script.push_str(SCHEMA);
+    push_inserts(&mut script, "runs", &runs);
+    push_inserts(&mut script, "gates", &gates);
+    push_inserts(&mut script, "efficiency", &efficiency);
+    script.push_str("COMMIT;\n");
+    Ok(script)
+}
+
+/// Write `records` into the DuckDB database at `path`, creating it if needed.
+pub fn write_database(path: &Path, records: &[ResultRecord]) -> anyhow::Result<()> {
+    let script = build_script(records)?;
+    let bin = std::env::var(DUCKDB_BIN_ENV).unwrap_or_else(|_| "duckdb".to_string());
+
+    let mut child = Command::new(&bin)
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| {
+            format!(
+                "Failed to run DuckDB CLI '{}'; install duckdb or set {}",
+                bin, DUCKDB_BIN_ENV
+            )
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(script.as_bytes())
+            .context("Failed to send export script to DuckDB")?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "DuckDB failed writing {} ({}): {}",
+            path.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record;
+    use crate::results::types::GateResultRecord;
+
+    #[test]
+    fn script_flattens_gates_and_efficiency() {
+        let mut record = create_test_record("run-1");
+        record.labels = vec!["nightly".to_string()];
+        record.outcome_detail = Some("it's broken".to_string());
+        record.judge_score = Some(f64::NAN);
+        record.metrics.details = vec![
+            GateResultRecord {
+                gate_type: "file_exists".to_string(),
+                passed: true,
+                message: "File 'a.txt' exists".to_string(),
+                ..Default::default()
+            },
+            GateResultRecord {
+                gate_type: "command_succeeds".to_string(),
+                passed: false,
+                message: "exit 1".to_string(),
+                command: Some("make test".to_string()),
+                exit_code: Some(1),
+                ..Default::default()
+            },
+        ];
+
+        let script = build_script(&[record, create_test_record("run-2")]).unwrap();
+
+        assert!(script.starts_with("BEGIN TRANSACTION;"));
+        assert!(script.trim_end().ends_with("COMMIT;"));
+        assert!(script.contains("'it''s broken'"));
+        assert!(script.contains("['nightly']::VARCHAR[]"));
+        assert!(script.contains("[]::VARCHAR[]"));
+        assert!(script.contains(
+            "('run-1', 1, 'command_succeeds', false, NULL, 'exit 1', NULL, NULL, NULL, 'make test', 1, NULL, false, NULL)"
+        ));
+        assert!(script.contains("'File ''a.txt'' exists'"));
+        assert!(!script.contains("NaN"));
+
+        let efficiency = script.split("INSERT INTO efficiency").nth(1).unwrap();
+        assert!(efficiency.contains("('run-1', "));
+        assert!(efficiency.contains("('run-2', "));
+    }
+
+    #[test]
+    fn empty_export_still_creates_tables() {
+        let script = build_script(&[]).unwrap();
+        assert!(script.contains("CREATE OR REPLACE TABLE runs"));
+        assert!(script.contains("CREATE OR REPLACE TABLE gates"));
+        assert!(script.contains("CREATE OR REPLACE TABLE efficiency"));
+        assert!(!script.contains("INSERT INTO"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Export of recorded results, optionally anonymized for public sharing.
+//!
+//! Anonymized records keep every metric (gate pass/fail and scores,
+//! efficiency, cost, timing) but drop or obscure anything that can reveal a
+//! private benchmark: scenario names, gate and evaluator messages, file paths
+//! and caller-supplied organization strings.
+
+use crate::results::types::ResultRecord;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+
+/// Placeholder for caller-supplied strings removed from exported fields.
+pub const REDACTED: &str = "[redacted]";
+
+/// Stable pseudonym for a scenario id, so runs of one scenario still group together.
+pub fn pseudonymize_scenario(scenario_id: &str) -> String {
+    let digest = hex::encode(Sha256::digest(scenario_id.as_bytes()));
+    format!("scenario-{}", &digest[..12])
+}
+
+/// Replaces caller-supplied strings (case-insensitive) with [`REDACTED`].
+pub struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    pub fn new(strings: &[String]) -> anyhow::Result<Self> {
+        let patterns = strings
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| Regex::new(&format!("(?i){}", regex::escape(s))))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        self.patterns.iter().fold(text.to_string(), |text, re| {
+            re.replace_all(&text, REDACTED).into_owned()
+        })
+    }
+}
+
+/// Strip identifying content from a record while keeping its metrics.
+pub fn anonymize_record(record: &ResultRecord, redactor: &Redactor) -> ResultRecord {
+    let mut record = record.clone();
+    record.scenario_id = pseudonymize_scenario(&record.scenario_id);
+    record.tool = redactor.apply(&record.tool);
+    record.model = redactor.apply(&record.model);
+    record.outcome_detail = record.outcome_detail.map(|d| redactor.apply(&d));
+    record.labels = record.labels.iter().map(|l| redactor.apply(l)).collect();
+    record.transcript_path = String::new();
+    record.cache_key = None;
+
+    for gate in &mut record.metrics.details {
+        gate.message = String::new();
+        gate.expected = None;
+        gate.actual = None;
+        gate.path = None;
+        gate.command = None;
+    }
+    for evaluator in &mut record.metrics.evaluator_results {
+        evaluator.name = redactor.apply(&evaluator.name);
+        evaluator.metrics = None;
+        evaluator.summary = None;
+        evaluator.error = None;
+    }
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_scenario;
+    use crate::results::types::{EvaluatorResultRecord, GateResultRecord};
+
+    #[test]
+    fn anonymized_records_keep_metrics_only() {
+        let mut record = create_test_record_with_scenario("run-1", "acme_billing_export");
+        record.model = "ft:gpt-4o:AcmeCorp:notes".to_string();
+        record.labels = vec!["acme-nightly".to_string()];
+        record.transcript_path = "/home/alice/acme/results/run-1/artifacts".to_string();
+        record.metrics.details = vec![GateResultRecord {
+            gate_type: "file_exists".to_string(),
+            passed: false,
+            message: "Missing /home/alice/acme/invoices.csv".to_string(),
+            score: Some(0.5),
+            path: Some("acme/invoices.csv".to_string()),
+            command: Some("acme-export --check".to_string()),
+            exit_code: Some(1),
+            ..Default::default()
+        }];
+        record.metrics.evaluator_results = vec![EvaluatorResultRecord {
+            name: "acme_style".to_string(),
+            metrics: Some(serde_json::json!({"customer": "Initech"})),
+            score: Some(0.8),
+            summary: Some("Matches Acme house style".to_string()),
+            error: None,
+        }];
+
+        let redactor = Redactor::new(&["acme".to_string()]).unwrap();
+        let anon = anonymize_record(&record, &redactor);
+
+        assert_eq!(
+            anon.scenario_id,
+            pseudonymize_scenario("acme_billing_export")
+        );
+        assert_eq!(anon.model, "ft:gpt-4o:[redacted]Corp:notes");
+        assert_eq!(anon.labels, vec!["[redacted]-nightly"]);
+        assert!(anon.transcript_path.is_empty());
+        assert!(anon.metrics.details[0].message.is_empty());
+        assert!(anon.metrics.details[0].path.is_none());
+        assert!(anon.metrics.details[0].command.is_none());
+        assert_eq!(anon.metrics.details[0].exit_code, Some(1));
+        assert_eq!(anon.metrics.details[0].score, Some(0.5));
+        assert_eq!(anon.metrics.evaluator_results[0].name, "[redacted]_style");
+        assert_eq!(anon.metrics.evaluator_results[0].score, Some(0.8));
+        assert!(anon.metrics.evaluator_results[0].summary.is_none());
+        assert_eq!(anon.cost_usd, record.cost_usd);
+        assert_eq!(
+            anon.metrics.efficiency.total_commands,
+            record.metrics.efficiency.total_commands
+        );
+
+        let json = serde_json::to_string(&anon).unwrap();
+        assert!(!json.to_lowercase().contains("acme"));
+        assert!(!json.contains("alice"));
+        assert!(!json.contains("Initech"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Garbage collection of run artifact directories.
+//!
+//! Each run writes `<results>/<timestamp>-<tool>-<model>-<scenario>/`. Directories
+//! that no result record (or cache entry) points at are left behind by crashed
+//! runs, dry runs and deleted records; this module finds them.
+
+use crate::results::types::ResultRecord;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Name of the run directory a record's `transcript_path` lives in.
+///
+/// Transcripts are written to `<run_dir>/artifacts`, so the run directory is
+/// the parent of that component.
+pub fn run_dir_name(record: &ResultRecord) -> Option<String> {
+    let path = Path::new(&record.transcript_path);
+    let dir = if path.file_name().is_some_and(|n| n == "artifacts") {
+        path.parent()?
+    } else {
+        path
+    };
+    dir.file_name().map(|n| n.to_string_lossy().to_string())
+}
+
+/// Run directories in `base_dir` not referenced by any of `records`.
+///
+/// Only directories named like run directories (`YYYYMMDD-HHMMSS-...`) are
+/// considered. With `cutoff`, directories modified after it are kept so runs
+/// still in progress are not collected.
+pub fn find_orphaned_run_dirs(
+    base_dir: &Path,
+    records: &[ResultRecord],
+    cutoff: Option<DateTime<Utc>>,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let run_dir_pattern = Regex::new(r"^\d{8}-\d{6}-")?;
+    let referenced: HashSet<String> = records.iter().filter_map(run_dir_name).collect();
+
+    let mut orphans = Vec::new();
+    if !base_dir.exists() {
+        return Ok(orphans);
+    }
+    for entry in std::fs::read_dir(base_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || !run_dir_pattern.is_match(&name) || referenced.contains(&name) {
+            continue;
+        }
+        if let Some(cutoff) = cutoff {
+            let modified = entry.metadata()?.modified()?;
+            if DateTime::<Utc>::from(modified) > cutoff {
+                continue;
+            }
+        }
+        orphans.push(path);
+    }
+    orphans.sort();
+    Ok(orphans)
+}
+
+/// Total size in bytes of the files under `path`.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(ty) if ty.is_dir() => dir_size(&entry.path()),
+            Ok(ty) if ty.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Human-readable byte count (e.g. "1.5 MiB").
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record;
+    use std::fs;
+
+    #[test]
+    fn finds_unreferenced_run_dirs_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("20250101-120000-opencode-gpt-4o-kept");
+        let orphan = dir.path().join("20250101-130000-opencode-gpt-4o-orphan");
+        fs::create_dir_all(kept.join("artifacts")).unwrap();
+        fs::create_dir_all(&orphan).unwrap();
+        fs::write(orphan.join("transcript.raw.txt"), "0123456789").unwrap();
+        fs::create_dir_all(dir.path().join("cache")).unwrap();
+
+        let mut record = create_test_record("run-1");
+        record.transcript_path = kept.join("artifacts").to_string_lossy().to_string();
+
+        let orphans = find_orphaned_run_dirs(dir.path(), &[record], None).unwrap();
+        assert_eq!(orphans, vec![orphan.clone()]);
+        assert_eq!(dir_size(&orphan), 10);
+    }
+
+    #[test]
+    fn keeps_recent_dirs_with_cutoff() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("20250101-130000-mock-mock-running")).unwrap();
+
+        let cutoff = Utc::now() - chrono::Duration::hours(1);
+        let orphans = find_orphaned_run_dirs(dir.path(), &[], Some(cutoff)).unwrap();
+        assert!(orphans.is_empty());
+    }
+
+    #[test]
+    fn formats_byte_counts() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,490 @@
+//! Static HTML site generated from recorded results (`report --html <DIR>`).
+//!
+//! `index.html` lists the latest run of each scenario/tool/model with a
+//! per-suite comparison; every listed run gets `runs/<run-id>/index.html`
+//! with its gates, efficiency metrics, evaluator results and judge feedback,
+//! next to copies of its transcripts and reports. Everything is read from the
+//! results database and run directories, so the site can be regenerated at
+//! any time without re-running scenarios.
+
+use crate::judge::JudgeResponse;
+use crate::results::stats::{latest_runs, weighted_aggregate};
+use crate::results::types::ResultRecord;
+use crate::transcript::redact::redact_sensitive;
+use chrono::Utc;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Run artifacts copied next to each run page: (directory, file name).
+///
+/// `results` is the run directory, `artifacts` its `artifacts/` subdirectory.
+const RUN_FILES: &[(&str, &str)] = &[
+    ("artifacts", "transcript.raw.txt"),
+    ("artifacts", "transcript.human.txt"),
+    ("artifacts", "transcript.stdout.txt"),
+    ("artifacts", "transcript.stderr.txt"),
+    ("artifacts", "events.jsonl"),
+    ("artifacts", "run.json"),
+    ("results", "evaluation.md"),
+    ("results", "report.md"),
+    ("results", "metrics.json"),
+    ("results", "score_breakdown.json"),
+];
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f4f4f4}td.num{text-align:right}\
+.pass{color:#2a7d2a;font-weight:bold}.fail{color:#c0392b;font-weight:bold}\
+.warn{color:#b7791f;font-weight:bold}\
+.muted{color:#777}pre{white-space:pre-wrap;margin:0}";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn fmt_opt(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v))
+}
+
+fn suite_name(r: &ResultRecord) -> String {
+    format!("{}/{}", r.tool, r.model)
+}
+
+fn page_start(out: &mut String, title: &str) {
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>",
+        escape_html(title),
+        STYLE
+    );
+}
+
+fn page_end(out: &mut String) {
+    let _ = writeln!(out, "</body>\n</html>");
+}
+
+fn outcome_cell(r: &ResultRecord) -> String {
+    format!(
+        "<span class=\"{}\">{}</span>",
+        if r.passed() { "pass" } else { "fail" },
+        escape_html(&r.outcome_summary())
+    )
+}
+
+/// Render `index.html`: the per-suite comparison and the list of runs.
+pub fn render_index(latest: &[&ResultRecord], weights: &HashMap<String, f64>) -> String {
+    let mut out = String::new();
+    page_start(&mut out, "llm-tool-test results");
+    let _ = writeln!(out, "<h1>llm-tool-test results</h1>");
+    let _ = writeln!(
+        out,
+        "<p class=\"muted\">Generated {} from {} run(s); each scenario/tool/model uses its latest run.</p>",
+        Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        latest.len()
+    );
+    if latest.is_empty() {
+        let _ = writeln!(out, "<p>No results recorded.</p>");
+        page_end(&mut out);
+        return out;
+    }
+
+    let mut suites: BTreeMap<String, Vec<&ResultRecord>> = BTreeMap::new();
+    for r in latest {
+        suites.entry(suite_name(r)).or_default().push(r);
+    }
+    let _ = writeln!(out, "<h2>Comparison</h2>\n<table>");
+    let _ = writeln!(
+        out,
+        "<tr><th>Tool/Model</th><th>Scenarios</th><th>Pass Rate</th><th>Score</th><th>Cost (USD)</th></tr>"
+    );
+    for (suite, runs) in &suites {
+        let (pass_rate, mean_score) = weighted_aggregate(runs, weights);
+        let cost: f64 = runs.iter().filter_map(|r| r.cost_usd).sum();
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td><td class=\"num\">{}</td><td class=\"num\">{:.4}</td></tr>",
+            escape_html(suite),
+            runs.len(),
+            pass_rate * 100.0,
+            fmt_opt(mean_score, 2),
+            cost
+        );
+    }
+    let _ = writeln!(out, "</table>");
+
+    let _ = writeln!(out, "<h2>Scenarios</h2>\n<table>");
+    let _ = writeln!(
+        out,
+        "<tr><th>Scenario</th><th>Tool/Model</th><th>Outcome</th><th>Gates</th><th>Score</th><th>Judge</th><th>Duration</th><th>Cost (USD)</th><th>Run</th></tr>"
+    );
+    let mut runs = latest.to_vec();
+    runs.sort_by(|a, b| {
+        (&a.scenario_id, &a.tool, &a.model).cmp(&(&b.scenario_id, &b.tool, &b.model))
+    });
+    for r in runs {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}/{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}s</td><td class=\"num\">{}</td><td><a href=\"runs/{id}/index.html\">{id}</a></td></tr>",
+            escape_html(&r.scenario_id),
+            escape_html(&suite_name(r)),
+            outcome_cell(r),
+            r.metrics.gates_passed,
+            r.metrics.gates_total,
+            fmt_opt(r.metrics.composite_score, 2),
+            fmt_opt(r.judge_score, 2),
+            r.duration_secs,
+            fmt_opt(r.cost_usd, 4),
+            id = escape_html(&r.id)
+        );
+    }
+    let _ = writeln!(out, "</table>");
+    page_end(&mut out);
+    out
+}
+
+/// Render the drill-down page of one run.
+///
+/// `judge` is the run's `judge.json`, if it was judged; `files` are the
+/// artifacts copied next to the page.
+pub fn render_run(record: &ResultRecord, judge: Option<&JudgeResponse>, files: &[&str]) -> String {
+    let r = record;
+    let mut out = String::new();
+    page_start(
+        &mut out,
+        &format!("{} ({}) - {}", r.scenario_id, suite_name(r), r.id),
+    );
+    let _ = writeln!(
+        out,
+        "<p><a href=\"../../index.html\">&larr; All runs</a></p>\n<h1>{} <span class=\"muted\">{}</span></h1>",
+        escape_html(&r.scenario_id),
+        escape_html(&suite_name(r))
+    );
+
+    let mut summary = vec![
+        ("Run", escape_html(&r.id)),
+        ("Outcome", outcome_cell(r)),
+        (
+            "Finished",
+            r.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        ),
+        ("Duration", format!("{:.1}s", r.duration_secs)),
+        (
+            "Gates",
+            format!("{}/{}", r.metrics.gates_passed, r.metrics.gates_total),
+        ),
+        ("Composite score", fmt_opt(r.metrics.composite_score, 2)),
+        ("Judge score", fmt_opt(r.judge_score, 2)),
+        ("Cost (USD)", fmt_opt(r.cost_usd, 4)),
+        ("Judge cost (USD)", fmt_opt(r.judge_cost_usd, 4)),
+    ];
+    if !r.labels.is_empty() {
+        summary.push(("Labels", escape_html(&r.labels.join(", "))));
+    }
+    let _ = writeln!(out, "<h2>Summary</h2>\n<table>");
+    for (name, value) in summary {
+        let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", name, value);
+    }
+    let _ = writeln!(out, "</table>");
+
+    let _ = writeln!(out, "<h2>Gates</h2>");
+    if r.metrics.details.is_empty() {
+        let _ = writeln!(out, "<p>No gates evaluated.</p>");
+    } else {
+        let _ = writeln!(
+            out,
+            "<table>\n<tr><th>#</th><th>Gate</th><th>Result</th><th>Message</th><th>Details</th><th>Time</th></tr>"
+        );
+        for (index, gate) in r.metrics.details.iter().enumerate() {
+            let mut details = Vec::new();
+            for (label, value) in [
+                ("command", &gate.command),
+                ("path", &gate.path),
+                ("expected", &gate.expected),
+                ("actual", &gate.actual),
+            ] {
+                if let Some(value) = value {
+                    details.push(format!(
+                        "{}: {}",
+                        label,
+                        escape_html(&redact_sensitive(value))
+                    ));
+                }
+            }
+            if let Some(code) = gate.exit_code {
+                details.push(format!("exit code: {}", code));
+            }
+            if let Some(attempts) = gate.attempts {
+                details.push(format!("attempts: {}", attempts));
+            }
+            let (class, label) = match (gate.passed, gate.warning) {
+                (true, _) => ("pass", "PASS"),
+                (false, true) => ("warn", "WARN"),
+                (false, false) => ("fail", "FAIL"),
+            };
+            let _ = writeln!(
+                out,
+                "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"{}\">{}</td><td><pre>{}</pre></td><td><pre>{}</pre></td><td class=\"num\">{}</td></tr>",
+                index + 1,
+                escape_html(&gate.gate_type),
+                class,
+                label,
+                escape_html(&redact_sensitive(&gate.message)),
+                details.join("\n"),
+                gate.duration_secs
+                    .map_or_else(|| "-".to_string(), |d| format!("{:.2}s", d))
+            );
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    let e = &r.metrics.efficiency;
+    let _ = writeln!(out, "<h2>Efficiency</h2>\n<table>");
+    for (name, value) in [
+        ("Total commands", e.total_commands.to_string()),
+        ("Unique commands", e.unique_commands.to_string()),
+        ("Errors", e.error_count.to_string()),
+        ("Retries", e.retry_count.to_string()),
+        ("Help invocations", e.help_invocations.to_string()),
+        (
+            "First-try success rate",
+            format!("{:.0}%", e.first_try_success_rate * 100.0),
+        ),
+        ("Iteration ratio", format!("{:.2}", e.iteration_ratio)),
+        ("Wasted actions", e.wasted_actions.to_string()),
+    ] {
+        let _ = writeln!(
+            out,
+            "<tr><th>{}</th><td class=\"num\">{}</td></tr>",
+            name, value
+        );
+    }
+    let _ = writeln!(out, "</table>");
+
+    if let Some(phases) = &r.phase_durations {
+        let _ = writeln!(out, "<h2>Phases</h2>\n<table>");
+        for (name, secs) in [
+            ("Setup", phases.setup_secs),
+            ("Agent", phases.agent_secs),
+            ("Post scripts", phases.post_scripts_secs),
+            ("Gates", phases.gates_secs),
+            ("Judge", phases.judge_secs),
+            ("Evaluators", phases.evaluators_secs),
+        ] {
+            let _ = writeln!(
+                out,
+                "<tr><th>{}</th><td class=\"num\">{:.1}s</td></tr>",
+                name, secs
+            );
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    if let Some(judge) = judge {
+        let _ = writeln!(
+            out,
+            "<h2>Judge Feedback</h2>\n<p>Weighted score {:.2}, confidence {:.2}</p>",
+            judge.weighted_score, judge.confidence
+        );
+        if !judge.scores.is_empty() {
+            let scores: BTreeMap<_, _> = judge.scores.iter().collect();
+            let _ = writeln!(out, "<table>\n<tr><th>Criterion</th><th>Score</th></tr>");
+            for (criterion, score) in scores {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{}</td><td class=\"num\">{:.2}</td></tr>",
+                    escape_html(criterion),
+                    score
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+        for (title, items) in [("Issues", &judge.issues), ("Highlights", &judge.highlights)] {
+            if items.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "<h3>{}</h3>\n<ul>", title);
+            for item in items {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(item));
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+    }
+
+    if !r.metrics.evaluator_results.is_empty() {
+        let _ = writeln!(
+            out,
+            "<h2>Evaluators</h2>\n<table>\n<tr><th>Evaluator</th><th>Score</th><th>Summary</th></tr>"
+        );
+        for result in &r.metrics.evaluator_results {
+            let summary = match (&result.error, &result.summary) {
+                (Some(error), _) => format!("<span class=\"fail\">{}</span>", escape_html(error)),
+                (None, Some(summary)) => escape_html(summary),
+                (None, None) => String::new(),
+            };
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+                escape_html(&result.name),
+                fmt_opt(result.score, 2),
+                summary
+            );
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    if !r.metrics.component_errors.is_empty() {
+        let _ = writeln!(out, "<h2>Component Errors</h2>\n<ul>");
+        for error in &r.metrics.component_errors {
+            let _ = writeln!(
+                out,
+                "<li><b>{}</b>: {}</li>",
+                escape_html(&error.component),
+                escape_html(&error.message)
+            );
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+
+    let _ = writeln!(out, "<h2>Artifacts</h2>");
+    if files.is_empty() {
+        let _ = writeln!(
+            out,
+            "<p class=\"muted\">No artifacts found (the run directory may have been pruned).</p>"
+        );
+    } else {
+        let _ = writeln!(out, "<ul>");
+        for file in files {
+            let _ = writeln!(out, "<li><a href=\"{0}\">{0}</a></li>", escape_html(file));
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+    page_end(&mut out);
+    out
+}
+
+/// Write the site for the latest run of each scenario/tool/model in `records`
+/// to `dir`, returning the number of run pages written.
+pub fn write_site(
+    dir: &Path,
+    records: &[ResultRecord],
+    weights: &HashMap<String, f64>,
+) -> anyhow::Result<usize> {
+    let latest = latest_runs(records);
+    std::fs::create_dir_all(dir)?;
+    crate::artifacts::write_atomic(&dir.join("index.html"), render_index(&latest, weights))?;
+
+    for r in &latest {
+        let page_dir = dir.join("runs").join(&r.id);
+        std::fs::create_dir_all(&page_dir)?;
+
+        let artifacts_dir = Path::new(&r.transcript_path);
+        let results_dir = artifacts_dir.parent().unwrap_or(artifacts_dir);
+        let mut files = Vec::new();
+        for (location, name) in RUN_FILES {
+            let source = match *location {
+                "artifacts" => artifacts_dir.join(name),
+                _ => results_dir.join(name),
+            };
+            if let Ok(content) = crate::artifacts::read_to_string(&source) {
+                crate::artifacts::write_atomic(&page_dir.join(name), content)?;
+                files.push(*name);
+            }
+        }
+        let judge: Option<JudgeResponse> =
+            crate::artifacts::read_to_string(&results_dir.join("judge.json"))
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok());
+
+        crate::artifacts::write_atomic(
+            &page_dir.join("index.html"),
+            render_run(r, judge.as_ref(), &files),
+        )?;
+    }
+    Ok(latest.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::{create_test_record, create_test_record_with_scenario};
+    use crate::results::types::{GateResultRecord, Outcome};
+
+    #[test]
+    fn index_lists_suites_and_links_run_pages() {
+        let mut failing = create_test_record_with_scenario("run-2", "<capture>");
+        failing.gates_passed = false;
+        failing.outcome = Outcome::Fail;
+        let passing = create_test_record("run-1");
+
+        let html = render_index(&[&passing, &failing], &HashMap::new());
+        assert!(html.contains(
+            "<td>opencode/gpt-4o</td><td class=\"num\">2</td><td class=\"num\">50.0%</td>"
+        ));
+        assert!(html.contains("<td>&lt;capture&gt;</td>"));
+        assert!(html.contains("<a href=\"runs/run-2/index.html\">run-2</a>"));
+        assert!(html.find("&lt;capture&gt;") < html.find("test-scenario"));
+    }
+
+    #[test]
+    fn run_page_shows_gates_judge_feedback_and_artifacts() {
+        let mut record = create_test_record("run-1");
+        record.metrics.details = vec![GateResultRecord {
+            gate_type: "CommandSucceeds".to_string(),
+            passed: false,
+            message: "Command failed".to_string(),
+            command: Some("qipu list".to_string()),
+            exit_code: Some(2),
+            ..Default::default()
+        }];
+        let judge = JudgeResponse {
+            scores: HashMap::from([("accuracy".to_string(), 0.5)]),
+            weighted_score: 0.5,
+            confidence: 0.9,
+            issues: vec!["Missed the <tag>".to_string()],
+            highlights: vec![],
+        };
+
+        let html = render_run(&record, Some(&judge), &["transcript.raw.txt"]);
+        assert!(html.contains("<td class=\"fail\">FAIL</td><td><pre>Command failed</pre></td><td><pre>command: qipu list\nexit code: 2</pre></td>"));
+        assert!(
+            html.contains("<h2>Judge Feedback</h2>\n<p>Weighted score 0.50, confidence 0.90</p>")
+        );
+        assert!(html.contains("<li>Missed the &lt;tag&gt;</li>"));
+        assert!(!html.contains("<h3>Highlights</h3>"));
+        assert!(html.contains("<a href=\"transcript.raw.txt\">transcript.raw.txt</a>"));
+    }
+
+    #[test]
+    fn writes_site_with_copied_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let run_dir = dir.path().join("results/20260101-opencode-gpt-4o-capture");
+        std::fs::create_dir_all(run_dir.join("artifacts")).unwrap();
+        std::fs::write(run_dir.join("artifacts/transcript.raw.txt"), "hello").unwrap();
+        std::fs::write(run_dir.join("evaluation.md"), "# Evaluation").unwrap();
+        std::fs::write(
+            run_dir.join("judge.json"),
+            r#"{"scores":{},"weighted_score":0.8,"confidence":1.0,"issues":[],"highlights":["Tidy"]}"#,
+        )
+        .unwrap();
+        let mut record = create_test_record("run-1");
+        record.transcript_path = run_dir.join("artifacts").display().to_string();
+
+        let site = dir.path().join("site");
+        assert_eq!(write_site(&site, &[record], &HashMap::new()).unwrap(), 1);
+        assert!(site.join("index.html").exists());
+        let page = std::fs::read_to_string(site.join("runs/run-1/index.html")).unwrap();
+        assert!(page.contains("<li>Tidy</li>"));
+        assert!(page.contains("<a href=\"evaluation.md\">evaluation.md</a>"));
+        assert!(!page.contains("report.md"));
+        assert_eq!(
+            std::fs::read_to_string(site.join("runs/run-1/transcript.raw.txt")).unwrap(),
+            "hello"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! JUnit XML output for CI test tabs (`run --report junit=<path>`).
+//!
+//! Each scenario × tool/model run becomes a `<testsuite>`; its gates,
+//! failed judge/evaluator components and the overall outcome are the
+//! `<testcase>`s. A run that could not produce a record is a suite with a
+//! single erroring `run` case.
+
+use crate::results::types::{Outcome, ResultRecord};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Report written after `run` finishes, given as `<format>=<path>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunReport {
+    /// JUnit XML at the given path
+    Junit(PathBuf),
+}
+
+impl FromStr for RunReport {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some(("junit", path)) if !path.is_empty() => Ok(RunReport::Junit(PathBuf::from(path))),
+            Some((format, _)) if format != "junit" => Err(format!(
+                "unknown report format '{}' (supported: junit)",
+                format
+            )),
+            _ => Err("expected <format>=<path>, e.g. junit=results.xml".to_string()),
+        }
+    }
+}
+
+/// Result of one testcase.
+enum CaseResult<'a> {
+    Passed,
+    Failed(&'a str, String),
+    Errored(&'a str, String),
+    Skipped(&'a str),
+}
+
+/// Test suites accumulated over a run, rendered as one JUnit document.
+#[derive(Debug, Default)]
+pub struct JunitReport {
+    suites: String,
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    skipped: usize,
+    time: f64,
+}
+
+impl JunitReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the outcome of running `scenario` with `tool`/`model`.
+    pub fn add_run(
+        &mut self,
+        scenario: &str,
+        tool: &str,
+        model: &str,
+        result: &anyhow::Result<ResultRecord>,
+    ) {
+        let classname = format!("{}.{}.{}", scenario, tool, model);
+        let mut suite = SuiteWriter::default();
+        let (time, timestamp) = match result {
+            Ok(record) => {
+                add_record_cases(&mut suite, &classname, record);
+                (record.duration_secs, Some(record.timestamp.to_rfc3339()))
+            }
+            Err(e) => {
+                let message = format!("{:#}", e);
+                suite.case(
+                    &classname,
+                    "run",
+                    0.0,
+                    CaseResult::Errored(&message, message.clone()),
+                );
+                (0.0, None)
+            }
+        };
+
+        let _ = write!(
+            self.suites,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+            escape_xml(&format!("{} [{}/{}]", scenario, tool, model)),
+            suite.tests,
+            suite.failures,
+            suite.errors,
+            suite.skipped,
+            time
+        );
+        if let Some(timestamp) = timestamp {
+            let _ = write!(self.suites, " timestamp=\"{}\"", timestamp);
+        }
+        let _ = writeln!(self.suites, ">");
+        if let Ok(record) = result {
+            let _ = writeln!(self.suites, "    <properties>");
+            let mut properties = vec![
+                ("tool", tool.to_string()),
+                ("model", model.to_string()),
+                ("outcome", record.outcome_summary()),
+                ("run_id", record.id.clone()),
+                ("transcript", record.transcript_path.clone()),
+            ];
+            if let Some(cost) = record.cost_usd {
+                properties.push(("cost_usd", format!("{:.4}", cost)));
+            }
+            if let Some(score) = record.metrics.composite_score {
+                properties.push(("composite_score", format!("{:.3}", score)));
+            }
+            for (name, value) in properties {
+                let _ = writeln!(
+                    self.suites,
+                    "      <property name=\"{}\" value=\"{}\"/>",
+                    name,
+                    escape_xml(&value)
+                );
+            }
+            let _ = writeln!(self.suites, "    </properties>");
+        }
+        self.suites.push_str(&suite.cases);
+        let _ = writeln!(self.suites, "  </testsuite>");
+
+        self.tests += suite.tests;
+        self.failures += suite.failures;
+        self.errors += suite.errors;
+        self.skipped += suite.skipped;
+        self.time += time;
+    }
+
+    /// The complete JUnit XML document.
+    pub fn render(&self) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"llm-tool-test\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n{}</testsuites>\n",
+            self.tests, self.failures, self.errors, self.skipped, self.time, self.suites
+        )
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        crate::artifacts::write_atomic(path, self.render())
+            .map_err(|e| anyhow::anyhow!("Failed to write JUnit report {}: {}", path.display(), e))
+    }
+}
+
+/// One testcase per gate and per failed component, then the run's outcome.
+fn add_record_cases(suite: &mut SuiteWriter, classname: &str, record: &ResultRecord) {
+    for (index, gate) in record.metrics.details.iter().enumerate() {
+        let name = format!("gate {}: {}", index + 1, gate.gate_type);
+        let result = if gate.passed {
+            CaseResult::Passed
+        } else if gate.warning || gate.skipped || gate.message.starts_with("Skipped: ") {
+            // Warning gates never fail the run, so they must not fail CI either
+            CaseResult::Skipped(&gate.message)
+        } else {
+            let mut body = gate.message.clone();
+            for (label, value) in [
+                ("command", &gate.command),
+                ("path", &gate.path),
+                ("expected", &gate.expected),
+                ("actual", &gate.actual),
+            ] {
+                if let Some(value) = value {
+                    let _ = write!(body, "\n{}: {}", label, value);
+                }
+            }
+            if let Some(code) = gate.exit_code {
+                let _ = write!(body, "\nexit code: {}", code);
+            }
+            CaseResult::Failed(&gate.message, body)
+        };
+        suite.case(classname, &name, gate.duration_secs.unwrap_or(0.0), result);
+    }
+
+    for error in &record.metrics.component_errors {
+        suite.case(
+            classname,
+            &error.component,
+            0.0,
+            CaseResult::Errored(&error.message, error.message.clone()),
+        );
+    }
+
+    let summary = record.outcome_summary();
+    let result = match record.outcome {
+        Outcome::Pass => CaseResult::Passed,
+        Outcome::Fail => CaseResult::Failed(&summary, summary.clone()),
+        Outcome::DryRun | Outcome::Skipped | Outcome::SkippedUnavailable => {
+            CaseResult::Skipped(&summary)
+        }
+        Outcome::Error
+        | Outcome::Timeout
+        | Outcome::Stalled
+        | Outcome::Cancelled
+        | Outcome::TurnLimit
+        | Outcome::SetupInvalid => CaseResult::Errored(&summary, summary.clone()),
+    };
+    suite.case(classname, "outcome", record.duration_secs, result);
+}
+
+/// Testcases of one suite with their tallies.
+#[derive(Default)]
+struct SuiteWriter {
+    cases: String,
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    skipped: usize,
+}
+
+impl SuiteWriter {
+    fn case(&mut self, classname: &str, name: &str, time: f64, result: CaseResult<'_>) {
+        self.tests += 1;
+        let _ = write!(
+            self.cases,
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+            escape_xml(classname),
+            escape_xml(name),
+            time
+        );
+        let (tag, message, body) = match result {
+            CaseResult::Passed => {
+                let _ = writeln!(self.cases, "/>");
+                return;
+            }
+            CaseResult::Failed(message, body) => {
+                self.failures += 1;
+                ("failure", message, Some(body))
+            }
+            CaseResult::Errored(message, body) => {
+                self.errors += 1;
+                ("error", message, Some(body))
+            }
+            CaseResult::Skipped(message) => {
+                self.skipped += 1;
+                ("skipped", message, None)
+            }
+        };
+        let message = escape_xml(first_line(message));
+        let _ = match body {
+            Some(body) => writeln!(
+                self.cases,
+                ">\n      <{tag} message=\"{message}\">{}</{tag}>\n    </testcase>",
+                escape_xml(&body)
+            ),
+            None => writeln!(
+                self.cases,
+                ">\n      <{tag} message=\"{message}\"/>\n    </testcase>"
+            ),
+        };
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+/// Escape text for XML, dropping control characters XML 1.0 cannot carry.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' | '\t' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record;
+    use crate::results::types::GateResultRecord;
+
+    fn gate(gate_type: &str, passed: bool, message: &str) -> GateResultRecord {
+        GateResultRecord {
+            gate_type: gate_type.to_string(),
+            passed,
+            message: message.to_string(),
+            duration_secs: Some(0.25),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_report_targets() {
+        assert_eq!(
+            "junit=out/results.xml".parse::<RunReport>(),
+            Ok(RunReport::Junit(PathBuf::from("out/results.xml")))
+        );
+        assert!("html=report.html"
+            .parse::<RunReport>()
+            .unwrap_err()
+            .contains("unknown report format 'html'"));
+        assert!("junit".parse::<RunReport>().is_err());
+    }
+
+    #[test]
+    fn renders_gates_outcomes_and_run_errors() {
+        let mut record = create_test_record("capture");
+        record.outcome = Outcome::Fail;
+        record.outcome_detail = Some("1/3 gates passed".to_string());
+        let mut failed = gate("CommandSucceeds", false, "Command failed <qipu list>");
+        failed.command = Some("qipu list".to_string());
+        failed.exit_code = Some(2);
+        record.metrics.details = vec![
+            gate("FileExists", true, "ok"),
+            failed,
+            gate("NoTranscriptErrors", false, "Skipped: 'build' did not pass"),
+        ];
+
+        let mut report = JunitReport::new();
+        report.add_run("capture", "mock", "m1", &Ok(record));
+        report.add_run(
+            "broken",
+            "mock",
+            "m1",
+            &Err(anyhow::anyhow!("Fixture not found")),
+        );
+        let xml = report.render();
+
+        assert!(xml.contains(
+            "<testsuites name=\"llm-tool-test\" tests=\"5\" failures=\"2\" errors=\"1\" skipped=\"1\""
+        ));
+        assert!(xml.contains(
+            "<testcase classname=\"capture.mock.m1\" name=\"gate 1: FileExists\" time=\"0.250\"/>"
+        ));
+        assert!(xml.contains("<failure message=\"Command failed &lt;qipu list&gt;\">"));
+        assert!(xml.contains("command: qipu list\nexit code: 2</failure>"));
+        assert!(xml.contains("<skipped message=\"Skipped: 'build' did not pass\"/>"));
+        assert!(xml.contains("name=\"outcome\""));
+        assert!(xml.contains("<failure message=\"Fail: 1/3 gates passed\">"));
+        assert!(xml.contains("<property name=\"outcome\" value=\"Fail: 1/3 gates passed\"/>"));
+        assert!(xml.contains(
+            "<testsuite name=\"broken [mock/m1]\" tests=\"1\" failures=\"0\" errors=\"1\""
+        ));
+        assert!(xml.contains("<error message=\"Fixture not found\">Fixture not found</error>"));
+    }
+}
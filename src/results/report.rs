@@ -0,0 +1,515 @@
+//! Suite-level reports generated from recorded results.
+//!
+//! Produces SVG badges (shields.io style) showing the weighted pass rate or
+//! composite score of the latest suite, for embedding in READMEs, a
+//! Markdown summary aggregating a matrix run into one document, and a
+//! capability matrix crossing scenario `skills` with tool/model pass rates.
+
+use crate::results::stats::{latest_runs, weighted_aggregate};
+use crate::results::types::ResultRecord;
+use crate::transcript::redact::redact_sensitive;
+use chrono::Utc;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// Approximate advance width of an 11px Verdana character, in pixels.
+const CHAR_WIDTH: f64 = 6.5;
+
+/// Horizontal padding on each side of a badge segment, in pixels.
+const PADDING: f64 = 6.0;
+
+/// Value shown on a badge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BadgeMetric {
+    /// Weighted share of scenarios whose gates passed
+    PassRate,
+    /// Weighted mean composite score
+    Score,
+}
+
+impl BadgeMetric {
+    /// Default left-hand label for the badge.
+    pub fn label(self) -> &'static str {
+        match self {
+            BadgeMetric::PassRate => "pass rate",
+            BadgeMetric::Score => "score",
+        }
+    }
+}
+
+/// Compute the badge value over the latest run of each scenario in `records`.
+///
+/// Returns `None` when there are no runs (or, for `Score`, no scored runs).
+pub fn badge_value(
+    records: &[ResultRecord],
+    weights: &HashMap<String, f64>,
+    metric: BadgeMetric,
+) -> Option<f64> {
+    let latest = latest_runs(records);
+    if latest.is_empty() {
+        return None;
+    }
+    let (pass_rate, mean_score) = weighted_aggregate(&latest, weights);
+    match metric {
+        BadgeMetric::PassRate => Some(pass_rate),
+        BadgeMetric::Score => mean_score,
+    }
+}
+
+/// Badge color for a value in `0.0..=1.0`.
+fn badge_color(value: Option<f64>) -> &'static str {
+    match value {
+        None => "#9f9f9f",
+        Some(v) if v >= 0.9 => "#4c1",
+        Some(v) if v >= 0.75 => "#97ca00",
+        Some(v) if v >= 0.5 => "#dfb317",
+        Some(_) => "#e05d44",
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render a flat two-segment SVG badge.
+pub fn render_badge(label: &str, value: Option<f64>, metric: BadgeMetric) -> String {
+    let text = match (value, metric) {
+        (None, _) => "no data".to_string(),
+        (Some(v), BadgeMetric::PassRate) => format!("{:.0}%", v * 100.0),
+        (Some(v), BadgeMetric::Score) => format!("{:.2}", v),
+    };
+    let segment_width = |s: &str| (s.chars().count() as f64 * CHAR_WIDTH + 2.0 * PADDING).round();
+    let left = segment_width(label);
+    let right = segment_width(&text);
+    let total = left + right;
+    let label = escape_xml(label);
+    let text = escape_xml(&text);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total}" height="20" role="img" aria-label="{label}: {text}">
+  <title>{label}: {text}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r"><rect width="{total}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{left}" height="20" fill="#555"/>
+    <rect x="{left}" width="{right}" height="20" fill="{color}"/>
+    <rect width="{total}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{text_x}" y="14">{text}</text>
+  </g>
+</svg>
+"##,
+        color = badge_color(value),
+        label_x = left / 2.0,
+        text_x = left + right / 2.0,
+    )
+}
+
+fn suite_name(r: &ResultRecord) -> String {
+    format!("{}/{}", r.tool, r.model)
+}
+
+fn fmt_opt(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v))
+}
+
+/// Escape text for use inside a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Render a Markdown summary of the latest run of each scenario/tool/model.
+///
+/// Contains a per-suite comparison table, a scenario x suite matrix, the
+/// failed gates of failing runs, cost totals and links to each run's artifacts.
+pub fn render_summary(records: &[ResultRecord], weights: &HashMap<String, f64>) -> String {
+    let latest = latest_runs(records);
+    let mut out = String::new();
+    let _ = writeln!(out, "# Suite Summary\n");
+    let _ = writeln!(
+        out,
+        "Generated {} from {} run(s); each scenario/tool/model uses its latest run.\n",
+        Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        latest.len()
+    );
+    if latest.is_empty() {
+        let _ = writeln!(out, "No results recorded.");
+        return out;
+    }
+
+    let mut suites: BTreeMap<String, Vec<&ResultRecord>> = BTreeMap::new();
+    for r in &latest {
+        suites.entry(suite_name(r)).or_default().push(r);
+    }
+
+    let _ = writeln!(out, "## Comparison\n");
+    let _ = writeln!(
+        out,
+        "| Tool/Model | Scenarios | Pass Rate | Score | Cost (USD) |"
+    );
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|");
+    for (suite, runs) in &suites {
+        let (pass_rate, mean_score) = weighted_aggregate(runs, weights);
+        let cost: f64 = runs.iter().filter_map(|r| r.cost_usd).sum();
+        let _ = writeln!(
+            out,
+            "| {} | {} | {:.1}% | {} | {:.4} |",
+            table_cell(suite),
+            runs.len(),
+            pass_rate * 100.0,
+            fmt_opt(mean_score, 2),
+            cost
+        );
+    }
+
+    let scenarios: BTreeSet<&str> = latest.iter().map(|r| r.scenario_id.as_str()).collect();
+    let cells: HashMap<(&str, String), &ResultRecord> = latest
+        .iter()
+        .map(|r| ((r.scenario_id.as_str(), suite_name(r)), *r))
+        .collect();
+    let _ = writeln!(out, "\n## Results\n");
+    let _ = write!(out, "| Scenario |");
+    for suite in suites.keys() {
+        let _ = write!(out, " {} |", table_cell(suite));
+    }
+    let _ = write!(out, "\n|---|");
+    for _ in suites.keys() {
+        let _ = write!(out, ":---:|");
+    }
+    out.push('\n');
+    for scenario in &scenarios {
+        let _ = write!(out, "| {} |", table_cell(scenario));
+        for suite in suites.keys() {
+            let cell = match cells.get(&(*scenario, suite.clone())) {
+                Some(r) => format!(
+                    "{} {}",
+                    if r.passed() { "PASS" } else { "FAIL" },
+                    fmt_opt(r.metrics.composite_score, 2)
+                ),
+                None => "-".to_string(),
+            };
+            let _ = write!(out, " {} |", cell);
+        }
+        out.push('\n');
+    }
+
+    let failures: Vec<&&ResultRecord> = latest.iter().filter(|r| !r.passed()).collect();
+    let _ = writeln!(out, "\n## Notable Failures\n");
+    if failures.is_empty() {
+        let _ = writeln!(out, "None.");
+    }
+    for r in failures {
+        let _ = writeln!(
+            out,
+            "### {} ({})\n\n{}/{} gates passed, outcome: {} ([artifacts]({}))\n",
+            r.scenario_id,
+            suite_name(r),
+            r.metrics.gates_passed,
+            r.metrics.gates_total,
+            r.outcome_summary(),
+            r.transcript_path
+        );
+        for gate in r.metrics.details.iter().filter(|g| !g.passed) {
+            let _ = writeln!(
+                out,
+                "- `{}`{}: {}",
+                gate.gate_type,
+                if gate.warning { " (warning)" } else { "" },
+                redact_sensitive(&gate.message)
+            );
+        }
+        out.push('\n');
+    }
+
+    let agent_cost: f64 = latest.iter().filter_map(|r| r.cost_usd).sum();
+    let judge_cost: f64 = latest.iter().filter_map(|r| r.judge_cost_usd).sum();
+    let _ = writeln!(out, "## Costs\n");
+    let _ = writeln!(out, "- **Agent**: ${:.4}", agent_cost);
+    let _ = writeln!(out, "- **Judge**: ${:.4}", judge_cost);
+    let _ = writeln!(out, "- **Total**: ${:.4}", agent_cost + judge_cost);
+
+    let _ = writeln!(out, "\n## Runs\n");
+    let _ = writeln!(
+        out,
+        "| Scenario | Tool/Model | Outcome | Duration | Artifacts |"
+    );
+    let _ = writeln!(out, "|---|---|---|---:|---|");
+    for r in &latest {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {:.1}s | [{}]({}) |",
+            table_cell(&r.scenario_id),
+            table_cell(&suite_name(r)),
+            table_cell(&r.outcome_summary()),
+            r.duration_secs,
+            r.id,
+            r.transcript_path
+        );
+    }
+    out
+}
+
+/// Pass rate of one tool/model over the scenarios exercising one skill.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityCell {
+    pub tool: String,
+    pub model: String,
+    pub scenarios: usize,
+    pub passed: usize,
+    pub pass_rate: f64,
+}
+
+/// All tool/model results for one skill.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillCapability {
+    pub skill: String,
+    pub cells: Vec<CapabilityCell>,
+}
+
+/// Skill x tool/model pass rates over the latest run of each scenario.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityMatrix {
+    /// Tool/model combinations, as `tool/model`
+    pub suites: Vec<String>,
+    pub skills: Vec<SkillCapability>,
+}
+
+/// Scenarios run and passed.
+type PassTally = (usize, usize);
+
+/// Cross scenario skills with outcomes of the latest run of each scenario/tool/model.
+///
+/// `skills` maps scenario ids to their `skills` metadata; runs of scenarios
+/// without skills are left out. A scenario counts toward every skill it lists.
+pub fn compute_capabilities(
+    records: &[ResultRecord],
+    skills: &HashMap<String, Vec<String>>,
+) -> CapabilityMatrix {
+    let mut tallies: BTreeMap<&str, BTreeMap<(&str, &str), PassTally>> = BTreeMap::new();
+    let mut suites: BTreeSet<String> = BTreeSet::new();
+    for r in latest_runs(records) {
+        let Some(scenario_skills) = skills.get(&r.scenario_id) else {
+            continue;
+        };
+        for skill in scenario_skills {
+            let tally = tallies
+                .entry(skill.as_str())
+                .or_default()
+                .entry((r.tool.as_str(), r.model.as_str()))
+                .or_default();
+            tally.0 += 1;
+            tally.1 += usize::from(r.passed());
+            suites.insert(suite_name(r));
+        }
+    }
+
+    let skills = tallies
+        .into_iter()
+        .map(|(skill, by_suite)| SkillCapability {
+            skill: skill.to_string(),
+            cells: by_suite
+                .into_iter()
+                .map(|((tool, model), (scenarios, passed))| CapabilityCell {
+                    tool: tool.to_string(),
+                    model: model.to_string(),
+                    scenarios,
+                    passed,
+                    pass_rate: passed as f64 / scenarios as f64,
+                })
+                .collect(),
+        })
+        .collect();
+    CapabilityMatrix {
+        suites: suites.into_iter().collect(),
+        skills,
+    }
+}
+
+/// The cell of `suite` (`tool/model`) in a skill row.
+fn cell_for<'a>(skill: &'a SkillCapability, suite: &str) -> Option<&'a CapabilityCell> {
+    skill
+        .cells
+        .iter()
+        .find(|c| format!("{}/{}", c.tool, c.model) == suite)
+}
+
+/// Render a capability matrix as Markdown: a skill x tool/model table followed
+/// by each tool/model's strongest and weakest skill.
+pub fn render_capabilities(matrix: &CapabilityMatrix) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Capability Matrix\n");
+    if matrix.skills.is_empty() {
+        let _ = writeln!(out, "No results for scenarios with `skills` metadata.");
+        return out;
+    }
+    let _ = writeln!(
+        out,
+        "Pass rate per skill over the latest run of each scenario; a scenario counts toward every skill it lists.\n"
+    );
+
+    let _ = write!(out, "| Skill |");
+    for suite in &matrix.suites {
+        let _ = write!(out, " {} |", table_cell(suite));
+    }
+    let _ = write!(out, "\n|---|");
+    for _ in &matrix.suites {
+        let _ = write!(out, "---:|");
+    }
+    out.push('\n');
+    for skill in &matrix.skills {
+        let _ = write!(out, "| {} |", table_cell(&skill.skill));
+        for suite in &matrix.suites {
+            let cell = match cell_for(skill, suite) {
+                Some(c) => format!("{:.0}% ({}/{})", c.pass_rate * 100.0, c.passed, c.scenarios),
+                None => "-".to_string(),
+            };
+            let _ = write!(out, " {} |", cell);
+        }
+        out.push('\n');
+    }
+
+    let _ = writeln!(out, "\n## Highlights\n");
+    for suite in &matrix.suites {
+        let cells: Vec<(&str, &CapabilityCell)> = matrix
+            .skills
+            .iter()
+            .filter_map(|s| cell_for(s, suite).map(|c| (s.skill.as_str(), c)))
+            .collect();
+        let best = cells
+            .iter()
+            .max_by(|a, b| a.1.pass_rate.total_cmp(&b.1.pass_rate));
+        let worst = cells
+            .iter()
+            .min_by(|a, b| a.1.pass_rate.total_cmp(&b.1.pass_rate));
+        if let (Some(best), Some(worst)) = (best, worst) {
+            let _ = writeln!(
+                out,
+                "- **{}** passes {:.0}% of {} scenarios but {:.0}% of {}",
+                suite,
+                best.1.pass_rate * 100.0,
+                best.0,
+                worst.1.pass_rate * 100.0,
+                worst.0
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_scenario;
+    use crate::results::Outcome;
+    use chrono::{Duration, Utc};
+
+    fn record(scenario: &str, age_hours: i64, passed: bool, score: f64) -> ResultRecord {
+        let mut r = create_test_record_with_scenario("run", scenario);
+        r.timestamp = Utc::now() - Duration::hours(age_hours);
+        r.gates_passed = passed;
+        r.outcome = if passed { Outcome::Pass } else { Outcome::Fail };
+        r.metrics.composite_score = Some(score);
+        r
+    }
+
+    #[test]
+    fn badge_uses_latest_run_per_scenario() {
+        let mut skipped = record("b", 0, false, 0.0);
+        skipped.outcome = Outcome::SkippedUnavailable;
+        let records = vec![
+            record("a", 2, false, 0.2),
+            record("a", 1, true, 0.9),
+            record("b", 1, false, 0.5),
+            skipped,
+        ];
+        let weights = HashMap::new();
+        assert_eq!(
+            badge_value(&records, &weights, BadgeMetric::PassRate),
+            Some(0.5)
+        );
+        let score = badge_value(&records, &weights, BadgeMetric::Score).unwrap();
+        assert!((score - 0.7).abs() < 1e-9);
+        assert_eq!(badge_value(&[], &weights, BadgeMetric::PassRate), None);
+    }
+
+    #[test]
+    fn renders_value_and_color() {
+        let svg = render_badge("qipu & co", Some(0.95), BadgeMetric::PassRate);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">95%</text>"));
+        assert!(svg.contains("qipu &amp; co"));
+        assert!(svg.contains("#4c1"));
+
+        let svg = render_badge("score", None, BadgeMetric::Score);
+        assert!(svg.contains(">no data</text>"));
+        assert!(svg.contains("#9f9f9f"));
+    }
+
+    #[test]
+    fn summary_lists_matrix_failures_and_costs() {
+        let passing = record("capture", 1, true, 0.9);
+        let mut failing = record("capture", 1, false, 0.4);
+        failing.tool = "claude-code".to_string();
+        failing.judge_cost_usd = Some(0.02);
+        failing.transcript_path = "results/run-2/artifacts".to_string();
+        failing.metrics.details = vec![crate::results::types::GateResultRecord {
+            gate_type: "note_exists".to_string(),
+            passed: false,
+            message: "No note titled 'Rust'".to_string(),
+            score: None,
+            ..Default::default()
+        }];
+
+        let summary = render_summary(&[passing, failing], &HashMap::new());
+        assert!(summary.contains("| claude-code/gpt-4o | opencode/gpt-4o |"));
+        assert!(summary.contains("| capture | FAIL 0.40 | PASS 0.90 |"));
+        assert!(summary.contains("### capture (claude-code/gpt-4o)"));
+        assert!(summary.contains("- `note_exists`: No note titled 'Rust'"));
+        assert!(summary.contains("([artifacts](results/run-2/artifacts))"));
+        assert!(summary.contains("- **Total**: $0.0400"));
+    }
+
+    #[test]
+    fn capabilities_cross_skills_with_pass_rates() {
+        let mut records = vec![
+            record("commit", 1, true, 0.9),
+            record("rebase", 1, true, 0.8),
+            record("rename", 1, false, 0.3),
+            record("untagged", 1, false, 0.1),
+        ];
+        let mut other = record("rename", 1, true, 0.9);
+        other.tool = "claude-code".to_string();
+        records.push(other);
+        let skills: HashMap<String, Vec<String>> = [
+            ("commit", vec!["git"]),
+            ("rebase", vec!["git"]),
+            ("rename", vec!["git", "refactoring"]),
+        ]
+        .into_iter()
+        .map(|(s, k)| (s.to_string(), k.into_iter().map(String::from).collect()))
+        .collect();
+
+        let matrix = compute_capabilities(&records, &skills);
+        assert_eq!(matrix.suites, vec!["claude-code/gpt-4o", "opencode/gpt-4o"]);
+        let git = &matrix.skills[0];
+        assert_eq!(git.skill, "git");
+        let opencode = git.cells.iter().find(|c| c.tool == "opencode").unwrap();
+        assert_eq!((opencode.passed, opencode.scenarios), (2, 3));
+
+        let markdown = render_capabilities(&matrix);
+        assert!(markdown.contains("| git | 100% (1/1) | 67% (2/3) |"));
+        assert!(markdown.contains("| refactoring | 100% (1/1) | 0% (0/1) |"));
+        assert!(markdown
+            .contains("- **opencode/gpt-4o** passes 67% of git scenarios but 0% of refactoring"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Retention of run artifacts.
+//!
+//! Every run keeps its result record, but full artifacts (transcripts, event
+//! logs, the fixture) are only worth their disk space for recent runs and for
+//! runs that need investigating. With `[retention] keep_last` set, older
+//! passing runs of a scenario are pruned automatically after each matrix run.
+//!
+//! Independently, each run's working copies (`fixture/` and the isolated
+//! `home/`) are torn down right after the matrix run according to
+//! [`KeepFixture`]; by default only failed runs keep them.
+
+use crate::config::RetentionConfig;
+use crate::results::gc::{dir_size, run_dir_name};
+use crate::results::types::{Outcome, ResultRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Subdirectories of a run directory holding its working copies.
+const FIXTURE_DIRS: &[&str] = &["fixture", "home"];
+
+/// Which runs keep their fixture once they finish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeepFixture {
+    /// Keep fixtures of runs that did not pass
+    #[default]
+    OnFailure,
+    /// Keep every fixture
+    Always,
+    /// Remove every fixture
+    Never,
+}
+
+impl KeepFixture {
+    /// Whether a run with `outcome` keeps its fixture.
+    pub fn keeps(self, outcome: &Outcome) -> bool {
+        match self {
+            KeepFixture::OnFailure => *outcome != Outcome::Pass,
+            KeepFixture::Always => true,
+            KeepFixture::Never => false,
+        }
+    }
+}
+
+/// Records whose full artifacts fall outside `policy`.
+///
+/// Runs are ranked newest first within each scenario; the first
+/// `keep_last` are retained, as is every non-passing run when
+/// `keep_failed` is set. Returns nothing when `keep_last` is unset.
+pub fn runs_to_prune<'a>(
+    records: &'a [ResultRecord],
+    policy: &RetentionConfig,
+) -> Vec<&'a ResultRecord> {
+    let Some(keep_last) = policy.keep_last else {
+        return Vec::new();
+    };
+
+    let mut by_scenario: HashMap<&str, Vec<&ResultRecord>> = HashMap::new();
+    for record in records {
+        by_scenario
+            .entry(record.scenario_id.as_str())
+            .or_default()
+            .push(record);
+    }
+
+    let mut prune: Vec<&ResultRecord> = Vec::new();
+    for runs in by_scenario.values_mut() {
+        runs.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.id.cmp(&a.id)));
+        prune.extend(
+            runs.iter()
+                .skip(keep_last)
+                .filter(|r| !(policy.keep_failed && r.outcome != Outcome::Pass)),
+        );
+    }
+    prune.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+    prune
+}
+
+/// Remove the subdirectories of `run_dir` (`artifacts/`, `fixture/`,
+/// `home/`), keeping the summary files at its top level.
+///
+/// Returns the bytes reclaimed; already-pruned directories reclaim nothing.
+pub fn prune_run_dir(run_dir: &Path) -> std::io::Result<u64> {
+    let entries = match std::fs::read_dir(run_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut reclaimed = 0;
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let path = entry.path();
+            reclaimed += dir_size(&path);
+            std::fs::remove_dir_all(&path)?;
+        }
+    }
+    Ok(reclaimed)
+}
+
+/// Outcome of applying a retention policy.
+#[derive(Debug, Default)]
+pub struct RetentionSummary {
+    /// Run directories that had artifacts removed
+    pub pruned: Vec<PathBuf>,
+    /// Bytes freed
+    pub reclaimed: u64,
+}
+
+/// Prune the run directories in `base_dir` of records outside `policy`.
+pub fn apply_retention(
+    base_dir: &Path,
+    records: &[ResultRecord],
+    policy: &RetentionConfig,
+) -> anyhow::Result<RetentionSummary> {
+    let prune = runs_to_prune(records, policy);
+    // Runs started in the same second share a directory; never prune one a retained run uses
+    let retained: HashSet<String> = records
+        .iter()
+        .filter(|r| !prune.iter().any(|p| std::ptr::eq(*p, *r)))
+        .filter_map(run_dir_name)
+        .collect();
+
+    let mut summary = RetentionSummary::default();
+    for record in prune {
+        let Some(name) = run_dir_name(record).filter(|n| !retained.contains(n)) else {
+            continue;
+        };
+        let run_dir = base_dir.join(name);
+        let reclaimed = prune_run_dir(&run_dir)?;
+        if reclaimed > 0 {
+            summary.reclaimed += reclaimed;
+            summary.pruned.push(run_dir);
+        }
+    }
+    Ok(summary)
+}
+
+/// Remove the fixture and isolated HOME of each run in `records` that `keep`
+/// does not retain.
+///
+/// Run directories shared with a run that keeps its fixture are left alone.
+pub fn tear_down_fixtures(
+    base_dir: &Path,
+    records: &[ResultRecord],
+    keep: KeepFixture,
+) -> anyhow::Result<RetentionSummary> {
+    let retained: HashSet<String> = records
+        .iter()
+        .filter(|r| keep.keeps(&r.outcome))
+        .filter_map(run_dir_name)
+        .collect();
+
+    let mut summary = RetentionSummary::default();
+    for record in records.iter().filter(|r| !keep.keeps(&r.outcome)) {
+        let Some(name) = run_dir_name(record).filter(|n| !retained.contains(n)) else {
+            continue;
+        };
+        let run_dir = base_dir.join(name);
+        let mut reclaimed = 0;
+        for dir in FIXTURE_DIRS.iter().map(|d| run_dir.join(d)) {
+            if dir.is_dir() {
+                reclaimed += dir_size(&dir);
+                std::fs::remove_dir_all(&dir)?;
+            }
+        }
+        if reclaimed > 0 {
+            summary.reclaimed += reclaimed;
+            summary.pruned.push(run_dir);
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_scenario;
+    use chrono::{Duration, TimeZone, Utc};
+    use std::fs;
+
+    fn run(id: &str, scenario: &str, minute: i64, outcome: Outcome) -> ResultRecord {
+        let mut record = create_test_record_with_scenario(id, scenario);
+        record.timestamp =
+            Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap() + Duration::minutes(minute);
+        record.outcome = outcome;
+        record.transcript_path = format!("20250101-12{:02}00-mock-mock-{}/artifacts", minute, id);
+        record
+    }
+
+    fn policy(keep_last: Option<usize>, keep_failed: bool) -> RetentionConfig {
+        RetentionConfig {
+            keep_last,
+            keep_failed,
+            ..RetentionConfig::default()
+        }
+    }
+
+    fn ids(records: Vec<&ResultRecord>) -> Vec<&str> {
+        records.into_iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_the_last_runs_per_scenario_and_failures() {
+        let records = vec![
+            run("a1", "alpha", 1, Outcome::Pass),
+            run("a2", "alpha", 2, Outcome::Fail),
+            run("a3", "alpha", 3, Outcome::Pass),
+            run("a4", "alpha", 4, Outcome::Pass),
+            run("b1", "beta", 5, Outcome::Pass),
+        ];
+
+        assert_eq!(ids(runs_to_prune(&records, &policy(Some(2), true))), ["a1"]);
+        assert_eq!(
+            ids(runs_to_prune(&records, &policy(Some(1), false))),
+            ["a1", "a2", "a3"]
+        );
+        assert!(runs_to_prune(&records, &policy(None, false)).is_empty());
+    }
+
+    #[test]
+    fn prunes_artifacts_but_keeps_summaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let records = vec![
+            run("old", "alpha", 1, Outcome::Pass),
+            run("new", "alpha", 2, Outcome::Pass),
+        ];
+        for record in &records {
+            let artifacts = dir.path().join(&record.transcript_path);
+            fs::create_dir_all(&artifacts).unwrap();
+            fs::write(artifacts.join("transcript.raw.txt"), "0123456789").unwrap();
+            fs::write(artifacts.parent().unwrap().join("metrics.json"), "{}").unwrap();
+        }
+
+        let summary = apply_retention(dir.path(), &records, &policy(Some(1), true)).unwrap();
+        let old_dir = dir.path().join("20250101-120100-mock-mock-old");
+        assert_eq!(summary.pruned, vec![old_dir.clone()]);
+        assert_eq!(summary.reclaimed, 10);
+        assert!(!old_dir.join("artifacts").exists());
+        assert!(old_dir.join("metrics.json").exists());
+        assert!(dir
+            .path()
+            .join("20250101-120200-mock-mock-new/artifacts/transcript.raw.txt")
+            .exists());
+
+        // Applying the policy again finds nothing left to prune
+        let again = apply_retention(dir.path(), &records, &policy(Some(1), true)).unwrap();
+        assert!(again.pruned.is_empty());
+    }
+
+    #[test]
+    fn never_prunes_a_directory_shared_with_a_retained_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = run("old", "alpha", 1, Outcome::Pass);
+        let mut new = run("new", "alpha", 2, Outcome::Pass);
+        new.transcript_path = old.transcript_path.clone();
+        fs::create_dir_all(dir.path().join(&old.transcript_path)).unwrap();
+
+        let summary = apply_retention(dir.path(), &[old, new], &policy(Some(1), true)).unwrap();
+        assert!(summary.pruned.is_empty());
+    }
+
+    #[test]
+    fn tears_down_fixtures_by_outcome() {
+        let dir = tempfile::tempdir().unwrap();
+        let records = vec![
+            run("passed", "alpha", 1, Outcome::Pass),
+            run("failed", "alpha", 2, Outcome::Fail),
+        ];
+        let run_dir = |record: &ResultRecord| {
+            dir.path()
+                .join(&record.transcript_path)
+                .parent()
+                .unwrap()
+                .to_path_buf()
+        };
+        let seed = || {
+            for record in &records {
+                for sub in ["fixture", "home", "artifacts"] {
+                    fs::create_dir_all(run_dir(record).join(sub)).unwrap();
+                    fs::write(run_dir(record).join(sub).join("file.txt"), "12345").unwrap();
+                }
+            }
+        };
+
+        seed();
+        let summary = tear_down_fixtures(dir.path(), &records, KeepFixture::OnFailure).unwrap();
+        assert_eq!(summary.pruned, vec![run_dir(&records[0])]);
+        assert_eq!(summary.reclaimed, 10);
+        assert!(!run_dir(&records[0]).join("fixture").exists());
+        assert!(!run_dir(&records[0]).join("home").exists());
+        assert!(run_dir(&records[0]).join("artifacts/file.txt").exists());
+        assert!(run_dir(&records[1]).join("fixture/file.txt").exists());
+
+        seed();
+        let kept = tear_down_fixtures(dir.path(), &records, KeepFixture::Always).unwrap();
+        assert!(kept.pruned.is_empty());
+
+        let removed = tear_down_fixtures(dir.path(), &records, KeepFixture::Never).unwrap();
+        assert_eq!(removed.pruned.len(), 2);
+        assert!(!run_dir(&records[1]).join("fixture").exists());
+    }
+}
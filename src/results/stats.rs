@@ -0,0 +1,536 @@
+//! Aggregate statistics over recorded results.
+//!
+//! Summarizes runs per scenario/tool/model and per tool/model suite, and
+//! flags runs whose efficiency metrics deviate from the history of their
+//! scenario/tool/model (`stats --anomalies`). Also provides the recent
+//! pass/fail history shown by `scenarios --with-history` and the spread of
+//! `run --repeats` runs.
+
+use crate::results::types::ResultRecord;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Earlier runs required before a run can be judged anomalous.
+pub const MIN_HISTORY: usize = 5;
+
+type GroupKey = (String, String, String);
+
+fn group_key(r: &ResultRecord) -> GroupKey {
+    (r.scenario_id.clone(), r.tool.clone(), r.model.clone())
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Weighted mean of `(value, weight)` pairs; `None` if the weights sum to zero.
+fn weighted_mean(values: &[(f64, f64)]) -> Option<f64> {
+    let total_weight: f64 = values.iter().map(|(_, w)| w).sum();
+    (total_weight > 0.0).then(|| values.iter().map(|(v, w)| v * w).sum::<f64>() / total_weight)
+}
+
+/// Population mean and standard deviation.
+fn mean_stddev(values: &[f64]) -> Option<(f64, f64)> {
+    let mean = mean(values)?;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    Some((mean, variance.sqrt()))
+}
+
+/// Summary of all runs for one scenario/tool/model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioStats {
+    pub scenario: String,
+    pub tool: String,
+    pub model: String,
+    pub runs: usize,
+    pub pass_rate: f64,
+    pub mean_duration_secs: f64,
+    pub mean_commands: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_score: Option<f64>,
+}
+
+/// Suite-level aggregate for one tool/model over the latest run of each scenario.
+///
+/// Pass rate and score are weighted by each scenario's `weight`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteStats {
+    pub tool: String,
+    pub model: String,
+    pub scenarios: usize,
+    pub pass_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_score: Option<f64>,
+}
+
+/// Per-scenario and per-suite statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub scenarios: Vec<ScenarioStats>,
+    pub suites: Vec<SuiteStats>,
+}
+
+/// Chronological sort key; the run ID breaks ties between equal timestamps.
+fn run_order(r: &ResultRecord) -> (DateTime<Utc>, &str) {
+    (r.timestamp, r.id.as_str())
+}
+
+/// The most recent run of each scenario/tool/model in `records`.
+///
+/// Records in which the tool never ran (dry runs, invalid setups, unavailable
+/// tools) are ignored.
+pub fn latest_runs(records: &[ResultRecord]) -> Vec<&ResultRecord> {
+    let mut latest: BTreeMap<GroupKey, &ResultRecord> = BTreeMap::new();
+    for record in records.iter().filter(|r| r.outcome.tool_ran()) {
+        let entry = latest.entry(group_key(record)).or_insert(record);
+        if run_order(record) > run_order(entry) {
+            *entry = record;
+        }
+    }
+    latest.into_values().collect()
+}
+
+/// Weighted pass rate and mean composite score over `runs`.
+///
+/// `weights` maps scenario ids to their weight; unlisted scenarios weigh 1.0.
+pub fn weighted_aggregate(
+    runs: &[&ResultRecord],
+    weights: &HashMap<String, f64>,
+) -> (f64, Option<f64>) {
+    let weight = |r: &ResultRecord| weights.get(&r.scenario_id).copied().unwrap_or(1.0);
+    let passes: Vec<(f64, f64)> = runs
+        .iter()
+        .map(|r| (if r.passed() { 1.0 } else { 0.0 }, weight(r)))
+        .collect();
+    let scores: Vec<(f64, f64)> = runs
+        .iter()
+        .filter_map(|r| Some((r.metrics.composite_score?, weight(r))))
+        .collect();
+    (
+        weighted_mean(&passes).unwrap_or(0.0),
+        weighted_mean(&scores),
+    )
+}
+
+/// Compute statistics over `records`.
+///
+/// `weights` maps scenario ids to their suite weight; unlisted scenarios weigh 1.0.
+/// Records in which the tool never ran are ignored.
+pub fn compute_stats(records: &[ResultRecord], weights: &HashMap<String, f64>) -> Stats {
+    let mut groups: BTreeMap<GroupKey, Vec<&ResultRecord>> = BTreeMap::new();
+    for record in records.iter().filter(|r| r.outcome.tool_ran()) {
+        groups.entry(group_key(record)).or_default().push(record);
+    }
+
+    let mut suites: BTreeMap<(String, String), Vec<&ResultRecord>> = BTreeMap::new();
+    let scenarios = groups
+        .iter()
+        .map(|((scenario, tool, model), runs)| {
+            let latest = runs.iter().max_by_key(|r| r.timestamp).copied();
+            if let Some(latest) = latest {
+                suites
+                    .entry((tool.clone(), model.clone()))
+                    .or_default()
+                    .push(latest);
+            }
+
+            let costs: Vec<f64> = runs.iter().filter_map(|r| r.cost_usd).collect();
+            let scores: Vec<f64> = runs
+                .iter()
+                .filter_map(|r| r.metrics.composite_score)
+                .collect();
+            let durations: Vec<f64> = runs.iter().map(|r| r.duration_secs).collect();
+            let commands: Vec<f64> = runs
+                .iter()
+                .map(|r| r.metrics.efficiency.total_commands as f64)
+                .collect();
+            ScenarioStats {
+                scenario: scenario.clone(),
+                tool: tool.clone(),
+                model: model.clone(),
+                runs: runs.len(),
+                pass_rate: runs.iter().filter(|r| r.passed()).count() as f64 / runs.len() as f64,
+                mean_duration_secs: mean(&durations).unwrap_or(0.0),
+                mean_commands: mean(&commands).unwrap_or(0.0),
+                mean_cost_usd: mean(&costs),
+                mean_score: mean(&scores),
+            }
+        })
+        .collect();
+
+    let suites = suites
+        .into_iter()
+        .map(|((tool, model), latest)| {
+            let (pass_rate, mean_score) = weighted_aggregate(&latest, weights);
+            SuiteStats {
+                tool,
+                model,
+                scenarios: latest.len(),
+                pass_rate,
+                mean_score,
+            }
+        })
+        .collect();
+
+    Stats { scenarios, suites }
+}
+
+/// Runs shown in a scenario's recent history.
+pub const HISTORY_LEN: usize = 10;
+
+/// Recent outcomes of one scenario across all tools/models.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScenarioHistory {
+    /// Whether each recent run passed its gates, oldest first
+    pub passed: Vec<bool>,
+    /// Mean composite score over the same runs, if any were scored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_score: Option<f64>,
+}
+
+impl ScenarioHistory {
+    /// Pass/fail history as `✓`/`✗` characters, oldest first.
+    pub fn sparkline(&self) -> String {
+        self.passed
+            .iter()
+            .map(|&p| if p { '✓' } else { '✗' })
+            .collect()
+    }
+}
+
+/// The last `limit` runs of every scenario in `records`, keyed by scenario id.
+///
+/// Dry runs and runs stopped by setup assertions are skipped since the tool
+/// never ran, so they say nothing about stability.
+pub fn scenario_histories(
+    records: &[ResultRecord],
+    limit: usize,
+) -> HashMap<String, ScenarioHistory> {
+    let mut runs: HashMap<&str, Vec<&ResultRecord>> = HashMap::new();
+    for record in records.iter().filter(|r| r.outcome.tool_ran()) {
+        runs.entry(record.scenario_id.as_str())
+            .or_default()
+            .push(record);
+    }
+    runs.into_iter()
+        .map(|(scenario, mut runs)| {
+            runs.sort_by(|a, b| run_order(a).cmp(&run_order(b)));
+            let recent = &runs[runs.len().saturating_sub(limit)..];
+            let scores: Vec<f64> = recent
+                .iter()
+                .filter_map(|r| r.metrics.composite_score)
+                .collect();
+            let history = ScenarioHistory {
+                passed: recent.iter().map(|r| r.passed()).collect(),
+                mean_score: mean(&scores),
+            };
+            (scenario.to_string(), history)
+        })
+        .collect()
+}
+
+/// A run whose metric deviates from its scenario/tool/model history.
+#[derive(Debug, Clone, Serialize)]
+pub struct Anomaly {
+    pub run_id: String,
+    pub scenario: String,
+    pub tool: String,
+    pub model: String,
+    pub timestamp: DateTime<Utc>,
+    /// `duration`, `commands` or `cost`
+    pub metric: &'static str,
+    pub value: f64,
+    pub history_mean: f64,
+    pub history_stddev: f64,
+    /// Signed distance from the historical mean in standard deviations
+    pub sigma: f64,
+}
+
+/// Reads a metric value from a record, if recorded.
+type MetricReader = fn(&ResultRecord) -> Option<f64>;
+
+/// Metrics checked for anomalies, with how to read them from a record.
+const ANOMALY_METRICS: &[(&str, MetricReader)] = &[
+    ("duration", |r| Some(r.duration_secs)),
+    ("commands", |r| {
+        Some(r.metrics.efficiency.total_commands as f64)
+    }),
+    ("cost", |r| r.cost_usd),
+];
+
+/// Flag runs whose duration, command count or cost is more than `threshold`
+/// standard deviations from the earlier runs of the same scenario/tool/model.
+///
+/// Runs with fewer than [`MIN_HISTORY`] earlier values, or whose history has
+/// no variance, are not judged, nor are records in which the tool never ran.
+/// Results are ordered oldest first.
+pub fn find_anomalies(records: &[ResultRecord], threshold: f64) -> Vec<Anomaly> {
+    let mut groups: HashMap<GroupKey, Vec<&ResultRecord>> = HashMap::new();
+    for record in records.iter().filter(|r| r.outcome.tool_ran()) {
+        groups.entry(group_key(record)).or_default().push(record);
+    }
+
+    let mut anomalies = Vec::new();
+    for runs in groups.values_mut() {
+        runs.sort_by(|a, b| run_order(a).cmp(&run_order(b)));
+        for (i, run) in runs.iter().enumerate() {
+            for (metric, read) in ANOMALY_METRICS {
+                let Some(value) = read(run) else { continue };
+                let history: Vec<f64> = runs[..i].iter().filter_map(|r| read(r)).collect();
+                if history.len() < MIN_HISTORY {
+                    continue;
+                }
+                let Some((history_mean, history_stddev)) = mean_stddev(&history) else {
+                    continue;
+                };
+                if history_stddev <= f64::EPSILON {
+                    continue;
+                }
+                let sigma = (value - history_mean) / history_stddev;
+                if sigma.abs() > threshold {
+                    anomalies.push(Anomaly {
+                        run_id: run.id.clone(),
+                        scenario: run.scenario_id.clone(),
+                        tool: run.tool.clone(),
+                        model: run.model.clone(),
+                        timestamp: run.timestamp,
+                        metric,
+                        value,
+                        history_mean,
+                        history_stddev,
+                        sigma,
+                    });
+                }
+            }
+        }
+    }
+    anomalies.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.metric.cmp(b.metric)));
+    anomalies
+}
+
+/// Pass rate and score spread over the `run --repeats` runs of one cell.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepeatStats {
+    pub runs: usize,
+    pub passed: usize,
+    pub pass_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_score: Option<f64>,
+    /// Population variance of the composite score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_variance: Option<f64>,
+}
+
+impl RepeatStats {
+    /// Some repeats passed and some did not.
+    pub fn is_flaky(&self) -> bool {
+        self.passed > 0 && self.passed < self.runs
+    }
+}
+
+/// Summarize the repeats of one cell; `None` is a repeat that produced no
+/// record and counts as a failure.
+pub fn repeat_stats(runs: &[Option<&ResultRecord>]) -> RepeatStats {
+    let passed = runs.iter().flatten().filter(|r| r.passed()).count();
+    let scores: Vec<f64> = runs
+        .iter()
+        .flatten()
+        .filter_map(|r| r.metrics.composite_score)
+        .collect();
+    let spread = mean_stddev(&scores);
+    RepeatStats {
+        runs: runs.len(),
+        passed,
+        pass_rate: if runs.is_empty() {
+            0.0
+        } else {
+            passed as f64 / runs.len() as f64
+        },
+        mean_score: spread.map(|(mean, _)| mean),
+        score_variance: spread.map(|(_, stddev)| stddev.powi(2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_tool;
+    use crate::results::types::Outcome;
+    use chrono::Duration;
+
+    #[test]
+    fn repeat_stats_report_pass_rate_and_score_variance() {
+        let mut runs: Vec<ResultRecord> = [(true, 0.9), (false, 0.5), (true, 0.7)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(passed, score))| {
+                let mut r = run(&format!("r{}", i), "opencode", 1, 1.0, passed);
+                r.metrics.composite_score = Some(score);
+                r
+            })
+            .collect();
+        runs[1].outcome = Outcome::Fail;
+        let mut refs: Vec<Option<&ResultRecord>> = runs.iter().map(Some).collect();
+        refs.push(None);
+
+        let stats = repeat_stats(&refs);
+        assert_eq!((stats.runs, stats.passed), (4, 2));
+        assert!((stats.pass_rate - 0.5).abs() < 1e-9);
+        assert!((stats.mean_score.unwrap() - 0.7).abs() < 1e-9);
+        assert!((stats.score_variance.unwrap() - 0.08 / 3.0).abs() < 1e-9);
+        assert!(stats.is_flaky());
+        assert!(!repeat_stats(&refs[..1]).is_flaky());
+    }
+
+    #[test]
+    fn latest_run_ties_are_broken_by_run_id() {
+        let first = run(
+            "run-20250130-120000-000000001-aaaaaaaa",
+            "opencode",
+            1,
+            1.0,
+            false,
+        );
+        let mut second = run(
+            "run-20250130-120000-000000002-00000000",
+            "opencode",
+            1,
+            1.0,
+            true,
+        );
+        second.timestamp = first.timestamp;
+        for records in [
+            vec![first.clone(), second.clone()],
+            vec![second.clone(), first.clone()],
+        ] {
+            assert_eq!(latest_runs(&records)[0].id, second.id);
+        }
+    }
+
+    #[test]
+    fn histories_keep_the_latest_runs_in_order() {
+        let mut records: Vec<ResultRecord> = (0..12)
+            .map(|i| run(&format!("r{}", i), "opencode", 100 - i, 10.0, i % 3 != 0))
+            .collect();
+        records.reverse();
+        let mut dry = run("dry", "opencode", 0, 0.0, false);
+        dry.outcome = Outcome::DryRun;
+        records.push(dry);
+        let mut invalid = run("invalid", "opencode", 0, 0.0, false);
+        invalid.outcome = Outcome::SetupInvalid;
+        records.push(invalid);
+
+        let histories = scenario_histories(&records, 5);
+        let capture = &histories["capture"];
+        // Runs 7..=11, where every third run (9) failed
+        assert_eq!(capture.sparkline(), "✓✓✗✓✓");
+        assert!((capture.mean_score.unwrap() - 0.9).abs() < 1e-9);
+    }
+
+    fn run(id: &str, tool: &str, minutes_ago: i64, duration: f64, passed: bool) -> ResultRecord {
+        let mut r = create_test_record_with_tool(id, "capture", tool);
+        r.timestamp = Utc::now() - Duration::minutes(minutes_ago);
+        r.duration_secs = duration;
+        r.gates_passed = passed;
+        r.outcome = if passed { Outcome::Pass } else { Outcome::Fail };
+        r
+    }
+
+    #[test]
+    fn summarizes_scenarios_and_suites() {
+        let records = vec![
+            run("r1", "opencode", 3, 10.0, false),
+            run("r2", "opencode", 2, 20.0, true),
+            run("r3", "claude-code", 1, 30.0, true),
+        ];
+        let stats = compute_stats(&records, &HashMap::new());
+
+        assert_eq!(stats.scenarios.len(), 2);
+        let opencode = stats
+            .scenarios
+            .iter()
+            .find(|s| s.tool == "opencode")
+            .unwrap();
+        assert_eq!(opencode.runs, 2);
+        assert_eq!(opencode.pass_rate, 0.5);
+        assert_eq!(opencode.mean_duration_secs, 15.0);
+        assert_eq!(opencode.mean_cost_usd, Some(0.01));
+
+        // Suite pass rate uses the latest run only
+        let suite = stats.suites.iter().find(|s| s.tool == "opencode").unwrap();
+        assert_eq!(suite.scenarios, 1);
+        assert_eq!(suite.pass_rate, 1.0);
+    }
+
+    #[test]
+    fn runs_failed_by_the_judge_do_not_count_as_passes() {
+        let mut judged = run("r2", "opencode", 1, 10.0, true);
+        judged.judge_score = Some(0.4);
+        judged.outcome = Outcome::Fail;
+        judged.outcome_detail = Some("judge score 0.40 below threshold 0.70".to_string());
+        let records = vec![run("r1", "opencode", 2, 10.0, true), judged];
+        assert!(records[1].gates_passed);
+
+        let stats = compute_stats(&records, &HashMap::new());
+        assert_eq!(stats.scenarios[0].pass_rate, 0.5);
+        assert_eq!(stats.suites[0].pass_rate, 0.0);
+        assert_eq!(scenario_histories(&records, 5)["capture"].sparkline(), "✓✗");
+    }
+
+    #[test]
+    fn skipped_runs_are_left_out_of_stats() {
+        let mut skipped = run("r2", "opencode", 1, 0.0, false);
+        skipped.outcome = Outcome::SkippedUnavailable;
+        let records = vec![run("r1", "opencode", 2, 10.0, true), skipped];
+
+        assert_eq!(latest_runs(&records)[0].id, "r1");
+        let stats = compute_stats(&records, &HashMap::new());
+        assert_eq!(stats.scenarios[0].runs, 1);
+        assert_eq!(stats.scenarios[0].pass_rate, 1.0);
+        assert_eq!(stats.suites[0].pass_rate, 1.0);
+    }
+
+    #[test]
+    fn flags_runs_far_outside_history() {
+        let mut records: Vec<_> = [10.0, 11.0, 9.0, 10.5, 9.5, 10.0]
+            .iter()
+            .enumerate()
+            .map(|(i, d)| run(&format!("r{}", i), "opencode", 100 - i as i64, *d, true))
+            .collect();
+        records.push(run("slow", "opencode", 1, 60.0, true));
+
+        let anomalies = find_anomalies(&records, 3.0);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].run_id, "slow");
+        assert_eq!(anomalies[0].metric, "duration");
+        assert!(anomalies[0].sigma > 3.0);
+    }
+
+    #[test]
+    fn needs_enough_history() {
+        let records = vec![
+            run("r1", "opencode", 3, 10.0, true),
+            run("r2", "opencode", 2, 11.0, true),
+            run("r3", "opencode", 1, 500.0, true),
+        ];
+        assert!(find_anomalies(&records, 3.0).is_empty());
+    }
+
+    #[test]
+    fn suite_aggregates_are_weighted() {
+        let mut critical = run("r1", "opencode", 2, 10.0, true);
+        critical.scenario_id = "critical".to_string();
+        critical.metrics.composite_score = Some(1.0);
+        let mut minor = run("r2", "opencode", 1, 10.0, false);
+        minor.scenario_id = "minor".to_string();
+        minor.metrics.composite_score = Some(0.0);
+        let weights = HashMap::from([("critical".to_string(), 3.0)]);
+
+        let stats = compute_stats(&[critical, minor], &weights);
+        assert_eq!(stats.suites[0].pass_rate, 0.75);
+        assert_eq!(stats.suites[0].mean_score, Some(0.75));
+    }
+}
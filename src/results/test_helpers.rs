@@ -1,5 +1,7 @@
 use crate::results::db::ResultsDB;
-use crate::results::types::{EfficiencyMetricsRecord, EvaluationMetricsRecord, ResultRecord};
+use crate::results::types::{
+    EfficiencyMetricsRecord, EvaluationMetricsRecord, Outcome, ResultRecord,
+};
 use chrono::Utc;
 use tempfile::TempDir;
 
@@ -54,13 +56,22 @@ pub fn create_test_record_with_tool(id: &str, scenario_id: &str, tool: &str) ->
                 help_invocations: 0,
                 first_try_success_rate: 1.0,
                 iteration_ratio: 1.5,
+                wasted_actions: 0,
             },
+            conversation: None,
             composite_score: Some(0.9),
+            score_penalties: None,
             evaluator_results: vec![],
+            component_errors: vec![],
         },
         judge_score: Some(0.9),
-        outcome: "PASS".to_string(),
+        outcome: Outcome::Pass,
+        outcome_detail: None,
         transcript_path: "/path/to/transcript.txt".to_string(),
         cache_key: Some("cache-key-123".to_string()),
+        judge_cost_usd: None,
+        labels: vec![],
+        phase_durations: None,
+        repeat_index: None,
     }
 }
@@ -5,8 +5,8 @@ use sha2::{Digest, Sha256};
 
 /// Cache key for deduplicating test runs.
 ///
-/// Computed from scenario content, prompt, tool,
-/// and model to uniquely identify a test configuration.
+/// Computed from scenario content, prompt, tool, model and the matrix
+/// entry's tool settings to uniquely identify a test configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct CacheKey {
     /// Hash of the scenario YAML content
@@ -17,6 +17,9 @@ pub struct CacheKey {
     pub tool: String,
     /// Model name
     pub model: String,
+    /// Hash of the matrix entry's `env` and `extra_args`, if it has any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_settings_hash: Option<String>,
 }
 
 impl CacheKey {
@@ -49,9 +52,32 @@ impl CacheKey {
             prompt_hash,
             tool: tool.to_string(),
             model: model.to_string(),
+            tool_settings_hash: None,
         }
     }
 
+    /// Distinguish runs whose matrix entry sets `env` or `extra_args`.
+    ///
+    /// Keys without tool settings are unchanged, so existing cache entries stay valid.
+    pub fn with_tool_settings(
+        mut self,
+        env: &std::collections::BTreeMap<String, String>,
+        extra_args: &[String],
+    ) -> Self {
+        if env.is_empty() && extra_args.is_empty() {
+            return self;
+        }
+        let mut hasher = Sha256::new();
+        for (key, value) in env {
+            hasher.update(format!("env:{}={}\0", key, value).as_bytes());
+        }
+        for arg in extra_args {
+            hasher.update(format!("arg:{}\0", arg).as_bytes());
+        }
+        self.tool_settings_hash = Some(format!("{:x}", hasher.finalize()));
+        self
+    }
+
     /// Convert the cache key to a string representation.
     ///
     /// Used as the filename for cached results.
@@ -62,9 +88,48 @@ impl CacheKey {
     pub fn as_string(&self) -> String {
         // Sanitize model name to avoid path separator issues in filenames
         let safe_model = self.model.replace(['/', '\\'], "_");
-        format!(
+        let key = format!(
             "{}_{}_{}_{}",
             self.scenario_hash, self.prompt_hash, self.tool, safe_model,
+        );
+        match &self.tool_settings_hash {
+            Some(hash) => format!("{}_{}", key, &hash[..16]),
+            None => key,
+        }
+    }
+}
+
+/// Cache key for LLM-as-judge responses.
+///
+/// A judge given the same rubric, transcript and model is expected to give
+/// the same verdict, so re-evaluating an unchanged run reuses the response.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct JudgeCacheKey {
+    /// Hash of the rubric file content
+    pub rubric_hash: String,
+    /// Hash of the transcript content
+    pub transcript_hash: String,
+    /// Judge model name
+    pub model: String,
+}
+
+impl JudgeCacheKey {
+    /// Compute a judge cache key from the rubric and transcript contents.
+    pub fn compute(rubric: &str, transcript: &str, model: &str) -> Self {
+        Self {
+            rubric_hash: format!("{:x}", Sha256::digest(rubric.as_bytes())),
+            transcript_hash: format!("{:x}", Sha256::digest(transcript.as_bytes())),
+            model: model.to_string(),
+        }
+    }
+
+    /// Convert the key to a string, used as the cache filename.
+    pub fn as_string(&self) -> String {
+        format!(
+            "{}_{}_{}",
+            self.rubric_hash,
+            self.transcript_hash,
+            self.model.replace(['/', '\\'], "_")
         )
     }
 }
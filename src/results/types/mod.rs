@@ -8,15 +8,16 @@ pub mod cache_key;
 mod tests;
 
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-pub use cache_key::CacheKey;
+pub use cache_key::{CacheKey, JudgeCacheKey};
 
 /// A complete test run result record.
 ///
 /// Contains all metadata and metrics for a single scenario execution,
 /// including timing, cost, gate results, and quality metrics.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ResultRecord {
     /// Unique run identifier
     pub id: String,
@@ -35,26 +36,207 @@ pub struct ResultRecord {
     /// Estimated cost in USD (if tool reports it)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cost_usd: Option<f64>,
-    /// Whether all gates passed
+    /// Whether all gates passed. A run can pass its gates and still fail on
+    /// the judge threshold or a harness limit; `outcome` decides pass/fail.
     pub gates_passed: bool,
     /// Detailed evaluation metrics
     pub metrics: EvaluationMetricsRecord,
     /// Optional LLM-as-judge score (0.0-1.0)
     pub judge_score: Option<f64>,
-    /// Final outcome ("PASS", "FAIL", "ERROR")
-    pub outcome: String,
+    /// Final outcome
+    pub outcome: Outcome,
+    /// Human-readable detail for the outcome (e.g. "1/2 gates passed")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome_detail: Option<String>,
     /// Path to the saved transcript file
     pub transcript_path: String,
     /// Optional cache key for this result
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_key: Option<String>,
+    /// Cost of LLM-as-judge evaluation in USD (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_cost_usd: Option<f64>,
+    /// Free-form labels attached at run time (`run --label`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Time spent in each phase of the run (absent for dry runs and older records)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase_durations: Option<PhaseDurations>,
+    /// 1-based position among the `run --repeats` runs of the same
+    /// scenario/tool/model (absent for single runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat_index: Option<u32>,
+}
+
+/// Evaluation phase whose failure is isolated from the rest of the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EvaluationPhase {
+    /// LLM-as-judge scoring
+    Judge,
+    /// A custom evaluator script
+    Evaluator,
+}
+
+/// A judge or evaluator that failed; the run was scored without it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ComponentError {
+    /// Phase the failure happened in
+    pub phase: EvaluationPhase,
+    /// Failed component: `judge`, or the evaluator's name
+    pub component: String,
+    /// Error chain or panic message
+    pub message: String,
+}
+
+/// Wall-clock seconds spent in each phase of a run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PhaseDurations {
+    /// Fixture creation and `setup` commands
+    pub setup_secs: f64,
+    /// Running the tool under test, including retries
+    pub agent_secs: f64,
+    /// `scripts.post` commands
+    pub post_scripts_secs: f64,
+    /// Gate evaluation, including gates captured before post scripts
+    pub gates_secs: f64,
+    /// LLM-as-judge evaluation
+    pub judge_secs: f64,
+    /// Custom evaluator scripts
+    pub evaluators_secs: f64,
+}
+
+impl PhaseDurations {
+    /// Phases with their durations, in execution order.
+    pub fn phases(&self) -> [(&'static str, f64); 6] {
+        [
+            ("setup", self.setup_secs),
+            ("agent", self.agent_secs),
+            ("post_scripts", self.post_scripts_secs),
+            ("gates", self.gates_secs),
+            ("judge", self.judge_secs),
+            ("evaluators", self.evaluators_secs),
+        ]
+    }
+}
+
+impl ResultRecord {
+    /// Outcome with its detail, e.g. `Fail: 1/2 gates passed`.
+    pub fn outcome_summary(&self) -> String {
+        self.outcome.describe(self.outcome_detail.as_deref())
+    }
+
+    /// Whether the run passed: its gates, a blocking judge and any harness
+    /// limits. This is what pass rates count.
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Pass
+    }
+}
+
+/// How a run ended, serialized as a stable snake_case string.
+///
+/// Records written before outcomes were structured stored free-form strings
+/// ("Pass", "Fail: 1/2 gates passed", "TURN_LIMIT", ...); those still
+/// deserialize to the matching variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// All gates (and the judge, if enabled) passed
+    Pass,
+    /// At least one gate failed
+    Fail,
+    /// The harness could not complete the run
+    Error,
+    /// The tool was killed at its `timeout_secs`
+    Timeout,
+    /// The tool produced no output for `run.stall_timeout_secs` and was stopped
+    Stalled,
+    /// The run was interrupted before it finished
+    Cancelled,
+    /// The run was not attempted
+    Skipped,
+    /// `--dry-run`: nothing was executed
+    DryRun,
+    /// The tool was stopped for exceeding `run.max_turns`
+    TurnLimit,
+    /// A `setup_assertions` gate failed, so the tool was never run
+    SetupInvalid,
+    /// A matrix cell whose tool is not installed or not authenticated; the
+    /// rest of the matrix ran without it
+    SkippedUnavailable,
+}
+
+impl Outcome {
+    /// Human-readable outcome, followed by `detail` if any.
+    pub fn describe(self, detail: Option<&str>) -> String {
+        match detail {
+            Some(detail) => format!("{}: {}", self, detail),
+            None => self.to_string(),
+        }
+    }
+
+    /// Whether the tool under test actually ran, so the record says something about it.
+    pub fn tool_ran(self) -> bool {
+        !matches!(
+            self,
+            Self::DryRun | Self::SetupInvalid | Self::SkippedUnavailable
+        )
+    }
+
+    /// Parse a current or legacy outcome string; unrecognized text is an `Error`.
+    pub fn parse(text: &str) -> Self {
+        let normalized = text.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+        let word = normalized
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .next()
+            .unwrap_or_default();
+        match word {
+            "pass" | "passed" => Self::Pass,
+            "fail" | "failed" => Self::Fail,
+            "timeout" | "timed_out" => Self::Timeout,
+            "stalled" => Self::Stalled,
+            "cancelled" | "canceled" => Self::Cancelled,
+            "skipped" => Self::Skipped,
+            "dry_run" => Self::DryRun,
+            // `budget_exceeded` was only ever recorded for turn limits
+            "turn_limit" | "budget_exceeded" => Self::TurnLimit,
+            "setup_invalid" => Self::SetupInvalid,
+            "skipped_unavailable" => Self::SkippedUnavailable,
+            _ => Self::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::Pass => "Pass",
+            Self::Fail => "Fail",
+            Self::Error => "Error",
+            Self::Timeout => "Timeout",
+            Self::Stalled => "Stalled",
+            Self::Cancelled => "Cancelled",
+            Self::Skipped => "Skipped",
+            Self::DryRun => "Dry run",
+            Self::TurnLimit => "Turn limit",
+            Self::SetupInvalid => "Setup invalid",
+            Self::SkippedUnavailable => "Skipped (unavailable)",
+        };
+        f.write_str(label)
+    }
+}
+
+impl<'de> Deserialize<'de> for Outcome {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::parse(&String::deserialize(deserializer)?))
+    }
 }
 
 /// Evaluation metrics for a test run.
 ///
 /// Aggregates gate results, efficiency metrics,
 /// and a composite quality score.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvaluationMetricsRecord {
     /// Number of gates that passed
     pub gates_passed: usize,
@@ -64,16 +246,25 @@ pub struct EvaluationMetricsRecord {
     pub details: Vec<GateResultRecord>,
     /// Efficiency metrics
     pub efficiency: EfficiencyMetricsRecord,
+    /// Conversation-structure metrics (absent in older records)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<ConversationMetricsRecord>,
     /// Composite quality score (0.0-1.0), only present if scenario configures composite weights
     #[serde(skip_serializing_if = "Option::is_none")]
     pub composite_score: Option<f64>,
+    /// Deductions included in the composite score, if the scenario configures penalties
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_penalties: Option<ScorePenaltiesRecord>,
     /// Results from custom evaluators
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub evaluator_results: Vec<EvaluatorResultRecord>,
+    /// Judge and evaluator failures the run was scored without
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub component_errors: Vec<ComponentError>,
 }
 
 /// Record of a custom evaluator result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvaluatorResultRecord {
     /// Name of the evaluator
     pub name: String,
@@ -92,7 +283,7 @@ pub struct EvaluatorResultRecord {
 }
 
 /// Efficiency metrics measuring tool interaction patterns.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EfficiencyMetricsRecord {
     /// Total number of commands executed
     pub total_commands: usize,
@@ -108,10 +299,43 @@ pub struct EfficiencyMetricsRecord {
     pub first_try_success_rate: f64,
     /// Ratio of total commands to unique commands
     pub iteration_ratio: f64,
+    /// File actions that did not contribute to the result
+    #[serde(default)]
+    pub wasted_actions: usize,
+}
+
+/// Conversation-structure metrics describing how the agent interacted.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConversationMetricsRecord {
+    /// Model turns in the transcript
+    pub turns: usize,
+    /// Assistant text messages
+    pub assistant_messages: usize,
+    /// Mean length of assistant messages in characters
+    pub avg_assistant_message_chars: f64,
+    /// Turns in which the agent called at least one tool
+    pub tool_call_turns: usize,
+    /// Turns with text only
+    pub text_turns: usize,
+    /// Ratio of tool-call turns to text turns
+    pub tool_turn_ratio: f64,
+    /// Assistant messages that state a plan or explain the approach
+    pub plan_messages: usize,
+}
+
+/// Deductions applied to a composite score for command errors and retries.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScorePenaltiesRecord {
+    /// Deduction for command errors, before the cap
+    pub errors: f64,
+    /// Deduction for retried commands, before the cap
+    pub retries: f64,
+    /// Deduction applied to the composite score, after the cap
+    pub total: f64,
 }
 
 /// Result of evaluating a single gate.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct GateResultRecord {
     /// Type of gate evaluated
     pub gate_type: String,
@@ -119,4 +343,34 @@ pub struct GateResultRecord {
     pub passed: bool,
     /// Human-readable message about the result
     pub message: String,
+    /// Partial credit (0.0-1.0) for soft-score gates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// What the gate required (substring, pattern, size bounds, digest, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// What the gate observed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+    /// File the gate checked, relative to the fixture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Shell command the gate ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Exit code of `command`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Time spent evaluating the gate (absent in older records)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// Warning-only gate: a failure is reported but did not fail the run
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub warning: bool,
+    /// Gate not evaluated because its `when` condition was unmet
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Times the gate was evaluated, when `retries` made it more than once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
 }
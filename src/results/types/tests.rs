@@ -109,6 +109,44 @@ fn test_cache_key_as_string() {
     assert!(key_string.contains(&key.model));
 }
 
+#[test]
+fn test_cache_key_tool_settings() {
+    let key = CacheKey::compute("name: test", "prompt", "claude-code", "sonnet");
+    let unchanged = key.clone().with_tool_settings(&Default::default(), &[]);
+    assert_eq!(unchanged.as_string(), key.as_string());
+
+    let args = ["--permission-mode".to_string(), "plan".to_string()];
+    let with_args = key.clone().with_tool_settings(&Default::default(), &args);
+    let env = std::collections::BTreeMap::from([("MAX_THINKING".to_string(), "1".to_string())]);
+    let with_env = key.clone().with_tool_settings(&env, &[]);
+    assert_ne!(with_args.as_string(), key.as_string());
+    assert_ne!(with_args, with_env);
+    assert!(with_args.as_string().starts_with(&key.as_string()));
+}
+
+#[test]
+fn test_judge_cache_key_covers_rubric_transcript_and_model() {
+    let key = JudgeCacheKey::compute("rubric", "transcript", "openai/gpt-4o");
+    assert_eq!(
+        key,
+        JudgeCacheKey::compute("rubric", "transcript", "openai/gpt-4o")
+    );
+    assert_ne!(
+        key,
+        JudgeCacheKey::compute("rubric v2", "transcript", "openai/gpt-4o")
+    );
+    assert_ne!(
+        key,
+        JudgeCacheKey::compute("rubric", "transcript 2", "openai/gpt-4o")
+    );
+    assert_ne!(
+        key,
+        JudgeCacheKey::compute("rubric", "transcript", "gpt-4o-mini")
+    );
+    assert!(key.as_string().ends_with("_openai_gpt-4o"));
+    assert!(!key.as_string().contains('/'));
+}
+
 #[test]
 fn test_cache_key_equality() {
     let scenario_yaml = "name: test\ntask:\n  prompt: test";
@@ -141,6 +179,8 @@ fn test_result_record_json_round_trip() {
                 gate_type: "min_notes".to_string(),
                 passed: true,
                 message: "Passed".to_string(),
+                score: None,
+                ..Default::default()
             }],
             efficiency: EfficiencyMetricsRecord {
                 total_commands: 3,
@@ -150,14 +190,23 @@ fn test_result_record_json_round_trip() {
                 help_invocations: 0,
                 first_try_success_rate: 1.0,
                 iteration_ratio: 1.5,
+                wasted_actions: 0,
             },
+            conversation: None,
             composite_score: Some(0.95),
+            score_penalties: None,
             evaluator_results: vec![],
+            component_errors: vec![],
         },
         judge_score: Some(0.9),
-        outcome: "PASS".to_string(),
+        outcome: Outcome::Pass,
+        outcome_detail: None,
         transcript_path: "/path/to/transcript.txt".to_string(),
         cache_key: Some("cache-key-123".to_string()),
+        judge_cost_usd: None,
+        labels: vec![],
+        phase_durations: None,
+        repeat_index: None,
     };
 
     let json = serde_json::to_string(&original).unwrap();
@@ -210,17 +259,95 @@ fn test_result_record_json_skip_none_cache_key() {
                 help_invocations: 0,
                 first_try_success_rate: 1.0,
                 iteration_ratio: 1.5,
+                wasted_actions: 0,
             },
+            conversation: None,
             composite_score: Some(0.85),
+            score_penalties: None,
             evaluator_results: vec![],
+            component_errors: vec![],
         },
         judge_score: None,
-        outcome: "PASS".to_string(),
+        outcome: Outcome::Pass,
+        outcome_detail: None,
         transcript_path: "/path/to/transcript.txt".to_string(),
         cache_key: None,
+        judge_cost_usd: None,
+        labels: vec![],
+        phase_durations: None,
+        repeat_index: None,
     };
 
     let json = serde_json::to_string(&record).unwrap();
     assert!(!json.contains("\"cache_key\""));
     assert!(json.contains("\"judge_score\":null"));
 }
+
+#[test]
+fn test_outcome_serializes_as_snake_case() {
+    assert_eq!(
+        serde_json::to_string(&Outcome::TurnLimit).unwrap(),
+        "\"turn_limit\""
+    );
+    for outcome in [Outcome::Pass, Outcome::DryRun, Outcome::Cancelled] {
+        let json = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(serde_json::from_str::<Outcome>(&json).unwrap(), outcome);
+    }
+}
+
+#[test]
+fn test_outcome_parses_legacy_strings() {
+    assert_eq!(Outcome::parse("PASS"), Outcome::Pass);
+    assert_eq!(Outcome::parse("Fail: 1/2 gates passed"), Outcome::Fail);
+    assert_eq!(Outcome::parse("Dry run"), Outcome::DryRun);
+    assert_eq!(Outcome::parse("TURN_LIMIT"), Outcome::TurnLimit);
+    assert_eq!(Outcome::parse("budget_exceeded"), Outcome::TurnLimit);
+    assert_eq!(Outcome::parse("STALLED"), Outcome::Stalled);
+    assert_eq!(Outcome::parse("timeout"), Outcome::Timeout);
+    assert_eq!(Outcome::parse("SETUP_INVALID"), Outcome::SetupInvalid);
+    assert_eq!(
+        Outcome::parse(&Outcome::SetupInvalid.describe(Some("1/1 failed"))),
+        Outcome::SetupInvalid
+    );
+    assert_eq!(
+        Outcome::parse("SKIPPED_UNAVAILABLE"),
+        Outcome::SkippedUnavailable
+    );
+    assert_eq!(Outcome::parse("Error: tool crashed"), Outcome::Error);
+    assert_eq!(Outcome::parse("something else"), Outcome::Error);
+}
+
+#[test]
+fn test_phase_durations_round_trip_and_default_to_none() {
+    let mut record = crate::results::test_helpers::create_test_record("run-1");
+    let json = serde_json::to_string(&record).unwrap();
+    assert!(!json.contains("phase_durations"));
+    assert!(serde_json::from_str::<ResultRecord>(&json)
+        .unwrap()
+        .phase_durations
+        .is_none());
+
+    record.phase_durations = Some(PhaseDurations {
+        setup_secs: 1.5,
+        agent_secs: 30.0,
+        gates_secs: 0.25,
+        ..Default::default()
+    });
+    let json = serde_json::to_string(&record).unwrap();
+    let phases = serde_json::from_str::<ResultRecord>(&json)
+        .unwrap()
+        .phase_durations
+        .unwrap();
+    assert_eq!(phases.agent_secs, 30.0);
+    assert_eq!(phases.phases()[0], ("setup", 1.5));
+    assert_eq!(phases.judge_secs, 0.0);
+}
+
+#[test]
+fn test_outcome_summary_includes_detail() {
+    let mut record = crate::results::test_helpers::create_test_record("run-1");
+    assert_eq!(record.outcome_summary(), "Pass");
+    record.outcome = Outcome::Fail;
+    record.outcome_detail = Some("1/2 gates passed".to_string());
+    assert_eq!(record.outcome_summary(), "Fail: 1/2 gates passed");
+}
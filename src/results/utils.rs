@@ -3,11 +3,41 @@
 //! Provides helper functions for generating run IDs
 //! and estimating costs from token usage.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Nanosecond timestamp of the last generated run ID in this process.
+static LAST_RUN_ID_NANOS: AtomicI64 = AtomicI64::new(i64::MIN);
+
+/// Current time in nanoseconds, strictly greater than any earlier call in this process.
+fn next_run_id_nanos() -> i64 {
+    let now = Utc::now().timestamp_nanos_opt().unwrap_or(i64::MAX);
+    let previous = LAST_RUN_ID_NANOS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last.saturating_add(1)))
+        })
+        .unwrap_or(now);
+    now.max(previous.saturating_add(1))
+}
+
+/// Random run ID suffix from the OS, or the process ID if the OS source fails.
+fn run_id_suffix() -> u32 {
+    let mut bytes = [0u8; 4];
+    match getrandom::getrandom(&mut bytes) {
+        Ok(()) => u32::from_le_bytes(bytes),
+        Err(_) => std::process::id(),
+    }
+}
 
 /// Generate a unique run ID based on current timestamp.
 ///
-/// Format: `run-YYYYMMDD-HHMMSS-microseconds`
+/// Format: `run-YYYYMMDD-HHMMSS-nanoseconds-xxxxxxxx`
+///
+/// Within a process the timestamp part is strictly increasing, so IDs
+/// generated concurrently never collide and sort in generation order. The
+/// random hex suffix keeps IDs from separate processes apart. IDs recorded
+/// before the suffix was added (`run-YYYYMMDD-HHMMSS-nanoseconds`) share the
+/// same prefix and still sort chronologically alongside new ones.
 ///
 /// # Returns
 ///
@@ -22,6 +52,50 @@ use chrono::Utc;
 /// assert!(run_id.starts_with("run-"));
 /// ```
 pub fn generate_run_id() -> String {
-    let now = Utc::now();
-    format!("run-{}", now.format("%Y%m%d-%H%M%S-%f"))
+    let timestamp = DateTime::from_timestamp_nanos(next_run_id_nanos());
+    format!(
+        "run-{}-{:08x}",
+        timestamp.format("%Y%m%d-%H%M%S-%f"),
+        run_id_suffix()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn run_ids_are_unique_under_concurrency() {
+        let ids: Vec<String> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| (0..500).map(|_| generate_run_id()).collect::<Vec<_>>()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+        let unique: HashSet<&str> = ids.iter().map(String::as_str).collect();
+        assert_eq!(unique.len(), ids.len());
+    }
+
+    #[test]
+    fn run_ids_sort_in_generation_order() {
+        let ids: Vec<String> = (0..100).map(|_| generate_run_id()).collect();
+        // Compare on the timestamp part; the random suffix only breaks cross-process ties
+        let prefixes: Vec<&str> = ids.iter().map(|id| &id[..id.len() - 9]).collect();
+        let mut sorted = prefixes.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(prefixes, sorted);
+    }
+
+    #[test]
+    fn new_run_ids_sort_after_legacy_ids() {
+        let legacy = "run-20250130-120000-123456789";
+        let id = generate_run_id();
+        assert_eq!(id.len(), legacy.len() + 9);
+        assert!(id.as_str() > legacy);
+    }
 }
@@ -1,7 +1,9 @@
+use crate::output::ToolModelConfig;
 use crate::results::{Cache, CacheKey, ResultRecord};
 
-pub fn compute_cache_key(scenario_yaml: &str, prompt: &str, tool: &str, model: &str) -> CacheKey {
-    CacheKey::compute(scenario_yaml, prompt, tool, model)
+pub fn compute_cache_key(scenario_yaml: &str, prompt: &str, cell: &ToolModelConfig) -> CacheKey {
+    CacheKey::compute(scenario_yaml, prompt, &cell.tool, &cell.model)
+        .with_tool_settings(&cell.env, &cell.extra_args)
 }
 
 pub fn check_cache(cache: &Cache, cache_key: &CacheKey) -> anyhow::Result<Option<ResultRecord>> {
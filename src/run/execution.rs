@@ -1,11 +1,32 @@
-use crate::adapter::{TokenUsage, ToolAdapter};
+use crate::adapter::{
+    AdapterError, Progress, RunOptions, StallTimeout, StreamCapture, TokenUsage, ToolAdapter,
+    TurnLimit,
+};
+use crate::config::RetryConfig;
 use crate::evaluation::EvaluationMetrics;
+use crate::exec::{Clock, SystemClock};
 use crate::fixture::TestEnv;
-use crate::scenario::Scenario;
+use crate::output::ToolModelConfig;
+use crate::results::{Cache, Outcome};
+use crate::run::fault::{inject_adapter_fault, inject_evaluation_fault, FaultPhase};
+use crate::run::progress::{with_heartbeat, HEARTBEAT_INTERVAL};
+use crate::run::rate_limit::RateLimiter;
+use crate::sandbox::{executor_for, Sandbox};
+use crate::scenario::{JudgeAggregate, JudgeSpec, Scenario};
 use crate::script_runner::ScriptRunner;
+use crate::transcript::normalize::normalize_terminal_output;
 use crate::transcript::TranscriptWriter;
+use std::collections::HashMap;
 use std::path::Path;
 
+/// Run the tool, retrying transient provider failures per `retry`.
+///
+/// Each retried attempt is recorded as a `tool_attempt` event. Returns the
+/// final attempt's output and exit code, the cost and token usage summed over
+/// all attempts, and the number of attempts made. Backoff delays are slept on
+/// `clock`.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
 pub fn execute_tool(
     adapter: &dyn ToolAdapter,
     s: &Scenario,
@@ -13,33 +34,143 @@ pub fn execute_tool(
     tool: &str,
     model: &str,
     effective_timeout: u64,
-) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>)> {
-    let start_time = std::time::Instant::now();
-    println!("Running tool '{}' with model '{}'...", tool, model);
-    let (output, exit_code, cost_opt, token_usage) =
-        adapter.run(s, &env.root, Some(model), effective_timeout)?;
-    let _duration = start_time.elapsed();
-
-    Ok((output, exit_code, cost_opt, token_usage))
+    limiter: &RateLimiter,
+    retry: &RetryConfig,
+    writer: &TranscriptWriter,
+    options: &RunOptions,
+    clock: &dyn Clock,
+) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>, u32)> {
+    let mut attempt = 1;
+    let mut total_cost: Option<f64> = None;
+    let mut total_usage: Option<TokenUsage> = None;
+    loop {
+        if let Some(streams) = &options.streams {
+            streams.clear();
+        }
+        let permit = limiter.acquire(tool);
+        println!("Running tool '{}' with model '{}'...", tool, model);
+        let (output, exit_code, cost_opt, token_usage) =
+            adapter.run(s, &env.root, Some(model), effective_timeout, options)?;
+        permit.finish(adapter.is_rate_limited(&output, exit_code));
+        if let Some(cost) = cost_opt {
+            *total_cost.get_or_insert(0.0) += cost;
+        }
+        if let Some(usage) = token_usage {
+            let total = total_usage.get_or_insert(TokenUsage {
+                input: 0,
+                output: 0,
+            });
+            total.input += usage.input;
+            total.output += usage.output;
+        }
+
+        // A run stopped by the harness is final
+        let stopped = options.turn_limit.as_ref().is_some_and(|l| l.reached())
+            || options.stall_timeout.as_ref().is_some_and(|t| t.stalled());
+        if attempt > retry.max_retries
+            || stopped
+            || !adapter.is_transient_failure(&output, exit_code)
+        {
+            return Ok((output, exit_code, total_cost, total_usage, attempt));
+        }
+
+        let delay = retry.delay_for(attempt);
+        eprintln!(
+            "Transient failure from '{}' (attempt {}/{}); retrying in {:.1}s",
+            tool,
+            attempt,
+            retry.max_retries + 1,
+            delay.as_secs_f64()
+        );
+        writer.append_event(&serde_json::json!({
+            "type": "tool_attempt",
+            "tool": tool,
+            "attempt": attempt,
+            "exit_code": exit_code,
+            "output": &output,
+            "retry_in_secs": delay.as_secs_f64(),
+        }))?;
+        clock.sleep(delay);
+        attempt += 1;
+    }
 }
 
-pub fn create_adapter_and_check(tool: &str) -> anyhow::Result<Box<dyn ToolAdapter>> {
+/// Adapter for `tool`: a built-in adapter or a plugin from the configured
+/// plugins directory. `custom` needs a scenario and is not handled here.
+pub fn create_adapter(
+    tool: &str,
+    config: &crate::config::Config,
+) -> anyhow::Result<Box<dyn ToolAdapter>> {
     use crate::adapter::{
-        claude_code::ClaudeCodeAdapter, mock::MockAdapter, opencode::OpenCodeAdapter,
+        aider::AiderAdapter, claude_code::ClaudeCodeAdapter, mock::MockAdapter,
+        openai::OpenAiAdapter, opencode::OpenCodeAdapter,
     };
-    let adapter: Box<dyn ToolAdapter> = match tool {
+    Ok(match tool {
+        "aider" => Box::new(AiderAdapter),
         "claude-code" => Box::new(ClaudeCodeAdapter),
         "mock" => Box::new(MockAdapter),
+        "openai" => Box::new(OpenAiAdapter::new(config.openai.clone())),
         "opencode" => Box::new(OpenCodeAdapter),
-        _ => anyhow::bail!("Unknown tool: {}", tool),
+        _ => {
+            let plugins_dir = Path::new(config.get_plugins_path());
+            match crate::adapter::plugin::discover(plugins_dir, tool) {
+                Some(plugin) => Box::new(plugin),
+                None => {
+                    let plugins = crate::adapter::plugin::list(plugins_dir);
+                    anyhow::bail!(
+                        "Unknown tool: {} (built-in: aider, claude-code, custom, mock, openai, opencode; plugins in {}: {})",
+                        tool,
+                        plugins_dir.display(),
+                        if plugins.is_empty() {
+                            "none".to_string()
+                        } else {
+                            plugins.join(", ")
+                        }
+                    )
+                }
+            }
+        }
+    })
+}
+
+pub fn create_adapter_and_check(
+    tool: &str,
+    scenario: &Scenario,
+) -> anyhow::Result<Box<dyn ToolAdapter>> {
+    let adapter: Box<dyn ToolAdapter> = if tool == "custom" {
+        Box::new(crate::adapter::custom::CustomAdapter::for_scenario(
+            scenario,
+        )?)
+    } else {
+        create_adapter(tool, &crate::config::Config::load_or_default())?
     };
 
-    println!("Checking availability for tool: {}", tool);
-    adapter.check_availability()?;
+    // A sandboxed tool lives in the image, so only the engine can be checked here
+    let available = if let Some(sandbox) = Sandbox::for_scenario(scenario, []) {
+        println!("Checking container engine for sandboxed tool: {}", tool);
+        sandbox.check_engine()
+    } else {
+        println!("Checking availability for tool: {}", tool);
+        adapter.check_availability()
+    };
+    available.map_err(|e| match e.downcast::<AdapterError>() {
+        Ok(AdapterError::NotAvailable(diagnostic)) => AdapterError::NotAvailable(diagnostic),
+        Ok(other) => AdapterError::NotAvailable(other.to_string()),
+        Err(e) => AdapterError::NotAvailable(format!("{:#}", e)),
+    })?;
 
     Ok(adapter)
 }
 
+/// Environment for scripts: isolated HOME/XDG variables overlaid with the target env.
+fn script_env(scenario: &Scenario, env: &TestEnv) -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = env.isolation_env().into_iter().collect();
+    if let Some(target_env) = &scenario.target.env {
+        vars.extend(target_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    vars
+}
+
 fn run_post_scripts(
     scenario: &Scenario,
     env: &TestEnv,
@@ -59,8 +190,9 @@ fn run_post_scripts(
             model.to_string(),
             transcript_path.map(|p| p.to_path_buf()),
             Some(writer.base_dir.join("events.jsonl")),
-            scenario.target.env.clone().unwrap_or_default(),
-        );
+            script_env(scenario, env),
+        )
+        .with_executor(executor_for(scenario, [results_dir.to_path_buf()]));
 
         for entry in &scripts.post {
             let result = runner.run(&entry.command, entry.timeout_secs)?;
@@ -83,19 +215,45 @@ fn run_post_scripts(
     Ok(())
 }
 
+/// Capture the lint baselines of `lint_clean` gates, logging each as a
+/// `lint_baseline` event.
+fn record_lint_baselines(
+    s: &Scenario,
+    env: &TestEnv,
+    results_dir: &Path,
+    writer: &TranscriptWriter,
+) -> anyhow::Result<()> {
+    for baseline in crate::evaluation::capture_lint_baselines(s, &env.root, results_dir)? {
+        let mut event = serde_json::json!({
+            "type": "lint_baseline",
+            "command": baseline.command,
+            "warnings": baseline.warnings,
+        });
+        if let Some(pattern) = baseline.pattern {
+            event["pattern"] = serde_json::json!(pattern);
+        }
+        writer.append_event(&event)?;
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 pub fn run_evaluation_flow(
     adapter: &dyn ToolAdapter,
     s: &Scenario,
     env: &TestEnv,
-    tool: &str,
-    model: &str,
+    cell: &ToolModelConfig,
     effective_timeout: u64,
     no_judge: bool,
+    judge_cache: Option<&Cache>,
     writer: &TranscriptWriter,
     transcript_dir: &Path,
     results_dir: &Path,
+    limiter: &RateLimiter,
+    retry: &RetryConfig,
+    expected_secs: Option<f64>,
+    fault: Option<FaultPhase>,
 ) -> anyhow::Result<(
     String,
     i32,
@@ -104,34 +262,137 @@ pub fn run_evaluation_flow(
     std::time::Duration,
     EvaluationMetrics,
 )> {
+    let (tool, model) = (cell.tool.as_str(), cell.model.as_str());
+    let turn_limit = s.run.as_ref().and_then(|r| r.max_turns).map(TurnLimit::new);
+    if turn_limit.is_some() && !adapter.enforces_turn_limit() {
+        eprintln!(
+            "Warning: tool '{}' cannot enforce run.max_turns; the turn limit is ignored",
+            tool
+        );
+    }
+    let stall_timeout = s
+        .run
+        .as_ref()
+        .and_then(|r| r.stall_timeout_secs)
+        .map(StallTimeout::new);
+    if stall_timeout.is_some() && !adapter.detects_stalls() {
+        eprintln!(
+            "Warning: tool '{}' cannot detect stalls; run.stall_timeout_secs is ignored",
+            tool
+        );
+    }
+    let progress = Progress::new(
+        &crate::transcript::TranscriptAnalyzer::resolve_command_pattern(
+            &s.target.binary,
+            s.target.command_pattern.as_deref(),
+        ),
+    );
+    if !cell.extra_args.is_empty() && !adapter.accepts_extra_args() {
+        eprintln!(
+            "Warning: tool '{}' has no command line; extra_args are ignored",
+            tool
+        );
+    }
+    let mut tool_env = env.isolation_env();
+    tool_env.extend(cell.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    let options = RunOptions {
+        env: tool_env,
+        extra_args: cell.extra_args.clone(),
+        turn_limit: turn_limit.clone(),
+        stall_timeout: stall_timeout.clone(),
+        progress: Some(progress.clone()),
+        streams: Some(StreamCapture::new(
+            s.run.as_ref().is_some_and(|r| r.separate_stderr),
+        )),
+        sandbox: Sandbox::for_scenario(s, [results_dir.to_path_buf()]),
+    };
+
+    // Count lint warnings in the untouched fixture so lint_clean gates only see new ones
+    record_lint_baselines(s, env, results_dir, writer)?;
+
     let start = std::time::Instant::now();
-    let (output, exit_code, cost, token_usage) =
-        execute_tool(adapter, s, env, tool, model, effective_timeout)?;
+    let (mut output, exit_code, cost, token_usage, attempts) =
+        with_heartbeat(writer, &progress, HEARTBEAT_INTERVAL, expected_secs, || {
+            inject_adapter_fault(fault)?;
+            execute_tool(
+                adapter,
+                s,
+                env,
+                tool,
+                model,
+                effective_timeout,
+                limiter,
+                retry,
+                writer,
+                &options,
+                &SystemClock,
+            )
+        })?;
     let duration = start.elapsed();
+    let turn_limit = turn_limit.filter(|l| l.reached()).map(|l| l.max_turns);
+    if let Some(max_turns) = turn_limit {
+        println!("Tool stopped after exceeding {} turns", max_turns);
+        output.push_str(&format!(
+            "\n[llm-tool-test] Turn limit of {} exceeded; tool terminated\n",
+            max_turns
+        ));
+    }
+    let stalled = stall_timeout.filter(|t| t.stalled()).map(|t| t.secs);
+    if let Some(secs) = stalled {
+        println!("Tool stopped after {} seconds without output", secs);
+        output.push_str(&format!(
+            "\n[llm-tool-test] No output for {} seconds; tool terminated as stalled\n",
+            secs
+        ));
+    }
+
+    // Strip escape sequences and spinner redraws before anything reads the output
+    if crate::config::Config::load_or_default()
+        .transcript
+        .keep_terminal_output
+    {
+        writer.write_terminal(&output)?;
+    }
+    let output = normalize_terminal_output(&output);
 
     // Write transcript immediately after execution so evaluation can read it
     writer.write_raw(&output)?;
-    let event = if let Some(c) = cost {
-        serde_json::json!({
-            "type": "execution",
-            "tool": tool,
-            "output": &output,
-            "exit_code": exit_code,
-            "cost_usd": c
-        })
-    } else {
-        serde_json::json!({
-            "type": "execution",
-            "tool": tool,
-            "output": &output,
-            "exit_code": exit_code
-        })
-    };
+    let mut event = serde_json::json!({
+        "type": "execution",
+        "tool": tool,
+        "output": &output,
+        "exit_code": exit_code
+    });
+    if let Some(streams) = options.streams.as_ref().and_then(|s| s.get()) {
+        let stdout = normalize_terminal_output(&streams.stdout);
+        let stderr = normalize_terminal_output(&streams.stderr);
+        writer.write_streams(&stdout, &stderr)?;
+        event["stdout"] = serde_json::json!(stdout);
+        event["stderr"] = serde_json::json!(stderr);
+    }
+    if let Some(c) = cost {
+        event["cost_usd"] = serde_json::json!(c);
+    }
+    if attempts > 1 {
+        event["attempts"] = serde_json::json!(attempts);
+    }
+    if let Some(max_turns) = turn_limit {
+        event["turn_limit"] = serde_json::json!(max_turns);
+    }
+    if let Some(secs) = stalled {
+        event["stalled_after_secs"] = serde_json::json!(secs);
+    }
     writer.append_event(&event)?;
 
+    // Capture process/port state before post scripts get a chance to tear it down
+    let started = std::time::Instant::now();
+    let live_results = crate::evaluation::evaluate_live_gates(s, &env.root);
+    let live_gates_secs = started.elapsed().as_secs_f64();
+
     // Run post-execution scripts after transcript writing, before evaluation
     let transcript_path = transcript_dir.join("transcript.raw.txt");
     let events_path = writer.base_dir.join("events.jsonl");
+    let started = std::time::Instant::now();
     run_post_scripts(
         s,
         env,
@@ -141,6 +402,7 @@ pub fn run_evaluation_flow(
         Some(&transcript_path),
         writer,
     )?;
+    let post_scripts_secs = started.elapsed().as_secs_f64();
 
     // Create script runner for evaluation (used by script gates)
     let script_runner = ScriptRunner::new(
@@ -151,23 +413,540 @@ pub fn run_evaluation_flow(
         model.to_string(),
         Some(transcript_path),
         Some(events_path),
-        s.target.env.clone().unwrap_or_default(),
-    );
+        script_env(s, env),
+    )
+    .with_executor(executor_for(s, [results_dir.to_path_buf()]));
 
     println!("Running evaluation...");
-    let metrics = crate::evaluation::evaluate(s, &env.root, no_judge, Some(&script_runner))?;
+    inject_evaluation_fault(fault);
+    let mut metrics = crate::evaluation::evaluate(
+        s,
+        &env.root,
+        no_judge,
+        judge_cache,
+        Some(&script_runner),
+        live_results,
+    )?;
+    metrics.turn_limit = turn_limit;
+    metrics.stalled_after_secs = stalled;
+    metrics.phase_durations.agent_secs = duration.as_secs_f64();
+    metrics.phase_durations.post_scripts_secs = post_scripts_secs;
+    metrics.phase_durations.gates_secs += live_gates_secs;
     println!("Evaluation metrics: {:?}", metrics);
 
     Ok((output, exit_code, cost, token_usage, duration, metrics))
 }
 
-pub fn determine_outcome(metrics: &EvaluationMetrics) -> String {
-    if metrics.gates_passed < metrics.gates_total {
-        format!(
-            "Fail: {}/{} gates passed",
-            metrics.gates_passed, metrics.gates_total
+/// Outcome of an evaluated run, with its human-readable detail.
+///
+/// A judge score below `judge.pass_threshold` fails the run unless the judge
+/// is configured with `blocking: false`, in which case it is only noted. A
+/// judge ensemble fails the run when its aggregate does not pass, unless
+/// every judge is non-blocking.
+pub fn determine_outcome(
+    metrics: &EvaluationMetrics,
+    judge: Option<&JudgeSpec>,
+) -> (Outcome, Option<String>) {
+    let judge_shortfall = judge.and_then(|spec| match &metrics.judge_ensemble {
+        Some(ensemble) if ensemble.passed => None,
+        Some(ensemble) => {
+            let detail = match ensemble.aggregate {
+                JudgeAggregate::Majority => format!(
+                    "{}/{} judges passed, majority needed",
+                    ensemble.passing, ensemble.scored
+                ),
+                aggregate => format!(
+                    "judge {} score {:.2} below threshold {:.2}",
+                    aggregate.name(),
+                    ensemble.score,
+                    ensemble.pass_threshold
+                ),
+            };
+            Some((spec.blocking(), detail))
+        }
+        None => {
+            let config = spec.judges().first()?;
+            let score = metrics.judge_score.filter(|s| *s < config.pass_threshold)?;
+            Some((
+                config.blocking,
+                format!(
+                    "judge score {:.2} below threshold {:.2}",
+                    score, config.pass_threshold
+                ),
+            ))
+        }
+    });
+
+    if let Some(secs) = metrics.stalled_after_secs {
+        (
+            Outcome::Stalled,
+            Some(format!("stalled after {} seconds without output", secs)),
         )
+    } else if let Some(max_turns) = metrics.turn_limit {
+        (
+            Outcome::TurnLimit,
+            Some(format!("turn limit of {} exceeded", max_turns)),
+        )
+    } else if metrics.gates_passed < metrics.gates_total {
+        let gates = format!(
+            "{}/{} gates passed",
+            metrics.gates_passed, metrics.gates_total
+        );
+        let detail = match judge_shortfall {
+            Some((_, judge)) => format!("{}; {}", gates, judge),
+            None => gates,
+        };
+        (Outcome::Fail, Some(detail))
     } else {
-        "Pass".to_string()
+        let warnings = metrics
+            .details
+            .iter()
+            .filter(|g| g.warning && !g.passed)
+            .count();
+        let warnings = (warnings > 0).then(|| format!("{} warning gate(s) failed", warnings));
+        match judge_shortfall {
+            Some((true, judge)) => (Outcome::Fail, Some(judge)),
+            Some((false, judge)) => {
+                let judge = format!("{} (non-blocking)", judge);
+                let detail = match warnings {
+                    Some(warnings) => format!("{}; {}", warnings, judge),
+                    None => judge,
+                };
+                (Outcome::Pass, Some(detail))
+            }
+            None => (Outcome::Pass, warnings),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::{AdapterError, ToolStatus};
+    use crate::exec::fake::FakeClock;
+    use crate::scenario::JudgeConfig;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    /// Fails with a 503 until `failures` attempts have been made.
+    struct FlakyAdapter {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    impl ToolAdapter for FlakyAdapter {
+        fn is_available(&self) -> Result<ToolStatus, AdapterError> {
+            Ok(ToolStatus {
+                available: true,
+                authenticated: true,
+            })
+        }
+
+        fn run(
+            &self,
+            _scenario: &Scenario,
+            _cwd: &Path,
+            _model: Option<&str>,
+            _timeout_secs: u64,
+            _options: &RunOptions,
+        ) -> anyhow::Result<(String, i32, Option<f64>, Option<TokenUsage>)> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let usage = Some(TokenUsage {
+                input: 100,
+                output: 10,
+            });
+            if call <= self.failures {
+                Ok((
+                    "Error: 503 Service Unavailable".to_string(),
+                    1,
+                    Some(0.25),
+                    usage,
+                ))
+            } else {
+                Ok(("done".to_string(), 0, Some(0.5), usage))
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn run_flaky(
+        failures: u32,
+        retry: RetryConfig,
+        clock: &dyn Clock,
+    ) -> (
+        (String, i32, Option<f64>, Option<TokenUsage>, u32),
+        Vec<serde_json::Value>,
+    ) {
+        let dir = tempfile::tempdir().unwrap();
+        let env = TestEnv::new(dir.path().join("env")).unwrap();
+        let writer =
+            TranscriptWriter::new(dir.path().join("artifacts"), dir.path().join("results"))
+                .unwrap();
+        let scenario: Scenario = serde_yaml::from_str(
+            "name: flaky\ndescription: d\ntemplate_folder: t\ntarget:\n  binary: t\ntask:\n  prompt: p\nevaluation:\n  gates: []\n",
+        )
+        .unwrap();
+        let adapter = FlakyAdapter {
+            failures,
+            calls: AtomicU32::new(0),
+        };
+        let run = execute_tool(
+            &adapter,
+            &scenario,
+            &env,
+            "flaky",
+            "model",
+            10,
+            &RateLimiter::default(),
+            &retry,
+            &writer,
+            &RunOptions {
+                env: env.isolation_env(),
+                ..Default::default()
+            },
+            clock,
+        )
+        .unwrap();
+        let events = std::fs::read_to_string(writer.base_dir.join("events.jsonl"))
+            .unwrap_or_default()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        (run, events)
+    }
+
+    fn no_delay(max_retries: u32) -> RetryConfig {
+        RetryConfig {
+            max_retries,
+            initial_delay_secs: 0.0,
+            max_delay_secs: 0.0,
+        }
+    }
+
+    #[test]
+    fn lint_baseline_events_match_the_event_schema() {
+        use crate::transcript::types::EventRecord;
+        let dir = tempfile::tempdir().unwrap();
+        let env = TestEnv::new(dir.path().join("env")).unwrap();
+        std::fs::create_dir_all(&env.root).unwrap();
+        let writer =
+            TranscriptWriter::new(dir.path().join("artifacts"), dir.path().join("results"))
+                .unwrap();
+        let scenario: Scenario = serde_yaml::from_str(
+            "name: lint\ndescription: d\ntemplate_folder: t\ntarget:\n  binary: t\ntask:\n  prompt: p\nevaluation:\n  gates:\n    - type: lint_clean\n      command: \"printf 'warning: a\\\\nW: b\\\\n'\"\n      pattern: \"^W:\"\n",
+        )
+        .unwrap();
+
+        record_lint_baselines(&scenario, &env, dir.path(), &writer).unwrap();
+
+        let events = std::fs::read_to_string(writer.base_dir.join("events.jsonl")).unwrap();
+        let events: Vec<EventRecord> = events
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {}", e, line)))
+            .collect();
+        match &events[..] {
+            [EventRecord::LintBaseline {
+                pattern, warnings, ..
+            }] => {
+                assert_eq!(pattern.as_deref(), Some("^W:"));
+                assert_eq!(*warnings, 1);
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn harness_stop_outcomes_override_gates() {
+        let mut metrics = crate::evaluation::EvaluationMetrics {
+            gates_passed: 1,
+            gates_total: 1,
+            details: Vec::new(),
+            judge_score: None,
+            judge_response: None,
+            judge_cost_usd: None,
+            judge_ensemble: None,
+            efficiency: crate::transcript::TranscriptAnalyzer::analyze_with_events("", None),
+            conversation: Default::default(),
+            composite_score: None,
+            score_penalties: None,
+            score_breakdown: None,
+            evaluator_results: Vec::new(),
+            turn_limit: None,
+            stalled_after_secs: None,
+            phase_durations: Default::default(),
+            component_errors: Vec::new(),
+        };
+        assert_eq!(determine_outcome(&metrics, None), (Outcome::Pass, None));
+        metrics.turn_limit = Some(5);
+        assert_eq!(
+            determine_outcome(&metrics, None),
+            (
+                Outcome::TurnLimit,
+                Some("turn limit of 5 exceeded".to_string())
+            )
+        );
+        metrics.stalled_after_secs = Some(60);
+        assert_eq!(determine_outcome(&metrics, None).0, Outcome::Stalled);
+    }
+
+    #[test]
+    fn judge_threshold_fails_the_run_unless_non_blocking() {
+        let mut metrics = crate::evaluation::EvaluationMetrics {
+            gates_passed: 2,
+            gates_total: 2,
+            details: Vec::new(),
+            judge_score: Some(0.42),
+            judge_response: None,
+            judge_cost_usd: None,
+            judge_ensemble: None,
+            efficiency: crate::transcript::TranscriptAnalyzer::analyze_with_events("", None),
+            conversation: Default::default(),
+            composite_score: None,
+            score_penalties: None,
+            score_breakdown: None,
+            evaluator_results: Vec::new(),
+            turn_limit: None,
+            stalled_after_secs: None,
+            phase_durations: Default::default(),
+            component_errors: Vec::new(),
+        };
+        let mut judge = JudgeConfig {
+            enabled: true,
+            rubric: "rubric.yaml".to_string(),
+            pass_threshold: 0.7,
+            blocking: true,
+            model: None,
+        };
+
+        assert_eq!(
+            determine_outcome(&metrics, Some(&JudgeSpec::Single(judge.clone()))),
+            (
+                Outcome::Fail,
+                Some("judge score 0.42 below threshold 0.70".to_string())
+            )
+        );
+        metrics.gates_passed = 1;
+        assert_eq!(
+            determine_outcome(&metrics, Some(&JudgeSpec::Single(judge.clone())))
+                .1
+                .as_deref(),
+            Some("1/2 gates passed; judge score 0.42 below threshold 0.70")
+        );
+
+        metrics.gates_passed = 2;
+        judge.blocking = false;
+        assert_eq!(
+            determine_outcome(&metrics, Some(&JudgeSpec::Single(judge.clone()))),
+            (
+                Outcome::Pass,
+                Some("judge score 0.42 below threshold 0.70 (non-blocking)".to_string())
+            )
+        );
+
+        // No score (judge disabled or skipped with --no-judge) leaves the outcome to the gates
+        metrics.judge_score = None;
+        judge.blocking = true;
+        assert_eq!(
+            determine_outcome(&metrics, Some(&JudgeSpec::Single(judge.clone()))),
+            (Outcome::Pass, None)
+        );
+        metrics.judge_score = Some(0.7);
+        assert_eq!(
+            determine_outcome(&metrics, Some(&JudgeSpec::Single(judge.clone()))),
+            (Outcome::Pass, None)
+        );
+    }
+
+    #[test]
+    fn judge_ensembles_fail_the_run_on_their_aggregate() {
+        let vote = |score: f64| crate::evaluation::JudgeVote {
+            model: "m".to_string(),
+            rubric: "rubric.yaml".to_string(),
+            pass_threshold: 0.7,
+            score: Some(score),
+            error: None,
+            response: None,
+        };
+        let judge = |blocking: bool| JudgeConfig {
+            enabled: true,
+            rubric: "rubric.yaml".to_string(),
+            pass_threshold: 0.7,
+            blocking,
+            model: None,
+        };
+        let ensemble = |aggregate: JudgeAggregate| {
+            crate::evaluation::aggregate_judges(aggregate, vec![vote(0.9), vote(0.5), vote(0.6)])
+        };
+        let mut metrics = crate::evaluation::EvaluationMetrics {
+            gates_passed: 1,
+            gates_total: 1,
+            details: Vec::new(),
+            judge_score: Some(2.0 / 3.0),
+            judge_response: None,
+            judge_cost_usd: None,
+            judge_ensemble: ensemble(JudgeAggregate::Mean),
+            efficiency: crate::transcript::TranscriptAnalyzer::analyze_with_events("", None),
+            conversation: Default::default(),
+            composite_score: None,
+            score_penalties: None,
+            score_breakdown: None,
+            evaluator_results: Vec::new(),
+            turn_limit: None,
+            stalled_after_secs: None,
+            phase_durations: Default::default(),
+            component_errors: Vec::new(),
+        };
+        let spec = JudgeSpec::Ensemble(vec![judge(true), judge(false), judge(false)]);
+
+        assert_eq!(
+            determine_outcome(&metrics, Some(&spec)),
+            (
+                Outcome::Fail,
+                Some("judge mean score 0.67 below threshold 0.70".to_string())
+            )
+        );
+        metrics.judge_ensemble = ensemble(JudgeAggregate::Majority);
+        assert_eq!(
+            determine_outcome(&metrics, Some(&spec)).1.as_deref(),
+            Some("1/3 judges passed, majority needed")
+        );
+
+        // Only non-blocking judges leave the outcome to the gates
+        let advisory = JudgeSpec::Ensemble(vec![judge(false), judge(false)]);
+        assert_eq!(
+            determine_outcome(&metrics, Some(&advisory)),
+            (
+                Outcome::Pass,
+                Some("1/3 judges passed, majority needed (non-blocking)".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn failed_warning_gates_are_noted_on_passing_runs() {
+        let mut metrics = crate::evaluation::EvaluationMetrics {
+            gates_passed: 1,
+            gates_total: 1,
+            details: vec![
+                crate::evaluation::GateResult {
+                    passed: true,
+                    ..Default::default()
+                },
+                crate::evaluation::GateResult {
+                    passed: false,
+                    warning: true,
+                    ..Default::default()
+                },
+            ],
+            judge_score: Some(0.42),
+            judge_response: None,
+            judge_cost_usd: None,
+            judge_ensemble: None,
+            efficiency: crate::transcript::TranscriptAnalyzer::analyze_with_events("", None),
+            conversation: Default::default(),
+            composite_score: None,
+            score_penalties: None,
+            score_breakdown: None,
+            evaluator_results: Vec::new(),
+            turn_limit: None,
+            stalled_after_secs: None,
+            phase_durations: Default::default(),
+            component_errors: Vec::new(),
+        };
+        assert_eq!(
+            determine_outcome(&metrics, None),
+            (Outcome::Pass, Some("1 warning gate(s) failed".to_string()))
+        );
+
+        let judge = JudgeConfig {
+            enabled: true,
+            rubric: "rubric.yaml".to_string(),
+            pass_threshold: 0.7,
+            blocking: false,
+            model: None,
+        };
+        assert_eq!(
+            determine_outcome(&metrics, Some(&JudgeSpec::Single(judge.clone())))
+                .1
+                .as_deref(),
+            Some("1 warning gate(s) failed; judge score 0.42 below threshold 0.70 (non-blocking)")
+        );
+
+        metrics.gates_passed = 0;
+        assert_eq!(
+            determine_outcome(&metrics, None),
+            (Outcome::Fail, Some("0/1 gates passed".to_string()))
+        );
+    }
+
+    #[test]
+    fn retries_transient_failures_and_records_attempts() {
+        let ((_, exit_code, cost, usage, attempts), events) =
+            run_flaky(2, no_delay(3), &SystemClock);
+        assert_eq!(exit_code, 0);
+        assert_eq!(attempts, 3);
+        // Every attempt is billed
+        assert_eq!(cost, Some(1.0));
+        let usage = usage.unwrap();
+        assert_eq!((usage.input, usage.output), (300, 30));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["type"], "tool_attempt");
+        assert_eq!(events[1]["attempt"], 2);
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let ((_, exit_code, _, _, attempts), events) = run_flaky(5, no_delay(1), &SystemClock);
+        assert_eq!(exit_code, 1);
+        assert_eq!(attempts, 2);
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn backoff_sleeps_on_the_injected_clock() {
+        let clock = FakeClock::default();
+        let retry = RetryConfig {
+            max_retries: 3,
+            initial_delay_secs: 30.0,
+            max_delay_secs: 45.0,
+        };
+        let ((_, exit_code, _, _, attempts), events) = run_flaky(3, retry, &clock);
+        assert_eq!((exit_code, attempts), (0, 4));
+        assert_eq!(
+            clock.sleeps(),
+            vec![
+                Duration::from_secs(30),
+                Duration::from_secs(45),
+                Duration::from_secs(45)
+            ]
+        );
+        assert_eq!(events[2]["retry_in_secs"], 45.0);
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_max() {
+        let retry = RetryConfig {
+            max_retries: 5,
+            initial_delay_secs: 5.0,
+            max_delay_secs: 12.0,
+        };
+        assert_eq!(retry.delay_for(1).as_secs_f64(), 5.0);
+        assert_eq!(retry.delay_for(2).as_secs_f64(), 10.0);
+        assert_eq!(retry.delay_for(3).as_secs_f64(), 12.0);
+    }
+
+    #[test]
+    fn detects_transient_output() {
+        use crate::adapter::looks_transient;
+        assert!(looks_transient("upstream returned 502 Bad Gateway"));
+        assert!(looks_transient("read ECONNRESET"));
+        assert!(looks_transient("429 Too Many Requests"));
+        assert!(!looks_transient("error: file not found"));
+        // A 502 the agent ran into earlier is not the error the tool exited with
+        let transcript = format!(
+            "$ curl localhost:8080\n502 Bad Gateway\n{}Error: invalid API key\n",
+            "step\n".repeat(5)
+        );
+        assert!(!looks_transient(&transcript));
     }
 }
@@ -0,0 +1,65 @@
+//! Fault injection for hardening the run pipeline.
+//!
+//! The hidden `run --inject-fault <phase>` flag forces one failure path so
+//! integration tests can check that every failure still ends in a well-formed
+//! `ResultRecord` with an `error` outcome, rather than a half-written run.
+
+use std::any::Any;
+
+/// Pipeline phase to fail on purpose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FaultPhase {
+    /// The tool adapter returns an error instead of running
+    Adapter,
+    /// Evaluation panics before any gate runs
+    Evaluation,
+    /// Every artifact write fails as if the disk were full
+    Artifacts,
+}
+
+/// Fail like an adapter error when `fault` targets the adapter.
+pub fn inject_adapter_fault(fault: Option<FaultPhase>) -> anyhow::Result<()> {
+    if fault == Some(FaultPhase::Adapter) {
+        anyhow::bail!("Injected fault: adapter error");
+    }
+    Ok(())
+}
+
+/// Panic when `fault` targets evaluation.
+pub fn inject_evaluation_fault(fault: Option<FaultPhase>) {
+    if fault == Some(FaultPhase::Evaluation) {
+        panic!("Injected fault: evaluation panic");
+    }
+}
+
+/// The message carried by a caught panic.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn injects_only_the_requested_phase() {
+        assert!(inject_adapter_fault(None).is_ok());
+        assert!(inject_adapter_fault(Some(FaultPhase::Artifacts)).is_ok());
+        assert!(inject_adapter_fault(Some(FaultPhase::Adapter)).is_err());
+        inject_evaluation_fault(Some(FaultPhase::Adapter));
+
+        let panic =
+            std::panic::catch_unwind(|| inject_evaluation_fault(Some(FaultPhase::Evaluation)))
+                .unwrap_err();
+        assert_eq!(
+            panic_message(panic.as_ref()),
+            "Injected fault: evaluation panic"
+        );
+    }
+}
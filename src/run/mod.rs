@@ -1,10 +1,16 @@
 pub mod cache;
 pub mod execution;
+pub mod fault;
+pub mod progress;
+pub mod rate_limit;
 pub mod records;
+pub mod repro;
 pub mod setup;
+pub mod timeout;
 pub mod transcript;
 pub mod utils;
 
+use crate::config::RetryConfig;
 use crate::output;
 use crate::results::{Cache, ResultRecord, ResultsDB};
 use crate::scenario::Scenario;
@@ -13,35 +19,60 @@ use crate::scenario::Scenario;
 pub fn run_single_scenario(
     s: &Scenario,
     scenario_path: &std::path::Path,
-    tool: &str,
-    model: &str,
+    cell: &output::ToolModelConfig,
     dry_run: bool,
+    materialize: bool,
     no_cache: bool,
-    timeout_secs: u64,
+    timeout_secs: Option<u64>,
     no_judge: bool,
+    no_judge_cache: bool,
     _base_dir: &std::path::Path,
     results_db: &ResultsDB,
     cache: &Cache,
+    labels: &[String],
+    limiter: &rate_limit::RateLimiter,
+    retry: &RetryConfig,
+    fault: Option<fault::FaultPhase>,
+    skip_unavailable: bool,
+    repeat_index: Option<u32>,
 ) -> anyhow::Result<ResultRecord> {
     use crate::run::cache::{check_cache, compute_cache_key};
     use crate::run::execution::{create_adapter_and_check, determine_outcome, run_evaluation_flow};
-    use crate::run::records::{build_result_record, finalize_execution, handle_dry_run};
-    use crate::run::setup::{prepare_writer_and_setup, setup_scenario_env};
+    use crate::run::records::{
+        build_error_record, build_result_record, build_setup_invalid_record,
+        build_unavailable_record, error_outcome, finalize_execution, handle_dry_run,
+        handle_materialize,
+    };
+    use crate::run::setup::{check_setup_assertions, prepare_writer_and_setup, setup_scenario_env};
     use crate::run::transcript::write_transcript_files;
 
-    let effective_timeout = s
-        .run
-        .as_ref()
-        .and_then(|r| r.timeout_secs)
-        .unwrap_or(timeout_secs);
+    let (tool, model) = (cell.tool.as_str(), cell.model.as_str());
+    let suite = crate::scenario::suite::load_for_scenario(scenario_path)?;
+    let timeout = timeout::resolve(
+        timeout_secs,
+        s,
+        suite.timeout_secs,
+        &crate::config::Config::load_or_default().timeouts,
+    );
+    let effective_timeout = timeout.secs;
 
-    let results_dir = crate::run::utils::get_results_dir(tool, model, &s.name);
-    std::fs::create_dir_all(&results_dir)?;
+    let mut results_dir = crate::run::utils::get_results_dir(tool, model, &s.name);
+    if let Some(index) = repeat_index {
+        // Repeats of a fast run can start within the same second
+        let mut name = results_dir.file_name().unwrap_or_default().to_os_string();
+        name.push(format!("-r{}", index));
+        results_dir.set_file_name(name);
+    }
+    let results_dir = crate::run::utils::create_unique_dir(&results_dir)?;
 
+    let started = std::time::Instant::now();
     let (env, scenario_yaml, prompt) = setup_scenario_env(s, scenario_path, &results_dir)?;
-    let cache_key = compute_cache_key(&scenario_yaml, &prompt, tool, model);
+    let mut setup_secs = started.elapsed().as_secs_f64();
+    let cache_key = compute_cache_key(&scenario_yaml, &prompt, cell);
 
-    if !no_cache {
+    // A cached result would skip setup, which is the point of materializing,
+    // and would answer every repeat the same way
+    if !no_cache && !materialize && repeat_index.is_none() {
         if let Some(cached) = check_cache(cache, &cache_key)? {
             println!("Cache HIT! Using cached result: {}", cached.id);
             output::print_result_summary(&cached);
@@ -49,60 +80,181 @@ pub fn run_single_scenario(
         }
     }
 
+    if dry_run && materialize {
+        return handle_materialize(
+            s,
+            tool,
+            model,
+            &cache_key,
+            labels,
+            &results_dir,
+            &env,
+            effective_timeout,
+        );
+    }
     if dry_run {
-        return handle_dry_run(s, tool, model, &cache_key);
+        return handle_dry_run(s, tool, model, &cache_key, labels);
     }
 
-    let adapter = create_adapter_and_check(tool)?;
-
-    let (transcript_dir, writer, setup_success, setup_commands) =
-        prepare_writer_and_setup(&results_dir, &env, s, effective_timeout)?;
+    let adapter = match create_adapter_and_check(tool, s) {
+        Ok(adapter) => adapter,
+        Err(e) if skip_unavailable => {
+            let Some(crate::adapter::AdapterError::NotAvailable(diagnostic)) = e.downcast_ref()
+            else {
+                return Err(e);
+            };
+            eprintln!(
+                "SKIPPED_UNAVAILABLE: {}; continuing with the rest of the matrix",
+                diagnostic
+            );
+            let _ = std::fs::remove_dir_all(&results_dir);
+            let record = ResultRecord {
+                repeat_index,
+                ..build_unavailable_record(s, tool, model, &cache_key, diagnostic.clone(), labels)
+            };
+            results_db.append(&record)?;
+            output::print_result_summary(&record);
+            return Ok(record);
+        }
+        Err(e) => return Err(e),
+    };
 
-    let (output, exit_code, cost, token_usage, duration, metrics) = run_evaluation_flow(
-        adapter.as_ref(),
-        s,
-        &env,
-        tool,
-        model,
-        effective_timeout,
-        no_judge,
-        &writer,
-        &transcript_dir,
+    let tool_version = adapter.version();
+    let target_version = crate::adapter::command_version(&s.target.binary);
+    repro::write_repro_script(
         &results_dir,
+        &repro::ReproContext {
+            scenario: s,
+            scenario_path,
+            scenario_hash: &cache_key.scenario_hash,
+            tool,
+            model,
+            tool_version: tool_version.as_deref(),
+            target_version: target_version.as_deref(),
+            timeout_secs: effective_timeout,
+            no_judge,
+            labels,
+        },
     )?;
 
-    let outcome = determine_outcome(&metrics);
+    println!("Timeout: {}s (from {})", timeout.secs, timeout.source);
+    let expected_secs =
+        progress::DurationEstimator::load(results_db).estimate(s.stable_id(), tool, model);
+    if let Some(secs) = expected_secs {
+        println!(
+            "Expected duration: ~{} (median of past runs)",
+            progress::format_duration(secs)
+        );
+    }
 
-    write_transcript_files(
-        &writer,
-        s,
-        tool,
-        model,
-        &cache_key,
-        &output,
-        exit_code,
-        cost,
-        token_usage,
-        duration,
-        &metrics,
-        &outcome,
-        setup_success,
-        setup_commands,
-        &env,
-    )?;
+    // Any failure from here on still produces a record, so runs never end half-written
+    let run_started = std::time::Instant::now();
+    let attempt = std::panic::catch_unwind(std::panic::AssertUnwindSafe(
+        || -> anyhow::Result<(ResultRecord, bool)> {
+            let started = std::time::Instant::now();
+            let (transcript_dir, writer, setup_success, setup_commands) =
+                prepare_writer_and_setup(&results_dir, &env, s, effective_timeout, fault)?;
+            let failures = check_setup_assertions(s, &env, &writer)?;
+            setup_secs += started.elapsed().as_secs_f64();
+            if !failures.is_empty() {
+                eprintln!("SETUP_INVALID: the fixture does not match setup_assertions; not running the tool");
+                let record = build_setup_invalid_record(
+                    s,
+                    tool,
+                    model,
+                    &cache_key,
+                    &failures,
+                    run_started.elapsed().as_secs_f64(),
+                    transcript_dir.to_string_lossy().to_string(),
+                    labels,
+                );
+                return Ok((record, setup_success));
+            }
 
-    let transcript_path = transcript_dir.to_string_lossy().to_string();
-    let record = build_result_record(
-        s,
-        tool,
-        model,
-        &cache_key,
-        metrics,
-        outcome,
-        duration.as_secs_f64(),
-        cost,
-        transcript_path,
-    );
+            let (output, exit_code, cost, token_usage, duration, mut metrics) =
+                run_evaluation_flow(
+                    adapter.as_ref(),
+                    s,
+                    &env,
+                    cell,
+                    effective_timeout,
+                    no_judge,
+                    (!no_judge_cache).then_some(cache),
+                    &writer,
+                    &transcript_dir,
+                    &results_dir,
+                    limiter,
+                    retry,
+                    expected_secs,
+                    fault,
+                )?;
+
+            metrics.phase_durations.setup_secs = setup_secs;
+            let (outcome, outcome_detail) =
+                determine_outcome(&metrics, s.evaluation.judge.as_ref());
+
+            write_transcript_files(
+                &writer,
+                s,
+                tool,
+                model,
+                &cache_key,
+                &output,
+                exit_code,
+                cost,
+                token_usage,
+                duration,
+                &metrics,
+                &outcome.describe(outcome_detail.as_deref()),
+                setup_success,
+                setup_commands,
+                &env,
+                timeout,
+            )?;
+
+            let transcript_path = transcript_dir.to_string_lossy().to_string();
+            let record = build_result_record(
+                s,
+                tool,
+                model,
+                &cache_key,
+                metrics,
+                outcome,
+                outcome_detail,
+                duration.as_secs_f64(),
+                cost,
+                transcript_path,
+                labels,
+            );
+            Ok((record, setup_success))
+        },
+    ));
+    let result = match attempt {
+        Ok(Ok(completed)) => Ok(completed),
+        Ok(Err(e)) => Err((error_outcome(&e), format!("{:#}", e))),
+        Err(panic) => Err((
+            crate::results::Outcome::Error,
+            format!("panic: {}", fault::panic_message(panic.as_ref())),
+        )),
+    };
+    let (mut record, setup_success) = result.unwrap_or_else(|(outcome, detail)| {
+        eprintln!("Run failed: {}", detail);
+        let record = ResultRecord {
+            outcome,
+            ..build_error_record(
+                s,
+                tool,
+                model,
+                &cache_key,
+                detail,
+                run_started.elapsed().as_secs_f64(),
+                results_dir.join("artifacts").to_string_lossy().to_string(),
+                labels,
+            )
+        };
+        (record, true)
+    });
+    record.repeat_index = repeat_index;
 
     finalize_execution(
         results_db,
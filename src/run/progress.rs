@@ -0,0 +1,222 @@
+//! Liveness and progress reporting while a tool runs.
+//!
+//! A heartbeat thread appends `heartbeat` events (elapsed time, output bytes,
+//! commands observed) to `events.jsonl` at a fixed interval, and durations of
+//! past runs from the results database give an ETA for the running cell and
+//! for the remaining cells of a matrix.
+
+use crate::adapter::Progress;
+use crate::results::{ResultRecord, ResultsDB};
+use crate::transcript::TranscriptWriter;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How often a running tool reports a heartbeat.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Expected run durations learned from the results history.
+#[derive(Debug, Default)]
+pub struct DurationEstimator {
+    /// Durations keyed by (scenario id, tool, model)
+    by_cell: HashMap<(String, String, String), Vec<f64>>,
+    /// Durations keyed by scenario id, across all tools and models
+    by_scenario: HashMap<String, Vec<f64>>,
+}
+
+impl DurationEstimator {
+    pub fn from_records(records: &[ResultRecord]) -> Self {
+        let mut estimator = Self::default();
+        for record in records.iter().filter(|r| r.duration_secs > 0.0) {
+            estimator
+                .by_cell
+                .entry((
+                    record.scenario_id.clone(),
+                    record.tool.clone(),
+                    record.model.clone(),
+                ))
+                .or_default()
+                .push(record.duration_secs);
+            estimator
+                .by_scenario
+                .entry(record.scenario_id.clone())
+                .or_default()
+                .push(record.duration_secs);
+        }
+        estimator
+    }
+
+    /// Estimator over all recorded runs; an unreadable database gives no estimates.
+    pub fn load(db: &ResultsDB) -> Self {
+        Self::from_records(&db.load_all().unwrap_or_default())
+    }
+
+    /// Median duration of past runs of this cell, falling back to runs of the
+    /// scenario with any tool or model.
+    pub fn estimate(&self, scenario_id: &str, tool: &str, model: &str) -> Option<f64> {
+        let key = (scenario_id.to_string(), tool.to_string(), model.to_string());
+        self.by_cell
+            .get(&key)
+            .or_else(|| self.by_scenario.get(scenario_id))
+            .map(|durations| median(durations))
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Expected time for a set of cells. Cells without history count as the mean
+/// of those with one; `None` when no cell has history.
+pub fn total_estimate(estimates: &[Option<f64>]) -> Option<f64> {
+    let known: Vec<f64> = estimates.iter().flatten().copied().collect();
+    if known.is_empty() {
+        return None;
+    }
+    let mean = known.iter().sum::<f64>() / known.len() as f64;
+    Some(estimates.iter().map(|e| e.unwrap_or(mean)).sum())
+}
+
+/// Human-readable duration, e.g. `45s`, `3m 20s`, `1h 05m`.
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0).round() as u64;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Run `f`, appending a `heartbeat` event to `writer` every `interval` until
+/// it returns. `expected_secs` adds a remaining-time estimate to each beat.
+pub fn with_heartbeat<T>(
+    writer: &TranscriptWriter,
+    progress: &Progress,
+    interval: Duration,
+    expected_secs: Option<f64>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let start = Instant::now();
+    let (done, stop) = mpsc::channel::<()>();
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop.recv_timeout(interval) {
+                beat(writer, progress, start.elapsed(), expected_secs);
+            }
+        });
+        let result = f();
+        drop(done);
+        result
+    })
+}
+
+fn beat(
+    writer: &TranscriptWriter,
+    progress: &Progress,
+    elapsed: Duration,
+    expected_secs: Option<f64>,
+) {
+    let elapsed = elapsed.as_secs_f64();
+    let (bytes, commands) = (progress.bytes(), progress.commands());
+    let mut event = serde_json::json!({
+        "type": "heartbeat",
+        "elapsed_secs": elapsed,
+        "output_bytes": bytes,
+        "commands": commands,
+    });
+    let eta = match expected_secs {
+        Some(expected) if expected > elapsed => {
+            event["eta_secs"] = serde_json::json!(expected - elapsed);
+            format!("; ETA ~{}", format_duration(expected - elapsed))
+        }
+        Some(expected) => format!("; past the usual ~{}", format_duration(expected)),
+        None => String::new(),
+    };
+    println!(
+        "Still running: {} elapsed, {} bytes of output, {} command(s){}",
+        format_duration(elapsed),
+        bytes,
+        commands,
+        eta
+    );
+    if let Err(e) = writer.append_event(&event) {
+        tracing::debug!("Failed to write heartbeat event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::test_helpers::create_test_record_with_tool;
+
+    fn record(tool: &str, scenario: &str, duration: f64) -> ResultRecord {
+        let mut record = create_test_record_with_tool("run", scenario, tool);
+        record.duration_secs = duration;
+        record
+    }
+
+    #[test]
+    fn estimate_prefers_cell_history() {
+        let estimator = DurationEstimator::from_records(&[
+            record("opencode", "s1", 100.0),
+            record("opencode", "s1", 120.0),
+            record("opencode", "s1", 400.0),
+            record("claude-code", "s1", 10.0),
+        ]);
+        assert_eq!(estimator.estimate("s1", "opencode", "gpt-4o"), Some(120.0));
+        assert_eq!(estimator.estimate("s1", "mock", "gpt-4o"), Some(110.0));
+        assert_eq!(estimator.estimate("s2", "opencode", "gpt-4o"), None);
+    }
+
+    #[test]
+    fn total_estimate_fills_unknown_cells() {
+        assert_eq!(
+            total_estimate(&[Some(60.0), None, Some(120.0)]),
+            Some(270.0)
+        );
+        assert_eq!(total_estimate(&[None, None]), None);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(42.4), "42s");
+        assert_eq!(format_duration(200.0), "3m 20s");
+        assert_eq!(format_duration(3900.0), "1h 05m");
+    }
+
+    #[test]
+    fn heartbeat_events_are_written_while_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer =
+            TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+        let progress = Progress::new(r"^\s*(mytool)\s+(\S+)");
+        let value = with_heartbeat(
+            &writer,
+            &progress,
+            Duration::from_millis(50),
+            Some(60.0),
+            || {
+                progress.record(b"mytool list\nsome output\n");
+                std::thread::sleep(Duration::from_millis(180));
+                7
+            },
+        );
+        assert_eq!(value, 7);
+
+        let events = writer.read_events().unwrap();
+        let beats: Vec<_> = events.iter().filter(|e| e["type"] == "heartbeat").collect();
+        assert!(beats.len() >= 2, "expected heartbeats, got {:?}", events);
+        assert_eq!(beats[0]["commands"], 1);
+        assert_eq!(beats[0]["output_bytes"], 24);
+        assert!(beats[0]["eta_secs"].as_f64().unwrap() < 60.0);
+    }
+}
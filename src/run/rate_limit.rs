@@ -0,0 +1,249 @@
+//! Launch scheduling for tool runs.
+//!
+//! Enforces per-tool concurrency limits and launch spacing from
+//! [`RateLimitConfig`], and pauses further launches of a tool with
+//! exponential backoff after a run is rate limited by the provider.
+
+use crate::config::RateLimitConfig;
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct ToolState {
+    active: usize,
+    last_launch: Option<Instant>,
+    backoff: Option<Duration>,
+    resume_at: Option<Instant>,
+}
+
+/// Shared scheduler state for all tools in a run.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<HashMap<String, ToolState>>,
+    released: Condvar,
+}
+
+/// A granted launch slot. Dropping it frees the slot; call [`LaunchPermit::finish`]
+/// to report whether the run was rate limited.
+pub struct LaunchPermit<'a> {
+    limiter: &'a RateLimiter,
+    tool: String,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(HashMap::new()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until `tool` may be launched, then reserve a slot for it.
+    pub fn acquire(&self, tool: &str) -> LaunchPermit<'_> {
+        let max_concurrent = self.config.max_concurrent_for(tool).unwrap_or(usize::MAX);
+        let launch_delay = Duration::from_secs_f64(self.config.launch_delay_for(tool).max(0.0));
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let tool_state = state.entry(tool.to_string()).or_default();
+            if tool_state.active >= max_concurrent.max(1) {
+                state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+
+            let now = Instant::now();
+            let ready_at = [
+                tool_state.last_launch.map(|t| t + launch_delay),
+                tool_state.resume_at,
+            ]
+            .into_iter()
+            .flatten()
+            .max();
+            match ready_at {
+                Some(ready_at) if ready_at > now => {
+                    let (guard, _) = self
+                        .released
+                        .wait_timeout(state, ready_at - now)
+                        .unwrap_or_else(|e| e.into_inner());
+                    state = guard;
+                }
+                _ => {
+                    tool_state.active += 1;
+                    tool_state.last_launch = Some(now);
+                    return LaunchPermit {
+                        limiter: self,
+                        tool: tool.to_string(),
+                    };
+                }
+            }
+        }
+    }
+
+    fn record_outcome(&self, tool: &str, rate_limited: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let tool_state = state.entry(tool.to_string()).or_default();
+        if rate_limited {
+            let initial = Duration::from_secs_f64(self.config.backoff_initial_secs.max(0.0));
+            let max = Duration::from_secs_f64(self.config.backoff_max_secs.max(0.0));
+            let backoff = tool_state
+                .backoff
+                .map_or(initial, |b| b.saturating_mul(2))
+                .min(max);
+            eprintln!(
+                "Rate limit detected for tool '{}'; pausing launches for {:.0}s",
+                tool,
+                backoff.as_secs_f64()
+            );
+            tool_state.backoff = Some(backoff);
+            tool_state.resume_at = Some(Instant::now() + backoff);
+        } else {
+            tool_state.backoff = None;
+            tool_state.resume_at = None;
+        }
+    }
+
+    fn release(&self, tool: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(tool_state) = state.get_mut(tool) {
+            tool_state.active = tool_state.active.saturating_sub(1);
+        }
+        self.released.notify_all();
+    }
+}
+
+impl LaunchPermit<'_> {
+    /// Report the run's outcome, starting or clearing backoff for the tool.
+    pub fn finish(self, rate_limited: bool) {
+        self.limiter.record_outcome(&self.tool, rate_limited);
+    }
+}
+
+impl Drop for LaunchPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(&self.tool);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolRateLimit;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn backoff(limiter: &RateLimiter, tool: &str) -> Option<Duration> {
+        limiter
+            .state
+            .lock()
+            .unwrap()
+            .get(tool)
+            .and_then(|s| s.backoff)
+    }
+
+    fn config(launch_delay_secs: f64) -> RateLimitConfig {
+        RateLimitConfig {
+            launch_delay_secs,
+            backoff_initial_secs: 0.05,
+            backoff_max_secs: 0.15,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn spaces_launches_of_the_same_tool() {
+        let limiter = RateLimiter::new(config(0.05));
+        let start = Instant::now();
+        limiter.acquire("opencode").finish(false);
+        limiter.acquire("opencode").finish(false);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Other tools are not delayed
+        let start = Instant::now();
+        limiter.acquire("claude-code").finish(false);
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_and_resets_on_success() {
+        let limiter = RateLimiter::new(config(0.0));
+        limiter.acquire("opencode").finish(true);
+        assert_eq!(
+            backoff(&limiter, "opencode"),
+            Some(Duration::from_millis(50))
+        );
+
+        let start = Instant::now();
+        limiter.acquire("opencode").finish(true);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(
+            backoff(&limiter, "opencode"),
+            Some(Duration::from_millis(100))
+        );
+
+        limiter.acquire("opencode").finish(true);
+        assert_eq!(
+            backoff(&limiter, "opencode"),
+            Some(Duration::from_millis(150))
+        );
+
+        limiter.acquire("opencode").finish(false);
+        assert_eq!(backoff(&limiter, "opencode"), None);
+    }
+
+    #[test]
+    fn limits_concurrent_runs_per_tool() {
+        let mut config = config(0.0);
+        config.tools.insert(
+            "opencode".to_string(),
+            ToolRateLimit {
+                max_concurrent: Some(1),
+                launch_delay_secs: None,
+            },
+        );
+        let limiter = Arc::new(RateLimiter::new(config));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let (limiter, active, peak) = (limiter.clone(), active.clone(), peak.clone());
+                std::thread::spawn(move || {
+                    let permit = limiter.acquire("opencode");
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    permit.finish(false);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn detects_rate_limited_output() {
+        use crate::adapter::looks_rate_limited;
+        assert!(looks_rate_limited("Error: 429 Too Many Requests"));
+        assert!(looks_rate_limited("rate_limit_error: slow down"));
+        assert!(looks_rate_limited("API is overloaded"));
+        assert!(!looks_rate_limited("Created 4290 notes"));
+        assert!(!looks_rate_limited("all good"));
+        // The agent discussing a 429 earlier is not the tool being throttled
+        let transcript = format!(
+            "Handling 429 Too Many Requests in the client\n{}Done.\n",
+            "step\n".repeat(5)
+        );
+        assert!(!looks_rate_limited(&transcript));
+    }
+}
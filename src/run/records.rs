@@ -1,6 +1,8 @@
-use crate::evaluation::EvaluationMetrics;
+use crate::evaluation::{EvaluationMetrics, GateResult};
+use crate::fixture::TestEnv;
 use crate::output;
-use crate::results::{Cache, CacheKey, EvaluationMetricsRecord, ResultRecord, ResultsDB};
+use crate::results::{Cache, CacheKey, EvaluationMetricsRecord, Outcome, ResultRecord, ResultsDB};
+use crate::run::setup::{check_setup_assertions, prepare_writer_and_setup};
 use crate::scenario::Scenario;
 use std::path::Path;
 
@@ -11,16 +13,21 @@ pub fn build_result_record(
     model: &str,
     cache_key: &CacheKey,
     metrics: EvaluationMetrics,
-    outcome: String,
+    outcome: Outcome,
+    outcome_detail: Option<String>,
     duration_secs: f64,
     cost: Option<f64>,
     transcript_path: String,
+    labels: &[String],
 ) -> ResultRecord {
-    use crate::results::{EfficiencyMetricsRecord, EvaluatorResultRecord, GateResultRecord};
+    use crate::results::{
+        ConversationMetricsRecord, EfficiencyMetricsRecord, EvaluatorResultRecord,
+        GateResultRecord, ScorePenaltiesRecord,
+    };
 
     ResultRecord {
         id: crate::results::generate_run_id(),
-        scenario_id: s.name.clone(),
+        scenario_id: s.stable_id().to_string(),
         scenario_hash: cache_key.scenario_hash.clone(),
         tool: tool.to_string(),
         model: model.to_string(),
@@ -38,6 +45,16 @@ pub fn build_result_record(
                     gate_type: d.gate_type,
                     passed: d.passed,
                     message: d.message,
+                    score: d.score,
+                    expected: d.expected,
+                    actual: d.actual,
+                    path: d.path,
+                    command: d.command,
+                    exit_code: d.exit_code,
+                    duration_secs: d.duration_secs,
+                    warning: d.warning,
+                    skipped: d.skipped,
+                    attempts: d.attempts,
                 })
                 .collect(),
             efficiency: EfficiencyMetricsRecord {
@@ -48,8 +65,23 @@ pub fn build_result_record(
                 help_invocations: metrics.efficiency.help_invocations,
                 first_try_success_rate: metrics.efficiency.first_try_success_rate,
                 iteration_ratio: metrics.efficiency.iteration_ratio,
+                wasted_actions: metrics.efficiency.wasted_actions,
             },
+            conversation: Some(ConversationMetricsRecord {
+                turns: metrics.conversation.turns,
+                assistant_messages: metrics.conversation.assistant_messages,
+                avg_assistant_message_chars: metrics.conversation.avg_assistant_message_chars,
+                tool_call_turns: metrics.conversation.tool_call_turns,
+                text_turns: metrics.conversation.text_turns,
+                tool_turn_ratio: metrics.conversation.tool_turn_ratio,
+                plan_messages: metrics.conversation.plan_messages,
+            }),
             composite_score: metrics.composite_score,
+            score_penalties: metrics.score_penalties.map(|p| ScorePenaltiesRecord {
+                errors: p.errors,
+                retries: p.retries,
+                total: p.total,
+            }),
             evaluator_results: metrics
                 .evaluator_results
                 .into_iter()
@@ -61,11 +93,43 @@ pub fn build_result_record(
                     error: e.error,
                 })
                 .collect(),
+            component_errors: metrics.component_errors,
         },
         judge_score: metrics.judge_score,
         outcome,
+        outcome_detail,
         transcript_path: transcript_path.clone(),
         cache_key: Some(cache_key.as_string()),
+        judge_cost_usd: metrics.judge_cost_usd,
+        labels: labels.to_vec(),
+        phase_durations: Some(metrics.phase_durations),
+        repeat_index: None,
+    }
+}
+
+/// Metrics for a run that never reached evaluation.
+fn empty_metrics(gates_total: usize) -> EvaluationMetricsRecord {
+    use crate::results::EfficiencyMetricsRecord;
+
+    EvaluationMetricsRecord {
+        gates_passed: 0,
+        gates_total,
+        details: vec![],
+        efficiency: EfficiencyMetricsRecord {
+            total_commands: 0,
+            unique_commands: 0,
+            error_count: 0,
+            retry_count: 0,
+            help_invocations: 0,
+            first_try_success_rate: 0.0,
+            iteration_ratio: 0.0,
+            wasted_actions: 0,
+        },
+        conversation: None,
+        composite_score: None,
+        score_penalties: None,
+        evaluator_results: vec![],
+        component_errors: vec![],
     }
 }
 
@@ -74,14 +138,77 @@ pub fn handle_dry_run(
     tool: &str,
     model: &str,
     cache_key: &CacheKey,
+    labels: &[String],
 ) -> anyhow::Result<ResultRecord> {
-    use crate::results::{EfficiencyMetricsRecord, EvaluationMetricsRecord};
-
     println!("Dry run - skipping execution");
 
-    let record = ResultRecord {
+    let record = dry_run_record(s, tool, model, cache_key, labels);
+    output::print_result_summary(&record);
+    Ok(record)
+}
+
+/// `--dry-run --materialize`: set up the fixture and run the setup commands
+/// as a real run would, then stop before the tool and leave everything in
+/// place so scenario authors can inspect the starting state.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_materialize(
+    s: &Scenario,
+    tool: &str,
+    model: &str,
+    cache_key: &CacheKey,
+    labels: &[String],
+    results_dir: &Path,
+    env: &TestEnv,
+    effective_timeout: u64,
+) -> anyhow::Result<ResultRecord> {
+    println!("Dry run - materializing environment without running the tool");
+
+    let (artifacts_dir, writer, setup_success, setup_commands) =
+        prepare_writer_and_setup(results_dir, env, s, effective_timeout, None)?;
+    for (command, success, _) in &setup_commands {
+        println!("  [{}] {}", if *success { "ok" } else { "FAILED" }, command);
+    }
+    let failures = check_setup_assertions(s, env, &writer)?;
+
+    println!("Environment left in place at: {}", env.root.display());
+    if let Some(home) = &env.home {
+        println!("Isolated HOME: {}", home.display());
+    }
+    println!(
+        "Setup output: {}",
+        artifacts_dir.join("events.jsonl").display()
+    );
+
+    let mut record = dry_run_record(s, tool, model, cache_key, labels);
+    record.gates_passed = setup_success;
+    record.outcome_detail = Some(
+        if setup_success {
+            "environment materialized"
+        } else {
+            "setup command failed"
+        }
+        .to_string(),
+    );
+    record.transcript_path = artifacts_dir.to_string_lossy().to_string();
+    if !failures.is_empty() {
+        record.gates_passed = false;
+        record.outcome = Outcome::SetupInvalid;
+        record.outcome_detail = Some(setup_invalid_detail(s, &failures));
+    }
+    output::print_result_summary(&record);
+    Ok(record)
+}
+
+fn dry_run_record(
+    s: &Scenario,
+    tool: &str,
+    model: &str,
+    cache_key: &CacheKey,
+    labels: &[String],
+) -> ResultRecord {
+    ResultRecord {
         id: crate::results::generate_run_id(),
-        scenario_id: s.name.clone(),
+        scenario_id: s.stable_id().to_string(),
         scenario_hash: cache_key.scenario_hash.clone(),
         tool: tool.to_string(),
         model: model.to_string(),
@@ -89,30 +216,129 @@ pub fn handle_dry_run(
         duration_secs: 0.0,
         cost_usd: None,
         gates_passed: true,
-        metrics: EvaluationMetricsRecord {
-            gates_passed: 0,
-            gates_total: 0,
-            details: vec![],
-            efficiency: EfficiencyMetricsRecord {
-                total_commands: 0,
-                unique_commands: 0,
-                error_count: 0,
-                retry_count: 0,
-                help_invocations: 0,
-                first_try_success_rate: 0.0,
-                iteration_ratio: 0.0,
-            },
-            composite_score: None,
-            evaluator_results: vec![],
-        },
+        metrics: empty_metrics(0),
         judge_score: None,
-        outcome: "Dry run".to_string(),
+        outcome: Outcome::DryRun,
+        outcome_detail: None,
         transcript_path: String::new(),
         cache_key: Some(cache_key.as_string()),
-    };
+        judge_cost_usd: None,
+        labels: labels.to_vec(),
+        phase_durations: None,
+        repeat_index: None,
+    }
+}
 
-    output::print_result_summary(&record);
-    Ok(record)
+/// Outcome of a run that failed with `error`: `timeout` when the tool was
+/// killed at its `timeout_secs`, otherwise `error`.
+pub fn error_outcome(error: &anyhow::Error) -> Outcome {
+    if crate::adapter::AdapterError::is_timeout(error) {
+        Outcome::Timeout
+    } else {
+        Outcome::Error
+    }
+}
+
+/// Record for a run the harness could not complete, with `detail` saying why.
+///
+/// No gate counts as passed, whatever the scenario defines.
+#[allow(clippy::too_many_arguments)]
+pub fn build_error_record(
+    s: &Scenario,
+    tool: &str,
+    model: &str,
+    cache_key: &CacheKey,
+    detail: String,
+    duration_secs: f64,
+    transcript_path: String,
+    labels: &[String],
+) -> ResultRecord {
+    ResultRecord {
+        id: crate::results::generate_run_id(),
+        scenario_id: s.stable_id().to_string(),
+        scenario_hash: cache_key.scenario_hash.clone(),
+        tool: tool.to_string(),
+        model: model.to_string(),
+        timestamp: chrono::Utc::now(),
+        duration_secs,
+        cost_usd: None,
+        gates_passed: false,
+        metrics: empty_metrics(s.evaluation.gates.len()),
+        judge_score: None,
+        outcome: Outcome::Error,
+        outcome_detail: Some(detail),
+        transcript_path,
+        cache_key: Some(cache_key.as_string()),
+        judge_cost_usd: None,
+        labels: labels.to_vec(),
+        phase_durations: None,
+        repeat_index: None,
+    }
+}
+
+/// Record for a run stopped because `setup_assertions` failed; the tool never ran.
+#[allow(clippy::too_many_arguments)]
+pub fn build_setup_invalid_record(
+    s: &Scenario,
+    tool: &str,
+    model: &str,
+    cache_key: &CacheKey,
+    failures: &[GateResult],
+    duration_secs: f64,
+    transcript_path: String,
+    labels: &[String],
+) -> ResultRecord {
+    ResultRecord {
+        outcome: Outcome::SetupInvalid,
+        ..build_error_record(
+            s,
+            tool,
+            model,
+            cache_key,
+            setup_invalid_detail(s, failures),
+            duration_secs,
+            transcript_path,
+            labels,
+        )
+    }
+}
+
+/// Record for a matrix cell skipped because its tool is unavailable;
+/// `diagnostic` is the adapter's explanation.
+pub fn build_unavailable_record(
+    s: &Scenario,
+    tool: &str,
+    model: &str,
+    cache_key: &CacheKey,
+    diagnostic: String,
+    labels: &[String],
+) -> ResultRecord {
+    ResultRecord {
+        outcome: Outcome::SkippedUnavailable,
+        ..build_error_record(
+            s,
+            tool,
+            model,
+            cache_key,
+            diagnostic,
+            0.0,
+            String::new(),
+            labels,
+        )
+    }
+}
+
+fn setup_invalid_detail(s: &Scenario, failures: &[GateResult]) -> String {
+    let reasons: Vec<String> = failures
+        .iter()
+        .map(|f| format!("{}: {}", f.gate_type, f.message))
+        .collect();
+    format!(
+        "{}/{} setup assertions failed: {}",
+        failures.len(),
+        s.setup_assertions.len(),
+        reasons.join("; ")
+    )
 }
 
 pub fn finalize_execution(
@@ -124,10 +350,19 @@ pub fn finalize_execution(
     setup_success: bool,
 ) -> anyhow::Result<ResultRecord> {
     results_db.append(record)?;
-    cache.put(cache_key, record)?;
+    // A harness failure or bad fixture says nothing about the tool; let the next run retry it.
+    // A repeat is one sample of a distribution, not the answer for the cell.
+    if record.repeat_index.is_none()
+        && !matches!(
+            record.outcome,
+            Outcome::Error | Outcome::SetupInvalid | Outcome::SkippedUnavailable
+        )
+    {
+        cache.put(cache_key, record)?;
+    }
 
     let metrics_json = serde_json::to_string_pretty(&record.metrics)?;
-    std::fs::write(results_dir.join("metrics.json"), metrics_json)?;
+    crate::artifacts::write_atomic(&results_dir.join("metrics.json"), metrics_json)?;
 
     println!("\nRun completed: {}", record.id);
     println!("Artifacts written to: {}", results_dir.display());
@@ -0,0 +1,191 @@
+//! Reproduction script written alongside each run.
+//!
+//! `repro.sh` records the exact `llm-tool-test run` invocation together with
+//! the tool/target versions, scenario hash and fixture source observed at run
+//! time, and checks that the environment variables the scenario references are
+//! set before re-executing.
+
+use crate::scenario::Scenario;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Everything needed to re-execute a run with the identical configuration.
+pub struct ReproContext<'a> {
+    pub scenario: &'a Scenario,
+    pub scenario_path: &'a Path,
+    pub scenario_hash: &'a str,
+    pub tool: &'a str,
+    pub model: &'a str,
+    pub tool_version: Option<&'a str>,
+    pub target_version: Option<&'a str>,
+    pub timeout_secs: u64,
+    pub no_judge: bool,
+    pub labels: &'a [String],
+}
+
+/// Quote a value for POSIX `sh`.
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@+,".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Environment variables referenced as `$VAR` or `${VAR}` in the scenario's `target.env`.
+fn required_env_vars(scenario: &Scenario) -> BTreeSet<String> {
+    let Ok(re) = Regex::new(r"\$\{?([A-Za-z_][A-Za-z0-9_]*)\}?") else {
+        return BTreeSet::new();
+    };
+    scenario
+        .target
+        .env
+        .iter()
+        .flat_map(|env| env.values())
+        .flat_map(|value| re.captures_iter(value))
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Render the contents of `repro.sh`.
+pub fn render_repro_script(ctx: &ReproContext) -> String {
+    let s = ctx.scenario;
+    let scenario_path = std::fs::canonicalize(ctx.scenario_path)
+        .unwrap_or_else(|_| ctx.scenario_path.to_path_buf());
+    let fixture_source = crate::utils::resolve_fixtures_path("templates").join(&s.template_folder);
+    let fixture_source = std::fs::canonicalize(&fixture_source).unwrap_or(fixture_source);
+    let unknown = "unknown";
+
+    let mut out = String::from("#!/bin/sh\n");
+    out.push_str("# Reproduce this llm-tool-test run with the identical configuration.\n#\n");
+    out.push_str(&format!(
+        "# Generated:          {}\n",
+        chrono::Utc::now().to_rfc3339()
+    ));
+    out.push_str(&format!(
+        "# llm-tool-test:      {}\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str(&format!("# Scenario:           {}\n", s.stable_id()));
+    out.push_str(&format!(
+        "# Scenario file:      {}\n",
+        scenario_path.display()
+    ));
+    out.push_str(&format!("# Scenario hash:      {}\n", ctx.scenario_hash));
+    out.push_str(&format!(
+        "# Fixture source:     {}\n",
+        fixture_source.display()
+    ));
+    out.push_str(&format!(
+        "# Tool:               {} ({})\n",
+        ctx.tool,
+        ctx.tool_version.unwrap_or(unknown)
+    ));
+    out.push_str(&format!("# Model:              {}\n", ctx.model));
+    out.push_str(&format!(
+        "# Target:             {} ({})\n",
+        s.target.binary,
+        ctx.target_version.unwrap_or(unknown)
+    ));
+    out.push_str(
+        "#\n# Results only match if the scenario hash and versions above still match.\n\n",
+    );
+    out.push_str("set -eu\n\n");
+
+    let env_vars = required_env_vars(s);
+    if !env_vars.is_empty() {
+        out.push_str("# Required environment variables\n");
+        for var in &env_vars {
+            out.push_str(&format!(": \"${{{var}:?{var} must be set}}\"\n"));
+        }
+        out.push('\n');
+    }
+
+    let mut args = vec![
+        "llm-tool-test".to_string(),
+        "run".to_string(),
+        "--scenario".to_string(),
+        shell_quote(&scenario_path.to_string_lossy()),
+        "--tool".to_string(),
+        shell_quote(ctx.tool),
+        "--model".to_string(),
+        shell_quote(ctx.model),
+        "--timeout-secs".to_string(),
+        ctx.timeout_secs.to_string(),
+        "--no-cache".to_string(),
+    ];
+    if ctx.no_judge {
+        args.push("--no-judge".to_string());
+    }
+    for label in ctx.labels {
+        args.push("--label".to_string());
+        args.push(shell_quote(label));
+    }
+    out.push_str(&format!("exec {} \"$@\"\n", args.join(" ")));
+    out
+}
+
+/// Write `repro.sh` into the run's results directory and make it executable.
+pub fn write_repro_script(results_dir: &Path, ctx: &ReproContext) -> anyhow::Result<()> {
+    let path = results_dir.join("repro.sh");
+    crate::artifacts::write_atomic(&path, render_repro_script(ctx))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario() -> Scenario {
+        serde_yaml::from_str(
+            "name: capture\nid: capture-v1\ndescription: d\ntemplate_folder: qipu\ntarget:\n  binary: qipu\n  env:\n    QIPU_TOKEN: \"${QIPU_TOKEN}\"\n    QIPU_HOME: \"$HOME/.qipu\"\ntask:\n  prompt: p\nevaluation:\n  gates: []\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn script_records_invocation_versions_and_env() {
+        let s = scenario();
+        let labels = vec!["nightly run".to_string()];
+        let script = render_repro_script(&ReproContext {
+            scenario: &s,
+            scenario_path: Path::new("fixtures/capture.yaml"),
+            scenario_hash: "abc123",
+            tool: "opencode",
+            model: "gpt-4o",
+            tool_version: Some("opencode 0.5.1"),
+            target_version: None,
+            timeout_secs: 120,
+            no_judge: true,
+            labels: &labels,
+        });
+
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("# Scenario:           capture-v1\n"));
+        assert!(script.contains("# Scenario hash:      abc123\n"));
+        assert!(script.contains("# Tool:               opencode (opencode 0.5.1)\n"));
+        assert!(script.contains("# Target:             qipu (unknown)\n"));
+        assert!(script.contains(": \"${HOME:?HOME must be set}\"\n"));
+        assert!(script.contains(": \"${QIPU_TOKEN:?QIPU_TOKEN must be set}\"\n"));
+        assert!(script.contains(
+            "--tool opencode --model gpt-4o --timeout-secs 120 --no-cache --no-judge --label 'nightly run' \"$@\"\n"
+        ));
+    }
+
+    #[test]
+    fn quotes_shell_metacharacters() {
+        assert_eq!(shell_quote("gpt-4o"), "gpt-4o");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
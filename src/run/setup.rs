@@ -1,5 +1,10 @@
+use crate::config::Config;
+use crate::evaluation::GateResult;
 use crate::fixture::TestEnv;
-use crate::scenario::{Scenario, Setup};
+use crate::run::fault::FaultPhase;
+use crate::sandbox::Sandbox;
+use crate::scenario::{bundle, Scenario, Setup};
+use crate::transcript::template::ReportRenderer;
 use crate::transcript::TranscriptWriter;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -9,7 +14,11 @@ pub fn setup_scenario_env(
     scenario_path: &std::path::Path,
     results_dir: &Path,
 ) -> anyhow::Result<(TestEnv, String, String)> {
-    let scenario_yaml = std::fs::read_to_string(scenario_path)?;
+    let (mut scenario_yaml, _) = crate::scenario::bundle::read_source(scenario_path)?;
+    if !s.evaluation.use_gates.is_empty() {
+        // Include resolved gate sets so edits to gatesets.yaml invalidate the cache
+        scenario_yaml.push_str(&serde_yaml::to_string(&s.evaluation.gates)?);
+    }
     let prompt = s.task.prompt.clone();
 
     println!(
@@ -17,11 +26,23 @@ pub fn setup_scenario_env(
         s.template_folder
     );
     let env_root = results_dir.join("fixture");
-    let env = TestEnv::new(env_root)?;
+    let mut env = TestEnv::new(env_root)?;
     env.setup_fixture(&s.template_folder)?;
 
     println!("Environment created at: {:?}", env.root);
 
+    if let Some(isolation) = s
+        .run
+        .as_ref()
+        .and_then(|r| r.isolation.as_ref())
+        .filter(|i| i.enabled)
+    {
+        env.setup_isolated_home(results_dir.join("home"), isolation.home_template.as_deref())?;
+        if let Some(home) = &env.home {
+            println!("Isolated HOME created at: {:?}", home);
+        }
+    }
+
     Ok((env, scenario_yaml, prompt))
 }
 
@@ -32,18 +53,16 @@ pub fn execute_setup_commands(
     writer: &TranscriptWriter,
     effective_timeout: u64,
     target_env: Option<&HashMap<String, String>>,
+    sandbox: Option<Sandbox>,
 ) -> anyhow::Result<(bool, Vec<(String, bool, String)>)> {
     println!("Running {} setup command(s)...", setup.commands.len());
-    let runner = crate::session::SessionRunner::new();
+    let runner = crate::session::SessionRunner::new().with_sandbox(sandbox);
     let mut setup_success = true;
     let mut setup_commands: Vec<(String, bool, String)> = Vec::new();
-    let env_vars: Vec<(String, String)> = target_env
-        .map(|vars| {
-            vars.iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect::<Vec<(String, String)>>()
-        })
-        .unwrap_or_default();
+    let mut env_vars = env.isolation_env();
+    if let Some(vars) = target_env {
+        env_vars.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
 
     for (i, cmd) in setup.commands.iter().enumerate() {
         println!("  Command {}/{}: {}", i + 1, setup.commands.len(), cmd);
@@ -83,10 +102,20 @@ pub fn prepare_writer_and_setup(
     env: &TestEnv,
     s: &Scenario,
     effective_timeout: u64,
+    fault: Option<FaultPhase>,
 ) -> anyhow::Result<(PathBuf, TranscriptWriter, bool, Vec<(String, bool, String)>)> {
     let artifacts_dir = results_dir.join("artifacts");
     std::fs::create_dir_all(&artifacts_dir)?;
-    let writer = TranscriptWriter::new(artifacts_dir.clone(), results_dir.to_path_buf())?;
+    let mut writer = TranscriptWriter::new(artifacts_dir.clone(), results_dir.to_path_buf())?;
+    if s.encrypted && !bundle::reveal_prompts() {
+        writer.add_redaction(&s.task.prompt, bundle::REDACTED_PROMPT);
+    }
+    writer.set_renderer(ReportRenderer::from_config(
+        &Config::load_or_default().report,
+    )?);
+    if fault == Some(FaultPhase::Artifacts) {
+        writer.simulate_disk_full();
+    }
 
     let (setup_success, setup_commands) = if let Some(setup) = &s.setup {
         execute_setup_commands(
@@ -95,6 +124,7 @@ pub fn prepare_writer_and_setup(
             &writer,
             effective_timeout,
             s.target.env.as_ref(),
+            Sandbox::for_scenario(s, [results_dir.to_path_buf()]),
         )?
     } else {
         (true, vec![])
@@ -103,10 +133,42 @@ pub fn prepare_writer_and_setup(
     Ok((artifacts_dir, writer, setup_success, setup_commands))
 }
 
+/// Evaluate `setup_assertions` and log each result as a `setup_assertion` event.
+///
+/// Returns the assertions that failed; none means the starting state is valid.
+pub fn check_setup_assertions(
+    s: &Scenario,
+    env: &TestEnv,
+    writer: &TranscriptWriter,
+) -> anyhow::Result<Vec<GateResult>> {
+    if s.setup_assertions.is_empty() {
+        return Ok(Vec::new());
+    }
+    println!(
+        "Checking {} setup assertion(s)...",
+        s.setup_assertions.len()
+    );
+    let results = crate::evaluation::evaluate_setup_assertions(s, &env.root);
+    for (i, result) in results.iter().enumerate() {
+        writer.append_event(&serde_json::json!({
+            "type": "setup_assertion",
+            "index": i,
+            "gate_type": result.gate_type,
+            "passed": result.passed,
+            "message": result.message,
+        }))?;
+    }
+    Ok(results
+        .into_iter()
+        .filter(|r| !r.passed && !r.warning)
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::scenario::Setup;
+    use crate::transcript::types::EventRecord;
     use tempfile::tempdir;
 
     #[test]
@@ -127,11 +189,80 @@ mod tests {
         target_env.insert("TARGET_ENV_TEST".to_string(), "works".to_string());
 
         let (setup_success, commands) =
-            execute_setup_commands(&setup, &env, &writer, 10, Some(&target_env))
+            execute_setup_commands(&setup, &env, &writer, 10, Some(&target_env), None)
                 .expect("run setup commands");
 
         assert!(setup_success);
         assert_eq!(commands.len(), 1);
         assert!(commands[0].1);
     }
+
+    #[test]
+    fn setup_events_match_the_event_schema() {
+        let dir = tempdir().expect("create temp dir");
+        let env = TestEnv::new(dir.path().join("fixture")).expect("create test env");
+        std::fs::create_dir_all(&env.root).expect("create fixture root");
+        std::fs::write(env.root.join("brief.md"), "brief").expect("write brief");
+        let writer =
+            TranscriptWriter::new(dir.path().join("artifacts"), dir.path().join("results"))
+                .expect("create writer");
+        let scenario: Scenario = serde_yaml::from_str(
+            "name: s\ndescription: d\ntemplate_folder: t\ntarget:\n  binary: t\ntask:\n  prompt: p\nsetup:\n  commands: [\"true\"]\nsetup_assertions:\n  - type: file_exists\n    path: brief.md\n  - type: file_exists\n    path: missing.md\nevaluation:\n  gates: []\n",
+        )
+        .expect("parse scenario");
+
+        execute_setup_commands(
+            scenario.setup.as_ref().unwrap(),
+            &env,
+            &writer,
+            10,
+            None,
+            None,
+        )
+        .expect("run setup commands");
+        let failures = check_setup_assertions(&scenario, &env, &writer).expect("check assertions");
+        assert_eq!(failures.len(), 1);
+
+        let events =
+            std::fs::read_to_string(writer.base_dir.join("events.jsonl")).expect("read events");
+        let events: Vec<EventRecord> = events
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {}", e, line)))
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[0],
+            EventRecord::SetupCommand { success: true, .. }
+        ));
+        assert!(matches!(
+            events[2],
+            EventRecord::SetupAssertion {
+                index: 1,
+                passed: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn setup_commands_use_isolated_home() {
+        let dir = tempdir().expect("create temp dir");
+        let mut env = TestEnv::new(dir.path().join("fixture")).expect("create test env");
+        env.setup_isolated_home(dir.path().join("home"), None)
+            .expect("create isolated home");
+
+        let artifacts_dir = dir.path().join("artifacts");
+        let results_dir = dir.path().join("results");
+        let writer = TranscriptWriter::new(artifacts_dir, results_dir).expect("create writer");
+
+        let setup = Setup {
+            commands: vec!["touch \"$HOME/seeded\" && test -d \"$XDG_CONFIG_HOME\"".to_string()],
+        };
+
+        let (setup_success, _) = execute_setup_commands(&setup, &env, &writer, 10, None, None)
+            .expect("run setup commands");
+
+        assert!(setup_success);
+        assert!(env.home.as_ref().unwrap().join("seeded").exists());
+    }
 }
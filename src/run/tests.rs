@@ -1,12 +1,13 @@
 use super::*;
+use crate::run::rate_limit::RateLimiter;
 use crate::scenario::Scenario;
 use std::path::PathBuf;
 
 #[test]
-fn test_scenario_timeout_overrides_cli() {
+fn test_cli_timeout_overrides_scenario() {
     let scenario_yaml = r#"
 name: timeout_test_override
-description: "Test scenario timeout overrides CLI"
+description: "Test CLI timeout overrides scenario"
 template_folder: qipu
 target:
   binary: qipu
@@ -34,19 +35,26 @@ run:
     let template_dir = PathBuf::from("llm-test-fixtures/templates/qipu");
     std::fs::create_dir_all(&template_dir).unwrap();
 
-    let cli_timeout = 300;
+    let cli_timeout = Some(300);
     let result = run_single_scenario(
         &scenario,
         &fixture_file,
-        "mock",
-        "mock",
+        &"mock:mock".parse().unwrap(),
+        false,
         false,
         true,
         cli_timeout,
         false,
+        true,
         &base_dir,
         &results_db,
         &cache,
+        &[],
+        &RateLimiter::default(),
+        &RetryConfig::default(),
+        None,
+        false,
+        None,
     );
 
     let _ = std::fs::remove_file(&fixture_file);
@@ -89,19 +97,26 @@ evaluation:
     let template_dir = PathBuf::from("llm-test-fixtures/templates/qipu");
     std::fs::create_dir_all(&template_dir).unwrap();
 
-    let cli_timeout = 60;
+    let cli_timeout = Some(60);
     let result = run_single_scenario(
         &scenario,
         &fixture_file,
-        "mock",
-        "mock",
+        &"mock:mock".parse().unwrap(),
+        false,
         false,
         true,
         cli_timeout,
         false,
+        true,
         &base_dir,
         &results_db,
         &cache,
+        &[],
+        &RateLimiter::default(),
+        &RetryConfig::default(),
+        None,
+        false,
+        None,
     );
 
     let _ = std::fs::remove_file(&fixture_file);
@@ -113,3 +128,19 @@ evaluation:
         result
     );
 }
+
+#[test]
+fn adapter_timeouts_are_recorded_as_timeouts() {
+    use crate::results::Outcome;
+    use crate::run::records::error_outcome;
+
+    let err = crate::session::SessionRunner::new()
+        .run_command("sleep", &["5"], std::path::Path::new("."), 1)
+        .unwrap_err();
+    let err = err.context("Tool execution failed");
+    assert_eq!(error_outcome(&err), Outcome::Timeout);
+    assert_eq!(
+        error_outcome(&anyhow::anyhow!("adapter crashed")),
+        Outcome::Error
+    );
+}
@@ -0,0 +1,146 @@
+//! Resolution of the timeout applied to a tool run.
+//!
+//! The first tier that sets a value wins:
+//!
+//! 1. `--timeout-secs` on the command line
+//! 2. `run.timeout_secs` in the scenario
+//! 3. `timeout_secs` in the nearest `suite.yaml` (see [`crate::scenario::suite`])
+//! 4. `[timeouts] tiers` in the config, indexed by the scenario's `tier`
+//! 5. `[timeouts] default_secs` in the config
+//! 6. [`DEFAULT_TIMEOUT_SECS`]
+
+use crate::config::TimeoutConfig;
+use crate::scenario::Scenario;
+use serde::{Deserialize, Serialize};
+
+/// Timeout used when no tier sets one.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Which tier of the hierarchy supplied a run's timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutSource {
+    Cli,
+    Scenario,
+    Suite,
+    Tier,
+    Config,
+    Default,
+}
+
+impl std::fmt::Display for TimeoutSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TimeoutSource::Cli => "--timeout-secs",
+            TimeoutSource::Scenario => "scenario run.timeout_secs",
+            TimeoutSource::Suite => "suite.yaml",
+            TimeoutSource::Tier => "config tier default",
+            TimeoutSource::Config => "config default",
+            TimeoutSource::Default => "built-in default",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The timeout a run uses and where it came from, recorded in `run.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectiveTimeout {
+    pub secs: u64,
+    pub source: TimeoutSource,
+}
+
+/// Pick the run timeout for `s` from the first tier that sets one.
+pub fn resolve(
+    cli: Option<u64>,
+    s: &Scenario,
+    suite: Option<u64>,
+    config: &TimeoutConfig,
+) -> EffectiveTimeout {
+    let scenario = s.run.as_ref().and_then(|r| r.timeout_secs);
+    let tier = config.tiers.get(s.tier).copied();
+    [
+        (cli, TimeoutSource::Cli),
+        (scenario, TimeoutSource::Scenario),
+        (suite, TimeoutSource::Suite),
+        (tier, TimeoutSource::Tier),
+        (config.default_secs, TimeoutSource::Config),
+    ]
+    .into_iter()
+    .find_map(|(secs, source)| secs.map(|secs| EffectiveTimeout { secs, source }))
+    .unwrap_or(EffectiveTimeout {
+        secs: DEFAULT_TIMEOUT_SECS,
+        source: TimeoutSource::Default,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(tier: usize, timeout_secs: Option<u64>) -> Scenario {
+        let mut yaml = format!(
+            r#"
+name: timeouts
+description: "Timeout resolution"
+template_folder: qipu
+tier: {}
+target:
+  binary: qipu
+task:
+  prompt: "Create a note"
+evaluation:
+  gates: []
+"#,
+            tier
+        );
+        if let Some(secs) = timeout_secs {
+            yaml.push_str(&format!("run:\n  timeout_secs: {}\n", secs));
+        }
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    fn resolved(
+        cli: Option<u64>,
+        s: &Scenario,
+        suite: Option<u64>,
+        config: &TimeoutConfig,
+    ) -> (u64, TimeoutSource) {
+        let timeout = resolve(cli, s, suite, config);
+        (timeout.secs, timeout.source)
+    }
+
+    #[test]
+    fn test_resolve_follows_tier_order() {
+        let config = TimeoutConfig {
+            default_secs: Some(200),
+            tiers: vec![60, 900],
+        };
+        let with_scenario = scenario(1, Some(120));
+        let without_scenario = scenario(1, None);
+
+        assert_eq!(
+            resolved(Some(30), &with_scenario, Some(90), &config),
+            (30, TimeoutSource::Cli)
+        );
+        assert_eq!(
+            resolved(None, &with_scenario, Some(90), &config),
+            (120, TimeoutSource::Scenario)
+        );
+        assert_eq!(
+            resolved(None, &without_scenario, Some(90), &config),
+            (90, TimeoutSource::Suite)
+        );
+        assert_eq!(
+            resolved(None, &without_scenario, None, &config),
+            (900, TimeoutSource::Tier)
+        );
+        assert_eq!(
+            resolved(None, &scenario(2, None), None, &config),
+            (200, TimeoutSource::Config)
+        );
+        assert_eq!(
+            resolved(None, &without_scenario, None, &TimeoutConfig::default()),
+            (DEFAULT_TIMEOUT_SECS, TimeoutSource::Default)
+        );
+    }
+}
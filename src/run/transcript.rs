@@ -11,7 +11,7 @@ pub fn write_transcript_files(
     tool: &str,
     model: &str,
     cache_key: &CacheKey,
-    _output: &str,
+    output: &str,
     _exit_code: i32,
     cost: Option<f64>,
     token_usage: Option<crate::adapter::TokenUsage>,
@@ -21,11 +21,12 @@ pub fn write_transcript_files(
     setup_success: bool,
     setup_commands: Vec<(String, bool, String)>,
     _env: &TestEnv,
+    timeout: crate::run::timeout::EffectiveTimeout,
 ) -> anyhow::Result<()> {
     // Note: transcript.raw.txt and execution event are already written in run_evaluation_flow
 
     let run_metadata = RunMetadata {
-        scenario_id: s.name.clone(),
+        scenario_id: s.stable_id().to_string(),
         scenario_hash: cache_key.scenario_hash.clone(),
         tool: tool.to_string(),
         model: model.to_string(),
@@ -36,11 +37,13 @@ pub fn write_transcript_files(
             input: t.input,
             output: t.output,
         }),
+        estimated_tokens: Some(crate::transcript::tokens::estimate_transcript(output)),
+        timeout: Some(timeout),
     };
     writer.write_run_metadata(&run_metadata)?;
 
     let report = crate::transcript::RunReport {
-        scenario_id: s.name.clone(),
+        scenario_id: s.stable_id().to_string(),
         tool: tool.to_string(),
         model: model.to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
@@ -61,6 +64,8 @@ pub fn write_transcript_files(
                 gate_type: d.gate_type.clone(),
                 passed: d.passed,
                 message: d.message.clone(),
+                score: d.score,
+                warning: d.warning,
             })
             .collect(),
         efficiency: crate::transcript::types::EfficiencyReport {
@@ -69,6 +74,7 @@ pub fn write_transcript_files(
             error_count: metrics.efficiency.error_count,
             first_try_success_rate: metrics.efficiency.first_try_success_rate,
             iteration_ratio: metrics.efficiency.iteration_ratio,
+            wasted_actions: metrics.efficiency.wasted_actions,
         },
         setup_success,
         setup_commands: setup_commands
@@ -121,7 +127,7 @@ pub fn write_transcript_files(
         .collect();
 
     let evaluation = crate::transcript::EvaluationReport {
-        scenario_id: s.name.clone(),
+        scenario_id: s.stable_id().to_string(),
         tool: tool.to_string(),
         model: model.to_string(),
         outcome: outcome.to_string(),
@@ -131,10 +137,25 @@ pub fn write_transcript_files(
         duration_secs: duration.as_secs_f64(),
         cost_usd: cost,
         composite_score: metrics.composite_score,
+        score_breakdown: metrics.score_breakdown.clone(),
         judge_feedback,
+        judge_ensemble: metrics.judge_ensemble.clone(),
         evaluator_results,
     };
+    if let Some(breakdown) = &metrics.score_breakdown {
+        writer.write_score_breakdown(breakdown)?;
+    }
+    if let Some(response) = &metrics.judge_response {
+        writer.write_judge_response(response)?;
+    }
     writer.write_evaluation(&evaluation)?;
 
+    if let Some(threshold) = crate::config::Config::load_or_default()
+        .transcript
+        .compress_above_bytes
+    {
+        writer.compress_large_artifacts(threshold)?;
+    }
+
     Ok(())
 }
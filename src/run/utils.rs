@@ -41,3 +41,61 @@ pub fn get_results_dir(tool: &str, model: &str, scenario_name: &str) -> PathBuf
     let base_path = config.get_results_path();
     PathBuf::from(base_path).join(dir_name)
 }
+
+/// Create `dir`, or `dir-2`, `dir-3`, ... if it already exists, and return the
+/// created path.
+///
+/// Run directory names have second resolution, so parallel runs of matrix
+/// cells that differ only in `env` or `extra_args`, or of same-named
+/// scenarios, can ask for the same one. `create_dir` fails atomically on an
+/// existing directory, so no two runs ever share one.
+pub fn create_unique_dir(dir: &Path) -> std::io::Result<PathBuf> {
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut candidate = dir.to_path_buf();
+    for n in 2.. {
+        match fs::create_dir(&candidate) {
+            Ok(()) => return Ok(candidate),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let mut name = dir.file_name().unwrap_or_default().to_os_string();
+                name.push(format!("-{}", n));
+                candidate.set_file_name(name);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("ran out of directory suffixes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_runs_get_their_own_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("results").join("20250130-120000-mock-m-s");
+        let dirs: Vec<PathBuf> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| create_unique_dir(&dir).unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut names: Vec<String> = dirs
+            .iter()
+            .map(|d| d.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "20250130-120000-mock-m-s",
+                "20250130-120000-mock-m-s-2",
+                "20250130-120000-mock-m-s-3",
+                "20250130-120000-mock-m-s-4"
+            ]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Container sandbox for scenario runs.
+//!
+//! With `run.sandbox` enabled, the tool under test, setup commands, post
+//! scripts and command gates start inside `docker run` (or `podman run`)
+//! instead of directly on the host. [`Sandbox::wrap`] builds the engine
+//! command line; [`SandboxExecutor`] applies it to everything that goes
+//! through a [`CommandExecutor`], and
+//! [`crate::session::SessionRunner::with_sandbox`] applies it to the tool.
+//!
+//! Host directories of the run are mounted at their host paths, so the
+//! fixture path, `HOME` and the `LLM_TOOL_TEST_*` paths mean the same thing
+//! inside and outside the container.
+//!
+//! Killing the engine client does not stop its container, so every container
+//! gets a unique name and is removed with `<engine> rm -f` when a command is
+//! killed for its timeout, a stall or the turn limit.
+
+use crate::exec::{CommandExecutor, ExecOutput, SystemExecutor};
+use crate::scenario::{SandboxConfig, Scenario};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time allowed for removing a container after its command was killed.
+const REMOVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A scenario's sandbox, bound to the host directories of one run.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    config: SandboxConfig,
+    /// Host directories mounted read-write at the same path
+    dirs: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// The scenario's sandbox if it is enabled, sharing `dirs` with the container.
+    pub fn for_scenario(
+        scenario: &Scenario,
+        dirs: impl IntoIterator<Item = PathBuf>,
+    ) -> Option<Self> {
+        let config = scenario
+            .run
+            .as_ref()?
+            .sandbox
+            .as_ref()
+            .filter(|s| s.enabled)?;
+        Some(Self {
+            config: config.clone(),
+            dirs: dirs.into_iter().collect(),
+        })
+    }
+
+    /// Executable of the container engine.
+    pub fn engine(&self) -> &'static str {
+        self.config.engine.program()
+    }
+
+    /// Check that the container engine can be launched.
+    pub fn check_engine(&self) -> anyhow::Result<()> {
+        crate::adapter::command_version(self.engine())
+            .map(|_| ())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Container engine '{}' not found (needed by run.sandbox for image {})",
+                    self.engine(),
+                    self.config.image
+                )
+            })
+    }
+
+    /// A container name no other command of this or another run uses.
+    pub fn container_name() -> String {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        format!(
+            "llm-tool-test-{}-{}-{}",
+            std::process::id(),
+            millis,
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// Engine arguments that run `program args` in a container called `name`,
+    /// in `cwd`, with the variables named in `env` (and `pass_env`) taken from
+    /// the engine's own environment. `interactive` keeps stdin open.
+    pub fn wrap(
+        &self,
+        name: &str,
+        program: &str,
+        args: &[String],
+        cwd: &Path,
+        env: &[String],
+        interactive: bool,
+    ) -> Vec<String> {
+        let mut argv: Vec<String> = vec!["run".into(), "--rm".into(), "--name".into(), name.into()];
+        if interactive {
+            argv.push("-i".into());
+        }
+        if !self.config.network {
+            argv.extend(["--network".into(), "none".into()]);
+        }
+        if let Some(user) = self.user(cwd) {
+            argv.extend(["--user".into(), user]);
+        }
+        let mut dirs = self.dirs.clone();
+        if !dirs.iter().any(|dir| cwd.starts_with(dir)) {
+            dirs.push(cwd.to_path_buf());
+        }
+        for dir in dirs {
+            argv.extend(["-v".into(), format!("{0}:{0}", dir.display())]);
+        }
+        for mount in &self.config.mounts {
+            argv.extend(["-v".into(), resolve_mount(mount)]);
+        }
+        argv.extend(["-w".into(), cwd.display().to_string()]);
+        for key in env.iter().chain(&self.config.pass_env) {
+            argv.extend(["-e".into(), key.clone()]);
+        }
+        argv.push(self.config.image.clone());
+        argv.push(program.to_string());
+        argv.extend(args.iter().cloned());
+        argv
+    }
+
+    /// Stop and remove the container `name` through `executor`, after its
+    /// engine client was killed. Failures are only logged: the container may
+    /// already be gone.
+    pub fn remove(&self, name: &str, executor: &dyn CommandExecutor) {
+        let mut command = Command::new(self.engine());
+        command.args(["rm", "-f", name]);
+        match executor.run(command, None, Some(REMOVE_TIMEOUT)) {
+            Ok(out) if out.exit_code() == 0 => {}
+            Ok(out) => tracing::debug!(
+                "{} rm -f {} exited with {}: {}",
+                self.engine(),
+                name,
+                out.exit_code(),
+                String::from_utf8_lossy(&out.output.stderr).trim()
+            ),
+            Err(e) => tracing::debug!("{} rm -f {} failed: {}", self.engine(), name, e),
+        }
+    }
+
+    /// The configured user, or the owner of `cwd` so files the container
+    /// writes into the fixture stay removable on the host.
+    fn user(&self, cwd: &Path) -> Option<String> {
+        if self.config.user.is_some() {
+            return self.config.user.clone();
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let metadata = std::fs::metadata(cwd).ok()?;
+            Some(format!("{}:{}", metadata.uid(), metadata.gid()))
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+}
+
+/// `host:container[:mode]` with a relative host path resolved against the fixtures directory.
+fn resolve_mount(mount: &str) -> String {
+    match mount.split_once(':') {
+        Some((host, rest)) if !Path::new(host).is_absolute() => format!(
+            "{}:{}",
+            crate::utils::resolve_fixtures_path(host).display(),
+            rest
+        ),
+        _ => mount.to_string(),
+    }
+}
+
+/// Runs commands inside a [`Sandbox`] through another executor.
+#[derive(Debug)]
+pub struct SandboxExecutor {
+    sandbox: Sandbox,
+    inner: Arc<dyn CommandExecutor>,
+}
+
+impl SandboxExecutor {
+    pub fn new(sandbox: Sandbox, inner: Arc<dyn CommandExecutor>) -> Self {
+        Self { sandbox, inner }
+    }
+}
+
+fn lossy(s: &std::ffi::OsStr) -> String {
+    s.to_string_lossy().into_owned()
+}
+
+impl CommandExecutor for SandboxExecutor {
+    fn run(
+        &self,
+        command: Command,
+        stdin: Option<&[u8]>,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<ExecOutput> {
+        let cwd = match command.get_current_dir() {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+        let args: Vec<String> = command.get_args().map(lossy).collect();
+
+        // Values stay in the engine's environment; the container only gets the names
+        let mut wrapped = Command::new(self.sandbox.engine());
+        let mut env = Vec::new();
+        for (key, value) in command.get_envs() {
+            if let Some(value) = value {
+                wrapped.env(key, value);
+                env.push(lossy(key));
+            }
+        }
+        let name = Sandbox::container_name();
+        wrapped.args(self.sandbox.wrap(
+            &name,
+            &lossy(command.get_program()),
+            &args,
+            &cwd,
+            &env,
+            stdin.is_some(),
+        ));
+        let output = self.inner.run(wrapped, stdin, timeout);
+        if output.as_ref().map_or(true, |out| out.timed_out) {
+            self.sandbox.remove(&name, self.inner.as_ref());
+        }
+        output
+    }
+
+    fn runs_in_container(&self) -> bool {
+        true
+    }
+}
+
+/// Executor for the scenario's commands: sandboxed when `run.sandbox` is
+/// enabled, otherwise the host.
+pub fn executor_for(
+    scenario: &Scenario,
+    dirs: impl IntoIterator<Item = PathBuf>,
+) -> Arc<dyn CommandExecutor> {
+    match Sandbox::for_scenario(scenario, dirs) {
+        Some(sandbox) => Arc::new(SandboxExecutor::new(sandbox, Arc::new(SystemExecutor))),
+        None => Arc::new(SystemExecutor),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::fake::{FakeExecutor, FakeResponse};
+
+    fn scenario(sandbox: &str) -> Scenario {
+        serde_yaml::from_str(&format!(
+            "name: boxed\ndescription: d\ntemplate_folder: t\ntarget:\n  binary: qipu\ntask:\n  prompt: p\nevaluation:\n  gates: []\nrun:\n  sandbox:\n{}",
+            sandbox
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn wraps_commands_in_an_isolated_container() {
+        let s = scenario(
+            "    image: tools:1\n    engine: podman\n    user: \"1000:1000\"\n    mounts: [\"/opt/cache:/cache:ro\"]\n    pass_env: [ANTHROPIC_API_KEY]\n",
+        );
+        let run_dir = PathBuf::from("/tmp/run");
+        let sandbox = Sandbox::for_scenario(&s, [run_dir.clone()]).unwrap();
+        assert_eq!(sandbox.engine(), "podman");
+
+        let argv = sandbox.wrap(
+            "box-1",
+            "sh",
+            &["-c".to_string(), "qipu list".to_string()],
+            &run_dir.join("fixture"),
+            &["HOME".to_string()],
+            false,
+        );
+        assert_eq!(
+            argv.join(" "),
+            "run --rm --name box-1 --network none --user 1000:1000 -v /tmp/run:/tmp/run -v /opt/cache:/cache:ro \
+             -w /tmp/run/fixture -e HOME -e ANTHROPIC_API_KEY tools:1 sh -c qipu list"
+        );
+    }
+
+    #[test]
+    fn disabled_sandbox_runs_on_the_host() {
+        let s = scenario("    image: tools:1\n    enabled: false\n");
+        assert!(Sandbox::for_scenario(&s, []).is_none());
+    }
+
+    #[test]
+    fn executor_routes_commands_through_the_engine() {
+        let s = scenario("    image: tools:1\n    network: true\n    user: root\n");
+        let fake = Arc::new(FakeExecutor::new([FakeResponse::exit(0, "ok")]));
+        let executor = SandboxExecutor::new(Sandbox::for_scenario(&s, []).unwrap(), fake.clone());
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("true")
+            .current_dir("/work")
+            .env("TOKEN", "secret");
+        executor.run(cmd, Some(b"input"), None).unwrap();
+
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 1);
+        let call = &calls[0];
+        assert_eq!(call.program, "docker");
+        assert_eq!(call.args[2], "--name");
+        assert!(call.args[3].starts_with("llm-tool-test-"));
+        assert_eq!(
+            call.args[4..].join(" "),
+            "-i --user root -v /work:/work -w /work -e TOKEN tools:1 sh -c true"
+        );
+        assert_eq!(call.env, vec![("TOKEN".to_string(), "secret".to_string())]);
+        assert_eq!(call.stdin.as_deref(), Some("input"));
+    }
+
+    #[test]
+    fn timed_out_commands_remove_their_container() {
+        let s = scenario("    image: tools:1\n    user: root\n");
+        let fake = Arc::new(FakeExecutor::new([
+            FakeResponse::slow(Duration::from_secs(10)),
+            FakeResponse::exit(0, ""),
+        ]));
+        let executor = SandboxExecutor::new(Sandbox::for_scenario(&s, []).unwrap(), fake.clone());
+
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10").current_dir("/work");
+        let out = executor
+            .run(cmd, None, Some(Duration::from_secs(1)))
+            .unwrap();
+        assert!(out.timed_out);
+
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 2);
+        let name = &calls[0].args[3];
+        assert_eq!(calls[1].program, "docker");
+        assert_eq!(calls[1].args, vec!["rm", "-f", name.as_str()]);
+    }
+}
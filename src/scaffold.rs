@@ -0,0 +1,334 @@
+//! Scenario scaffolding (`new` command).
+//!
+//! Writes a scenario YAML, its template fixture folder and, for the `judge`
+//! template, a rubric stub into the fixtures directory, using the layout
+//! the harness reads at run time:
+//!
+//! ```text
+//! <fixtures>/<name>.yaml
+//! <fixtures>/templates/<name>/AGENTS.md
+//! <fixtures>/templates/<name>/README.md
+//! <fixtures>/rubrics/<name>.yaml          (judge only)
+//! ```
+//!
+//! The generated files pass `validate` as written; `TODO` markers show
+//! what the author still has to fill in.
+
+use crate::scenario::ToolConfig;
+use clap::ValueEnum;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Starting points for a new scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScaffoldTemplate {
+    /// Task, gates and a run timeout
+    Basic,
+    /// Basic plus an LLM-as-judge rubric
+    Judge,
+    /// Basic plus a tool/model matrix
+    Matrix,
+}
+
+/// Models used for the `matrix` template when the config names none.
+const FALLBACK_MATRIX: &[(&str, &str)] =
+    &[("claude-code", "claude-sonnet"), ("opencode", "gpt-4o")];
+
+/// Contents of the files making up a new scenario, keyed by path relative
+/// to the fixtures directory.
+pub fn scaffold_files(
+    name: &str,
+    template: ScaffoldTemplate,
+    binary: &str,
+    matrix: &[ToolConfig],
+) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    let valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_name {
+        anyhow::bail!(
+            "Invalid scenario name '{}': use letters, digits, '_' and '-'",
+            name
+        );
+    }
+    // The binary is written unquoted into YAML and shell commands
+    if binary.is_empty()
+        || !binary
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._/-".contains(c))
+    {
+        anyhow::bail!(
+            "Invalid binary '{}': use letters, digits, '.', '_', '/' and '-'",
+            binary
+        );
+    }
+
+    let rubric = format!("rubrics/{}.yaml", name);
+    let mut files = vec![(
+        PathBuf::from(format!("{}.yaml", name)),
+        scenario_yaml(name, template, binary, &rubric, matrix),
+    )];
+    let template_dir = Path::new("templates").join(name);
+    files.push((template_dir.join("AGENTS.md"), agents_md(binary)));
+    files.push((template_dir.join("README.md"), readme_md(name)));
+    if template == ScaffoldTemplate::Judge {
+        files.push((PathBuf::from(rubric), RUBRIC_YAML.to_string()));
+    }
+    Ok(files)
+}
+
+fn scenario_yaml(
+    name: &str,
+    template: ScaffoldTemplate,
+    binary: &str,
+    rubric: &str,
+    matrix: &[ToolConfig],
+) -> String {
+    let mut yaml = format!(
+        "\
+name: {name}
+description: \"TODO: what this scenario checks\"
+template_folder: {name}
+tags: []
+tier: 0
+
+target:
+  binary: {binary}
+  health_check: \"{binary} --version\"
+
+task:
+  prompt: |
+    TODO: describe the task. The agent starts in a copy of
+    templates/{name}/ and can read AGENTS.md to learn `{binary}`.
+
+evaluation:
+  gates:
+    # TODO: replace with checks of the state the task should leave behind
+    - type: command_succeeds
+      command: \"{binary} --version\"
+    - type: no_transcript_errors
+"
+    );
+    if template == ScaffoldTemplate::Judge {
+        let _ = write!(
+            yaml,
+            "  judge:\n    enabled: true\n    rubric: {}\n    pass_threshold: 0.7\n",
+            rubric
+        );
+    }
+    yaml.push_str("\nrun:\n  timeout_secs: 300\n");
+    if template == ScaffoldTemplate::Matrix {
+        yaml.push_str("\ntool_matrix:\n");
+        for entry in matrix {
+            let _ = writeln!(yaml, "  - tool: {}\n    models:", entry.tool);
+            for model in &entry.models {
+                let _ = writeln!(yaml, "      - {}", model);
+            }
+        }
+    }
+    yaml
+}
+
+fn agents_md(binary: &str) -> String {
+    format!(
+        "\
+# {binary}
+
+TODO: document `{binary}` the way agents should learn it. This file is
+what the scenario tests.
+
+## Commands
+
+- `{binary} --help`: list commands
+
+## Workflow
+
+TODO: the usual sequence of commands for common tasks.
+"
+    )
+}
+
+fn readme_md(name: &str) -> String {
+    format!(
+        "\
+# {name}
+
+TODO: project context the agent should read before starting the task.
+"
+    )
+}
+
+const RUBRIC_YAML: &str = "\
+# Criterion weights must sum to 1.0
+criteria:
+  - id: task_completion
+    weight: 0.6
+    description: \"TODO: the task was completed as the prompt asked\"
+  - id: tool_usage
+    weight: 0.4
+    description: \"TODO: the agent used the tool idiomatically, without needless retries\"
+output:
+  format: json
+  require_fields: [scores, weighted_score, confidence, issues, highlights]
+";
+
+/// Tool/model matrix for the `matrix` template: the configured default
+/// matrix, else every configured tool with its models, else a fallback.
+fn configured_matrix(config: &crate::config::Config) -> Vec<ToolConfig> {
+    let entry = |tool: &str, models: Vec<String>| ToolConfig {
+        tool: tool.to_string(),
+        models,
+        env: Default::default(),
+        extra_args: Vec::new(),
+    };
+    if !config.default_matrix.is_empty() {
+        return config.default_matrix.clone();
+    }
+    let mut tools: Vec<ToolConfig> = config
+        .tools
+        .iter()
+        .filter(|(_, tool)| !tool.models.is_empty())
+        .map(|(name, tool)| entry(name, tool.models.clone()))
+        .collect();
+    tools.sort_by(|a, b| a.tool.cmp(&b.tool));
+    if tools.is_empty() {
+        tools = FALLBACK_MATRIX
+            .iter()
+            .map(|(tool, model)| entry(tool, vec![model.to_string()]))
+            .collect();
+    }
+    tools
+}
+
+/// Write a new scenario into `dir` (the fixtures directory by default).
+pub fn handle_new_command(
+    config: &crate::config::Config,
+    name: &str,
+    template: ScaffoldTemplate,
+    binary: &str,
+    dir: Option<&Path>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let dir = dir.map_or_else(
+        || PathBuf::from(config.get_fixtures_path()),
+        Path::to_path_buf,
+    );
+    let files = scaffold_files(name, template, binary, &configured_matrix(config))?;
+
+    if !force {
+        let existing: Vec<String> = files
+            .iter()
+            .map(|(path, _)| dir.join(path))
+            .filter(|path| path.exists())
+            .map(|path| path.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            anyhow::bail!(
+                "Refusing to overwrite {}; pass --force to replace",
+                existing.join(", ")
+            );
+        }
+    }
+
+    for (path, content) in &files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)?;
+        println!("Created {}", path.display());
+    }
+
+    let scenario = dir.join(&files[0].0);
+    println!();
+    println!("Next steps:");
+    println!("  1. Fill in the TODOs in the files above");
+    println!("  2. llm-tool-test validate {}", scenario.display());
+    println!(
+        "  3. llm-tool-test run --scenario {} --dry-run --materialize",
+        scenario.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::validate::validate_scenario_source;
+
+    fn write_scaffold(dir: &Path, template: ScaffoldTemplate) -> PathBuf {
+        let matrix = configured_matrix(&crate::config::Config::default());
+        for (path, content) in scaffold_files("notes_basic", template, "qipu", &matrix).unwrap() {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir.join("notes_basic.yaml")
+    }
+
+    #[test]
+    fn every_template_passes_validation() {
+        for template in ScaffoldTemplate::value_variants() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = write_scaffold(dir.path(), *template);
+            let content = std::fs::read_to_string(&path).unwrap();
+
+            let diagnostics = validate_scenario_source(
+                "notes_basic.yaml",
+                &content,
+                &path,
+                Some(&dir.path().join("templates")),
+                Some(dir.path()),
+            );
+            assert!(diagnostics.is_empty(), "{:?}: {:?}", template, diagnostics);
+
+            let scenario = crate::scenario::load(&path).unwrap();
+            assert_eq!(scenario.template_folder, "notes_basic");
+            assert_eq!(
+                scenario.evaluation.judge.is_some(),
+                *template == ScaffoldTemplate::Judge
+            );
+            assert_eq!(
+                scenario.tool_matrix.is_some(),
+                *template == ScaffoldTemplate::Matrix
+            );
+        }
+    }
+
+    #[test]
+    fn matrix_comes_from_config() {
+        let mut config = crate::config::Config::default();
+        assert_eq!(configured_matrix(&config)[0].tool, "claude-code");
+
+        config.default_matrix = vec![ToolConfig {
+            tool: "aider".to_string(),
+            models: vec!["gpt-4o".to_string()],
+            env: Default::default(),
+            extra_args: Vec::new(),
+        }];
+        let files = scaffold_files(
+            "m",
+            ScaffoldTemplate::Matrix,
+            "qipu",
+            &configured_matrix(&config),
+        )
+        .unwrap();
+        assert!(files[0]
+            .1
+            .ends_with("tool_matrix:\n  - tool: aider\n    models:\n      - gpt-4o\n"));
+    }
+
+    #[test]
+    fn rejects_names_and_binaries_that_break_paths_or_yaml() {
+        let new =
+            |name: &str, binary: &str| scaffold_files(name, ScaffoldTemplate::Basic, binary, &[]);
+        assert!(new("notes-basic_2", "./bin/qipu").is_ok());
+        assert!(new("../escape", "qipu").is_err());
+        assert!(new("", "qipu").is_err());
+        assert!(new("notes", "qipu: {}").is_err());
+    }
+}
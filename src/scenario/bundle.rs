@@ -0,0 +1,165 @@
+//! Encrypted scenario bundles for private benchmarks.
+//!
+//! A bundle is a scenario YAML file encrypted with ChaCha20-Poly1305 and
+//! stored as `<name>.yaml.enc`, so prompts can be committed without being
+//! readable by crawlers. The key is a base64-encoded 32-byte secret read from
+//! [`KEY_ENV`] at run time; plaintext never needs to exist on disk.
+
+use anyhow::Context;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::path::{Path, PathBuf};
+
+/// Environment variable holding the base64-encoded bundle key.
+pub const KEY_ENV: &str = "LLM_TOOL_TEST_SCENARIO_KEY";
+
+/// Environment variable that, when set to `1`, keeps prompts of encrypted
+/// scenarios in transcripts and event logs instead of redacting them.
+pub const REVEAL_PROMPTS_ENV: &str = "LLM_TOOL_TEST_REVEAL_PROMPTS";
+
+/// Extension appended to the scenario file name (`capture.yaml.enc`).
+pub const EXTENSION: &str = "enc";
+
+/// First line of every bundle, identifying the format version.
+const HEADER: &str = "llm-tool-test-encrypted-v1";
+
+/// Length of the ChaCha20-Poly1305 nonce prepended to the ciphertext.
+const NONCE_LEN: usize = 12;
+
+/// Placeholder written in place of an encrypted scenario's prompt.
+pub const REDACTED_PROMPT: &str = "[ENCRYPTED PROMPT REDACTED]";
+
+/// Whether `content` is an encrypted bundle.
+pub fn is_bundle(content: &str) -> bool {
+    content.starts_with(HEADER)
+}
+
+/// Whether `path` names a bundle file (`*.yaml.enc`).
+pub fn is_bundle_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == EXTENSION)
+        && path
+            .file_stem()
+            .map(Path::new)
+            .and_then(Path::extension)
+            .is_some_and(|ext| ext == "yaml")
+}
+
+/// Path of the bundle written for a plaintext scenario file.
+pub fn bundle_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Generate a new random key, base64-encoded.
+pub fn generate_key() -> String {
+    STANDARD.encode(ChaCha20Poly1305::generate_key(&mut OsRng))
+}
+
+fn cipher(key: &str) -> anyhow::Result<ChaCha20Poly1305> {
+    let bytes = STANDARD
+        .decode(key.trim())
+        .context("Scenario key is not valid base64")?;
+    if bytes.len() != 32 {
+        anyhow::bail!(
+            "Scenario key must decode to 32 bytes, got {} (generate one with `llm-tool-test bundle keygen`)",
+            bytes.len()
+        );
+    }
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&bytes)))
+}
+
+/// Read the bundle key from [`KEY_ENV`].
+pub fn key_from_env() -> anyhow::Result<String> {
+    std::env::var(KEY_ENV).map_err(|_| {
+        anyhow::anyhow!(
+            "Encrypted scenario requires the {} environment variable to be set",
+            KEY_ENV
+        )
+    })
+}
+
+/// Encrypt scenario YAML into bundle text.
+pub fn encrypt(plaintext: &str, key: &str) -> anyhow::Result<String> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(key)?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt scenario"))?;
+    let mut payload = nonce.to_vec();
+    payload.extend(ciphertext);
+    Ok(format!("{}\n{}\n", HEADER, STANDARD.encode(payload)))
+}
+
+/// Decrypt bundle text back into scenario YAML.
+pub fn decrypt(content: &str, key: &str) -> anyhow::Result<String> {
+    let body = content
+        .strip_prefix(HEADER)
+        .ok_or_else(|| anyhow::anyhow!("Not an encrypted scenario bundle"))?;
+    let payload: String = body.split_whitespace().collect();
+    let payload = STANDARD
+        .decode(payload)
+        .context("Encrypted scenario is corrupt")?;
+    if payload.len() < NONCE_LEN {
+        anyhow::bail!("Encrypted scenario is truncated");
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let plaintext = cipher(key)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt scenario: wrong key or tampered file"))?;
+    String::from_utf8(plaintext).context("Decrypted scenario is not valid UTF-8")
+}
+
+/// Read a scenario file, decrypting it with the key from [`KEY_ENV`] if it is
+/// a bundle. Returns the YAML source and whether it was encrypted.
+pub fn read_source(path: &Path) -> anyhow::Result<(String, bool)> {
+    let content = std::fs::read_to_string(path)?;
+    if !is_bundle(&content) {
+        return Ok((content, false));
+    }
+    let plaintext = decrypt(&content, &key_from_env()?)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok((plaintext, true))
+}
+
+/// Whether prompts of encrypted scenarios should be kept in run artifacts.
+pub fn reveal_prompts() -> bool {
+    std::env::var(REVEAL_PROMPTS_ENV).is_ok_and(|v| v == "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = "name: secret\ntask:\n  prompt: \"Do the private thing\"\n";
+
+    #[test]
+    fn round_trips_with_the_same_key() {
+        let key = generate_key();
+        let bundle = encrypt(YAML, &key).unwrap();
+        assert!(is_bundle(&bundle));
+        assert!(!bundle.contains("private thing"));
+        assert_eq!(decrypt(&bundle, &key).unwrap(), YAML);
+    }
+
+    #[test]
+    fn rejects_wrong_key_and_bad_keys() {
+        let bundle = encrypt(YAML, &generate_key()).unwrap();
+        let err = decrypt(&bundle, &generate_key()).unwrap_err();
+        assert!(err.to_string().contains("wrong key"));
+        assert!(encrypt(YAML, "c2hvcnQ=").is_err());
+        assert!(decrypt("name: plain\n", &generate_key()).is_err());
+    }
+
+    #[test]
+    fn recognizes_bundle_paths() {
+        assert!(is_bundle_path(Path::new("fixtures/capture.yaml.enc")));
+        assert!(!is_bundle_path(Path::new("fixtures/capture.yaml")));
+        assert!(!is_bundle_path(Path::new("fixtures/notes.enc")));
+        assert_eq!(
+            bundle_path(Path::new("fixtures/capture.yaml")),
+            PathBuf::from("fixtures/capture.yaml.enc")
+        );
+    }
+}
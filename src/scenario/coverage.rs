@@ -0,0 +1,206 @@
+//! Coverage of the target tool's CLI surface by scenarios.
+//!
+//! Subcommands come from the tool's `--help` output or a manifest file; a
+//! subcommand counts as covered when a scenario's prompt or gate commands
+//! mention it as a whole word.
+
+use super::types::{Gate, Scenario};
+use anyhow::Context;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+/// Help-output headings that introduce a subcommand list.
+const SUBCOMMAND_HEADINGS: &[&str] = &["commands", "subcommands", "available commands"];
+
+/// A subcommand and the scenarios that exercise it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CoveredCommand {
+    pub name: String,
+    pub scenarios: Vec<String>,
+}
+
+/// Which subcommands are exercised by scenarios and which are not.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageReport {
+    pub binary: String,
+    pub covered: Vec<CoveredCommand>,
+    pub uncovered: Vec<String>,
+}
+
+/// Extract subcommand names from `--help` output.
+///
+/// Reads the indented lines following a `Commands:`-style heading up to the
+/// next blank line or heading. The built-in `help` subcommand is ignored.
+pub fn parse_help_subcommands(help: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut in_section = false;
+    for line in help.lines() {
+        let trimmed = line.trim();
+        let heading = trimmed.trim_end_matches(':').to_lowercase();
+        if !line.starts_with(char::is_whitespace) && !trimmed.is_empty() {
+            in_section = SUBCOMMAND_HEADINGS.contains(&heading.as_str());
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if trimmed.is_empty() {
+            in_section = false;
+            continue;
+        }
+        if let Some(name) = trimmed.split_whitespace().next() {
+            let name = name.trim_end_matches(',');
+            if name != "help" && !name.starts_with('-') && !commands.iter().any(|c| c == name) {
+                commands.push(name.to_string());
+            }
+        }
+    }
+    commands
+}
+
+/// Load subcommand names from a manifest: one per line, `#` starts a comment.
+pub fn load_manifest(path: &Path) -> anyhow::Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Text a scenario exposes for coverage matching: its prompt and gate commands.
+fn scenario_text(scenario: &Scenario) -> String {
+    let mut text = scenario.task.prompt.clone();
+    for entry in &scenario.evaluation.gates {
+        push_gate_commands(&entry.gate, &mut text);
+    }
+    text
+}
+
+/// Append the commands `gate` and its child gates run to `text`, one per line.
+fn push_gate_commands(gate: &Gate, text: &mut String) {
+    match gate {
+        Gate::CommandSucceeds { command }
+        | Gate::CommandOutputContains { command, .. }
+        | Gate::CommandOutputMatches { command, .. }
+        | Gate::CommandJsonPath { command, .. }
+        | Gate::Script { command, .. } => {
+            text.push('\n');
+            text.push_str(command);
+        }
+        _ => {}
+    }
+    for child in gate.children() {
+        push_gate_commands(child, text);
+    }
+}
+
+/// Compute coverage of `subcommands` by `scenarios`.
+pub fn compute_coverage(
+    binary: &str,
+    subcommands: &[String],
+    scenarios: &[Scenario],
+) -> anyhow::Result<CoverageReport> {
+    let texts: Vec<(&str, String)> = scenarios
+        .iter()
+        .map(|s| (s.name.as_str(), scenario_text(s)))
+        .collect();
+
+    let mut covered = Vec::new();
+    let mut uncovered = Vec::new();
+    for name in subcommands {
+        let re = Regex::new(&format!(r"(?i)(^|[^\w-]){}($|[^\w-])", regex::escape(name)))?;
+        let mut hits: Vec<String> = texts
+            .iter()
+            .filter(|(_, text)| re.is_match(text))
+            .map(|(scenario, _)| scenario.to_string())
+            .collect();
+        if hits.is_empty() {
+            uncovered.push(name.clone());
+        } else {
+            hits.sort();
+            covered.push(CoveredCommand {
+                name: name.clone(),
+                scenarios: hits,
+            });
+        }
+    }
+
+    Ok(CoverageReport {
+        binary: binary.to_string(),
+        covered,
+        uncovered,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELP: &str = "A note-taking tool
+
+Usage: qipu [OPTIONS] <COMMAND>
+
+Commands:
+  create  Create a new note
+  list    List notes
+  link    Link two notes
+  help    Print this message
+
+Options:
+  -h, --help  Print help
+";
+
+    fn scenario(name: &str, prompt: &str, command: &str) -> Scenario {
+        serde_yaml::from_str(&format!(
+            "name: {}\ndescription: d\ntemplate_folder: t\ntarget:\n  binary: qipu\ntask:\n  prompt: \"{}\"\nevaluation:\n  gates:\n    - type: command_succeeds\n      command: \"{}\"\n",
+            name, prompt, command
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_clap_style_help() {
+        assert_eq!(parse_help_subcommands(HELP), vec!["create", "list", "link"]);
+    }
+
+    #[test]
+    fn reports_covered_and_uncovered_subcommands() {
+        let subcommands = parse_help_subcommands(HELP);
+        let scenarios = vec![
+            scenario("capture", "Create a note about Rust", "qipu list"),
+            scenario(
+                "listing",
+                "Show what notes exist",
+                "qipu list --format json",
+            ),
+        ];
+        let report = compute_coverage("qipu", &subcommands, &scenarios).unwrap();
+
+        assert_eq!(
+            report.covered,
+            vec![
+                CoveredCommand {
+                    name: "create".to_string(),
+                    scenarios: vec!["capture".to_string()],
+                },
+                CoveredCommand {
+                    name: "list".to_string(),
+                    scenarios: vec!["capture".to_string(), "listing".to_string()],
+                },
+            ]
+        );
+        assert_eq!(report.uncovered, vec!["link"]);
+    }
+
+    #[test]
+    fn manifest_skips_comments_and_blank_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("commands.txt");
+        std::fs::write(&path, "# qipu commands\ncreate\n\nlink  # linking\n").unwrap();
+        assert_eq!(load_manifest(&path).unwrap(), vec!["create", "link"]);
+    }
+}
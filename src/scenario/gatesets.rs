@@ -0,0 +1,82 @@
+//! Reusable gate sets shared across scenarios.
+//!
+//! A `gatesets.yaml` file maps set names to lists of gates:
+//!
+//! ```yaml
+//! rust_builds:
+//!   - type: command_succeeds
+//!     command: cargo build
+//! git_clean:
+//!   - type: command_output_matches
+//!     command: git status --porcelain
+//!     pattern: "^$"
+//! ```
+//!
+//! Scenarios reference sets with `evaluation.use_gates`. The file is looked up
+//! in the scenario's directory and then each parent directory.
+
+use super::types::{GateEntry, Scenario};
+use anyhow::Context;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// File name searched for when resolving `use_gates`.
+pub const GATESETS_FILE: &str = "gatesets.yaml";
+
+/// Named gate groups loaded from a `gatesets.yaml` file.
+pub type GateSets = HashMap<String, Vec<GateEntry>>;
+
+/// Find the nearest `gatesets.yaml`, starting at `start_dir` and walking up.
+pub fn find_gatesets_file(start_dir: &Path) -> Option<PathBuf> {
+    start_dir
+        .ancestors()
+        .map(|dir| dir.join(GATESETS_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Load gate sets from a YAML file.
+pub fn load_gate_sets(path: &Path) -> anyhow::Result<GateSets> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read gate sets file {}", path.display()))?;
+    serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse gate sets file {}", path.display()))
+}
+
+/// Prepend gates from the sets named in `evaluation.use_gates`, in order.
+///
+/// Does nothing when the scenario references no gate sets.
+pub fn expand_gate_sets(scenario: &mut Scenario, scenario_path: &Path) -> anyhow::Result<()> {
+    if scenario.evaluation.use_gates.is_empty() {
+        return Ok(());
+    }
+
+    let start_dir = scenario_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let gatesets_path = find_gatesets_file(start_dir).with_context(|| {
+        format!(
+            "Scenario '{}' uses gate sets but no {} was found above {}",
+            scenario.name,
+            GATESETS_FILE,
+            start_dir.display()
+        )
+    })?;
+    let sets = load_gate_sets(&gatesets_path)?;
+
+    let mut gates = Vec::new();
+    for name in &scenario.evaluation.use_gates {
+        let set = sets.get(name).with_context(|| {
+            format!(
+                "Unknown gate set '{}' (not defined in {})",
+                name,
+                gatesets_path.display()
+            )
+        })?;
+        gates.extend(set.iter().cloned());
+    }
+    gates.append(&mut scenario.evaluation.gates);
+    scenario.evaluation.gates = gates;
+
+    Ok(())
+}
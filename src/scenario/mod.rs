@@ -12,13 +12,25 @@
 //! println!("Running scenario: {}", scenario.name);
 //! ```
 
+pub mod bundle;
+pub mod coverage;
+pub mod gatesets;
+pub mod strict;
+pub mod suite;
+pub mod taxonomy;
 pub mod types;
+pub mod validate;
 
 pub use types::*;
 
 use std::path::Path;
 
-/// Load a scenario from a YAML file.
+/// Whether `path` is a scenario file by name: `*.yaml` or an encrypted `*.yaml.enc`.
+pub fn is_scenario_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "yaml") || bundle::is_bundle_path(path)
+}
+
+/// Load a scenario from a YAML file or an encrypted bundle (`*.yaml.enc`).
 ///
 /// # Arguments
 ///
@@ -38,10 +50,34 @@ use std::path::Path;
 /// let scenario = scenario::load(Path::new("scenarios/basic_note.yaml")).unwrap();
 /// ```
 pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Scenario> {
-    let content = std::fs::read_to_string(path)?;
-    let scenario: Scenario = serde_yaml::from_str(&content)?;
+    let (content, encrypted) = bundle::read_source(path.as_ref())?;
+    // Checked first: a misspelled required key would otherwise only be
+    // reported as missing
+    check_unknown_fields(&content)?;
+    let mut scenario: Scenario = serde_yaml::from_str(&content)?;
+    scenario.encrypted = encrypted;
+    scenario.target.validate_command_pattern()?;
+    gatesets::expand_gate_sets(&mut scenario, path.as_ref())?;
+    scenario.validate_workdirs()?;
+    taxonomy::validate_taxonomy(&scenario, path.as_ref())?;
     Ok(scenario)
 }
 
+/// Fail if `content` sets keys the scenario format does not define.
+fn check_unknown_fields(content: &str) -> anyhow::Result<()> {
+    let unknown = strict::unknown_fields(content);
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let lines: Vec<String> = unknown
+        .iter()
+        .map(|field| format!("  line {}: {}", field.line, field))
+        .collect();
+    anyhow::bail!(
+        "Scenario sets unknown field(s); fix them or set `strict: false`:\n{}",
+        lines.join("\n")
+    )
+}
+
 #[cfg(test)]
 mod tests;
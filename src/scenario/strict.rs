@@ -0,0 +1,292 @@
+//! Detection of scenario keys the format does not define.
+//!
+//! Serde ignores unknown keys, so a typo such as `subsstring:` silently
+//! leaves the field at its default. `#[serde(deny_unknown_fields)]` cannot
+//! be used because gate entries flatten the gate into the entry, so the
+//! YAML is instead walked alongside the scenario JSON Schema, which lists
+//! every key the format accepts.
+
+use crate::schema::SchemaKind;
+use serde_json::Value as Json;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// A key set in a scenario that the scenario format does not define.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownField {
+    /// The key as written
+    pub key: String,
+    /// Path of the mapping holding the key, e.g. `evaluation.gates[0]`;
+    /// empty at the top level
+    pub parent: String,
+    /// Closest known key at that place, if one is near enough to be a typo
+    pub suggestion: Option<String>,
+    /// 1-based line and column of the key in the source, if found
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown field '{}'", self.key)?;
+        if !self.parent.is_empty() {
+            write!(f, " in {}", self.parent)?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean '{}'?)", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Keys in `content` that the scenario format does not define, in document
+/// order.
+///
+/// Yields nothing for a document that sets `strict: false`, and for content
+/// that is not valid YAML, whose errors deserialization reports. Mappings
+/// with an unknown gate `type` are skipped for the same reason.
+pub fn unknown_fields(content: &str) -> Vec<UnknownField> {
+    static SCHEMA: OnceLock<Json> = OnceLock::new();
+    let root = SCHEMA.get_or_init(|| {
+        serde_json::to_value(SchemaKind::Scenario.schema()).expect("schema serializes")
+    });
+    let Ok(value) = serde_yaml::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    if value.get("strict").and_then(Value::as_bool) == Some(false) {
+        return Vec::new();
+    }
+
+    let mut fields = Vec::new();
+    walk(root, root, &value, "", &mut fields);
+
+    // Keys are found in document order, so each is searched for after the last
+    let lines: Vec<&str> = content.lines().collect();
+    let mut from = 0;
+    for field in &mut fields {
+        if let Some(index) = (from..lines.len()).find(|&i| line_has_key(lines[i], &field.key)) {
+            let line = lines[index];
+            field.line = index + 1;
+            field.column = line.len() - line.trim_start_matches([' ', '-']).len() + 1;
+            from = index + 1;
+        }
+    }
+    fields
+}
+
+/// Whether `line` sets `key`, possibly as the first key of a list item.
+fn line_has_key(line: &str, key: &str) -> bool {
+    let rest = line.trim_start_matches([' ', '-']);
+    [
+        format!("{}:", key),
+        format!("\"{}\":", key),
+        format!("'{}':", key),
+    ]
+    .iter()
+    .any(|prefix| rest.starts_with(prefix.as_str()))
+}
+
+/// Follow a `$ref` to its definition.
+fn resolve<'a>(schema: &'a Json, root: &'a Json) -> &'a Json {
+    match schema.get("$ref").and_then(Json::as_str) {
+        Some(reference) => {
+            let name = reference.trim_start_matches("#/definitions/");
+            resolve(&root["definitions"][name], root)
+        }
+        None => schema,
+    }
+}
+
+/// Schemas combined into `schema` that apply to `value`. Of `oneOf` and
+/// `anyOf` branches, a tagged branch (`type: <name>`) applies only when the
+/// tag matches, and a `null` branch never applies to a present value.
+///
+/// Returns false if tagged branches exist but none matches the tag.
+fn applicable<'a>(
+    schema: &'a Json,
+    root: &'a Json,
+    value: &Value,
+    out: &mut Vec<&'a Json>,
+) -> bool {
+    let schema = resolve(schema, root);
+    out.push(schema);
+    let mut known = true;
+    for sub in schema["allOf"].as_array().into_iter().flatten() {
+        known &= applicable(sub, root, value, out);
+    }
+    let branches = schema["oneOf"]
+        .as_array()
+        .into_iter()
+        .chain(schema["anyOf"].as_array())
+        .flatten();
+    let tag = value.get("type").and_then(Value::as_str);
+    let (mut tagged, mut matched) = (false, false);
+    for branch in branches {
+        let resolved = resolve(branch, root);
+        if resolved["type"] == "null" {
+            continue;
+        }
+        if let (Some(tags), Some(tag)) = (resolved["properties"]["type"]["enum"].as_array(), tag) {
+            tagged = true;
+            if !tags.iter().any(|t| t == tag) {
+                continue;
+            }
+            matched = true;
+        }
+        known &= applicable(branch, root, value, out);
+    }
+    known && (matched || !tagged)
+}
+
+fn walk(schema: &Json, root: &Json, value: &Value, path: &str, out: &mut Vec<UnknownField>) {
+    let mut schemas = Vec::new();
+    if !applicable(schema, root, value, &mut schemas) {
+        return;
+    }
+
+    match value {
+        Value::Mapping(mapping) => {
+            let mut properties = BTreeMap::new();
+            let mut additional = None;
+            let mut closed = false;
+            for schema in &schemas {
+                if let Some(props) = schema["properties"].as_object() {
+                    closed = true;
+                    properties.extend(props.iter().map(|(k, v)| (k.as_str(), v)));
+                }
+                match &schema["additionalProperties"] {
+                    Json::Bool(false) => closed = true,
+                    Json::Null => {}
+                    other => additional = Some(other),
+                }
+            }
+            // Maps with free-form keys (`env`) and arbitrary values (`Value`)
+            if additional.is_some() || !closed {
+                for (key, child) in mapping {
+                    let key = key.as_str().unwrap_or_default();
+                    let child_path = join(path, key);
+                    let child_schema = properties.get(key).copied().or(additional);
+                    if let Some(child_schema) = child_schema {
+                        walk(child_schema, root, child, &child_path, out);
+                    }
+                }
+                return;
+            }
+            for (key, child) in mapping {
+                let Some(key) = key.as_str() else { continue };
+                match properties.get(key) {
+                    Some(child_schema) => walk(child_schema, root, child, &join(path, key), out),
+                    None => out.push(UnknownField {
+                        key: key.to_string(),
+                        parent: path.to_string(),
+                        suggestion: suggest(key, properties.keys().copied()),
+                        line: 1,
+                        column: 1,
+                    }),
+                }
+            }
+        }
+        Value::Sequence(items) => {
+            let Some(item_schema) = schemas.iter().find_map(|s| s.get("items")) else {
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                let item_path = format!("{}[{}]", path, index);
+                walk(item_schema, root, item, &item_path, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// The known key closest to `key`, within two single-character edits.
+fn suggest<'a>(key: &str, known: impl Iterator<Item = &'a str>) -> Option<String> {
+    known
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "name: test
+description: \"Test\"
+template_folder: fixture
+target:
+  binary: tool
+  env:
+    ANY_NAME: value
+task:
+  prompt: \"Test prompt\"
+";
+
+    #[test]
+    fn known_keys_pass() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_contains\n      id: notes\n      path: notes.md\n      substring: done\n    - type: all_of\n      gates:\n        - type: file_exists\n          path: a\n  judge:\n    enabled: false\n    rubric: r.yaml\n    pass_threshold: 0.5\nrun:\n  timeout_secs: 60\n",
+            HEADER
+        );
+        assert_eq!(unknown_fields(&yaml), Vec::new());
+    }
+
+    #[test]
+    fn typos_are_located_with_suggestions() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_contains\n      path: notes.md\n      subsstring: done\n    - type: any_of\n      gates:\n        - type: file_exists\n          pth: a\ntimeout: 5\n",
+            HEADER
+        );
+        let fields = unknown_fields(&yaml);
+        assert_eq!(fields.len(), 3, "{:?}", fields);
+
+        assert_eq!(fields[0].key, "subsstring");
+        assert_eq!(fields[0].parent, "evaluation.gates[0]");
+        assert_eq!((fields[0].line, fields[0].column), (14, 7));
+        assert_eq!(
+            fields[0].to_string(),
+            "Unknown field 'subsstring' in evaluation.gates[0] (did you mean 'substring'?)"
+        );
+
+        assert_eq!(fields[1].parent, "evaluation.gates[1].gates[0]");
+        assert_eq!(fields[1].suggestion.as_deref(), Some("path"));
+        assert_eq!(fields[1].line, 18);
+
+        // Keys far from every known key get no suggestion
+        assert_eq!(fields[2].to_string(), "Unknown field 'timeout'");
+        assert_eq!((fields[2].line, fields[2].column), (19, 1));
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("substring", "substring"), 0);
+        assert_eq!(edit_distance("subsstring", "substring"), 1);
+        assert_eq!(edit_distance("pth", "path"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -0,0 +1,56 @@
+//! Defaults shared by every scenario in a directory tree.
+//!
+//! A `suite.yaml` file sets values that scenarios below it inherit unless
+//! they set their own:
+//!
+//! ```yaml
+//! timeout_secs: 900
+//! ```
+//!
+//! The nearest file wins: it is looked up in the scenario's directory and
+//! then each parent directory, like `gatesets.yaml`.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File name searched for when resolving suite defaults.
+pub const SUITE_FILE: &str = "suite.yaml";
+
+/// Defaults loaded from a `suite.yaml` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuiteDefaults {
+    /// Run timeout for scenarios that do not set `run.timeout_secs`
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Find the nearest `suite.yaml`, starting at `start_dir` and walking up.
+pub fn find_suite_file(start_dir: &Path) -> Option<PathBuf> {
+    start_dir
+        .ancestors()
+        .map(|dir| dir.join(SUITE_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Load the suite defaults that apply to the scenario at `scenario_path`.
+///
+/// Returns empty defaults when no `suite.yaml` exists above the scenario.
+pub fn load_for_scenario(scenario_path: &Path) -> anyhow::Result<SuiteDefaults> {
+    let start_dir = scenario_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let Some(path) = find_suite_file(start_dir) else {
+        return Ok(SuiteDefaults::default());
+    };
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read suite file {}", path.display()))?;
+    // An empty file deserializes as null rather than an empty mapping
+    if content.trim().is_empty() {
+        return Ok(SuiteDefaults::default());
+    }
+    serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse suite file {}", path.display()))
+}
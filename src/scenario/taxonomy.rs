@@ -0,0 +1,143 @@
+//! Scenario taxonomy: allowed values for `category`, `difficulty` and `skills`.
+//!
+//! A `taxonomy.yaml` file lists the values scenarios may use:
+//!
+//! ```yaml
+//! categories: [navigation, editing, recovery]
+//! difficulties: [easy, medium, hard]
+//! skills: [git, refactoring, search]
+//! ```
+//!
+//! The file is looked up in the scenario's directory and then each parent
+//! directory. A dimension missing from the file is not restricted; without a
+//! taxonomy file any value is accepted.
+
+use super::types::Scenario;
+use anyhow::Context;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// File name searched for when validating scenario metadata.
+pub const TAXONOMY_FILE: &str = "taxonomy.yaml";
+
+/// Allowed metadata values loaded from a `taxonomy.yaml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Taxonomy {
+    /// Allowed `category` values
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// Allowed `difficulty` values
+    #[serde(default)]
+    pub difficulties: Option<Vec<String>>,
+    /// Allowed `skills` entries
+    #[serde(default)]
+    pub skills: Option<Vec<String>>,
+}
+
+impl Taxonomy {
+    /// Describe every metadata value of `scenario` that the taxonomy does not allow.
+    pub fn violations(&self, scenario: &Scenario) -> Vec<String> {
+        fn check(out: &mut Vec<String>, field: &str, value: &str, allowed: &Option<Vec<String>>) {
+            if let Some(allowed) = allowed {
+                if !allowed.iter().any(|a| a == value) {
+                    out.push(format!(
+                        "Unknown {} '{}' (allowed: {})",
+                        field,
+                        value,
+                        allowed.join(", ")
+                    ));
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        if let Some(category) = &scenario.category {
+            check(&mut out, "category", category, &self.categories);
+        }
+        if let Some(difficulty) = &scenario.difficulty {
+            check(&mut out, "difficulty", difficulty, &self.difficulties);
+        }
+        for skill in &scenario.skills {
+            check(&mut out, "skill", skill, &self.skills);
+        }
+        out
+    }
+}
+
+/// Find the nearest `taxonomy.yaml`, starting at `start_dir` and walking up.
+pub fn find_taxonomy_file(start_dir: &Path) -> Option<PathBuf> {
+    start_dir
+        .ancestors()
+        .map(|dir| dir.join(TAXONOMY_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Load a taxonomy from a YAML file.
+pub fn load_taxonomy(path: &Path) -> anyhow::Result<Taxonomy> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read taxonomy file {}", path.display()))?;
+    serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse taxonomy file {}", path.display()))
+}
+
+/// Check the scenario's metadata against the nearest `taxonomy.yaml`.
+///
+/// Does nothing when the scenario sets no metadata or no taxonomy file exists.
+pub fn validate_taxonomy(scenario: &Scenario, scenario_path: &Path) -> anyhow::Result<()> {
+    if scenario.category.is_none() && scenario.difficulty.is_none() && scenario.skills.is_empty() {
+        return Ok(());
+    }
+
+    let start_dir = scenario_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let Some(taxonomy_path) = find_taxonomy_file(start_dir) else {
+        return Ok(());
+    };
+    let violations = load_taxonomy(&taxonomy_path)?.violations(scenario);
+    if !violations.is_empty() {
+        anyhow::bail!(
+            "Scenario '{}' does not match {}: {}",
+            scenario.name,
+            taxonomy_path.display(),
+            violations.join("; ")
+        );
+    }
+    Ok(())
+}
+
+/// Command-line filter selecting scenarios by taxonomy metadata.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct TaxonomyFilter {
+    /// Only include scenarios in this category
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// Only include scenarios of this difficulty
+    #[arg(long)]
+    pub difficulty: Option<String>,
+
+    /// Only include scenarios exercising this skill (repeatable; all must match)
+    #[arg(long = "skill")]
+    pub skills: Vec<String>,
+}
+
+impl TaxonomyFilter {
+    /// Whether no taxonomy filter was given.
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.difficulty.is_none() && self.skills.is_empty()
+    }
+
+    /// Whether `scenario` carries all the requested metadata.
+    pub fn matches(&self, scenario: &Scenario) -> bool {
+        let category_match = self.category.is_none() || self.category == scenario.category;
+        let difficulty_match = self.difficulty.is_none() || self.difficulty == scenario.difficulty;
+        let skills_match = self
+            .skills
+            .iter()
+            .all(|skill| scenario.skills.contains(skill));
+        category_match && difficulty_match && skills_match
+    }
+}
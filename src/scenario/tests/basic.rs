@@ -146,6 +146,27 @@ tags: [capture, links, retrieval]
     assert_eq!(scenario.tags, vec!["capture", "links", "retrieval"]);
 }
 
+#[test]
+fn test_stable_id_defaults_to_name() {
+    let yaml = r#"
+name: capture_basic
+description: "Test"
+template_folder: qipu
+target:
+  binary: qipu
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates: []
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(scenario.stable_id(), "capture_basic");
+
+    let with_id: Scenario = serde_yaml::from_str(&format!("id: capture-001\n{}", yaml)).unwrap();
+    assert_eq!(with_id.id.as_deref(), Some("capture-001"));
+    assert_eq!(with_id.stable_id(), "capture-001");
+}
+
 #[test]
 fn test_complete_scenario() {
     let yaml = r#"
@@ -202,3 +223,129 @@ setup:
     // Evaluation
     assert_eq!(scenario.evaluation.gates.len(), 2);
 }
+
+#[test]
+fn test_judge_is_one_config_or_a_list() {
+    let header = r#"name: test
+description: "Test"
+template_folder: qipu
+target:
+  binary: qipu
+task:
+  prompt: "Test prompt"
+evaluation:
+"#;
+    let single: Scenario = serde_yaml::from_str(&format!(
+        "{}  judge:\n    enabled: true\n    rubric: r.yaml\n    pass_threshold: 0.7\n",
+        header
+    ))
+    .unwrap();
+    let Some(JudgeSpec::Single(judge)) = &single.evaluation.judge else {
+        panic!("Expected a single judge");
+    };
+    assert_eq!(judge.model, None);
+    assert_eq!(single.evaluation.judge_aggregate, None);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scenario.yaml");
+    std::fs::write(
+        &path,
+        format!(
+            "{}  judge:\n    - enabled: true\n      rubric: r.yaml\n      pass_threshold: 0.7\n      model: gpt-4o\n    - enabled: true\n      rubric: r.yaml\n      pass_threshold: 0.6\n      blocking: false\n  judge_aggregate: median\n",
+            header
+        ),
+    )
+    .unwrap();
+    let ensemble = load(&path).unwrap();
+    let spec = ensemble.evaluation.judge.as_ref().unwrap();
+    assert!(matches!(spec, JudgeSpec::Ensemble(_)));
+    assert_eq!(spec.judges().len(), 2);
+    assert_eq!(spec.judges()[0].model.as_deref(), Some("gpt-4o"));
+    assert!(spec.blocking());
+    assert_eq!(
+        ensemble.evaluation.judge_aggregate,
+        Some(JudgeAggregate::Median)
+    );
+
+    // Keys of list entries are checked like those of a single judge
+    std::fs::write(
+        &path,
+        format!(
+            "{}  judge:\n    - enabled: true\n      rubric: r.yaml\n      pass_threshold: 0.7\n      modle: gpt-4o\n",
+            header
+        ),
+    )
+    .unwrap();
+    let err = load(&path).unwrap_err().to_string();
+    assert!(
+        err.contains("Unknown field 'modle' in evaluation.judge[0] (did you mean 'model'?)"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_load_rejects_invalid_command_pattern() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scenario.yaml");
+    std::fs::write(
+        &path,
+        r#"
+name: test
+description: "Test"
+template_folder: qipu
+target:
+  binary: qipu
+  command_pattern: "^(qipu"
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: command_succeeds
+      command: "true"
+"#,
+    )
+    .unwrap();
+    let err = load(&path).unwrap_err().to_string();
+    assert!(err.contains("Invalid target.command_pattern"), "{}", err);
+}
+
+#[test]
+fn test_load_rejects_unknown_fields_unless_lenient() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scenario.yaml");
+    let yaml = r#"name: test
+description: "Test"
+template_folder: qipu
+target:
+  binary: qipu
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: file_contains
+      path: notes.md
+      subsstring: done
+"#;
+    std::fs::write(&path, yaml).unwrap();
+    let err = load(&path).unwrap_err().to_string();
+    assert!(
+        err.contains(
+            "line 12: Unknown field 'subsstring' in evaluation.gates[0] (did you mean 'substring'?)"
+        ),
+        "{}",
+        err
+    );
+    assert!(err.contains("strict: false"), "{}", err);
+
+    // Extra keys load once strict checking is off
+    let extra = yaml.replace(
+        "subsstring: done",
+        "substring: done\n      note: from a newer version",
+    );
+    std::fs::write(&path, &extra).unwrap();
+    assert!(load(&path).is_err());
+    std::fs::write(&path, format!("strict: false\n{}", extra)).unwrap();
+    let scenario = load(&path).unwrap();
+    assert!(!scenario.strict);
+}
@@ -17,7 +17,7 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::CommandSucceeds { command } => assert_eq!(command, "true"),
         _ => panic!("Expected CommandSucceeds gate"),
     }
@@ -41,7 +41,7 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::CommandOutputContains { command, substring } => {
             assert_eq!(command, "printf hello");
             assert_eq!(substring, "hell");
@@ -68,7 +68,7 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::CommandOutputMatches { command, pattern } => {
             assert_eq!(command, "printf hello");
             assert_eq!(pattern, "^hello$");
@@ -96,7 +96,7 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::CommandJsonPath {
             command,
             path,
@@ -110,6 +110,207 @@ evaluation:
     }
 }
 
+#[test]
+fn test_file_json_path_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: file_json_path
+      path: "out/report.json"
+      json_path: "$.tasks"
+      assertion: "len >= 1"
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::FileJsonPath {
+            path,
+            json_path,
+            assertion,
+        } => {
+            assert_eq!(path, "out/report.json");
+            assert_eq!(json_path, "$.tasks");
+            assert_eq!(assertion, "len >= 1");
+        }
+        _ => panic!("Expected FileJsonPath gate"),
+    }
+}
+
+#[test]
+fn test_script_env_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: script_env
+      path: setup.sh
+      env:
+        DATABASE_URL: postgres://localhost/app
+      unset: [APP_SECRET]
+    - type: script_env
+      path: bin/env.sh
+      mode: eval
+      matches:
+        APP_PORT: "^[0-9]+$"
+      shell: sh
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::ScriptEnv {
+            mode,
+            env,
+            matches,
+            unset,
+            shell,
+            ..
+        } => {
+            assert_eq!(*mode, ScriptEnvMode::Source);
+            assert_eq!(env["DATABASE_URL"], "postgres://localhost/app");
+            assert!(matches.is_empty());
+            assert_eq!(unset, &["APP_SECRET"]);
+            assert_eq!(*shell, None);
+        }
+        _ => panic!("Expected ScriptEnv gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::ScriptEnv {
+            mode,
+            matches,
+            shell,
+            ..
+        } => {
+            assert_eq!(*mode, ScriptEnvMode::Eval);
+            assert_eq!(matches["APP_PORT"], "^[0-9]+$");
+            assert_eq!(shell.as_deref(), Some("sh"));
+        }
+        _ => panic!("Expected ScriptEnv gate"),
+    }
+}
+
+#[test]
+fn test_csv_gates() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: csv_row_count
+      path: "out/report.csv"
+      min: 10
+    - type: csv_cell_equals
+      path: "out/report.tsv"
+      row: 0
+      column: total
+      value: "42"
+    - type: csv_cell_equals
+      path: "out/raw.csv"
+      row: 3
+      column: 1
+      value: "x"
+      delimiter: ";"
+      header: false
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::CsvRowCount {
+            path,
+            min,
+            max,
+            delimiter,
+            header,
+        } => {
+            assert_eq!(path, "out/report.csv");
+            assert_eq!(*min, Some(10));
+            assert_eq!(*max, None);
+            assert_eq!(*delimiter, None);
+            assert!(*header);
+        }
+        _ => panic!("Expected CsvRowCount gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::CsvCellEquals { column, value, .. } => {
+            assert_eq!(column, &CsvColumn::Name("total".to_string()));
+            assert_eq!(value, "42");
+        }
+        _ => panic!("Expected CsvCellEquals gate"),
+    }
+    match &scenario.evaluation.gates[2].gate {
+        Gate::CsvCellEquals {
+            row,
+            column,
+            delimiter,
+            header,
+            ..
+        } => {
+            assert_eq!(*row, 3);
+            assert_eq!(column, &CsvColumn::Index(1));
+            assert_eq!(*delimiter, Some(';'));
+            assert!(!*header);
+        }
+        _ => panic!("Expected CsvCellEquals gate"),
+    }
+}
+
+#[test]
+fn test_json_equals_file_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: json_equals_file
+      path: "config.json"
+      expected_file: "expected/config.json"
+      ignore: ["$.generated_at", "$.plugins[*].version"]
+    - type: json_equals_file
+      path: "manifest.json"
+      expected_file: "expected/manifest.json"
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::JsonEqualsFile {
+            path,
+            expected_file,
+            ignore,
+        } => {
+            assert_eq!(path, "config.json");
+            assert_eq!(expected_file, "expected/config.json");
+            assert_eq!(ignore, &["$.generated_at", "$.plugins[*].version"]);
+        }
+        _ => panic!("Expected JsonEqualsFile gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::JsonEqualsFile { ignore, .. } => assert!(ignore.is_empty()),
+        _ => panic!("Expected JsonEqualsFile gate"),
+    }
+}
+
 #[test]
 fn test_file_gates() {
     let yaml = r#"
@@ -133,12 +334,12 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::FileExists { path } => assert_eq!(path, "README.md"),
         _ => panic!("Expected FileExists gate"),
     }
 
-    match &scenario.evaluation.gates[1] {
+    match &scenario.evaluation.gates[1].gate {
         Gate::FileContains { path, substring } => {
             assert_eq!(path, "README.md");
             assert_eq!(substring, "hello");
@@ -146,7 +347,7 @@ evaluation:
         _ => panic!("Expected FileContains gate"),
     }
 
-    match &scenario.evaluation.gates[2] {
+    match &scenario.evaluation.gates[2].gate {
         Gate::FileMatches { path, pattern } => {
             assert_eq!(path, "README.md");
             assert_eq!(pattern, "hello.*world");
@@ -173,7 +374,7 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::Script {
             command,
             description,
@@ -201,8 +402,632 @@ evaluation:
 "#;
     let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
 
-    match &scenario.evaluation.gates[0] {
+    match &scenario.evaluation.gates[0].gate {
         Gate::NoTranscriptErrors => {}
         _ => panic!("Expected NoTranscriptErrors gate"),
     }
 }
+
+#[test]
+fn test_no_env_leak_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: no_env_leak
+    - type: no_env_leak
+      ignore: [vendor/]
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::NoEnvLeak { ignore } => assert!(ignore.is_empty()),
+        _ => panic!("Expected NoEnvLeak gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::NoEnvLeak { ignore } => assert_eq!(ignore, &vec!["vendor/".to_string()]),
+        _ => panic!("Expected NoEnvLeak gate"),
+    }
+}
+
+#[test]
+fn test_binary_artifact_gates() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: file_size
+      path: out.bin
+      min: 10
+      max: 2048
+    - type: file_sha256
+      path: out.bin
+      equals: "abc123"
+    - type: image_dimensions
+      path: logo.png
+      width: 64
+      height: 32
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::FileSize { path, min, max } => {
+            assert_eq!(path, "out.bin");
+            assert_eq!(*min, Some(10));
+            assert_eq!(*max, Some(2048));
+        }
+        _ => panic!("Expected FileSize gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::FileSha256 { path, equals } => {
+            assert_eq!(path, "out.bin");
+            assert_eq!(equals, "abc123");
+        }
+        _ => panic!("Expected FileSha256 gate"),
+    }
+    match &scenario.evaluation.gates[2].gate {
+        Gate::ImageDimensions {
+            path,
+            width,
+            height,
+        } => {
+            assert_eq!(path, "logo.png");
+            assert_eq!(*width, Some(64));
+            assert_eq!(*height, Some(32));
+        }
+        _ => panic!("Expected ImageDimensions gate"),
+    }
+}
+
+#[test]
+fn test_archive_contains_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: archive_contains
+      path: dist/release.tar.gz
+      entry: release/VERSION
+      contains: "1.0.0"
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::ArchiveContains {
+            path,
+            entry,
+            contains,
+        } => {
+            assert_eq!(path, "dist/release.tar.gz");
+            assert_eq!(entry, "release/VERSION");
+            assert_eq!(contains.as_deref(), Some("1.0.0"));
+        }
+        _ => panic!("Expected ArchiveContains gate"),
+    }
+}
+
+#[test]
+fn test_process_and_port_gates() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: process_running
+      pattern: "my-server --port 8080"
+    - type: port_open
+      port: 8080
+    - type: port_open
+      port: 5432
+      host: localhost
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::ProcessRunning { pattern } => assert_eq!(pattern, "my-server --port 8080"),
+        _ => panic!("Expected ProcessRunning gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::PortOpen { port, host } => {
+            assert_eq!(*port, 8080);
+            assert!(host.is_none());
+        }
+        _ => panic!("Expected PortOpen gate"),
+    }
+    match &scenario.evaluation.gates[2].gate {
+        Gate::PortOpen { port, host } => {
+            assert_eq!(*port, 5432);
+            assert_eq!(host.as_deref(), Some("localhost"));
+        }
+        _ => panic!("Expected PortOpen gate"),
+    }
+}
+
+#[test]
+fn test_transcript_milestones_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: transcript_milestones
+      milestones: ["tool init", "tool create .*", "tool list"]
+      min_fraction: 0.66
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::TranscriptMilestones {
+            milestones,
+            min_fraction,
+        } => {
+            assert_eq!(milestones.len(), 3);
+            assert_eq!(milestones[1], "tool create .*");
+            assert_eq!(*min_fraction, Some(0.66));
+        }
+        _ => panic!("Expected TranscriptMilestones gate"),
+    }
+}
+
+#[test]
+fn test_gate_id_and_when_conditions() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: command_succeeds
+      id: build
+      command: cargo build
+    - type: command_output_contains
+      when: build
+      command: cargo test
+      substring: "test result: ok"
+    - type: no_transcript_errors
+      when: [build, tests]
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    let gates = &scenario.evaluation.gates;
+
+    assert_eq!(gates[0].id.as_deref(), Some("build"));
+    assert!(gates[0].when.is_none());
+    assert!(matches!(gates[0].gate, Gate::CommandSucceeds { .. }));
+
+    match &gates[1].when {
+        Some(GateCondition::Passed(id)) => assert_eq!(id, "build"),
+        other => panic!("Expected single-id condition, got {:?}", other),
+    }
+    match &gates[2].when {
+        Some(condition) => assert_eq!(condition.required_ids(), vec!["build", "tests"]),
+        None => panic!("Expected condition"),
+    }
+    assert!(matches!(gates[2].gate, Gate::NoTranscriptErrors));
+}
+
+#[test]
+fn test_soft_score_gates() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: files_exist
+      paths: [src/main.rs, src/lib.rs]
+      min_score: 0.5
+    - type: file_matches_all
+      path: README.md
+      patterns: ["^Title", "Usage"]
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::FilesExist { paths, min_score } => {
+            assert_eq!(paths.len(), 2);
+            assert_eq!(*min_score, Some(0.5));
+        }
+        _ => panic!("Expected FilesExist gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::FileMatchesAll {
+            path,
+            patterns,
+            min_score,
+        } => {
+            assert_eq!(path, "README.md");
+            assert_eq!(patterns.len(), 2);
+            assert!(min_score.is_none());
+        }
+        _ => panic!("Expected FileMatchesAll gate"),
+    }
+}
+
+#[test]
+fn test_wasm_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: wasm
+      module: checks.wasm
+      export: check_readme
+      timeout_secs: 5
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::Wasm {
+            module,
+            export,
+            timeout_secs,
+        } => {
+            assert_eq!(module, "checks.wasm");
+            assert_eq!(export, "check_readme");
+            assert_eq!(*timeout_secs, Some(5));
+        }
+        _ => panic!("Expected Wasm gate"),
+    }
+}
+
+#[test]
+fn test_gate_workdir_overrides() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  workdir: build
+  gates:
+    - type: file_exists
+      path: app.bin
+    - type: file_exists
+      workdir: ../artifacts
+      path: report.json
+"#;
+    let mut scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(scenario.evaluation.workdir.as_deref(), Some("build"));
+    assert_eq!(scenario.evaluation.gates[0].workdir, None);
+    assert_eq!(
+        scenario.evaluation.gates[1].workdir.as_deref(),
+        Some("../artifacts")
+    );
+    assert!(scenario.validate_workdirs().is_ok());
+
+    for workdir in ["/tmp", "../../outside", "a/../../.."] {
+        scenario.evaluation.gates[1].workdir = Some(workdir.to_string());
+        assert!(
+            scenario.validate_workdirs().is_err(),
+            "workdir {} should be rejected",
+            workdir
+        );
+    }
+}
+
+#[test]
+fn test_combinator_gates() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: any_of
+      gates:
+        - type: file_exists
+          path: README.md
+        - type: file_exists
+          path: README.rst
+    - type: not
+      gates:
+        - type: all_of
+          gates:
+            - type: file_exists
+              path: debug.log
+            - type: command_succeeds
+              command: "grep -q panic debug.log"
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::AnyOf { gates } => {
+            assert_eq!(gates.len(), 2);
+            assert!(matches!(&gates[1], Gate::FileExists { path } if path == "README.rst"));
+        }
+        _ => panic!("Expected AnyOf gate"),
+    }
+    let not = &scenario.evaluation.gates[1].gate;
+    assert!(matches!(not, Gate::Not { .. }));
+    match &not.children()[0] {
+        Gate::AllOf { gates } => assert_eq!(gates.len(), 2),
+        _ => panic!("Expected AllOf gate"),
+    }
+}
+
+#[test]
+fn test_gate_severity() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: file_exists
+      path: README.md
+    - type: file_exists
+      path: CHANGELOG.md
+      severity: warning
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(scenario.evaluation.gates[0].severity, GateSeverity::Error);
+    assert_eq!(scenario.evaluation.gates[1].severity, GateSeverity::Warning);
+
+    // The default is left out when serializing
+    let yaml = serde_yaml::to_string(&scenario.evaluation.gates).unwrap();
+    assert_eq!(yaml.matches("severity").count(), 1);
+}
+
+#[test]
+fn test_file_section_contains_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: file_section_contains
+      path: AGENTS.md
+      heading: Conventions
+      substring: "make lint"
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::FileSectionContains {
+            path,
+            heading,
+            substring,
+        } => {
+            assert_eq!(path, "AGENTS.md");
+            assert_eq!(heading, "Conventions");
+            assert_eq!(substring, "make lint");
+        }
+        _ => panic!("Expected FileSectionContains gate"),
+    }
+}
+
+#[test]
+fn test_toolchain_gates() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: rust_builds
+    - type: python_imports
+      module: app.models
+      timeout_secs: 120
+    - type: node_script_runs
+      path: scripts/check.js
+      args: ["--strict"]
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    let gates: Vec<_> = scenario.evaluation.gates.iter().map(|e| &e.gate).collect();
+
+    assert!(matches!(
+        gates[0],
+        Gate::RustBuilds {
+            path: None,
+            timeout_secs: None
+        }
+    ));
+    match gates[1] {
+        Gate::PythonImports {
+            module,
+            timeout_secs,
+        } => {
+            assert_eq!(module, "app.models");
+            assert_eq!(*timeout_secs, Some(120));
+        }
+        _ => panic!("Expected PythonImports gate"),
+    }
+    match gates[2] {
+        Gate::NodeScriptRuns { path, args, .. } => {
+            assert_eq!(path, "scripts/check.js");
+            assert_eq!(args, &["--strict"]);
+        }
+        _ => panic!("Expected NodeScriptRuns gate"),
+    }
+}
+
+#[test]
+fn test_tests_pass_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: tests_pass
+      command: pytest -q
+      framework: pytest
+      min_passed: 12
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    match &scenario.evaluation.gates[0].gate {
+        Gate::TestsPass {
+            command,
+            framework,
+            min_passed,
+            timeout_secs,
+        } => {
+            assert_eq!(command, "pytest -q");
+            assert_eq!(*framework, TestFramework::Pytest);
+            assert_eq!(*min_passed, Some(12));
+            assert_eq!(*timeout_secs, None);
+        }
+        _ => panic!("Expected TestsPass gate"),
+    }
+
+    let unknown = yaml.replace("framework: pytest", "framework: mocha");
+    assert!(serde_yaml::from_str::<Scenario>(&unknown).is_err());
+}
+
+#[test]
+fn test_lint_clean_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: lint_clean
+      command: npx eslint .
+      max_warnings: 2
+    - type: lint_clean
+      command: ruff check .
+      pattern: '^\S+:\d+:\d+: '
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    match &scenario.evaluation.gates[0].gate {
+        Gate::LintClean {
+            command,
+            max_warnings,
+            pattern,
+            ..
+        } => {
+            assert_eq!(command, "npx eslint .");
+            assert_eq!(*max_warnings, Some(2));
+            assert_eq!(*pattern, None);
+        }
+        _ => panic!("Expected LintClean gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::LintClean { pattern, .. } => {
+            assert_eq!(pattern.as_deref(), Some(r"^\S+:\d+:\d+: "));
+        }
+        _ => panic!("Expected LintClean gate"),
+    }
+}
+
+#[test]
+fn test_markdown_structure_gate() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: markdown_structure
+      path: docs/guide.md
+      headings: [Overview, '## Usage']
+      min_sections: 3
+      code_blocks: [bash]
+    - type: markdown_structure
+      path: README.md
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+
+    match &scenario.evaluation.gates[0].gate {
+        Gate::MarkdownStructure {
+            path,
+            headings,
+            min_sections,
+            code_blocks,
+        } => {
+            assert_eq!(path, "docs/guide.md");
+            assert_eq!(headings, &["Overview", "## Usage"]);
+            assert_eq!(*min_sections, Some(3));
+            assert_eq!(code_blocks, &["bash"]);
+        }
+        _ => panic!("Expected MarkdownStructure gate"),
+    }
+    match &scenario.evaluation.gates[1].gate {
+        Gate::MarkdownStructure {
+            headings,
+            min_sections,
+            code_blocks,
+            ..
+        } => {
+            assert!(headings.is_empty());
+            assert!(min_sections.is_none());
+            assert!(code_blocks.is_empty());
+        }
+        _ => panic!("Expected MarkdownStructure gate"),
+    }
+}
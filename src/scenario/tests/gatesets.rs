@@ -0,0 +1,67 @@
+use super::super::*;
+use std::fs;
+
+const SCENARIO_WITH_GATE_SETS: &str = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  use_gates: [builds, git_clean]
+  gates:
+    - type: file_exists
+      path: README.md
+"#;
+
+const GATE_SETS: &str = r#"
+builds:
+  - type: command_succeeds
+    command: cargo build
+git_clean:
+  - type: command_output_matches
+    command: git status --porcelain
+    pattern: "^$"
+"#;
+
+#[test]
+fn test_load_expands_gate_sets_from_parent_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let scenario_dir = dir.path().join("scenarios/nested");
+    fs::create_dir_all(&scenario_dir).unwrap();
+    fs::write(dir.path().join("scenarios/gatesets.yaml"), GATE_SETS).unwrap();
+    let scenario_path = scenario_dir.join("scenario.yaml");
+    fs::write(&scenario_path, SCENARIO_WITH_GATE_SETS).unwrap();
+
+    let scenario = load(&scenario_path).unwrap();
+    let gates = &scenario.evaluation.gates;
+    assert_eq!(gates.len(), 3);
+    assert!(
+        matches!(&gates[0].gate, Gate::CommandSucceeds { command } if command == "cargo build")
+    );
+    assert!(matches!(&gates[1].gate, Gate::CommandOutputMatches { .. }));
+    assert!(matches!(&gates[2].gate, Gate::FileExists { path } if path == "README.md"));
+}
+
+#[test]
+fn test_load_fails_for_unknown_gate_set() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("gatesets.yaml"), "builds: []\n").unwrap();
+    let scenario_path = dir.path().join("scenario.yaml");
+    fs::write(&scenario_path, SCENARIO_WITH_GATE_SETS).unwrap();
+
+    let err = load(&scenario_path).unwrap_err();
+    assert!(format!("{:#}", err).contains("Unknown gate set 'git_clean'"));
+}
+
+#[test]
+fn test_load_fails_without_gate_sets_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let scenario_path = dir.path().join("scenario.yaml");
+    fs::write(&scenario_path, SCENARIO_WITH_GATE_SETS).unwrap();
+
+    let err = load(&scenario_path).unwrap_err();
+    assert!(format!("{:#}", err).contains("gatesets.yaml"));
+}
@@ -1,4 +1,7 @@
 mod basic;
 mod gates;
+mod gatesets;
 mod run_config;
 mod setup;
+mod suite;
+mod taxonomy;
@@ -70,3 +70,27 @@ run:
     assert_eq!(run.timeout_secs, Some(300));
     assert_eq!(run.max_turns, None);
 }
+
+#[test]
+fn test_run_config_isolation() {
+    let yaml = r#"
+name: test
+description: "Test"
+template_folder: qipu
+target:
+  binary: qipu
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates:
+    - type: command_succeeds
+      command: "true"
+run:
+  isolation:
+    home_template: home_seed
+"#;
+    let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+    let isolation = scenario.run.unwrap().isolation.unwrap();
+    assert!(isolation.enabled);
+    assert_eq!(isolation.home_template, Some("home_seed".to_string()));
+}
@@ -0,0 +1,35 @@
+use super::super::suite::*;
+use std::fs;
+
+#[test]
+fn test_load_for_scenario_uses_nearest_suite_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("scenarios/slow");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(
+        dir.path().join("scenarios/suite.yaml"),
+        "timeout_secs: 600\n",
+    )
+    .unwrap();
+    fs::write(nested.join("suite.yaml"), "timeout_secs: 1800\n").unwrap();
+
+    let outer = load_for_scenario(&dir.path().join("scenarios/quick.yaml")).unwrap();
+    assert_eq!(outer.timeout_secs, Some(600));
+    let inner = load_for_scenario(&nested.join("long.yaml")).unwrap();
+    assert_eq!(inner.timeout_secs, Some(1800));
+}
+
+#[test]
+fn test_load_for_scenario_without_suite_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let defaults = load_for_scenario(&dir.path().join("scenario.yaml")).unwrap();
+    assert_eq!(defaults.timeout_secs, None);
+}
+
+#[test]
+fn test_load_for_scenario_rejects_unknown_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("suite.yaml"), "timeout: 600\n").unwrap();
+    let err = load_for_scenario(&dir.path().join("scenario.yaml")).unwrap_err();
+    assert!(format!("{:#}", err).contains("Failed to parse suite file"));
+}
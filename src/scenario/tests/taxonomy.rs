@@ -0,0 +1,87 @@
+use super::super::taxonomy::TaxonomyFilter;
+use super::super::*;
+use std::fs;
+
+const SCENARIO_WITH_METADATA: &str = r#"
+name: test
+description: "Test"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: "Test prompt"
+evaluation:
+  gates: []
+category: editing
+difficulty: hard
+skills: [git, refactoring]
+"#;
+
+#[test]
+fn test_load_accepts_metadata_allowed_by_taxonomy() {
+    let dir = tempfile::tempdir().unwrap();
+    let scenario_dir = dir.path().join("scenarios");
+    fs::create_dir_all(&scenario_dir).unwrap();
+    fs::write(
+        dir.path().join("taxonomy.yaml"),
+        "categories: [editing]\ndifficulties: [easy, hard]\nskills: [git, refactoring, search]\n",
+    )
+    .unwrap();
+    let scenario_path = scenario_dir.join("scenario.yaml");
+    fs::write(&scenario_path, SCENARIO_WITH_METADATA).unwrap();
+
+    let scenario = load(&scenario_path).unwrap();
+    assert_eq!(scenario.category.as_deref(), Some("editing"));
+    assert_eq!(scenario.difficulty.as_deref(), Some("hard"));
+    assert_eq!(scenario.skills, vec!["git", "refactoring"]);
+}
+
+#[test]
+fn test_load_rejects_values_outside_taxonomy() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("taxonomy.yaml"),
+        "difficulties: [easy, medium]\nskills: [git]\n",
+    )
+    .unwrap();
+    let scenario_path = dir.path().join("scenario.yaml");
+    fs::write(&scenario_path, SCENARIO_WITH_METADATA).unwrap();
+
+    let err = format!("{:#}", load(&scenario_path).unwrap_err());
+    assert!(err.contains("Unknown difficulty 'hard'"), "{}", err);
+    assert!(err.contains("Unknown skill 'refactoring'"), "{}", err);
+    // No `categories` list, so any category is accepted
+    assert!(!err.contains("category"), "{}", err);
+}
+
+#[test]
+fn test_load_without_taxonomy_accepts_any_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let scenario_path = dir.path().join("scenario.yaml");
+    fs::write(&scenario_path, SCENARIO_WITH_METADATA).unwrap();
+
+    assert!(load(&scenario_path).is_ok());
+}
+
+#[test]
+fn test_taxonomy_filter_matches_all_requested_metadata() {
+    let scenario: Scenario = serde_yaml::from_str(SCENARIO_WITH_METADATA).unwrap();
+
+    assert!(TaxonomyFilter::default().matches(&scenario));
+    let filter = TaxonomyFilter {
+        category: Some("editing".to_string()),
+        difficulty: None,
+        skills: vec!["git".to_string()],
+    };
+    assert!(filter.matches(&scenario));
+    let filter = TaxonomyFilter {
+        skills: vec!["git".to_string(), "search".to_string()],
+        ..Default::default()
+    };
+    assert!(!filter.matches(&scenario));
+    let filter = TaxonomyFilter {
+        difficulty: Some("easy".to_string()),
+        ..Default::default()
+    };
+    assert!(!filter.matches(&scenario));
+}
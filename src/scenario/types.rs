@@ -3,8 +3,9 @@
 //! This module defines all the data structures used to represent test scenarios,
 //! including task definitions, evaluation gates, and tool configurations.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// A test scenario defining a complete LLM tool evaluation case.
 ///
@@ -12,8 +13,12 @@ use std::collections::HashMap;
 /// - A task prompt for the LLM tool
 /// - Evaluation gates to verify success
 /// - Optional setup commands and configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Scenario {
+    /// Stable identifier used to key results; defaults to `name`.
+    /// Keeps result history attached when the scenario is renamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     /// Human-readable name for this scenario
     pub name: String,
     /// Detailed description of what this scenario tests
@@ -29,25 +34,69 @@ pub struct Scenario {
     /// Test tier level (default: 0)
     #[serde(default = "default_tier")]
     pub tier: usize,
+    /// Relative importance in suite-level aggregates (default: 1.0)
+    #[serde(default = "default_weight")]
+    pub weight: f64,
     /// Optional tool/model matrix configuration
     #[serde(default)]
     pub tool_matrix: Option<Vec<ToolConfig>>,
     /// Optional setup commands to run before the task
     #[serde(default)]
     pub setup: Option<Setup>,
+    /// Gates checked against the fixture after setup, before the tool runs;
+    /// any failure aborts the run with outcome `setup_invalid`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub setup_assertions: Vec<GateEntry>,
     /// Tags for categorizing scenarios
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Capability area, checked against `taxonomy.yaml` when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Difficulty level, checked against `taxonomy.yaml` when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<String>,
+    /// Skills exercised (e.g. `[git, refactoring]`), checked against `taxonomy.yaml` when present
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<String>,
     /// Optional runtime configuration
     #[serde(default)]
     pub run: Option<RunConfig>,
     /// Optional scripts configuration for hooks and evaluators
     #[serde(default)]
     pub scripts: Option<ScriptsConfig>,
+    /// Agent launched by `--tool custom`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_tool: Option<CustomToolConfig>,
+    /// Reject keys the scenario format does not define (default: true); set
+    /// to false to load files with extra keys, e.g. for a newer harness
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+    /// Whether the scenario was loaded from an encrypted bundle (set by `load`)
+    #[serde(skip)]
+    pub encrypted: bool,
+}
+
+impl Scenario {
+    /// Identifier recorded as `scenario_id` in results: `id` if set, otherwise `name`.
+    pub fn stable_id(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Check that `evaluation.workdir` and every gate's `workdir` stay inside
+    /// the run directory; see [`validate_workdir`].
+    pub fn validate_workdirs(&self) -> anyhow::Result<()> {
+        let gates = self.evaluation.gates.iter().chain(&self.setup_assertions);
+        self.evaluation
+            .workdir
+            .iter()
+            .chain(gates.filter_map(|entry| entry.workdir.as_ref()))
+            .try_for_each(|workdir| validate_workdir(workdir))
+    }
 }
 
 /// Target tool configuration for a scenario.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TargetConfig {
     /// Binary name for the tool under test
     pub binary: String,
@@ -62,60 +111,334 @@ pub struct TargetConfig {
     pub env: Option<HashMap<String, String>>,
 }
 
+impl TargetConfig {
+    /// Check that `command_pattern`, if set, compiles; an invalid pattern
+    /// would otherwise match no commands and leave the metrics empty.
+    pub fn validate_command_pattern(&self) -> anyhow::Result<()> {
+        if let Some(pattern) = &self.command_pattern {
+            regex::Regex::new(pattern).map_err(|e| {
+                anyhow::anyhow!("Invalid target.command_pattern '{}': {}", pattern, e)
+            })?;
+        }
+        Ok(())
+    }
+}
+
 /// Runtime configuration for scenario execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RunConfig {
     /// Optional timeout in seconds
     #[serde(default)]
     pub timeout_secs: Option<u64>,
-    /// Optional maximum number of turns/interactions
+    /// Optional maximum number of agent turns; the harness stops the tool
+    /// when it exceeds this and records outcome `turn_limit`
     #[serde(default)]
     pub max_turns: Option<usize>,
+    /// Optional no-output watchdog in seconds; the harness stops a tool that
+    /// stays silent this long and records outcome `stalled`
+    #[serde(default)]
+    pub stall_timeout_secs: Option<u64>,
+    /// Run the tool on pipes instead of a PTY so stdout and stderr are
+    /// recorded as separate streams
+    #[serde(default)]
+    pub separate_stderr: bool,
+    /// Optional HOME/XDG isolation for the tool under test
+    #[serde(default)]
+    pub isolation: Option<IsolationConfig>,
+    /// Optional container sandbox for the tool, setup commands, scripts and gates
+    #[serde(default)]
+    pub sandbox: Option<SandboxConfig>,
+}
+
+/// Per-run HOME/XDG isolation so tools don't read user-level configuration.
+///
+/// When enabled, a fresh HOME directory is created next to the fixture and
+/// `HOME`/`XDG_*` variables point at it for setup commands, scripts, and the tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IsolationConfig {
+    /// Whether to create an isolated HOME for the run (default: true)
+    #[serde(default = "default_isolation_enabled")]
+    pub enabled: bool,
+    /// Optional template folder (under `templates/`) copied into the isolated HOME
+    #[serde(default)]
+    pub home_template: Option<String>,
+}
+
+fn default_isolation_enabled() -> bool {
+    true
+}
+
+/// Run the tool under test and the run's shell commands inside a container.
+///
+/// The run directory (fixture and isolated HOME) is bind-mounted at its host
+/// path, so paths in `LLM_TOOL_TEST_*` variables and transcripts stay valid.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SandboxConfig {
+    /// Whether to use the sandbox (default: true)
+    #[serde(default = "default_isolation_enabled")]
+    pub enabled: bool,
+    /// Container engine used to start the sandbox (default: docker)
+    #[serde(default)]
+    pub engine: SandboxEngine,
+    /// Image containing the tool under test and the target binary
+    pub image: String,
+    /// Extra bind mounts as `host:container[:ro]`; relative host paths are
+    /// resolved against the fixtures directory
+    #[serde(default)]
+    pub mounts: Vec<String>,
+    /// Allow network access from the container (default: false)
+    #[serde(default)]
+    pub network: bool,
+    /// Host environment variables passed into the container (e.g. API keys)
+    #[serde(default)]
+    pub pass_env: Vec<String>,
+    /// User to run as (default: the owner of the run directory)
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+/// How a `script_env` gate gets the variables a script sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptEnvMode {
+    /// `. ./script`: the variables the script exports, like a setup script
+    /// meant to be sourced
+    #[default]
+    Source,
+    /// `eval "$(./script)"`: the assignments the script prints, like
+    /// `ssh-agent` or a `KEY=value` dotenv file; every assignment is exported
+    Eval,
+}
+
+impl ScriptEnvMode {
+    /// Name used in messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            ScriptEnvMode::Source => "sourced",
+            ScriptEnvMode::Eval => "evaluated",
+        }
+    }
+}
+
+/// Test framework whose output a `tests_pass` gate parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TestFramework {
+    /// `test result:` lines of `cargo test`
+    Cargo,
+    /// The final summary line of `pytest`
+    Pytest,
+    /// The `--json` report of `jest`, else its `Tests:` summary line
+    Jest,
+}
+
+impl TestFramework {
+    /// Name used in messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            TestFramework::Cargo => "cargo",
+            TestFramework::Pytest => "pytest",
+            TestFramework::Jest => "jest",
+        }
+    }
+}
+
+/// Container engine for [`SandboxConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxEngine {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl SandboxEngine {
+    /// Name of the engine's executable.
+    pub fn program(self) -> &'static str {
+        match self {
+            SandboxEngine::Docker => "docker",
+            SandboxEngine::Podman => "podman",
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// Check that an image is named and every mount has a host and a container path.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.image.trim().is_empty() {
+            anyhow::bail!("run.sandbox.image is empty");
+        }
+        for mount in &self.mounts {
+            let parts: Vec<&str> = mount.split(':').collect();
+            let valid = match parts.as_slice() {
+                [host, container] => !host.is_empty() && container.starts_with('/'),
+                [host, container, mode] => {
+                    !host.is_empty() && container.starts_with('/') && matches!(*mode, "ro" | "rw")
+                }
+                _ => false,
+            };
+            if !valid {
+                anyhow::bail!(
+                    "Invalid run.sandbox.mounts entry '{}': expected host:/container[:ro|rw]",
+                    mount
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Setup commands to prepare the test environment.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Setup {
     /// Shell commands to execute before running the task
     pub commands: Vec<String>,
 }
 
+/// An in-house agent described by its command line, run by `--tool custom`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CustomToolConfig {
+    /// Command line with `{prompt}`, `{model}` and `{cwd}` placeholders,
+    /// split into arguments like a shell would (no shell is involved)
+    pub command: String,
+    /// Regex whose first capture group is a cost in USD; all matches are summed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_pattern: Option<String>,
+    /// Regex whose first capture group is an input token count; all matches are summed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens_pattern: Option<String>,
+    /// Regex whose first capture group is an output token count; all matches are summed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens_pattern: Option<String>,
+    /// Exit codes that mean the agent succeeded (default: [0])
+    #[serde(default = "default_success_exit_codes")]
+    pub success_exit_codes: Vec<i32>,
+}
+
+impl CustomToolConfig {
+    /// The command template split into arguments, placeholders unexpanded.
+    pub fn argv(&self) -> anyhow::Result<Vec<String>> {
+        match shlex::split(&self.command) {
+            Some(argv) if !argv.is_empty() => Ok(argv),
+            Some(_) => anyhow::bail!("custom_tool.command is empty"),
+            None => anyhow::bail!(
+                "custom_tool.command has unbalanced quotes: {}",
+                self.command
+            ),
+        }
+    }
+
+    /// Check that the command splits and every pattern compiles.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.argv()?;
+        for (key, pattern) in [
+            ("cost_pattern", &self.cost_pattern),
+            ("input_tokens_pattern", &self.input_tokens_pattern),
+            ("output_tokens_pattern", &self.output_tokens_pattern),
+        ] {
+            if let Some(pattern) = pattern {
+                regex::Regex::new(pattern).map_err(|e| {
+                    anyhow::anyhow!("Invalid custom_tool.{} '{}': {}", key, pattern, e)
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_success_exit_codes() -> Vec<i32> {
+    vec![0]
+}
+
 fn default_tier() -> usize {
     0
 }
 
+fn default_weight() -> f64 {
+    1.0
+}
+
+fn default_strict() -> bool {
+    true
+}
+
 /// Configuration for a specific tool and its supported models.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ToolConfig {
     /// Tool name (e.g., "opencode", "claude-code")
     pub tool: String,
     /// List of supported model names
     #[serde(default)]
     pub models: Vec<String>,
+    /// Environment variables for this tool's runs, applied before the scenario's `target.env`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Arguments appended to the tool's command line (e.g. permission flags or agent selection)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
 }
 
 /// The task definition containing the prompt for the LLM tool.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Task {
     /// The prompt text to send to the LLM tool
     pub prompt: String,
 }
 
 /// Evaluation configuration defining how to assess task completion.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Evaluation {
     /// List of evaluation gates that must pass
-    pub gates: Vec<Gate>,
-    /// Optional judge configuration for LLM-as-judge scoring
     #[serde(default)]
-    pub judge: Option<JudgeConfig>,
+    pub gates: Vec<GateEntry>,
+    /// Named gate sets from `gatesets.yaml` to prepend to `gates`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub use_gates: Vec<String>,
+    /// Optional judge configuration for LLM-as-judge scoring: one judge,
+    /// or a list of judges whose scores are aggregated
+    #[serde(default)]
+    pub judge: Option<JudgeSpec>,
+    /// How the scores of a list of judges are combined (default: mean)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge_aggregate: Option<JudgeAggregate>,
     /// Optional composite scoring weights
     #[serde(default)]
     pub composite: Option<CompositeConfig>,
+    /// Directory file and command gates run in, relative to the fixture;
+    /// may reach into the run directory (e.g. `../artifacts`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+}
+
+/// Check that a gate `workdir` is a relative path that stays inside the run
+/// directory: the fixture, or the run directory that contains it.
+pub fn validate_workdir(workdir: &str) -> anyhow::Result<()> {
+    use std::path::Component;
+
+    // Depth below the fixture root; -1 is the run directory
+    let mut depth = 0i32;
+    for component in std::path::Path::new(workdir).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => depth -= 1,
+            Component::RootDir | Component::Prefix(_) => anyhow::bail!(
+                "Invalid workdir '{}': must be relative to the fixture",
+                workdir
+            ),
+        }
+        if depth < -1 {
+            anyhow::bail!(
+                "Invalid workdir '{}': must stay inside the run directory",
+                workdir
+            );
+        }
+    }
+    Ok(())
 }
 
 /// Configuration for LLM-as-judge evaluation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JudgeConfig {
     /// Whether judge evaluation is enabled
     pub enabled: bool,
@@ -123,10 +446,71 @@ pub struct JudgeConfig {
     pub rubric: String,
     /// Minimum score threshold to pass (0.0-1.0)
     pub pass_threshold: f64,
+    /// Whether a score below `pass_threshold` fails the run (default: true);
+    /// when false the shortfall is only noted in the outcome detail
+    #[serde(default = "default_judge_blocking")]
+    pub blocking: bool,
+    /// Judge model, overriding the `[judge]` model of the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// One judge, or an ensemble of judges scoring the same run.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum JudgeSpec {
+    /// A single judge (`judge: { rubric: ... }`)
+    Single(JudgeConfig),
+    /// Judges whose scores are combined per `judge_aggregate`
+    /// (`judge: [{ rubric: ... }, ...]`)
+    Ensemble(Vec<JudgeConfig>),
+}
+
+impl JudgeSpec {
+    /// Every configured judge, enabled or not.
+    pub fn judges(&self) -> &[JudgeConfig] {
+        match self {
+            JudgeSpec::Single(judge) => std::slice::from_ref(judge),
+            JudgeSpec::Ensemble(judges) => judges,
+        }
+    }
+
+    /// Whether any enabled judge fails the run on a score below threshold.
+    pub fn blocking(&self) -> bool {
+        self.judges().iter().any(|j| j.enabled && j.blocking)
+    }
+}
+
+/// Combination of the scores of a judge ensemble.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgeAggregate {
+    /// Mean score against the mean pass threshold
+    #[default]
+    Mean,
+    /// Median score against the median pass threshold
+    Median,
+    /// More than half of the judges meet their own pass threshold; the
+    /// score is the mean
+    Majority,
+}
+
+impl JudgeAggregate {
+    pub fn name(self) -> &'static str {
+        match self {
+            JudgeAggregate::Mean => "mean",
+            JudgeAggregate::Median => "median",
+            JudgeAggregate::Majority => "majority",
+        }
+    }
+}
+
+fn default_judge_blocking() -> bool {
+    true
 }
 
 /// Configuration for composite scoring weights.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CompositeConfig {
     /// Weight for judge score (0.0-1.0)
     #[serde(default = "default_judge_weight")]
@@ -137,6 +521,34 @@ pub struct CompositeConfig {
     /// Weight for interaction metrics (0.0-1.0)
     #[serde(default = "default_interaction_weight")]
     pub interaction_weight: f64,
+    /// Deductions from the composite score for command errors and retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalties: Option<PenaltyConfig>,
+}
+
+/// Per-occurrence deductions from the composite score.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PenaltyConfig {
+    /// Deducted for each target-tool command that failed (e.g. 0.02)
+    #[serde(default)]
+    pub per_error: f64,
+    /// Deducted for each repeated command
+    #[serde(default)]
+    pub per_retry: f64,
+    /// Upper bound on the total deduction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total: Option<f64>,
+}
+
+impl Default for CompositeConfig {
+    fn default() -> Self {
+        Self {
+            judge_weight: default_judge_weight(),
+            gate_weight: default_gate_weight(),
+            interaction_weight: default_interaction_weight(),
+            penalties: None,
+        }
+    }
 }
 
 fn default_judge_weight() -> f64 {
@@ -151,11 +563,118 @@ fn default_interaction_weight() -> f64 {
     0.10
 }
 
+/// A gate as listed in a scenario, with optional ordering metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GateEntry {
+    /// Identifier other gates can reference in `when`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Only evaluate this gate if the referenced earlier gates passed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<GateCondition>,
+    /// Directory this gate runs in, overriding `evaluation.workdir`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+    /// Let file paths resolve outside the fixture (and workdir), e.g. through
+    /// `../` or symlinks; rejected by default
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_outside_fixture: bool,
+    /// Whether a failure fails the run (`error`) or is only reported (`warning`)
+    #[serde(default, skip_serializing_if = "GateSeverity::is_error")]
+    pub severity: GateSeverity,
+    /// Re-evaluate a failing gate up to this many times before recording the
+    /// failure, for effects that settle asynchronously
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Seconds to wait before each retry (default 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_delay_secs: Option<f64>,
+    /// The gate assertion itself
+    #[serde(flatten)]
+    pub gate: Gate,
+}
+
+impl From<Gate> for GateEntry {
+    fn from(gate: Gate) -> Self {
+        Self {
+            id: None,
+            when: None,
+            workdir: None,
+            allow_outside_fixture: false,
+            severity: GateSeverity::Error,
+            retries: None,
+            retry_delay_secs: None,
+            gate,
+        }
+    }
+}
+
+/// How a failing gate affects the run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GateSeverity {
+    /// A failure fails the run
+    #[default]
+    Error,
+    /// A failure is recorded and reported but leaves `gates_passed` and the
+    /// outcome alone, e.g. for aspirational checks
+    Warning,
+}
+
+impl GateSeverity {
+    pub fn is_error(&self) -> bool {
+        *self == GateSeverity::Error
+    }
+}
+
+/// Column of a CSV file, by header name or position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum CsvColumn {
+    /// 0-based column index (`column: 2`)
+    Index(usize),
+    /// Header name (`column: total`); requires `header: true`
+    Name(String),
+}
+
+impl std::fmt::Display for CsvColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvColumn::Index(index) => write!(f, "{}", index),
+            CsvColumn::Name(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+fn default_csv_header() -> bool {
+    true
+}
+
+/// Condition on earlier gate results, referenced by gate `id`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum GateCondition {
+    /// A single gate that must have passed (`when: build`)
+    Passed(String),
+    /// All listed gates must have passed (`when: [build, lint]`)
+    AllPassed(Vec<String>),
+}
+
+impl GateCondition {
+    /// Gate ids that must have passed for the condition to hold.
+    pub fn required_ids(&self) -> Vec<&str> {
+        match self {
+            GateCondition::Passed(id) => vec![id.as_str()],
+            GateCondition::AllPassed(ids) => ids.iter().map(|id| id.as_str()).collect(),
+        }
+    }
+}
+
 /// Evaluation gate types for verifying task completion.
 ///
 /// Each gate represents a specific assertion about the resulting state
 /// after the LLM tool has executed the task.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Gate {
     /// Asserts a shell command succeeds
@@ -163,6 +682,89 @@ pub enum Gate {
         /// Shell command to execute
         command: String,
     },
+    /// Asserts `cargo build` succeeds
+    RustBuilds {
+        /// Relative path to the crate or workspace directory; defaults to the
+        /// working directory
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Seconds before the build is killed and the gate fails (default: 600)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Asserts a Python module imports with `python3`
+    PythonImports {
+        /// Dotted module name, e.g. `app.models`
+        module: String,
+        /// Seconds before the import is killed and the gate fails (default: 60)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Asserts a script runs to a zero exit with `node`
+    NodeScriptRuns {
+        /// Relative path to the script
+        path: String,
+        /// Arguments passed to the script
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        /// Seconds before the script is killed and the gate fails (default: 60)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Asserts the environment variables a produced shell script sets, by
+    /// running it in a shell started with only `PATH` and `HOME`
+    ScriptEnv {
+        /// Relative path to the script
+        path: String,
+        /// Whether the script is sourced or its output evaluated
+        #[serde(default)]
+        mode: ScriptEnvMode,
+        /// Variables that must be exported with exactly these values
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+        /// Variables that must be exported with values matching these regexes
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        matches: BTreeMap<String, String>,
+        /// Variables that must not be exported
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        unset: Vec<String>,
+        /// Shell that runs the script (default: `bash`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shell: Option<String>,
+        /// Seconds before the script is killed and the gate fails (default: 60)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Asserts a test suite passes, reading the counts from the test
+    /// framework's summary
+    TestsPass {
+        /// Shell command running the tests, e.g. `cargo test` or `npx jest --json`
+        command: String,
+        /// Framework whose summary the output contains
+        framework: TestFramework,
+        /// Minimum number of passing tests, so deleting tests cannot pass the gate
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_passed: Option<u64>,
+        /// Seconds before the tests are killed and the gate fails (default: 600)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Asserts a linter reports no more new warnings than allowed, compared
+    /// with its warning count before the tool ran
+    LintClean {
+        /// Shell command running the linter, e.g. `cargo clippy --quiet`
+        command: String,
+        /// New warnings tolerated on top of the baseline (default: 0)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_warnings: Option<u64>,
+        /// Regex matching an output line that reports a warning (default:
+        /// lines containing the word `warning`, case-insensitively)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        /// Seconds before the linter is killed and the gate fails (default: 600)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
     /// Asserts command stdout contains a substring
     CommandOutputContains {
         /// Shell command to execute
@@ -186,6 +788,62 @@ pub enum Gate {
         /// Assertion expression to apply to resolved value
         assertion: String,
     },
+    /// Asserts a JSON file contains data matching a path assertion
+    FileJsonPath {
+        /// Relative path to the JSON file
+        path: String,
+        /// JSON path to evaluate
+        json_path: String,
+        /// Assertion expression to apply to resolved value
+        assertion: String,
+    },
+    /// Asserts a JSON file is structurally equal to an expected file,
+    /// ignoring key order, number formatting and the listed paths
+    JsonEqualsFile {
+        /// Relative path to the JSON file
+        path: String,
+        /// JSON file holding the expected document, resolved against the
+        /// fixtures directory
+        expected_file: String,
+        /// JSON paths left out of the comparison, e.g. `$.generated_at` or
+        /// `$.items[*].id`
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        ignore: Vec<String>,
+    },
+    /// Asserts the number of data rows in a CSV file is within bounds
+    CsvRowCount {
+        /// Relative path to the CSV file
+        path: String,
+        /// Minimum number of data rows (inclusive)
+        #[serde(default)]
+        min: Option<u64>,
+        /// Maximum number of data rows (inclusive)
+        #[serde(default)]
+        max: Option<u64>,
+        /// Field delimiter; defaults to a tab for `.tsv` files, else a comma
+        #[serde(default)]
+        delimiter: Option<char>,
+        /// Whether the first row is a header rather than data
+        #[serde(default = "default_csv_header")]
+        header: bool,
+    },
+    /// Asserts a cell of a CSV file equals a value
+    CsvCellEquals {
+        /// Relative path to the CSV file
+        path: String,
+        /// 0-based index of the data row, not counting the header
+        row: usize,
+        /// Header name, or 0-based column index
+        column: CsvColumn,
+        /// Expected cell value, compared after trimming surrounding whitespace
+        value: String,
+        /// Field delimiter; defaults to a tab for `.tsv` files, else a comma
+        #[serde(default)]
+        delimiter: Option<char>,
+        /// Whether the first row is a header rather than data
+        #[serde(default = "default_csv_header")]
+        header: bool,
+    },
     /// Asserts a file exists in the fixture directory
     FileExists {
         /// Relative path to the target file
@@ -198,6 +856,30 @@ pub enum Gate {
         /// Substring to search for
         substring: String,
     },
+    /// Asserts a Markdown section of a file contains a substring
+    FileSectionContains {
+        /// Relative path to the Markdown file
+        path: String,
+        /// Heading text of the section, e.g. `Conventions` (case-insensitive)
+        heading: String,
+        /// Substring to search for within the section and its subsections
+        substring: String,
+    },
+    /// Asserts the structure of a Markdown file: headings, sections and code blocks
+    MarkdownStructure {
+        /// Relative path to the Markdown file
+        path: String,
+        /// Headings that must appear in this order (case-insensitive); other
+        /// headings may appear between them. Leading `#`s also pin the level.
+        #[serde(default)]
+        headings: Vec<String>,
+        /// Minimum number of headings (sections) in the file
+        #[serde(default)]
+        min_sections: Option<usize>,
+        /// Languages that each need at least one fenced code block, e.g. `rust`
+        #[serde(default)]
+        code_blocks: Vec<String>,
+    },
     /// Asserts file contents match a regex pattern
     FileMatches {
         /// Relative path to the target file
@@ -205,8 +887,99 @@ pub enum Gate {
         /// Regex pattern that must match file contents
         pattern: String,
     },
+    /// Soft-score gate: fraction of expected files present
+    FilesExist {
+        /// Relative paths that should exist
+        paths: Vec<String>,
+        /// Minimum score (0.0-1.0) required to pass; defaults to 1.0
+        #[serde(default)]
+        min_score: Option<f64>,
+    },
+    /// Soft-score gate: fraction of regex patterns matching file contents
+    FileMatchesAll {
+        /// Relative path to the target file
+        path: String,
+        /// Regex patterns to match against file contents
+        patterns: Vec<String>,
+        /// Minimum score (0.0-1.0) required to pass; defaults to 1.0
+        #[serde(default)]
+        min_score: Option<f64>,
+    },
+    /// Asserts a file's size in bytes is within bounds
+    FileSize {
+        /// Relative path to the target file
+        path: String,
+        /// Minimum size in bytes (inclusive)
+        #[serde(default)]
+        min: Option<u64>,
+        /// Maximum size in bytes (inclusive)
+        #[serde(default)]
+        max: Option<u64>,
+    },
+    /// Asserts a file's SHA-256 digest matches an expected value
+    FileSha256 {
+        /// Relative path to the target file
+        path: String,
+        /// Expected hex-encoded SHA-256 digest (case-insensitive)
+        equals: String,
+    },
+    /// Asserts an image file (PNG, GIF, BMP, JPEG) has the given dimensions
+    ImageDimensions {
+        /// Relative path to the target image
+        path: String,
+        /// Expected width in pixels
+        #[serde(default)]
+        width: Option<u32>,
+        /// Expected height in pixels
+        #[serde(default)]
+        height: Option<u32>,
+    },
+    /// Asserts a zip or tar archive contains an entry, optionally with given content
+    ArchiveContains {
+        /// Relative path to the archive (`.zip`, `.tar`, `.tar.gz`, `.tgz`)
+        path: String,
+        /// Path of the entry inside the archive
+        entry: String,
+        /// Substring the entry contents must contain
+        #[serde(default)]
+        contains: Option<String>,
+    },
+    /// Asserts a process whose command line matches a regex is running.
+    ///
+    /// Evaluated immediately after the tool exits, before post scripts run.
+    ProcessRunning {
+        /// Regex pattern matched against process command lines
+        pattern: String,
+    },
+    /// Asserts a TCP port accepts connections.
+    ///
+    /// Evaluated immediately after the tool exits, before post scripts run.
+    PortOpen {
+        /// TCP port to connect to
+        port: u16,
+        /// Host to connect to (defaults to 127.0.0.1)
+        #[serde(default)]
+        host: Option<String>,
+    },
+    /// Asserts the transcript hits an ordered list of milestones
+    TranscriptMilestones {
+        /// Regex patterns (e.g. command names) that must appear in order
+        milestones: Vec<String>,
+        /// Fraction of milestones (0.0-1.0) that must be reached in order to pass.
+        /// Defaults to 1.0 (all milestones).
+        #[serde(default)]
+        min_fraction: Option<f64>,
+    },
     /// Asserts no errors in the transcript
     NoTranscriptErrors,
+    /// Asserts the fixture contains no leaked secrets (API key patterns or
+    /// values of sensitive environment variables)
+    NoEnvLeak {
+        /// Relative files or directories to skip while scanning, matched by
+        /// whole path components
+        #[serde(default)]
+        ignore: Vec<String>,
+    },
     /// Asserts a script command passes and reports status
     Script {
         /// Shell command to execute
@@ -214,10 +987,48 @@ pub enum Gate {
         /// Human-readable gate description
         description: String,
     },
+    /// Asserts a WASI module export accepts the fixture.
+    ///
+    /// The export is called with the fixture mounted read-only and must
+    /// return 0 to pass.
+    Wasm {
+        /// Path to the `.wasm` (or `.wat`) module, relative to the fixtures directory
+        module: String,
+        /// Exported function `() -> i32` to call
+        export: String,
+        /// Timeout in seconds (default: 30)
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Passes when at least one child gate passes
+    AnyOf {
+        /// Child gates, all evaluated in order
+        gates: Vec<Gate>,
+    },
+    /// Passes when every child gate passes
+    AllOf {
+        /// Child gates, all evaluated in order
+        gates: Vec<Gate>,
+    },
+    /// Passes when no child gate passes
+    Not {
+        /// Child gates, all evaluated in order
+        gates: Vec<Gate>,
+    },
+}
+
+impl Gate {
+    /// Child gates of an `any_of`, `all_of` or `not` gate; empty for other gates.
+    pub fn children(&self) -> &[Gate] {
+        match self {
+            Gate::AnyOf { gates } | Gate::AllOf { gates } | Gate::Not { gates } => gates,
+            _ => &[],
+        }
+    }
 }
 
 /// Scripts configuration for scenario execution hooks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScriptsConfig {
     /// Post-execution scripts to run after agent completes
     #[serde(default)]
@@ -228,7 +1039,7 @@ pub struct ScriptsConfig {
 }
 
 /// A script entry for post-execution hooks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScriptEntry {
     /// Shell command to execute
     pub command: String,
@@ -238,7 +1049,7 @@ pub struct ScriptEntry {
 }
 
 /// A custom evaluator script entry.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvaluatorEntry {
     /// Shell command to execute
     pub command: String,
@@ -0,0 +1,1054 @@
+//! Scenario validation with source locations.
+//!
+//! Produces diagnostics carrying file, line and column so editors and
+//! pre-commit hooks can surface problems inline. Parse errors use the
+//! location reported by the YAML parser; semantic checks locate the
+//! offending gate or key by scanning the source text.
+
+use super::gatesets::expand_gate_sets;
+use super::taxonomy::validate_taxonomy;
+use super::types::{validate_workdir, CsvColumn, Gate, JudgeSpec, Scenario};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Severity of a validation diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single validation finding. Line and column are 1-based.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Whether a YAML document looks like a scenario (as opposed to a rubric or gate set file).
+pub fn looks_like_scenario(content: &str) -> bool {
+    match serde_yaml::from_str::<serde_yaml::Value>(content) {
+        Ok(serde_yaml::Value::Mapping(map)) => {
+            map.contains_key("task") || map.contains_key("evaluation")
+        }
+        Ok(_) => false,
+        // Broken YAML is reported rather than silently skipped
+        Err(_) => true,
+    }
+}
+
+/// Validate a scenario file, returning all diagnostics found.
+pub fn validate_scenario_file(path: &Path) -> Vec<Diagnostic> {
+    let file = path.display().to_string();
+    let content = match super::bundle::read_source(path) {
+        Ok((content, _)) => content,
+        Err(e) => {
+            return vec![Diagnostic {
+                file,
+                line: 1,
+                column: 1,
+                severity: Severity::Error,
+                message: format!("Failed to read file: {}", e),
+            }]
+        }
+    };
+    let templates = crate::utils::resolve_fixtures_path("templates");
+    let templates = templates.is_dir().then_some(templates.as_path());
+    let config = crate::config::Config::load_or_default();
+    let fixtures = Path::new(config.get_fixtures_path());
+    validate_scenario_source(&file, &content, path, templates, Some(fixtures))
+}
+
+/// Validate scenario YAML source.
+///
+/// `path` is used to resolve `gatesets.yaml`; when `templates_dir` is given,
+/// a missing `template_folder` is reported as a warning. When `fixtures_dir`
+/// is given, the judge rubric is resolved against it as at run time and
+/// loaded.
+pub fn validate_scenario_source(
+    file: &str,
+    content: &str,
+    path: &Path,
+    templates_dir: Option<&Path>,
+    fixtures_dir: Option<&Path>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut push = |line: usize, column: usize, severity: Severity, message: String| {
+        diagnostics.push(Diagnostic {
+            file: file.to_string(),
+            line,
+            column,
+            severity,
+            message,
+        })
+    };
+
+    for field in super::strict::unknown_fields(content) {
+        push(field.line, field.column, Severity::Error, field.to_string());
+    }
+
+    let mut scenario: Scenario = match serde_yaml::from_str(content) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            let (line, column) = e
+                .location()
+                .map(|loc| (loc.line(), loc.column()))
+                .unwrap_or((1, 1));
+            push(
+                line,
+                column,
+                Severity::Error,
+                strip_location(&e.to_string()),
+            );
+            return diagnostics;
+        }
+    };
+
+    let gate_lines = gate_item_locations(content);
+    let locate_gate = |index: usize| gate_lines.get(index).copied().unwrap_or((1, 1));
+
+    let mut seen_ids: HashSet<&str> = HashSet::new();
+    for (index, entry) in scenario.evaluation.gates.iter().enumerate() {
+        let (line, column) = locate_gate(index);
+
+        if let Some(condition) = &entry.when {
+            for id in condition.required_ids() {
+                if !seen_ids.contains(id) {
+                    push(
+                        line,
+                        column,
+                        Severity::Error,
+                        format!(
+                            "Gate {} `when` references '{}', which is not an earlier gate id",
+                            index + 1,
+                            id
+                        ),
+                    );
+                }
+            }
+        }
+        if let Some(id) = &entry.id {
+            if !seen_ids.insert(id.as_str()) {
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!("Duplicate gate id '{}'", id),
+                );
+            }
+        }
+
+        for problem in gate_definition_problems(&entry.gate) {
+            push(
+                line,
+                column,
+                Severity::Error,
+                format!("Gate {} has {}", index + 1, problem),
+            );
+        }
+
+        if let Some(Err(e)) = entry.workdir.as_deref().map(validate_workdir) {
+            push(
+                line,
+                column,
+                Severity::Error,
+                format!("Gate {}: {:#}", index + 1, e),
+            );
+        }
+
+        if let Some(delay) = entry
+            .retry_delay_secs
+            .filter(|d| !(d.is_finite() && *d >= 0.0))
+        {
+            push(
+                line,
+                column,
+                Severity::Error,
+                format!(
+                    "Gate {} retry_delay_secs {} must be a non-negative number",
+                    index + 1,
+                    delay
+                ),
+            );
+        }
+
+        for threshold in gate_thresholds(&entry.gate) {
+            if !(0.0..=1.0).contains(&threshold) {
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!(
+                        "Gate {} threshold {} must be between 0.0 and 1.0",
+                        index + 1,
+                        threshold
+                    ),
+                );
+            }
+        }
+    }
+
+    if !scenario.setup_assertions.is_empty() {
+        let (line, column) = key_location(content, "setup_assertions").unwrap_or((1, 1));
+        for (index, entry) in scenario.setup_assertions.iter().enumerate() {
+            if needs_tool_run(&entry.gate) {
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!(
+                        "Setup assertion {} needs a transcript or script runner, which do not exist before the tool runs",
+                        index + 1
+                    ),
+                );
+            }
+            if let Some(Err(e)) = entry.workdir.as_deref().map(validate_workdir) {
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!("Setup assertion {}: {:#}", index + 1, e),
+                );
+            }
+            for problem in gate_definition_problems(&entry.gate) {
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!("Setup assertion {} has {}", index + 1, problem),
+                );
+            }
+        }
+    }
+
+    if let Some(Err(e)) = scenario.evaluation.workdir.as_deref().map(validate_workdir) {
+        let (line, column) = key_location(content, "workdir").unwrap_or((1, 1));
+        push(line, column, Severity::Error, format!("{:#}", e));
+    }
+
+    if let Err(e) = scenario.target.validate_command_pattern() {
+        let (line, column) = key_location(content, "command_pattern").unwrap_or((1, 1));
+        push(line, column, Severity::Error, format!("{:#}", e));
+    }
+
+    if let Some(Err(e)) = scenario.custom_tool.as_ref().map(|c| c.validate()) {
+        let (line, column) = key_location(content, "custom_tool").unwrap_or((1, 1));
+        push(line, column, Severity::Error, format!("{:#}", e));
+    }
+
+    let sandbox = scenario.run.as_ref().and_then(|r| r.sandbox.as_ref());
+    if let Some(Err(e)) = sandbox.map(|s| s.validate()) {
+        let (line, column) = key_location(content, "sandbox").unwrap_or((1, 1));
+        push(line, column, Severity::Error, format!("{:#}", e));
+    }
+
+    if !(scenario.weight >= 0.0 && scenario.weight.is_finite()) {
+        let (line, column) = key_location(content, "weight").unwrap_or((1, 1));
+        push(
+            line,
+            column,
+            Severity::Error,
+            format!(
+                "Scenario weight {} must be a non-negative number",
+                scenario.weight
+            ),
+        );
+    }
+
+    if let Some(spec) = &scenario.evaluation.judge {
+        let (line, column) = key_location(content, "judge").unwrap_or((1, 1));
+        let ensemble = matches!(spec, JudgeSpec::Ensemble(_));
+        if spec.judges().is_empty() {
+            push(
+                line,
+                column,
+                Severity::Error,
+                "Judge list is empty".to_string(),
+            );
+        }
+        let rubric_locations = key_locations(content, "rubric");
+        for (i, judge) in spec.judges().iter().enumerate() {
+            let label = if ensemble {
+                format!("Judge {}", i + 1)
+            } else {
+                "Judge".to_string()
+            };
+            if !(0.0..=1.0).contains(&judge.pass_threshold) {
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!(
+                        "{} pass_threshold {} must be between 0.0 and 1.0",
+                        label, judge.pass_threshold
+                    ),
+                );
+            }
+            let Some(fixtures) = fixtures_dir else {
+                continue;
+            };
+            let rubric = crate::utils::resolve_against(fixtures, &judge.rubric);
+            let (line, column) = rubric_locations.get(i).copied().unwrap_or((line, column));
+            // A disabled judge never loads its rubric, so a problem is only a warning
+            let severity = if judge.enabled {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            if !rubric.is_file() {
+                push(
+                    line,
+                    column,
+                    severity,
+                    format!(
+                        "Rubric '{}' not found at {}",
+                        judge.rubric,
+                        rubric.display()
+                    ),
+                );
+            } else if let Err(e) = crate::judge::load_rubric(&rubric) {
+                push(line, column, severity, format!("{:#}", e));
+            }
+        }
+        if !ensemble && scenario.evaluation.judge_aggregate.is_some() {
+            let (line, column) = key_location(content, "judge_aggregate").unwrap_or((line, column));
+            push(
+                line,
+                column,
+                Severity::Warning,
+                "judge_aggregate has no effect with a single judge".to_string(),
+            );
+        }
+    }
+
+    if let Some(composite) = &scenario.evaluation.composite {
+        let (line, column) = key_location(content, "composite").unwrap_or((1, 1));
+        let weights = [
+            ("judge_weight", composite.judge_weight),
+            ("gate_weight", composite.gate_weight),
+            ("interaction_weight", composite.interaction_weight),
+        ];
+        let mut valid = true;
+        for (key, weight) in weights {
+            if !(weight >= 0.0 && weight.is_finite()) {
+                valid = false;
+                let (line, column) = key_location(content, key).unwrap_or((line, column));
+                push(
+                    line,
+                    column,
+                    Severity::Error,
+                    format!("Composite {} {} must be a non-negative number", key, weight),
+                );
+            }
+        }
+        let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        if valid && (total - 1.0).abs() > 0.01 {
+            push(
+                line,
+                column,
+                Severity::Warning,
+                format!(
+                    "Composite weights sum to {}, so composite scores will not span 0.0-1.0",
+                    total
+                ),
+            );
+        }
+    }
+
+    if scenario.evaluation.gates.is_empty() && scenario.evaluation.use_gates.is_empty() {
+        let (line, column) = key_location(content, "evaluation").unwrap_or((1, 1));
+        push(
+            line,
+            column,
+            Severity::Warning,
+            "Scenario defines no gates".to_string(),
+        );
+    }
+
+    if let Err(e) = expand_gate_sets(&mut scenario, path) {
+        let (line, column) = key_location(content, "use_gates").unwrap_or((1, 1));
+        push(line, column, Severity::Error, format!("{:#}", e));
+    }
+
+    if let Err(e) = validate_taxonomy(&scenario, path) {
+        let key = ["category", "difficulty", "skills"]
+            .into_iter()
+            .find_map(|key| key_location(content, key));
+        let (line, column) = key.unwrap_or((1, 1));
+        push(line, column, Severity::Error, format!("{:#}", e));
+    }
+
+    if let Some(templates) = templates_dir {
+        if !templates.join(&scenario.template_folder).exists() {
+            let (line, column) = key_location(content, "template_folder").unwrap_or((1, 1));
+            push(
+                line,
+                column,
+                Severity::Warning,
+                format!(
+                    "Template folder '{}' not found under {}",
+                    scenario.template_folder,
+                    templates.display()
+                ),
+            );
+        }
+    }
+
+    diagnostics
+}
+
+/// Problems in a gate's own definition, such as regexes that do not compile,
+/// phrased to follow "Gate N has".
+fn gate_definition_problems(gate: &Gate) -> Vec<String> {
+    let patterns = gate_patterns(gate).into_iter().filter_map(|pattern| {
+        Regex::new(pattern)
+            .err()
+            .map(|e| format!("invalid regex '{}': {}", pattern, e))
+    });
+    let json_paths = gate_json_paths(gate)
+        .into_iter()
+        .filter_map(|(path, assertion)| {
+            crate::evaluation::check_json_path_assertion(path, assertion).err()
+        });
+    let ignores = gate_ignore_paths(gate)
+        .into_iter()
+        .filter_map(|path| crate::evaluation::check_ignore_path(path).err());
+    let modules = gate_python_modules(gate)
+        .into_iter()
+        .filter(|module| !crate::evaluation::is_python_module_name(module))
+        .map(|module| format!("invalid Python module name '{}'", module));
+    let csv_columns = gate_headerless_csv_columns(gate)
+        .into_iter()
+        .map(|name| format!("column name '{}' but header: false", name));
+    let env_vars = gate_script_env_problems(gate);
+    patterns
+        .chain(json_paths)
+        .chain(ignores)
+        .chain(modules)
+        .chain(csv_columns)
+        .chain(env_vars)
+        .collect()
+}
+
+/// Invalid variable names and missing checks of `script_env` gates,
+/// including child gates.
+fn gate_script_env_problems(gate: &Gate) -> Vec<String> {
+    let mut problems = match gate {
+        Gate::ScriptEnv {
+            env,
+            matches,
+            unset,
+            ..
+        } => {
+            let names = env.keys().chain(matches.keys()).chain(unset);
+            let mut problems: Vec<String> = names
+                .filter(|name| !crate::evaluation::is_env_var_name(name))
+                .map(|name| format!("invalid environment variable name '{}'", name))
+                .collect();
+            if env.is_empty() && matches.is_empty() && unset.is_empty() {
+                problems.push("no env, matches or unset checks".to_string());
+            }
+            problems
+        }
+        _ => Vec::new(),
+    };
+    problems.extend(gate.children().iter().flat_map(gate_script_env_problems));
+    problems
+}
+
+/// CSV columns named by a gate and its child gates that have no header row
+/// to look the name up in.
+fn gate_headerless_csv_columns(gate: &Gate) -> Vec<&str> {
+    let mut names = match gate {
+        Gate::CsvCellEquals {
+            column: CsvColumn::Name(name),
+            header: false,
+            ..
+        } => vec![name.as_str()],
+        _ => Vec::new(),
+    };
+    names.extend(gate.children().iter().flat_map(gate_headerless_csv_columns));
+    names
+}
+
+/// `json_equals_file` ignore paths declared by a gate and its child gates.
+fn gate_ignore_paths(gate: &Gate) -> Vec<&str> {
+    let mut paths = match gate {
+        Gate::JsonEqualsFile { ignore, .. } => ignore.iter().map(|p| p.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    paths.extend(gate.children().iter().flat_map(gate_ignore_paths));
+    paths
+}
+
+/// Python modules imported by a gate and its child gates.
+fn gate_python_modules(gate: &Gate) -> Vec<&str> {
+    let mut modules = match gate {
+        Gate::PythonImports { module, .. } => vec![module.as_str()],
+        _ => Vec::new(),
+    };
+    modules.extend(gate.children().iter().flat_map(gate_python_modules));
+    modules
+}
+
+/// JSON paths and their assertions declared by a gate and its child gates.
+fn gate_json_paths(gate: &Gate) -> Vec<(&str, &str)> {
+    let mut paths = match gate {
+        Gate::CommandJsonPath {
+            path, assertion, ..
+        } => vec![(path.as_str(), assertion.as_str())],
+        Gate::FileJsonPath {
+            json_path,
+            assertion,
+            ..
+        } => vec![(json_path.as_str(), assertion.as_str())],
+        _ => Vec::new(),
+    };
+    paths.extend(gate.children().iter().flat_map(gate_json_paths));
+    paths
+}
+
+/// Regex patterns declared by a gate and its child gates.
+fn gate_patterns(gate: &Gate) -> Vec<&str> {
+    let mut patterns = match gate {
+        Gate::CommandOutputMatches { pattern, .. }
+        | Gate::FileMatches { pattern, .. }
+        | Gate::ProcessRunning { pattern } => vec![pattern.as_str()],
+        Gate::FileMatchesAll { patterns, .. } => patterns.iter().map(|p| p.as_str()).collect(),
+        Gate::ScriptEnv { matches, .. } => matches.values().map(|p| p.as_str()).collect(),
+        Gate::TranscriptMilestones { milestones, .. } => {
+            milestones.iter().map(|m| m.as_str()).collect()
+        }
+        Gate::LintClean {
+            pattern: Some(pattern),
+            ..
+        } => vec![pattern.as_str()],
+        _ => Vec::new(),
+    };
+    patterns.extend(gate.children().iter().flat_map(gate_patterns));
+    patterns
+}
+
+/// Whether a gate (or a child gate) inspects the tool's transcript or runs a
+/// scenario script, neither of which is available to `setup_assertions`.
+fn needs_tool_run(gate: &Gate) -> bool {
+    matches!(
+        gate,
+        Gate::TranscriptMilestones { .. } | Gate::NoTranscriptErrors | Gate::Script { .. }
+    ) || gate.children().iter().any(needs_tool_run)
+}
+
+/// Pass thresholds declared by soft-score gates, including child gates.
+fn gate_thresholds(gate: &Gate) -> Vec<f64> {
+    let threshold = match gate {
+        Gate::FilesExist { min_score, .. } | Gate::FileMatchesAll { min_score, .. } => *min_score,
+        Gate::TranscriptMilestones { min_fraction, .. } => *min_fraction,
+        _ => None,
+    };
+    threshold
+        .into_iter()
+        .chain(gate.children().iter().flat_map(gate_thresholds))
+        .collect()
+}
+
+/// Remove serde_yaml's trailing " at line X column Y" since location is reported separately.
+fn strip_location(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Locations of every `key:` in the document, including keys opening a list
+/// item (`- key:`).
+fn key_locations(content: &str, key: &str) -> Vec<(usize, usize)> {
+    let prefix = format!("{}:", key);
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let rest = line.trim_start_matches([' ', '-']);
+            rest.starts_with(&prefix)
+                .then(|| (i + 1, line.len() - rest.len() + 1))
+        })
+        .collect()
+}
+
+/// Location of the first `key:` in the document.
+fn key_location(content: &str, key: &str) -> Option<(usize, usize)> {
+    let prefix = format!("{}:", key);
+    content.lines().enumerate().find_map(|(i, line)| {
+        line.trim_start()
+            .starts_with(&prefix)
+            .then(|| (i + 1, indent_of(line) + 1))
+    })
+}
+
+/// Locations of each list item under the `gates:` key, in order.
+fn gate_item_locations(content: &str) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(start) = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("gates:") && indent_of(line) > 0)
+    else {
+        return Vec::new();
+    };
+    let key_indent = indent_of(lines[start]);
+
+    let mut locations = Vec::new();
+    let mut item_indent = None;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = indent_of(line);
+        let is_item = trimmed.starts_with("- ") || trimmed == "-";
+        match item_indent {
+            None if is_item && indent >= key_indent => item_indent = Some(indent),
+            None => break,
+            Some(expected) if indent < expected || (indent == expected && !is_item) => break,
+            Some(_) => {}
+        }
+        if is_item && Some(indent) == item_indent {
+            locations.push((i + 1, indent + 1));
+        }
+    }
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "name: test
+description: \"Test\"
+template_folder: fixture
+target:
+  binary: tool
+task:
+  prompt: \"Test prompt\"
+";
+
+    fn validate(yaml: &str) -> Vec<Diagnostic> {
+        let dir = tempfile::tempdir().unwrap();
+        validate_scenario_source(
+            "scenario.yaml",
+            yaml,
+            &dir.path().join("scenario.yaml"),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn valid_scenario_has_no_diagnostics() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_exists\n      path: README.md\n",
+            HEADER
+        );
+        assert!(validate(&yaml).is_empty());
+    }
+
+    #[test]
+    fn parse_error_reports_parser_location() {
+        let yaml = format!("{}evaluation:\n  gates:\n    - type: not_a_gate\n", HEADER);
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, 10);
+        assert!(diagnostics[0].message.contains("not_a_gate"));
+        assert!(!diagnostics[0].message.contains(" at line "));
+    }
+
+    #[test]
+    fn invalid_regex_points_at_gate() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n    - type: file_matches\n      path: b\n      pattern: \"(\"\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (12, 5));
+        assert!(diagnostics[0].message.contains("invalid regex"));
+    }
+
+    #[test]
+    fn invalid_command_pattern_points_at_target() {
+        let yaml = HEADER.replace(
+            "  binary: tool\n",
+            "  binary: tool\n  command_pattern: \"^(tool\"\n",
+        );
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n",
+            yaml
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (6, 3));
+        assert!(diagnostics[0]
+            .message
+            .contains("Invalid target.command_pattern '^(tool'"));
+    }
+
+    #[test]
+    fn invalid_sandbox_mount_points_at_sandbox() {
+        let yaml = format!(
+            "{}run:\n  sandbox:\n    image: tools:latest\n    mounts: [\"data\"]\nevaluation:\n  gates:\n    - type: file_exists\n      path: a\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (9, 3));
+        assert!(diagnostics[0]
+            .message
+            .contains("Invalid run.sandbox.mounts entry 'data'"));
+    }
+
+    #[test]
+    fn workdir_outside_run_directory_points_at_gate() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n    - type: file_exists\n      workdir: ../../elsewhere\n      path: b\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (12, 5));
+        assert!(diagnostics[0]
+            .message
+            .contains("Gate 2: Invalid workdir '../../elsewhere'"));
+    }
+
+    #[test]
+    fn negative_retry_delay_points_at_gate() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: port_open\n      port: 8080\n      retries: 3\n      retry_delay_secs: -1\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (10, 5));
+        assert!(diagnostics[0]
+            .message
+            .contains("Gate 1 retry_delay_secs -1 must be a non-negative number"));
+    }
+
+    #[test]
+    fn when_must_reference_earlier_gate() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n  - type: file_exists\n    when: build\n    path: a\n  - type: command_succeeds\n    id: build\n    command: \"true\"\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (10, 3));
+        assert!(diagnostics[0].message.contains("'build'"));
+    }
+
+    #[test]
+    fn child_gates_are_checked_and_located_at_their_parent() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: any_of\n      gates:\n        - type: file_matches\n          path: a\n          pattern: \"(\"\n    - type: file_exists\n      path: b\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (10, 5));
+        assert!(diagnostics[0]
+            .message
+            .contains("Gate 1 has invalid regex '('"));
+    }
+
+    #[test]
+    fn setup_assertions_cannot_use_transcript_gates() {
+        let yaml = format!(
+            "{}setup_assertions:\n  - type: file_exists\n    path: seed.md\n  - type: no_transcript_errors\nevaluation:\n  gates:\n    - type: file_exists\n      path: a\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 8);
+        assert!(diagnostics[0].message.starts_with("Setup assertion 2 "));
+    }
+
+    #[test]
+    fn unknown_gate_set_points_at_use_gates() {
+        let yaml = format!("{}evaluation:\n  use_gates: [missing]\n", HEADER);
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 9);
+        assert!(diagnostics[0].message.contains("gatesets.yaml"));
+    }
+
+    #[test]
+    fn taxonomy_violation_points_at_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("taxonomy.yaml"), "difficulties: [easy]\n").unwrap();
+        let yaml = format!(
+            "{}evaluation:\n  use_gates: []\n  gates: []\ndifficulty: hard\n",
+            HEADER
+        );
+        let diagnostics = validate_scenario_source(
+            "scenario.yaml",
+            &yaml,
+            &dir.path().join("scenario.yaml"),
+            None,
+            None,
+        );
+        let errors: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| matches!(d.severity, Severity::Error))
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 11);
+        assert!(errors[0].message.contains("Unknown difficulty 'hard'"));
+    }
+
+    #[test]
+    fn missing_template_folder_is_a_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = format!("{}evaluation:\n  use_gates: []\n  gates: []\n", HEADER);
+        let diagnostics = validate_scenario_source(
+            "scenario.yaml",
+            &yaml,
+            &dir.path().join("scenario.yaml"),
+            Some(dir.path()),
+            None,
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+        assert_eq!(diagnostics[1].line, 3);
+    }
+
+    #[test]
+    fn negative_weight_is_an_error() {
+        let yaml = format!(
+            "{}weight: -1\nevaluation:\n  gates:\n    - type: file_exists\n      path: a\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 8);
+        assert!(diagnostics[0].message.contains("weight"));
+    }
+
+    #[test]
+    fn invalid_json_paths_and_assertions_are_errors() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_json_path\n      path: out.json\n      json_path: items[0]\n      assertion: exists\n    - type: all_of\n      gates:\n        - type: command_json_path\n          command: tool list --json\n          path: $.items\n          assertion: len >= 1\n        - type: command_json_path\n          command: tool list --json\n          path: $.items\n          assertion: has 3 items\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].line, 10);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("Gate 1 has invalid JSON path 'items[0]'"));
+        assert_eq!(diagnostics[1].line, 14);
+        assert!(diagnostics[1]
+            .message
+            .starts_with("Gate 2 has invalid assertion 'has 3 items'"));
+    }
+
+    #[test]
+    fn invalid_ignore_paths_are_errors() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: json_equals_file\n      path: out.json\n      expected_file: expected.json\n      ignore: [\"$.items[*].id\", \"$.items[x]\"]\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("Gate 1 has invalid ignore path '$.items[x]'"));
+    }
+
+    #[test]
+    fn csv_column_names_need_a_header() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: csv_cell_equals\n      path: out.csv\n      row: 0\n      column: total\n      value: \"3\"\n      header: false\n    - type: csv_cell_equals\n      path: out.csv\n      row: 0\n      column: 2\n      value: \"3\"\n      header: false\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("Gate 1 has column name 'total' but header: false"));
+    }
+
+    #[test]
+    fn script_env_gates_need_valid_checks() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: script_env\n      path: setup.sh\n      env:\n        APP-ENV: test\n      matches:\n        APP_PORT: \"[0-9\"\n    - type: script_env\n      path: setup.sh\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(diagnostics.len(), 3, "{:?}", messages);
+        assert!(messages[0].starts_with("Gate 1 has invalid regex '[0-9'"));
+        assert_eq!(
+            messages[1],
+            "Gate 1 has invalid environment variable name 'APP-ENV'"
+        );
+        assert_eq!(messages[2], "Gate 2 has no env, matches or unset checks");
+    }
+
+    #[test]
+    fn each_judge_of_an_ensemble_is_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("rubrics")).unwrap();
+        std::fs::write(
+            dir.path().join("rubrics/good.yaml"),
+            "criteria:\n  - id: quality\n    weight: 1.0\n    description: Quality\n\
+             output:\n  format: json\n  require_fields: [scores]\n",
+        )
+        .unwrap();
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n  judge:\n    - enabled: true\n      rubric: rubrics/good.yaml\n      pass_threshold: 0.7\n    - enabled: true\n      rubric: rubrics/missing.yaml\n      pass_threshold: 1.5\n",
+            HEADER
+        );
+        let path = dir.path().join("s.yaml");
+        let diagnostics = validate_scenario_source("s.yaml", &yaml, &path, None, Some(dir.path()));
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(
+            diagnostics[0].message,
+            "Judge 2 pass_threshold 1.5 must be between 0.0 and 1.0"
+        );
+        assert!(diagnostics[1]
+            .message
+            .starts_with("Rubric 'rubrics/missing.yaml' not found"));
+        assert_eq!(
+            diagnostics[1].line,
+            yaml.lines()
+                .position(|l| l.contains("missing.yaml"))
+                .unwrap()
+                + 1
+        );
+    }
+
+    #[test]
+    fn unknown_fields_point_at_the_key() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n      workdri: out\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (12, 7));
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown field 'workdri' in evaluation.gates[0] (did you mean 'workdir'?)"
+        );
+
+        assert!(validate(&format!("strict: false\n{}", yaml)).is_empty());
+    }
+
+    #[test]
+    fn invalid_python_module_is_an_error() {
+        let yaml = format!(
+            "{}evaluation:\n  gates:\n    - type: python_imports\n      module: \"app; print(1)\"\n",
+            HEADER
+        );
+        let diagnostics = validate(&yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 10);
+        assert!(diagnostics[0]
+            .message
+            .contains("Gate 1 has invalid Python module name 'app; print(1)'"));
+    }
+
+    #[test]
+    fn judge_rubric_is_resolved_and_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("rubrics")).unwrap();
+        std::fs::write(
+            dir.path().join("rubrics/good.yaml"),
+            "criteria:\n  - id: quality\n    weight: 1.0\n    description: Quality\n\
+             output:\n  format: json\n  require_fields: [scores]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("rubrics/bad.yaml"),
+            "criteria:\n  - id: quality\n    weight: 0.5\n    description: Quality\n\
+             output:\n  format: json\n  require_fields: [scores]\n",
+        )
+        .unwrap();
+        let check = |rubric: &str, enabled: bool| {
+            let yaml = format!(
+                "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n  judge:\n    enabled: {}\n    rubric: {}\n    pass_threshold: 0.7\n",
+                HEADER, enabled, rubric
+            );
+            validate_scenario_source(
+                "scenario.yaml",
+                &yaml,
+                &dir.path().join("scenario.yaml"),
+                None,
+                Some(dir.path()),
+            )
+        };
+
+        assert!(check("rubrics/good.yaml", true).is_empty());
+
+        let bad = check("rubrics/bad.yaml", true);
+        assert_eq!(bad.len(), 1);
+        assert_eq!(bad[0].severity, Severity::Error);
+        assert_eq!(bad[0].line, 14);
+        assert!(bad[0].message.contains("must sum to 1.0"));
+
+        let missing = check("rubrics/missing.yaml", true);
+        assert_eq!(missing.len(), 1);
+        assert!(missing[0]
+            .message
+            .starts_with("Rubric 'rubrics/missing.yaml' not found"));
+
+        let disabled = check("rubrics/missing.yaml", false);
+        assert_eq!(disabled[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn composite_weights_are_checked() {
+        let check = |weights: &str| {
+            let yaml = format!(
+                "{}evaluation:\n  gates:\n    - type: file_exists\n      path: a\n  composite:\n{}",
+                HEADER, weights
+            );
+            validate(&yaml)
+        };
+
+        assert!(check(
+            "    judge_weight: 0.5\n    gate_weight: 0.5\n    interaction_weight: 0.0\n"
+        )
+        .is_empty());
+        // Defaults sum to 1.0
+        assert!(check("    penalties:\n      per_error: 0.1\n").is_empty());
+
+        let uneven = check("    judge_weight: 0.5\n    gate_weight: 0.5\n");
+        assert_eq!(uneven.len(), 1);
+        assert_eq!(uneven[0].severity, Severity::Warning);
+        assert_eq!(uneven[0].line, 12);
+        assert!(uneven[0].message.contains("sum to 1.1"));
+
+        let negative = check("    gate_weight: -0.35\n    judge_weight: 1.25\n");
+        assert_eq!(negative.len(), 1);
+        assert_eq!(negative[0].severity, Severity::Error);
+        assert_eq!(negative[0].line, 13);
+        assert!(negative[0].message.contains("gate_weight -0.35"));
+    }
+
+    #[test]
+    fn rubric_files_are_not_scenarios() {
+        assert!(!looks_like_scenario("criteria:\n  - id: clarity\n"));
+        assert!(looks_like_scenario(HEADER));
+        assert!(looks_like_scenario("name: [unterminated"));
+    }
+}
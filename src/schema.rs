@@ -0,0 +1,105 @@
+//! JSON Schemas for scenario files, result records and transcript events.
+//!
+//! The generated schemas are also checked in under `schemas/` for editor
+//! integration; `llm-tool-test schema --out-dir schemas` regenerates them.
+
+use crate::results::ResultRecord;
+use crate::scenario::Scenario;
+use crate::transcript::types::EventRecord;
+use clap::ValueEnum;
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use std::path::Path;
+
+/// Formats with a published JSON Schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaKind {
+    /// Scenario YAML files
+    Scenario,
+    /// Result records in `results.jsonl`
+    Result,
+    /// Lines of a run's `events.jsonl`
+    Events,
+}
+
+impl SchemaKind {
+    /// File name used when writing the schema to a directory.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            SchemaKind::Scenario => "scenario.schema.json",
+            SchemaKind::Result => "result.schema.json",
+            SchemaKind::Events => "events.schema.json",
+        }
+    }
+
+    /// Generate the JSON Schema for this format.
+    pub fn schema(self) -> RootSchema {
+        match self {
+            SchemaKind::Scenario => schema_for!(Scenario),
+            SchemaKind::Result => schema_for!(ResultRecord),
+            SchemaKind::Events => schema_for!(EventRecord),
+        }
+    }
+
+    /// Pretty-printed schema JSON with a trailing newline.
+    pub fn render(self) -> anyhow::Result<String> {
+        Ok(format!(
+            "{}\n",
+            serde_json::to_string_pretty(&self.schema())?
+        ))
+    }
+}
+
+/// Print a schema to stdout, or write schemas to `out_dir`.
+///
+/// With `out_dir` and no kind, all schemas are written.
+pub fn handle_schema_command(
+    kind: Option<SchemaKind>,
+    out_dir: Option<&Path>,
+) -> anyhow::Result<()> {
+    match (kind, out_dir) {
+        (_, Some(dir)) => {
+            std::fs::create_dir_all(dir)?;
+            let kinds = match kind {
+                Some(kind) => vec![kind],
+                None => SchemaKind::value_variants().to_vec(),
+            };
+            for kind in kinds {
+                let path = dir.join(kind.file_name());
+                std::fs::write(&path, kind.render()?)?;
+                println!("Wrote {}", path.display());
+            }
+        }
+        (Some(kind), None) => print!("{}", kind.render()?),
+        (None, None) => anyhow::bail!("Specify a schema (scenario, result, events) or --out-dir"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_in_schemas_are_up_to_date() {
+        let schemas_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas");
+        for kind in SchemaKind::value_variants() {
+            let path = schemas_dir.join(kind.file_name());
+            let checked_in = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+            assert_eq!(
+                checked_in,
+                kind.render().unwrap(),
+                "{} is stale; run `llm-tool-test schema --out-dir schemas`",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn scenario_schema_lists_gate_types() {
+        let json = SchemaKind::Scenario.render().unwrap();
+        assert!(json.contains("\"command_succeeds\""));
+        assert!(json.contains("\"use_gates\""));
+    }
+}
@@ -1,13 +1,15 @@
 //! Script runner utility for executing post-evaluation and custom evaluator scripts.
 //!
 //! This module provides a `ScriptRunner` that executes shell commands in the fixture
-//! directory with the appropriate environment variables set. It supports timeout
-//! enforcement using the `wait-timeout` crate.
+//! directory with the appropriate environment variables set. Commands run through a
+//! [`CommandExecutor`], which enforces the timeout.
 
+use crate::exec::{CommandExecutor, SystemExecutor};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use wait_timeout::ChildExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Result of executing a script.
 #[derive(Debug, Clone, PartialEq)]
@@ -40,6 +42,7 @@ pub struct ScriptRunner {
     transcript_path: Option<PathBuf>,
     events_path: Option<PathBuf>,
     target_env: HashMap<String, String>,
+    executor: Arc<dyn CommandExecutor>,
 }
 
 impl ScriptRunner {
@@ -64,56 +67,48 @@ impl ScriptRunner {
             transcript_path,
             events_path,
             target_env,
+            executor: Arc::new(SystemExecutor),
         }
     }
 
+    /// Transcript of the run being evaluated, if one was written.
+    pub fn transcript_path(&self) -> Option<&Path> {
+        self.transcript_path.as_deref()
+    }
+
+    /// Run directory holding the fixture and the run's artifacts.
+    pub fn results_dir(&self) -> &Path {
+        &self.results_dir
+    }
+
+    /// Run scripts through `executor` instead of spawning processes directly.
+    pub fn with_executor(mut self, executor: Arc<dyn CommandExecutor>) -> Self {
+        self.executor = executor;
+        self
+    }
+
     /// Run a shell command with the configured environment.
     ///
     /// The command is executed via `sh -c` in the fixture directory with
-    /// LLM_TOOL_TEST_* environment variables set. The timeout is enforced
-    /// using the wait-timeout crate.
+    /// LLM_TOOL_TEST_* environment variables set, and killed after
+    /// `timeout_secs`.
     pub fn run(&self, command: &str, timeout_secs: u64) -> anyhow::Result<ScriptResult> {
-        let mut child = Command::new("sh")
-            .arg("-c")
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
             .arg(command)
             .current_dir(&self.fixture_dir)
-            .envs(self.build_env())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| anyhow::anyhow!("Failed to spawn script: {}", e))?;
-
-        let timeout = std::time::Duration::from_secs(timeout_secs);
-        let result = match child.wait_timeout(timeout) {
-            Ok(Some(status)) => {
-                let exit_code = status.code().unwrap_or(-1);
-                let stdout = self.read_child_stdout(&mut child)?;
-                let stderr = self.read_child_stderr(&mut child)?;
-                ScriptResult {
-                    exit_code,
-                    stdout,
-                    stderr,
-                    timed_out: false,
-                }
-            }
-            Ok(None) => {
-                let _ = child.kill();
-                let stdout = self.read_child_stdout(&mut child)?;
-                let stderr = self.read_child_stderr(&mut child)?;
-                ScriptResult {
-                    exit_code: -1,
-                    stdout,
-                    stderr,
-                    timed_out: true,
-                }
-            }
-            Err(e) => {
-                let _ = child.kill();
-                return Err(anyhow::anyhow!("Error waiting for script: {}", e));
-            }
-        };
-
-        Ok(result)
+            .envs(self.build_env());
+        let result = self
+            .executor
+            .run(cmd, None, Some(Duration::from_secs(timeout_secs)))
+            .map_err(|e| anyhow::anyhow!("Failed to run script: {}", e))?;
+
+        Ok(ScriptResult {
+            exit_code: result.exit_code(),
+            stdout: String::from_utf8_lossy(&result.output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&result.output.stderr).into_owned(),
+            timed_out: result.timed_out,
+        })
     }
 
     /// Build the environment variables for script execution.
@@ -157,26 +152,6 @@ impl ScriptRunner {
 
         env
     }
-
-    fn read_child_stdout(&self, child: &mut std::process::Child) -> anyhow::Result<String> {
-        let mut stdout = String::new();
-        if let Some(ref mut pipe) = child.stdout {
-            use std::io::Read;
-            pipe.read_to_string(&mut stdout)
-                .map_err(|e| anyhow::anyhow!("Failed to read stdout: {}", e))?;
-        }
-        Ok(stdout)
-    }
-
-    fn read_child_stderr(&self, child: &mut std::process::Child) -> anyhow::Result<String> {
-        let mut stderr = String::new();
-        if let Some(ref mut pipe) = child.stderr {
-            use std::io::Read;
-            pipe.read_to_string(&mut stderr)
-                .map_err(|e| anyhow::anyhow!("Failed to read stderr: {}", e))?;
-        }
-        Ok(stderr)
-    }
 }
 
 #[cfg(test)]
@@ -303,4 +278,32 @@ mod tests {
         assert!(result.succeeded());
         assert!(result.stdout.contains("overridden"));
     }
+
+    #[test]
+    fn test_fake_executor_timeout_and_exit_code() {
+        use crate::exec::fake::{FakeExecutor, FakeResponse};
+        let executor = Arc::new(FakeExecutor::new([
+            FakeResponse::slow(Duration::from_secs(600)),
+            FakeResponse::exit(42, "partial").with_stderr("boom"),
+        ]));
+        let runner =
+            create_test_runner(PathBuf::from("/nonexistent")).with_executor(executor.clone());
+
+        let result = runner.run("./slow-check.sh", 5).unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, -1);
+
+        let result = runner.run("./check.sh", 5).unwrap();
+        assert!(!result.succeeded());
+        assert_eq!(result.exit_code, 42);
+        assert_eq!(result.stderr, "boom");
+
+        let calls = executor.calls();
+        assert_eq!(calls[0].args, vec!["-c", "./slow-check.sh"]);
+        assert_eq!(calls[0].timeout, Some(Duration::from_secs(5)));
+        assert_eq!(calls[0].cwd, Some(PathBuf::from("/nonexistent")));
+        assert!(calls[0]
+            .env
+            .contains(&("LLM_TOOL_TEST_AGENT".to_string(), "test_agent".to_string())));
+    }
 }
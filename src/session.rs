@@ -1,22 +1,136 @@
+use crate::adapter::{AdapterError, Progress, StallTimeout, StreamCapture, TurnLimit};
+use crate::sandbox::Sandbox;
 use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
 use std::io::Read;
 use std::path::Path;
 use std::sync::{mpsc::channel, Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use wait_timeout::ChildExt;
+
+/// How often a running command is checked against its turn limit.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Recognizes the output lines that start a model turn.
+type TurnEventFn = fn(&str) -> bool;
+
+/// Counts turn events in a command's output, line by line.
+struct TurnMonitor {
+    limit: TurnLimit,
+    is_turn: TurnEventFn,
+    turns: usize,
+    line: Vec<u8>,
+}
+
+impl TurnMonitor {
+    /// Feed output bytes, marking the limit reached once more than
+    /// `max_turns` turn events were seen.
+    fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+            if (self.is_turn)(String::from_utf8_lossy(&self.line).trim()) {
+                self.turns += 1;
+                if self.turns > self.limit.max_turns {
+                    self.limit.mark_reached();
+                }
+            }
+            self.line.clear();
+        }
+    }
+}
 
 #[cfg(test)]
 use std::fs;
 
 pub struct SessionRunner {
     pub pty_system: NativePtySystem,
+    turn_limit: Option<(TurnLimit, TurnEventFn)>,
+    stall_timeout: Option<StallTimeout>,
+    progress: Option<Progress>,
+    streams: Option<StreamCapture>,
+    sandbox: Option<Sandbox>,
 }
 
 impl SessionRunner {
     pub fn new() -> Self {
         Self {
             pty_system: NativePtySystem::default(),
+            turn_limit: None,
+            stall_timeout: None,
+            progress: None,
+            streams: None,
+            sandbox: None,
+        }
+    }
+
+    /// Terminate commands once their output contains more than
+    /// `limit.max_turns` lines for which `is_turn` returns true.
+    pub fn with_turn_limit(mut self, limit: Option<TurnLimit>, is_turn: TurnEventFn) -> Self {
+        self.turn_limit = limit.map(|limit| (limit, is_turn));
+        self
+    }
+
+    /// Terminate commands that produce no output for `stall.secs`.
+    pub fn with_stall_timeout(mut self, stall: Option<StallTimeout>) -> Self {
+        self.stall_timeout = stall;
+        self
+    }
+
+    /// Feed all command output into `progress` as it is read.
+    pub fn with_progress(mut self, progress: Option<Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Record stdout and stderr separately in `streams` for commands that run
+    /// on pipes; a PTY merges them, so it is skipped when `require_pipes` is set.
+    pub fn with_stream_capture(mut self, streams: Option<StreamCapture>) -> Self {
+        self.streams = streams;
+        self
+    }
+
+    /// Start commands inside `sandbox` instead of on the host.
+    pub fn with_sandbox(mut self, sandbox: Option<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    fn turn_monitor(&self) -> Option<TurnMonitor> {
+        self.turn_limit
+            .as_ref()
+            .map(|(limit, is_turn)| TurnMonitor {
+                limit: limit.clone(),
+                is_turn: *is_turn,
+                turns: 0,
+                line: Vec::new(),
+            })
+    }
+
+    /// Whether a running command must be killed: its turn limit was
+    /// exceeded or it has been silent since `last_output` for too long.
+    fn should_stop(&self, last_output: &Mutex<Instant>) -> bool {
+        if let Some(stall) = &self.stall_timeout {
+            let idle = last_output.lock().map(|t| t.elapsed()).unwrap_or_default();
+            if idle >= stall.duration() {
+                stall.mark_stalled();
+                return true;
+            }
         }
+        self.turn_limit
+            .as_ref()
+            .is_some_and(|(limit, _)| limit.reached())
+    }
+
+    /// Whether a command was killed at its stall or turn limit.
+    fn was_stopped(&self) -> bool {
+        self.stall_timeout.as_ref().is_some_and(|t| t.stalled())
+            || self
+                .turn_limit
+                .as_ref()
+                .is_some_and(|(limit, _)| limit.reached())
     }
 
     pub fn run_command(
@@ -37,9 +151,38 @@ impl SessionRunner {
         timeout_secs: u64,
         env_vars: &[(String, String)],
     ) -> anyhow::Result<(String, i32)> {
+        if let Some(sandbox) = &self.sandbox {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let env: Vec<String> = env_vars.iter().map(|(k, _)| k.clone()).collect();
+            let name = Sandbox::container_name();
+            let argv = sandbox.wrap(&name, cmd, &args, cwd, &env, false);
+            let argv: Vec<&str> = argv.iter().map(String::as_str).collect();
+            let result = self.run_on_host(sandbox.engine(), &argv, cwd, timeout_secs, env_vars);
+            // Killing the engine client leaves the container running
+            if result.is_err() || self.was_stopped() {
+                sandbox.remove(&name, &crate::exec::SystemExecutor);
+            }
+            return result;
+        }
+        self.run_on_host(cmd, args, cwd, timeout_secs, env_vars)
+    }
+
+    fn run_on_host(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        cwd: &Path,
+        timeout_secs: u64,
+        env_vars: &[(String, String)],
+    ) -> anyhow::Result<(String, i32)> {
+        if self.streams.as_ref().is_some_and(|s| s.require_pipes) {
+            return self.run_command_piped_with_env(cmd, args, cwd, timeout_secs, env_vars);
+        }
         // Try PTY first, fall back to piped stdout/stderr if PTY unavailable
         match self.run_command_pty_with_env(cmd, args, cwd, timeout_secs, env_vars) {
             Ok(result) => Ok(result),
+            // The command ran on the PTY; running it again would double its time
+            Err(e) if AdapterError::is_timeout(&e) => Err(e),
             Err(e) => {
                 tracing::debug!("PTY unavailable, falling back to pipes: {}", e);
                 self.run_command_piped_with_env(cmd, args, cwd, timeout_secs, env_vars)
@@ -70,6 +213,7 @@ impl SessionRunner {
         }
 
         let child = pair.slave.spawn_command(cmd_builder)?;
+        let mut killer = child.clone_killer();
         let mut reader = pair.master.try_clone_reader()?;
 
         // Drop slave to close the handle in the parent process.
@@ -82,6 +226,10 @@ impl SessionRunner {
         let child = Arc::new(Mutex::new(child));
 
         // Spawn thread to read output
+        let mut monitor = self.turn_monitor();
+        let last_output = Arc::new(Mutex::new(Instant::now()));
+        let reader_activity = Arc::clone(&last_output);
+        let progress = self.progress.clone();
         let reader_thread = thread::spawn(move || {
             let mut buf = [0u8; 1024];
             let mut output = Vec::new();
@@ -90,6 +238,15 @@ impl SessionRunner {
                     break;
                 }
                 output.extend_from_slice(&buf[..n]);
+                if let Ok(mut last) = reader_activity.lock() {
+                    *last = Instant::now();
+                }
+                if let Some(progress) = &progress {
+                    progress.record(&buf[..n]);
+                }
+                if let Some(monitor) = monitor.as_mut() {
+                    monitor.feed(&buf[..n]);
+                }
             }
             let _ = output_tx.send(output);
         });
@@ -108,21 +265,27 @@ impl SessionRunner {
             }
         });
 
-        // Wait for status with timeout
-        let timeout_duration = Duration::from_secs(timeout_secs);
-        let wait_result = status_rx.recv_timeout(timeout_duration);
-
-        let exit_status = match wait_result {
-            Ok(Ok(status)) => status,
-            Ok(Err(_)) => {
-                return Err(anyhow::anyhow!("Failed to wait for child process"));
+        // Wait for status with timeout, stopping the child at the turn or stall limit
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let exit_status = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                let _ = killer.kill();
+                return Err(AdapterError::command_timed_out(timeout_secs).into());
             }
-            Err(_) => {
-                // Timeout occurred
-                return Err(anyhow::anyhow!(
-                    "Command timed out after {} seconds",
-                    timeout_secs
-                ));
+            match status_rx.recv_timeout(remaining.min(POLL_INTERVAL)) {
+                Ok(Ok(status)) => break status,
+                Ok(Err(_)) => {
+                    return Err(anyhow::anyhow!("Failed to wait for child process"));
+                }
+                Err(_) if self.should_stop(&last_output) => {
+                    let _ = killer.kill();
+                    match status_rx.recv() {
+                        Ok(Ok(status)) => break status,
+                        _ => return Err(anyhow::anyhow!("Failed to wait for child process")),
+                    }
+                }
+                Err(_) => {}
             }
         };
 
@@ -166,9 +329,11 @@ impl SessionRunner {
             .take()
             .ok_or_else(|| anyhow::anyhow!("Failed to capture stderr"))?;
 
-        let (status_tx, status_rx) = channel();
-
         // Spawn threads to read stdout and stderr
+        let mut monitor = self.turn_monitor();
+        let last_output = Arc::new(Mutex::new(Instant::now()));
+        let stdout_activity = Arc::clone(&last_output);
+        let stdout_progress = self.progress.clone();
         let stdout_thread = thread::spawn(move || {
             use std::io::Read;
             let mut buf = [0u8; 1024];
@@ -179,10 +344,21 @@ impl SessionRunner {
                     break;
                 }
                 output.extend_from_slice(&buf[..n]);
+                if let Ok(mut last) = stdout_activity.lock() {
+                    *last = Instant::now();
+                }
+                if let Some(progress) = &stdout_progress {
+                    progress.record(&buf[..n]);
+                }
+                if let Some(monitor) = monitor.as_mut() {
+                    monitor.feed(&buf[..n]);
+                }
             }
             output
         });
 
+        let stderr_activity = Arc::clone(&last_output);
+        let stderr_progress = self.progress.clone();
         let stderr_thread = thread::spawn(move || {
             use std::io::Read;
             let mut buf = [0u8; 1024];
@@ -193,48 +369,46 @@ impl SessionRunner {
                     break;
                 }
                 output.extend_from_slice(&buf[..n]);
+                if let Ok(mut last) = stderr_activity.lock() {
+                    *last = Instant::now();
+                }
+                if let Some(progress) = &stderr_progress {
+                    progress.record(&buf[..n]);
+                }
             }
             output
         });
 
-        // Spawn thread to wait for child process
-        let wait_thread = thread::spawn(move || match child.wait() {
-            Ok(status) => {
-                let _ = status_tx.send(Ok(status));
+        // Wait for status with timeout, stopping the child at the turn or stall limit
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let exit_status = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(AdapterError::command_timed_out(timeout_secs).into());
             }
-            Err(e) => {
-                let _ = status_tx.send(Err(e));
-            }
-        });
-
-        // Wait for status with timeout
-        let timeout_duration = Duration::from_secs(timeout_secs);
-        let wait_result = status_rx.recv_timeout(timeout_duration);
-
-        let exit_status = match wait_result {
-            Ok(Ok(status)) => status,
-            Ok(Err(_)) => {
-                return Err(anyhow::anyhow!("Failed to wait for child process"));
-            }
-            Err(_) => {
-                // Timeout occurred
-                return Err(anyhow::anyhow!(
-                    "Command timed out after {} seconds",
-                    timeout_secs
-                ));
+            match child.wait_timeout(remaining.min(POLL_INTERVAL)) {
+                Ok(Some(status)) => break status,
+                Ok(None) if self.should_stop(&last_output) => {
+                    let _ = child.kill();
+                    break child.wait()?;
+                }
+                Ok(None) => {}
+                Err(_) => {
+                    return Err(anyhow::anyhow!("Failed to wait for child process"));
+                }
             }
         };
 
         // Collect output from both streams
-        let mut combined_output = Vec::new();
-        if let Ok(stdout_data) = stdout_thread.join() {
-            combined_output.extend_from_slice(&stdout_data);
-        }
-        if let Ok(stderr_data) = stderr_thread.join() {
-            combined_output.extend_from_slice(&stderr_data);
+        let stdout_data = stdout_thread.join().unwrap_or_default();
+        let stderr_data = stderr_thread.join().unwrap_or_default();
+        if let Some(streams) = &self.streams {
+            streams.set(&stdout_data, &stderr_data);
         }
-
-        let _ = wait_thread.join();
+        let mut combined_output = stdout_data;
+        combined_output.extend_from_slice(&stderr_data);
 
         let exit_code = exit_status.code().unwrap_or(-1);
         Ok((
@@ -260,6 +434,125 @@ mod tests {
         assert_eq!(exit_code, 0);
     }
 
+    #[test]
+    fn test_turn_limit_stops_command() {
+        let limit = TurnLimit::new(2);
+        let runner =
+            SessionRunner::new().with_turn_limit(Some(limit.clone()), |line| line == "turn");
+        let dir = tempdir().unwrap();
+        let started = std::time::Instant::now();
+        let (output, _) = runner
+            .run_command(
+                "sh",
+                &[
+                    "-c",
+                    "for i in 1 2 3 4 5 6; do echo turn; sleep 1; done; echo finished",
+                ],
+                dir.path(),
+                30,
+            )
+            .unwrap();
+        assert!(limit.reached());
+        assert!(!output.contains("finished"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_turn_limit_not_reached() {
+        let limit = TurnLimit::new(3);
+        let runner =
+            SessionRunner::new().with_turn_limit(Some(limit.clone()), |line| line == "turn");
+        let dir = tempdir().unwrap();
+        let (output, exit_code) = runner
+            .run_command(
+                "sh",
+                &["-c", "echo turn; echo turn; echo finished"],
+                dir.path(),
+                10,
+            )
+            .unwrap();
+        assert!(!limit.reached());
+        assert!(output.contains("finished"));
+        assert_eq!(exit_code, 0);
+    }
+
+    #[test]
+    fn test_stall_timeout_stops_silent_command() {
+        let stall = StallTimeout::new(1);
+        let runner = SessionRunner::new().with_stall_timeout(Some(stall.clone()));
+        let dir = tempdir().unwrap();
+        let started = std::time::Instant::now();
+        let (output, _) = runner
+            .run_command(
+                "sh",
+                &["-c", "echo started; sleep 10; echo finished"],
+                dir.path(),
+                30,
+            )
+            .unwrap();
+        assert!(stall.stalled());
+        assert!(output.contains("started"));
+        assert!(!output.contains("finished"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_stall_timeout_allows_steady_output() {
+        let stall = StallTimeout::new(1);
+        let runner = SessionRunner::new().with_stall_timeout(Some(stall.clone()));
+        let dir = tempdir().unwrap();
+        let (output, exit_code) = runner
+            .run_command(
+                "sh",
+                &[
+                    "-c",
+                    "for i in 1 2 3 4; do echo tick; sleep 0.5; done; echo finished",
+                ],
+                dir.path(),
+                30,
+            )
+            .unwrap();
+        assert!(!stall.stalled());
+        assert!(output.contains("finished"));
+        assert_eq!(exit_code, 0);
+    }
+
+    #[test]
+    fn test_progress_counts_output_and_commands() {
+        let progress = Progress::new(r"^\s*(mytool)\s+(\S+)");
+        let runner = SessionRunner::new().with_progress(Some(progress.clone()));
+        let dir = tempdir().unwrap();
+        runner
+            .run_command(
+                "sh",
+                &["-c", "echo mytool list; echo other; echo mytool show 1"],
+                dir.path(),
+                10,
+            )
+            .unwrap();
+        assert_eq!(progress.commands(), 2);
+        assert!(progress.bytes() >= 33);
+    }
+
+    #[test]
+    fn test_stream_capture_separates_stderr() {
+        let streams = StreamCapture::new(true);
+        let runner = SessionRunner::new().with_stream_capture(Some(streams.clone()));
+        let dir = tempdir().unwrap();
+        let (output, _) = runner
+            .run_command(
+                "sh",
+                &["-c", "echo narration; echo failure >&2"],
+                dir.path(),
+                10,
+            )
+            .unwrap();
+        assert!(output.contains("narration") && output.contains("failure"));
+        let captured = streams.get().unwrap();
+        assert_eq!(captured.stdout, "narration\n");
+        assert_eq!(captured.stderr, "failure\n");
+    }
+
     #[test]
     fn test_command_exceeds_timeout() {
         let runner = SessionRunner::new();
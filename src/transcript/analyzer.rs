@@ -1,10 +1,30 @@
-use crate::transcript::types::{CommandEvent, EfficiencyMetrics};
+use crate::transcript::file_actions::{detect_wasted_work, extract_file_actions};
+use crate::transcript::normalize::normalize_terminal_output;
+use crate::transcript::types::{CommandEvent, ConversationMetrics, EfficiencyMetrics};
 use regex::Regex;
 
 pub struct TranscriptAnalyzer;
 
+/// Whether a transcript line is the structured `step_start` event that opens
+/// a model turn (opencode's JSON output).
+pub fn is_step_start_line(line: &str) -> bool {
+    line.starts_with('{')
+        && serde_json::from_str::<serde_json::Value>(line)
+            .is_ok_and(|json| json.get("type").and_then(|t| t.as_str()) == Some("step_start"))
+}
+
 const DEFAULT_COMMAND_PATTERN: &str = r"^\s*([a-z][a-z0-9_.-]*)\s+(--help|[a-z][a-z0-9_-]*)\b";
 
+/// Assistant messages that lay out a plan or explain the approach.
+const PLAN_PATTERN: &str = r"(?i)\b(plan|approach|i will|i'll|let me|first,|next,|then,|finally,|because|in order to|steps?:)";
+
+/// One model turn: the assistant's messages and how many tools it called.
+#[derive(Debug, Default)]
+struct Turn {
+    messages: Vec<String>,
+    tool_calls: usize,
+}
+
 impl TranscriptAnalyzer {
     #[allow(dead_code)]
     pub fn analyze(transcript: &str) -> EfficiencyMetrics {
@@ -42,6 +62,112 @@ impl TranscriptAnalyzer {
         Self::analyze_with_events(transcript, Some(commands))
     }
 
+    /// Conversation-structure metrics for the transcript.
+    ///
+    /// Structured transcripts (opencode's JSON events) give turns from
+    /// `step_start`, messages from `text` and tool calls from `tool_use`
+    /// events. Plain transcripts are split into blank-line separated blocks:
+    /// blocks with target commands or exit codes are tool-call turns, the
+    /// rest are assistant text turns.
+    pub fn analyze_conversation_for_target(
+        transcript: &str,
+        target_binary: &str,
+        command_pattern: Option<&str>,
+    ) -> ConversationMetrics {
+        let transcript = normalize_terminal_output(transcript);
+        let turns = if transcript.lines().any(|l| is_step_start_line(l.trim())) {
+            Self::structured_turns(&transcript)
+        } else {
+            let pattern = Self::resolve_command_pattern(target_binary, command_pattern);
+            Self::plain_turns(&transcript, &pattern)
+        };
+
+        let messages: Vec<&String> = turns.iter().flat_map(|t| &t.messages).collect();
+        let tool_call_turns = turns.iter().filter(|t| t.tool_calls > 0).count();
+        let text_turns = turns.len() - tool_call_turns;
+        let avg_assistant_message_chars = if messages.is_empty() {
+            0.0
+        } else {
+            messages.iter().map(|m| m.chars().count()).sum::<usize>() as f64 / messages.len() as f64
+        };
+        let plan_messages = Regex::new(PLAN_PATTERN)
+            .map(|re| messages.iter().filter(|m| re.is_match(m)).count())
+            .unwrap_or(0);
+
+        ConversationMetrics {
+            turns: turns.len(),
+            assistant_messages: messages.len(),
+            avg_assistant_message_chars,
+            tool_call_turns,
+            text_turns,
+            tool_turn_ratio: tool_call_turns as f64 / text_turns.max(1) as f64,
+            plan_messages,
+        }
+    }
+
+    fn structured_turns(transcript: &str) -> Vec<Turn> {
+        let mut turns: Vec<Turn> = Vec::new();
+        for line in transcript.lines().map(str::trim) {
+            if !line.starts_with('{') {
+                continue;
+            }
+            let Ok(event) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+            let event_type = event.get("type").and_then(|t| t.as_str());
+            if event_type == Some("step_start") || turns.is_empty() {
+                turns.push(Turn::default());
+            }
+            let Some(turn) = turns.last_mut() else {
+                continue;
+            };
+            match event_type {
+                Some("text") => {
+                    let text = event
+                        .get("part")
+                        .and_then(|p| p.get("text"))
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("")
+                        .trim();
+                    if !text.is_empty() {
+                        turn.messages.push(text.to_string());
+                    }
+                }
+                Some("tool_use") => turn.tool_calls += 1,
+                _ => {}
+            }
+        }
+        turns
+    }
+
+    fn plain_turns(transcript: &str, command_pattern: &str) -> Vec<Turn> {
+        let command_regex = Regex::new(command_pattern).ok();
+        let exit_code_regex = Regex::new(r"(?i)exit\s+(?:code|status):?\s*\d+").unwrap();
+        transcript
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|block| !block.is_empty())
+            .map(|block| {
+                let tool_calls = block
+                    .lines()
+                    .filter(|line| command_regex.as_ref().is_some_and(|re| re.is_match(line)))
+                    .count()
+                    .max(usize::from(exit_code_regex.is_match(block)));
+                if tool_calls > 0 {
+                    Turn {
+                        messages: Vec::new(),
+                        tool_calls,
+                    }
+                } else {
+                    Turn {
+                        messages: vec![block.to_string()],
+                        tool_calls: 0,
+                    }
+                }
+            })
+            .collect()
+    }
+
     pub fn resolve_command_pattern(target_binary: &str, command_pattern: Option<&str>) -> String {
         if let Some(pattern) = command_pattern {
             if !pattern.trim().is_empty() {
@@ -105,9 +231,17 @@ impl TranscriptAnalyzer {
             help_invocations,
             first_try_success_rate,
             iteration_ratio,
+            wasted_actions: 0,
         }
     }
 
+    /// Count wasted file actions: files created then deleted, edits to files
+    /// outside `required`, and repeated reads of an unchanged file.
+    pub fn count_wasted_actions(transcript: &str, required: &[String]) -> usize {
+        let actions = extract_file_actions(&normalize_terminal_output(transcript));
+        detect_wasted_work(&actions, required).total()
+    }
+
     fn is_error_line(line: &str) -> bool {
         let line_lower = line.to_lowercase();
         line_lower.contains("error")
@@ -138,6 +272,7 @@ impl TranscriptAnalyzer {
         };
         let exit_code_regex = Regex::new(r"(?i)exit\s+(?:code|status):?\s*(\d+)").unwrap();
 
+        let transcript = normalize_terminal_output(transcript);
         let lines: Vec<&str> = transcript.lines().collect();
         let mut commands = Vec::new();
 
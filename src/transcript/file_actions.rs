@@ -0,0 +1,193 @@
+//! File reads, writes and deletes observed in a transcript, and the wasted
+//! work they reveal.
+//!
+//! Actions come from structured `tool_use` events (opencode's `read`,
+//! `write`, `edit` and `bash` tools) and from shell command lines in plain
+//! transcripts (`cat`, `rm`, `touch`, `sed -i`, `tee`, `>` redirects).
+
+use std::collections::HashMap;
+
+/// Reads of an unchanged file allowed before further reads count as waste.
+const REREAD_ALLOWANCE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileActionKind {
+    Read,
+    Write,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileAction {
+    pub kind: FileActionKind,
+    pub path: String,
+}
+
+/// Wasted actions found in a transcript, by pattern.
+#[derive(Debug, Default, PartialEq)]
+pub struct WastedWork {
+    /// Files written and later deleted
+    pub created_then_deleted: usize,
+    /// Files written that the task never referred to
+    pub unrequired_edits: usize,
+    /// Reads of a file beyond [`REREAD_ALLOWANCE`] without a write in between
+    pub repeated_reads: usize,
+}
+
+impl WastedWork {
+    pub fn total(&self) -> usize {
+        self.created_then_deleted + self.unrequired_edits + self.repeated_reads
+    }
+}
+
+/// Extract file actions in transcript order.
+pub fn extract_file_actions(transcript: &str) -> Vec<FileAction> {
+    let mut actions = Vec::new();
+    for line in transcript.lines().map(str::trim) {
+        if line.starts_with('{') {
+            if let Ok(event) = serde_json::from_str::<serde_json::Value>(line) {
+                actions.extend(actions_from_event(&event));
+                continue;
+            }
+        }
+        actions.extend(actions_from_shell(line.trim_start_matches("$ ")));
+    }
+    actions
+}
+
+fn actions_from_event(event: &serde_json::Value) -> Vec<FileAction> {
+    if event.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+        return Vec::new();
+    }
+    let part = &event["part"];
+    let input = &part["state"]["input"];
+    let path = ["filePath", "file_path", "path"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|p| p.as_str()));
+    let kind = match part.get("tool").and_then(|t| t.as_str()) {
+        Some("read") => FileActionKind::Read,
+        Some("write" | "edit" | "multiedit" | "patch") => FileActionKind::Write,
+        Some("bash") => {
+            return input
+                .get("command")
+                .and_then(|c| c.as_str())
+                .map(|c| c.lines().flat_map(actions_from_shell).collect())
+                .unwrap_or_default();
+        }
+        _ => return Vec::new(),
+    };
+    path.map(|path| action(kind, path)).into_iter().collect()
+}
+
+/// Actions of a shell command line; lines that are not commands yield none.
+fn actions_from_shell(line: &str) -> Vec<FileAction> {
+    let mut actions = Vec::new();
+    for command in line.split(['|', ';']).flat_map(|c| c.split("&&")) {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let Some((&program, args)) = words.split_first() else {
+            continue;
+        };
+        let operands = || {
+            args.iter()
+                .take_while(|a| !a.starts_with('>'))
+                .filter(|a| !a.starts_with('-'))
+        };
+        match program {
+            "cat" | "head" | "tail" | "less" | "more" | "bat" => actions.extend(
+                operands()
+                    .filter(|a| !a.starts_with('<'))
+                    .map(|a| action(FileActionKind::Read, a)),
+            ),
+            "rm" => actions.extend(operands().map(|a| action(FileActionKind::Delete, a))),
+            "touch" | "tee" => actions.extend(operands().map(|a| action(FileActionKind::Write, a))),
+            "sed" if args.iter().any(|a| a.starts_with("-i")) => {
+                if let Some(file) = operands().last() {
+                    actions.push(action(FileActionKind::Write, file));
+                }
+            }
+            _ => {}
+        }
+        // Output redirection writes its target: `> file`, `>> file`, `>file`
+        for (i, word) in words.iter().enumerate() {
+            let target = word.trim_start_matches(['1', '2']);
+            let target = target
+                .strip_prefix(">>")
+                .or_else(|| target.strip_prefix('>'));
+            match target {
+                Some("") => {
+                    if let Some(next) = words.get(i + 1) {
+                        actions.push(action(FileActionKind::Write, next));
+                    }
+                }
+                Some(file) if !file.starts_with('&') => {
+                    actions.push(action(FileActionKind::Write, file))
+                }
+                _ => {}
+            }
+        }
+    }
+    actions.retain(|a| !a.path.is_empty() && a.path != "/dev/null");
+    actions
+}
+
+fn action(kind: FileActionKind, path: &str) -> FileAction {
+    let path = path.trim_matches(['"', '\'']);
+    FileAction {
+        kind,
+        path: path.strip_prefix("./").unwrap_or(path).to_string(),
+    }
+}
+
+/// Find wasted work among `actions`.
+///
+/// `required` lists paths the task refers to (gate paths, files named in the
+/// prompt); unrequired edits are only counted when it is non-empty.
+pub fn detect_wasted_work(actions: &[FileAction], required: &[String]) -> WastedWork {
+    let mut wasted = WastedWork::default();
+    let mut reads_since_write: HashMap<&str, usize> = HashMap::new();
+    let mut written: Vec<&str> = Vec::new();
+    let mut deleted_after_write: Vec<&str> = Vec::new();
+
+    for action in actions {
+        let path = action.path.as_str();
+        match action.kind {
+            FileActionKind::Read => {
+                let reads = reads_since_write.entry(path).or_default();
+                if *reads >= REREAD_ALLOWANCE {
+                    wasted.repeated_reads += 1;
+                }
+                *reads += 1;
+            }
+            FileActionKind::Write => {
+                reads_since_write.insert(path, 0);
+                if !written.contains(&path) {
+                    written.push(path);
+                }
+                deleted_after_write.retain(|p| *p != path);
+            }
+            FileActionKind::Delete => {
+                if written.contains(&path) && !deleted_after_write.contains(&path) {
+                    deleted_after_write.push(path);
+                }
+            }
+        }
+    }
+
+    wasted.created_then_deleted = deleted_after_write.len();
+    if !required.is_empty() {
+        wasted.unrequired_edits = written
+            .iter()
+            .filter(|path| !deleted_after_write.contains(path))
+            .filter(|path| !is_required(path, required))
+            .count();
+    }
+    wasted
+}
+
+fn is_required(path: &str, required: &[String]) -> bool {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    required.iter().any(|r| {
+        let r = r.strip_prefix("./").unwrap_or(r);
+        path == r || path.ends_with(&format!("/{}", r)) || r.ends_with(&format!("/{}", path))
+    })
+}
@@ -1,16 +1,25 @@
 pub mod analyzer;
-mod redact;
+pub mod file_actions;
+pub mod normalize;
+pub(crate) mod redact;
+pub mod template;
+pub mod tokens;
 pub mod types;
 pub mod writer;
 
 pub use analyzer::TranscriptAnalyzer;
-pub use types::{EfficiencyMetrics, EvaluationReport, RunMetadata, RunReport, TokenUsage};
+pub use types::{
+    ConversationMetrics, EfficiencyMetrics, EvaluationReport, RunMetadata, RunReport, TokenUsage,
+};
 pub use writer::TranscriptWriter;
 
 #[cfg(test)]
 mod tests {
     mod analyzer;
+    mod file_actions;
     mod logging_tests;
+    mod normalize;
     mod redact;
+    mod tokens;
     mod writer_tests;
 }
@@ -0,0 +1,44 @@
+//! Terminal rendering normalization for captured tool output.
+//!
+//! Agent CLIs running in a PTY emit color codes, cursor movement and spinner
+//! redraws. `normalize_terminal_output` removes escape sequences and keeps
+//! only the last rendering of lines redrawn with carriage returns, so
+//! transcripts read as plain text and regex gates match what was displayed.
+
+use regex::Regex;
+
+/// CSI (colors, cursor movement), OSC (titles, hyperlinks), DCS/SOS/PM/APC
+/// strings and two-byte escape sequences.
+const ESCAPE_PATTERN: &str = concat!(
+    r"\x1b\[[0-?]*[ -/]*[@-~]",
+    r"|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)",
+    r"|\x1b[PX^_][^\x1b]*\x1b\\",
+    r"|\x1b[ -/]*[0-~]",
+    r"|\x9b[0-?]*[ -/]*[@-~]"
+);
+
+/// Strip ANSI escape sequences and collapse carriage-return progress lines.
+///
+/// A line redrawn with `\r` keeps its last non-blank rendering; `\r\n` line
+/// endings become `\n`, and remaining control characters other than tab
+/// are dropped.
+pub fn normalize_terminal_output(text: &str) -> String {
+    let stripped = match Regex::new(ESCAPE_PATTERN) {
+        Ok(re) => re.replace_all(text, "").into_owned(),
+        Err(_) => text.to_string(),
+    };
+
+    let mut normalized = String::with_capacity(stripped.len());
+    for (i, line) in stripped.split('\n').enumerate() {
+        if i > 0 {
+            normalized.push('\n');
+        }
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let rendered = line
+            .rsplit('\r')
+            .find(|segment| !segment.trim().is_empty())
+            .unwrap_or("");
+        normalized.extend(rendered.chars().filter(|c| *c == '\t' || !c.is_control()));
+    }
+    normalized
+}
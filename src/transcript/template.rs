@@ -0,0 +1,128 @@
+//! Template rendering for `report.md` and `evaluation.md`.
+//!
+//! Reports are rendered with minijinja from built-in templates. A file with the
+//! same name in the configured templates directory replaces the built-in one,
+//! so teams can change report structure without patching the writer.
+//!
+//! Templates see the report as `report` (or `evaluation`) and can use:
+//! - `t(key, **args)`: localized message, see [`crate::locale`]
+//! - `value|fixed(n)`: number with `n` decimals
+//! - `value|number`: number without trailing `.0`
+//! - `text|redact`: text with secrets redacted
+
+use crate::config::ReportConfig;
+use crate::locale::Locale;
+use crate::transcript::redact::redact_sensitive;
+use anyhow::Context;
+use minijinja::value::{Kwargs, Value};
+use minijinja::Environment;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Template for `report.md`.
+pub const REPORT_TEMPLATE: &str = "report.md.j2";
+
+/// Template for `evaluation.md`.
+pub const EVALUATION_TEMPLATE: &str = "evaluation.md.j2";
+
+/// Built-in template source for `name`.
+fn builtin(name: &str) -> Option<&'static str> {
+    match name {
+        REPORT_TEMPLATE => Some(include_str!("templates/report.md.j2")),
+        EVALUATION_TEMPLATE => Some(include_str!("templates/evaluation.md.j2")),
+        _ => None,
+    }
+}
+
+/// Renders report templates with a locale and optional template overrides.
+#[derive(Clone, Default)]
+pub struct ReportRenderer {
+    locale: Arc<Locale>,
+    templates_dir: Option<PathBuf>,
+}
+
+impl ReportRenderer {
+    pub fn new(locale: Locale, templates_dir: Option<PathBuf>) -> Self {
+        Self {
+            locale: Arc::new(locale),
+            templates_dir,
+        }
+    }
+
+    /// Build the renderer selected by the `[report]` config section.
+    ///
+    /// Override templates are compiled up front so syntax errors surface
+    /// before a run rather than after it.
+    pub fn from_config(config: &ReportConfig) -> anyhow::Result<Self> {
+        let renderer = Self::new(Locale::from_config(config)?, Some(config.templates_dir()));
+        let env = renderer.environment();
+        for name in [REPORT_TEMPLATE, EVALUATION_TEMPLATE] {
+            env.get_template(name)
+                .with_context(|| format!("Invalid report template {}", name))?;
+        }
+        Ok(renderer)
+    }
+
+    fn environment(&self) -> Environment<'static> {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_keep_trailing_newline(true);
+
+        let templates_dir = self.templates_dir.clone();
+        env.set_loader(move |name| load_template(templates_dir.as_deref(), name));
+
+        let locale = Arc::clone(&self.locale);
+        env.add_function(
+            "t",
+            move |key: &str, kwargs: Kwargs| -> Result<String, minijinja::Error> {
+                let values = kwargs
+                    .args()
+                    .map(|name| Ok((name, kwargs.get::<Value>(name)?)))
+                    .collect::<Result<Vec<_>, minijinja::Error>>()?;
+                let args: Vec<(&str, &dyn std::fmt::Display)> = values
+                    .iter()
+                    .map(|(name, value)| (*name, value as &dyn std::fmt::Display))
+                    .collect();
+                Ok(locale.format(key, &args))
+            },
+        );
+        env.add_filter("fixed", |value: f64, precision: usize| {
+            format!("{:.*}", precision, value)
+        });
+        env.add_filter("number", |value: f64| value.to_string());
+        env.add_filter("redact", |text: &str| redact_sensitive(text));
+        env
+    }
+
+    /// Render template `name` with `value` bound to `var`.
+    pub fn render<T: Serialize>(&self, name: &str, var: &str, value: &T) -> anyhow::Result<String> {
+        let env = self.environment();
+        let template = env
+            .get_template(name)
+            .with_context(|| format!("Failed to load report template {}", name))?;
+        let ctx = Value::from_iter([(var, Value::from_serialize(value))]);
+        template
+            .render(ctx)
+            .with_context(|| format!("Failed to render report template {}", name))
+    }
+}
+
+/// Load `name` from `templates_dir` if present there, else the built-in.
+fn load_template(
+    templates_dir: Option<&Path>,
+    name: &str,
+) -> Result<Option<String>, minijinja::Error> {
+    if let Some(path) = templates_dir.map(|dir| dir.join(name)) {
+        if path.is_file() {
+            return std::fs::read_to_string(&path).map(Some).map_err(|e| {
+                minijinja::Error::new(
+                    minijinja::ErrorKind::InvalidOperation,
+                    format!("Failed to read {}: {}", path.display(), e),
+                )
+            });
+        }
+    }
+    Ok(builtin(name).map(str::to_string))
+}
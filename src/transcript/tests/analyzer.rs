@@ -256,3 +256,59 @@ fn test_analyze_with_target_custom_pattern_no_capture_group() {
     assert_eq!(metrics.total_commands, 3);
     assert_eq!(metrics.help_invocations, 1);
 }
+
+#[test]
+fn test_analyze_ignores_terminal_escapes() {
+    let transcript =
+        "\x1b[2K\r\x1b[36mtaskmgr\x1b[0m create --title x\n\x1b[31mexit code: 1\x1b[0m\n";
+    let metrics = TranscriptAnalyzer::analyze_with_exit_codes(transcript);
+
+    assert_eq!(metrics.total_commands, 1);
+    assert_eq!(metrics.error_count, 1);
+}
+
+#[test]
+fn test_conversation_metrics_from_structured_events() {
+    let transcript = concat!(
+        "{\"type\":\"step_start\",\"part\":{}}\n",
+        "{\"type\":\"text\",\"part\":{\"text\":\"First, I'll list the existing notes.\"}}\n",
+        "{\"type\":\"tool_use\",\"part\":{\"tool\":\"bash\"}}\n",
+        "{\"type\":\"step_start\",\"part\":{}}\n",
+        "{\"type\":\"tool_use\",\"part\":{\"tool\":\"bash\"}}\n",
+        "{\"type\":\"step_start\",\"part\":{}}\n",
+        "{\"type\":\"text\",\"part\":{\"text\":\"Done.\"}}\n",
+    );
+    let metrics = TranscriptAnalyzer::analyze_conversation_for_target(transcript, "taskmgr", None);
+
+    assert_eq!(metrics.turns, 3);
+    assert_eq!(metrics.assistant_messages, 2);
+    assert_eq!(metrics.tool_call_turns, 2);
+    assert_eq!(metrics.text_turns, 1);
+    assert_eq!(metrics.tool_turn_ratio, 2.0);
+    assert_eq!(metrics.plan_messages, 1);
+    assert_eq!(metrics.avg_assistant_message_chars, 20.5);
+}
+
+#[test]
+fn test_conversation_metrics_from_plain_transcript() {
+    let transcript =
+        "Let me check the notes.\n\ntaskmgr list\nexit code: 0\n\nAll notes are listed.\n";
+    let metrics = TranscriptAnalyzer::analyze_conversation_for_target(transcript, "taskmgr", None);
+
+    assert_eq!(metrics.turns, 3);
+    assert_eq!(metrics.assistant_messages, 2);
+    assert_eq!(metrics.tool_call_turns, 1);
+    assert_eq!(metrics.text_turns, 2);
+    assert_eq!(metrics.tool_turn_ratio, 0.5);
+    assert_eq!(metrics.plan_messages, 1);
+}
+
+#[test]
+fn test_conversation_metrics_empty() {
+    let metrics = TranscriptAnalyzer::analyze_conversation_for_target("", "taskmgr", None);
+
+    assert_eq!(metrics.turns, 0);
+    assert_eq!(metrics.assistant_messages, 0);
+    assert_eq!(metrics.avg_assistant_message_chars, 0.0);
+    assert_eq!(metrics.tool_turn_ratio, 0.0);
+}
@@ -0,0 +1,108 @@
+use super::super::analyzer::TranscriptAnalyzer;
+use super::super::file_actions::{
+    detect_wasted_work, extract_file_actions, FileAction, FileActionKind,
+};
+
+fn read(path: &str) -> FileAction {
+    FileAction {
+        kind: FileActionKind::Read,
+        path: path.to_string(),
+    }
+}
+
+fn write(path: &str) -> FileAction {
+    FileAction {
+        kind: FileActionKind::Write,
+        path: path.to_string(),
+    }
+}
+
+fn delete(path: &str) -> FileAction {
+    FileAction {
+        kind: FileActionKind::Delete,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn test_extracts_shell_actions() {
+    let transcript = "$ cat notes.md\n\
+                      # Notes\n\
+                      $ echo hi > ./out.txt && rm -f tmp.log\n\
+                      $ sed -i 's/a/b/' config.toml\n\
+                      $ ls 2>/dev/null\n";
+    assert_eq!(
+        extract_file_actions(transcript),
+        vec![
+            read("notes.md"),
+            write("out.txt"),
+            delete("tmp.log"),
+            write("config.toml"),
+        ]
+    );
+}
+
+#[test]
+fn test_extracts_tool_use_events() {
+    let transcript = r#"{"type":"tool_use","part":{"tool":"read","state":{"input":{"filePath":"src/main.rs"}}}}
+{"type":"tool_use","part":{"tool":"edit","state":{"input":{"filePath":"src/lib.rs"}}}}
+{"type":"tool_use","part":{"tool":"bash","state":{"input":{"command":"rm scratch.txt"}}}}
+{"type":"text","part":{"text":"cat is a command"}}"#;
+    assert_eq!(
+        extract_file_actions(transcript),
+        vec![
+            read("src/main.rs"),
+            write("src/lib.rs"),
+            delete("scratch.txt")
+        ]
+    );
+}
+
+#[test]
+fn test_detects_created_then_deleted() {
+    let actions = vec![
+        write("scratch.txt"),
+        delete("scratch.txt"),
+        delete("old.txt"),
+    ];
+    let wasted = detect_wasted_work(&actions, &[]);
+    assert_eq!(wasted.created_then_deleted, 1);
+    assert_eq!(wasted.total(), 1);
+}
+
+#[test]
+fn test_detects_unrequired_edits() {
+    let actions = vec![
+        write("report.md"),
+        write("src/extra.rs"),
+        write("report.md"),
+    ];
+    let required = vec!["./report.md".to_string()];
+    assert_eq!(detect_wasted_work(&actions, &required).unrequired_edits, 1);
+    // Without known requirements no edit counts as unrequired
+    assert_eq!(detect_wasted_work(&actions, &[]).unrequired_edits, 0);
+}
+
+#[test]
+fn test_detects_repeated_reads() {
+    let actions = vec![
+        read("a.txt"),
+        read("a.txt"),
+        read("a.txt"),
+        write("a.txt"),
+        read("a.txt"),
+        read("a.txt"),
+        read("a.txt"),
+        read("a.txt"),
+    ];
+    assert_eq!(detect_wasted_work(&actions, &[]).repeated_reads, 3);
+}
+
+#[test]
+fn test_count_wasted_actions() {
+    let transcript = "$ touch draft.txt\n$ rm draft.txt\n$ echo done > result.txt\n";
+    assert_eq!(
+        TranscriptAnalyzer::count_wasted_actions(transcript, &["result.txt".to_string()]),
+        1
+    );
+}
@@ -0,0 +1,40 @@
+use super::super::normalize::normalize_terminal_output;
+
+#[test]
+fn test_strips_color_codes() {
+    let input = "\x1b[1;32mPASS\x1b[0m all good";
+    assert_eq!(normalize_terminal_output(input), "PASS all good");
+}
+
+#[test]
+fn test_strips_cursor_and_osc_sequences() {
+    let input = "\x1b]0;window title\x07\x1b[2K\x1b[1Gdone\x1b[?25h";
+    assert_eq!(normalize_terminal_output(input), "done");
+}
+
+#[test]
+fn test_collapses_carriage_return_progress() {
+    let input = "Downloading 10%\rDownloading 50%\rDownloading 100%\nnext line";
+    assert_eq!(
+        normalize_terminal_output(input),
+        "Downloading 100%\nnext line"
+    );
+}
+
+#[test]
+fn test_spinner_cleared_at_end_keeps_last_text() {
+    let input = "⠋ thinking\r⠙ thinking\r\x1b[K\rmytool list\n";
+    assert_eq!(normalize_terminal_output(input), "mytool list\n");
+}
+
+#[test]
+fn test_crlf_line_endings() {
+    let input = "line one\r\nline two\r\n";
+    assert_eq!(normalize_terminal_output(input), "line one\nline two\n");
+}
+
+#[test]
+fn test_plain_text_unchanged() {
+    let input = "mytool create --title \"x\"\n\texit code: 0\n";
+    assert_eq!(normalize_terminal_output(input), input);
+}
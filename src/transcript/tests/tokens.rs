@@ -0,0 +1,46 @@
+use super::super::tokens::{estimate_tokens, estimate_transcript};
+
+#[test]
+fn test_estimate_empty() {
+    assert_eq!(estimate_tokens(""), 0);
+    assert_eq!(estimate_transcript("").total, 0);
+}
+
+#[test]
+fn test_estimate_prose() {
+    // cl100k_base encodes this sentence as 10 tokens
+    let tokens = estimate_tokens("The quick brown fox jumps over the lazy dog.");
+    assert!((9..=11).contains(&tokens), "got {}", tokens);
+}
+
+#[test]
+fn test_estimate_numbers_and_symbols() {
+    assert_eq!(estimate_tokens("1234567"), 3);
+    assert_eq!(estimate_tokens("--"), 1);
+    assert_eq!(estimate_tokens("日本語"), 3);
+}
+
+#[test]
+fn test_estimate_long_identifiers_split() {
+    assert!(estimate_tokens("internationalization") > estimate_tokens("hello"));
+}
+
+#[test]
+fn test_estimate_transcript_per_turn() {
+    let transcript = concat!(
+        "{\"type\":\"step_start\",\"part\":{}}\n",
+        "{\"type\":\"text\",\"part\":{\"text\":\"Listing notes\"}}\n",
+        "{\"type\":\"step_start\",\"part\":{}}\n",
+        "{\"type\":\"text\",\"part\":{\"text\":\"Done\"}}\n",
+    );
+    let estimate = estimate_transcript(transcript);
+    assert_eq!(estimate.per_turn.len(), 2);
+    assert_eq!(estimate.total, estimate.per_turn.iter().sum::<usize>());
+}
+
+#[test]
+fn test_estimate_plain_transcript_is_one_turn() {
+    let estimate = estimate_transcript("taskmgr list\nexit code: 0\n");
+    assert_eq!(estimate.per_turn.len(), 1);
+    assert!(estimate.total > 0);
+}
@@ -1,5 +1,8 @@
+use super::super::template::ReportRenderer;
 use super::super::types::{EfficiencyReport, EvaluationReport, RunReport};
 use super::super::writer::TranscriptWriter;
+use crate::evaluation::{ScoreBreakdown, ScoreComponent, ScorePenalties};
+use crate::locale::{locale_path, Locale};
 use std::fs;
 
 #[test]
@@ -26,6 +29,7 @@ fn test_write_report_basic() {
             error_count: 0,
             first_try_success_rate: 0.9,
             iteration_ratio: 2.0,
+            wasted_actions: 1,
         },
         setup_success: true,
         setup_commands: vec![],
@@ -62,11 +66,13 @@ fn test_write_evaluation_basic() {
         duration_secs: 30.0,
         cost_usd: Some(0.015),
         composite_score: Some(0.82),
+        score_breakdown: None,
         judge_feedback: vec![
             "**Issues:**\nMinor formatting issue".to_string(),
             "**Highlights:**\nGood structure".to_string(),
             "**Criteria Scores:**\n- relevance: 0.85\n- clarity: 0.90".to_string(),
         ],
+        judge_ensemble: None,
         evaluator_results: vec![],
     };
 
@@ -101,6 +107,55 @@ fn test_write_evaluation_basic() {
     assert!(content.contains("[Fixture](../fixture/)"));
 }
 
+#[test]
+fn test_write_evaluation_with_judge_ensemble() {
+    let dir = tempfile::tempdir().unwrap();
+    let writer = TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+
+    let vote = |model: &str, score: Option<f64>| crate::evaluation::JudgeVote {
+        model: model.to_string(),
+        rubric: "rubrics/notes.yaml".to_string(),
+        pass_threshold: 0.7,
+        score,
+        error: score.is_none().then(|| "request timed out".to_string()),
+        response: None,
+    };
+    let ensemble = crate::evaluation::aggregate_judges(
+        crate::scenario::JudgeAggregate::Median,
+        vec![
+            vote("gpt-4o", Some(0.9)),
+            vote("claude-sonnet", Some(0.6)),
+            vote("qwen2.5", None),
+        ],
+    );
+    let evaluation = EvaluationReport {
+        scenario_id: "test_scenario".to_string(),
+        tool: "opencode".to_string(),
+        model: "gpt-4o".to_string(),
+        outcome: "Pass".to_string(),
+        judge_score_1_to_5: Some(4.0),
+        gates_passed: 1,
+        gates_total: 1,
+        duration_secs: 30.0,
+        cost_usd: None,
+        composite_score: None,
+        score_breakdown: None,
+        judge_feedback: vec![],
+        judge_ensemble: ensemble,
+        evaluator_results: vec![],
+    };
+
+    writer.write_evaluation(&evaluation).unwrap();
+
+    let content = fs::read_to_string(dir.path().join("evaluation.md")).unwrap();
+    assert!(content.contains("## Judge Ensemble"), "{}", content);
+    assert!(content.contains("| gpt-4o | rubrics/notes.yaml | 0.90 | 0.70 | Pass |"));
+    assert!(content.contains("| claude-sonnet | rubrics/notes.yaml | 0.60 | 0.70 | Fail |"));
+    assert!(content.contains("| qwen2.5 | rubrics/notes.yaml | - | 0.70 | ❌ request timed out |"));
+    assert!(content.contains("**Aggregate**: median score 0.75 against threshold 0.70: Pass"));
+    assert!(content.contains("**Disagreement**: spread 0.30, 1/2 judges passed"));
+}
+
 #[test]
 fn test_write_evaluation_without_judge_score() {
     let dir = tempfile::tempdir().unwrap();
@@ -117,14 +172,191 @@ fn test_write_evaluation_without_judge_score() {
         duration_secs: 20.0,
         cost_usd: Some(0.01),
         composite_score: Some(0.75),
+        score_breakdown: Some(ScoreBreakdown {
+            components: vec![
+                ScoreComponent::new("gates", 0.8, 0.5, "1.00 of 2 gate credit".to_string()),
+                ScoreComponent::new(
+                    "interaction",
+                    0.2,
+                    1.0,
+                    "first-try success rate".to_string(),
+                ),
+            ],
+            excluded: vec!["judge".to_string()],
+            weighted_sum: 0.6,
+            penalties: Some(ScorePenalties {
+                errors: 0.04,
+                retries: 0.02,
+                total: 0.05,
+            }),
+            evaluators: vec![],
+            score: 0.55,
+        }),
         judge_feedback: vec![],
+        judge_ensemble: None,
         evaluator_results: vec![],
     };
 
     writer.write_evaluation(&evaluation).unwrap();
+    writer
+        .write_score_breakdown(evaluation.score_breakdown.as_ref().unwrap())
+        .unwrap();
 
     let eval_path = dir.path().join("evaluation.md");
     let content = fs::read_to_string(&eval_path).unwrap();
+    assert!(content.contains("| gates | 0.80 | 0.50 | 0.400 | 1.00 of 2 gate credit |"));
+    assert!(content.contains("**Score Penalties**: -0.05 (errors -0.04, retries -0.02)"));
+    assert!(content.contains("**Excluded**: judge (weight spread over the other components)"));
+    assert!(content.contains("[Score Breakdown](score_breakdown.json)"));
+    let breakdown: ScoreBreakdown =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("score_breakdown.json")).unwrap())
+            .unwrap();
+    assert_eq!(breakdown.score, 0.55);
     assert!(!content.contains("Judge Score"));
     assert!(!content.contains("## Judge Feedback"));
 }
+
+#[test]
+fn test_write_evaluation_with_locale() {
+    let dir = tempfile::tempdir().unwrap();
+    let templates = tempfile::tempdir().unwrap();
+    fs::create_dir_all(templates.path().join("locales")).unwrap();
+    fs::write(
+        locale_path(templates.path(), "de"),
+        "[evaluation]\ntitle = \"Bewertung\"\nhuman_score = \"Menschliche Bewertung\"\n\n[field]\ntool = \"Werkzeug\"\n",
+    )
+    .unwrap();
+    let mut writer =
+        TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+    writer.set_renderer(ReportRenderer::new(
+        Locale::load("de", templates.path()).unwrap(),
+        None,
+    ));
+
+    let evaluation = EvaluationReport {
+        scenario_id: "test_scenario".to_string(),
+        tool: "opencode".to_string(),
+        model: "gpt-4o".to_string(),
+        outcome: "Pass".to_string(),
+        judge_score_1_to_5: None,
+        gates_passed: 1,
+        gates_total: 1,
+        duration_secs: 1.0,
+        cost_usd: None,
+        composite_score: None,
+        score_breakdown: None,
+        judge_feedback: vec![],
+        judge_ensemble: None,
+        evaluator_results: vec![],
+    };
+    writer.write_evaluation(&evaluation).unwrap();
+
+    let content = fs::read_to_string(dir.path().join("evaluation.md")).unwrap();
+    assert!(content.starts_with("# Bewertung\n\n"));
+    assert!(content.contains("- **Werkzeug**: opencode\n"));
+    assert!(content.contains("Menschliche Bewertung: __/5"));
+    assert!(content.contains("- **Model**: gpt-4o\n"));
+}
+
+#[test]
+fn test_redactions_apply_to_transcript_and_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut writer =
+        TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+    writer.add_redaction("Say \"secret\" now", "[REDACTED]");
+
+    writer.write_raw("> Say \"secret\" now\nok\n").unwrap();
+    writer
+        .append_event(&serde_json::json!({"prompt": "Say \"secret\" now"}))
+        .unwrap();
+
+    let raw = fs::read_to_string(dir.path().join("transcript.raw.txt")).unwrap();
+    assert_eq!(raw, "> [REDACTED]\nok\n");
+    let events = fs::read_to_string(dir.path().join("events.jsonl")).unwrap();
+    assert_eq!(events, "{\"prompt\":\"[REDACTED]\"}\n");
+}
+
+#[test]
+fn test_report_template_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let templates = tempfile::tempdir().unwrap();
+    fs::write(
+        templates.path().join("evaluation.md.j2"),
+        "{{ t(\"field.tool\") }}={{ evaluation.tool }} {{ evaluation.duration_secs|fixed(1) }}\n",
+    )
+    .unwrap();
+    let mut writer =
+        TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+    writer.set_renderer(ReportRenderer::new(
+        Locale::default(),
+        Some(templates.path().to_path_buf()),
+    ));
+
+    let evaluation = EvaluationReport {
+        scenario_id: "test_scenario".to_string(),
+        tool: "opencode".to_string(),
+        model: "gpt-4o".to_string(),
+        outcome: "Pass".to_string(),
+        judge_score_1_to_5: None,
+        gates_passed: 1,
+        gates_total: 1,
+        duration_secs: 2.0,
+        cost_usd: None,
+        composite_score: None,
+        score_breakdown: None,
+        judge_feedback: vec![],
+        judge_ensemble: None,
+        evaluator_results: vec![],
+    };
+    writer.write_evaluation(&evaluation).unwrap();
+
+    let content = fs::read_to_string(dir.path().join("evaluation.md")).unwrap();
+    assert_eq!(content, "Tool=opencode 2.0\n");
+}
+
+#[test]
+fn test_simulated_disk_full_leaves_no_partial_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut writer =
+        TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+    writer.write_raw("before").unwrap();
+    writer.simulate_disk_full();
+
+    let err = writer.write_raw("after").unwrap_err();
+    assert!(err.to_string().contains("no space left on device"));
+    assert!(writer
+        .append_event(&serde_json::json!({"type": "x"}))
+        .is_err());
+
+    // The earlier transcript survives intact and nothing else was created
+    let mut names: Vec<_> = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["transcript.human.txt", "transcript.raw.txt"]);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("transcript.raw.txt")).unwrap(),
+        "before"
+    );
+}
+
+#[test]
+fn test_compressed_artifacts_are_read_transparently() {
+    let dir = tempfile::tempdir().unwrap();
+    let writer = TranscriptWriter::new(dir.path().to_path_buf(), dir.path().to_path_buf()).unwrap();
+    writer.write_raw(&"$ qipu list\n".repeat(500)).unwrap();
+    for i in 0..50 {
+        writer.log_output(&format!("line {}", i)).unwrap();
+    }
+
+    writer.compress_large_artifacts(256).unwrap();
+
+    assert!(!dir.path().join("transcript.raw.txt").exists());
+    assert!(!dir.path().join("events.jsonl").exists());
+    assert!(dir.path().join("transcript.raw.txt.zst").exists());
+    assert!(dir.path().join("transcript.human.txt").exists());
+    assert_eq!(writer.read_events().unwrap().len(), 50);
+    let raw = crate::artifacts::read_to_string(&dir.path().join("transcript.raw.txt")).unwrap();
+    assert_eq!(raw, "$ qipu list\n".repeat(500));
+}
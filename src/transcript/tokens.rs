@@ -0,0 +1,64 @@
+//! Token count estimates for transcripts.
+//!
+//! Tools do not always report usage, so transcripts are measured with an
+//! approximation of BPE tokenizers such as `cl100k_base`: text is split into
+//! letter runs, digit groups of up to three, punctuation runs and whitespace,
+//! and each piece is charged what such tokenizers typically spend on it.
+//! Expect estimates within roughly 15% of a real tokenizer on English prose
+//! and shell output.
+
+use crate::transcript::analyzer::is_step_start_line;
+use crate::transcript::types::TokenEstimate;
+use regex::Regex;
+
+/// Letter runs, digit groups, single non-ASCII characters, whitespace runs
+/// and ASCII punctuation runs.
+const PIECE_PATTERN: &str = r"[A-Za-z]+|[0-9]{1,3}|[^\x00-\x7F]|\s+|[^\sA-Za-z0-9\x80-\x{10FFFF}]+";
+
+/// Estimated number of tokens in `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    let Ok(re) = Regex::new(PIECE_PATTERN) else {
+        return text.len().div_ceil(4);
+    };
+    re.find_iter(text)
+        .map(|piece| {
+            let piece = piece.as_str();
+            let first = piece.chars().next().unwrap_or(' ');
+            if first.is_ascii_alphabetic() {
+                // Common words are one token; long identifiers split every ~6 letters
+                piece.len().div_ceil(6)
+            } else if first.is_ascii_digit() || !first.is_ascii() {
+                1
+            } else if first.is_whitespace() {
+                // A single space merges into the following word
+                usize::from(piece != " ")
+            } else {
+                piece.len().div_ceil(2)
+            }
+        })
+        .sum()
+}
+
+/// Estimate tokens for the whole transcript and for each turn.
+///
+/// Turns are delimited by structured `step_start` events where the tool emits
+/// them (opencode); other transcripts count as a single turn.
+pub fn estimate_transcript(transcript: &str) -> TokenEstimate {
+    let mut per_turn = Vec::new();
+    let mut turn = String::new();
+    for line in transcript.lines() {
+        if is_step_start_line(line.trim()) && !turn.is_empty() {
+            per_turn.push(estimate_tokens(&turn));
+            turn.clear();
+        }
+        turn.push_str(line);
+        turn.push('\n');
+    }
+    if !turn.is_empty() {
+        per_turn.push(estimate_tokens(&turn));
+    }
+    TokenEstimate {
+        total: per_turn.iter().sum(),
+        per_turn,
+    }
+}
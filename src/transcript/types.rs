@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -10,6 +11,21 @@ pub struct RunMetadata {
     pub duration_secs: f64,
     pub cost_estimate_usd: Option<f64>,
     pub token_usage: Option<TokenUsage>,
+    /// Token counts estimated from the transcript, available even when the
+    /// tool reports no usage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_tokens: Option<TokenEstimate>,
+    /// Timeout the tool ran under and the tier that supplied it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<crate::run::timeout::EffectiveTimeout>,
+}
+
+/// Estimated transcript size in tokens (see [`crate::transcript::tokens`]).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenEstimate {
+    pub total: usize,
+    /// Tokens per model turn, in order
+    pub per_turn: Vec<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,7 +34,7 @@ pub struct TokenUsage {
     pub output: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct RunReport {
     pub scenario_id: String,
     pub tool: String,
@@ -37,11 +53,13 @@ pub struct RunReport {
     pub setup_commands: Vec<SetupCommandResult>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub(crate) struct GateDetail {
     pub gate_type: String,
     pub passed: bool,
     pub message: String,
+    pub score: Option<f64>,
+    pub warning: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,16 +69,17 @@ pub struct SetupCommandResult {
     pub output: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub(crate) struct EfficiencyReport {
     pub total_commands: usize,
     pub unique_commands: usize,
     pub error_count: usize,
     pub first_try_success_rate: f64,
     pub iteration_ratio: f64,
+    pub wasted_actions: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct EvaluationReport {
     pub scenario_id: String,
     pub tool: String,
@@ -72,11 +91,14 @@ pub struct EvaluationReport {
     pub duration_secs: f64,
     pub cost_usd: Option<f64>,
     pub composite_score: Option<f64>,
+    pub score_breakdown: Option<crate::evaluation::ScoreBreakdown>,
     pub judge_feedback: Vec<String>,
+    /// Individual judges and their aggregate, for scenarios listing several
+    pub judge_ensemble: Option<crate::evaluation::JudgeEnsemble>,
     pub evaluator_results: Vec<EvaluatorResultSummary>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct EvaluatorResultSummary {
     pub name: String,
     pub score: Option<f64>,
@@ -93,6 +115,29 @@ pub struct EfficiencyMetrics {
     pub help_invocations: usize,
     pub first_try_success_rate: f64,
     pub iteration_ratio: f64,
+    /// File actions that did not contribute to the result (see
+    /// [`crate::transcript::file_actions`])
+    #[serde(default)]
+    pub wasted_actions: usize,
+}
+
+/// Shape of the conversation between the agent and its tools.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationMetrics {
+    /// Model turns in the transcript
+    pub turns: usize,
+    /// Assistant text messages
+    pub assistant_messages: usize,
+    /// Mean length of assistant messages in characters
+    pub avg_assistant_message_chars: f64,
+    /// Turns in which the agent called at least one tool
+    pub tool_call_turns: usize,
+    /// Turns with text only
+    pub text_turns: usize,
+    /// `tool_call_turns / text_turns` (text turns counted as at least 1)
+    pub tool_turn_ratio: f64,
+    /// Assistant messages that state a plan or explain the approach
+    pub plan_messages: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -100,3 +145,111 @@ pub struct CommandEvent {
     pub command: String,
     pub exit_code: Option<i32>,
 }
+
+/// A line of `events.jsonl`.
+///
+/// Events are appended as JSON values during a run; this type documents their
+/// shape for the published JSON Schema.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventRecord {
+    /// A scenario setup command finished
+    SetupCommand {
+        /// Position of the command in `setup.commands`
+        index: usize,
+        /// Shell command that was run
+        command: String,
+        /// Process exit code
+        exit_code: i32,
+        /// Combined command output
+        output: String,
+        /// Whether the command exited successfully
+        success: bool,
+    },
+    /// A `setup_assertions` gate was checked before the tool ran
+    SetupAssertion {
+        /// Position of the gate in `setup_assertions`
+        index: usize,
+        /// Gate type, e.g. `FileExists`
+        gate_type: String,
+        /// Whether the assertion held
+        passed: bool,
+        /// Gate result message
+        message: String,
+    },
+    /// A `lint_clean` linter was run in the fixture before the tool
+    LintBaseline {
+        /// Linter command
+        command: String,
+        /// Warning pattern, present when the gate sets one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        /// Warnings counted before the tool ran
+        warnings: u64,
+    },
+    /// The tool under test finished executing
+    Execution {
+        /// Tool name
+        tool: String,
+        /// Raw tool output
+        output: String,
+        /// Tool exit code
+        exit_code: i32,
+        /// Cost reported by the tool, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cost_usd: Option<f64>,
+        /// Number of invocations made, present when transient failures were retried
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        attempts: Option<u32>,
+        /// Turn budget, present when the tool was stopped for exceeding it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        turn_limit: Option<usize>,
+        /// Idle period, present when the tool was stopped as stalled
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stalled_after_secs: Option<u64>,
+        /// Tool stdout, present when stdout and stderr were captured separately
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stdout: Option<String>,
+        /// Tool stderr, present when stdout and stderr were captured separately
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stderr: Option<String>,
+    },
+    /// Periodic liveness report while the tool is running
+    Heartbeat {
+        /// Seconds since the tool was launched
+        elapsed_secs: f64,
+        /// Bytes of tool output so far
+        output_bytes: u64,
+        /// Target-tool commands seen in the output so far
+        commands: usize,
+        /// Estimated seconds remaining, from durations of past runs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        eta_secs: Option<f64>,
+    },
+    /// A tool invocation failed transiently and will be retried
+    ToolAttempt {
+        /// Tool name
+        tool: String,
+        /// 1-based attempt number that failed
+        attempt: u32,
+        /// Tool exit code
+        exit_code: i32,
+        /// Raw tool output of the failed attempt
+        output: String,
+        /// Delay before the next attempt
+        retry_in_secs: f64,
+    },
+    /// A post-execution script finished
+    PostScript {
+        /// Script command that was run
+        command: String,
+        /// Script exit code
+        exit_code: i32,
+        /// Whether the script hit its timeout
+        timed_out: bool,
+        /// Captured stdout
+        stdout: String,
+        /// Captured stderr
+        stderr: String,
+    },
+}
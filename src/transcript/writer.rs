@@ -1,13 +1,19 @@
-use crate::transcript::redact::redact_sensitive;
+use crate::transcript::template::{ReportRenderer, EVALUATION_TEMPLATE, REPORT_TEMPLATE};
 use crate::transcript::types::{EvaluationReport, RunMetadata, RunReport};
 use serde_json::json;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct TranscriptWriter {
     pub base_dir: PathBuf,
     pub results_dir: PathBuf,
+    /// (secret, placeholder) pairs replaced in transcripts and events before writing
+    redactions: Vec<(String, String)>,
+    /// Renders `report.md` and `evaluation.md`
+    renderer: ReportRenderer,
+    /// Fail every write as if the disk were full (`--inject-fault artifacts`)
+    disk_full: bool,
 }
 
 impl TranscriptWriter {
@@ -21,13 +27,95 @@ impl TranscriptWriter {
         Ok(Self {
             base_dir: artifacts_dir,
             results_dir,
+            redactions: Vec::new(),
+            renderer: ReportRenderer::default(),
+            disk_full: false,
         })
     }
 
+    /// Make every later write fail with "no space left on device".
+    pub fn simulate_disk_full(&mut self) {
+        self.disk_full = true;
+    }
+
+    fn check_disk_space(&self) -> std::io::Result<()> {
+        if self.disk_full {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::StorageFull,
+                "no space left on device (simulated)",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Write an artifact atomically, so a failed write never leaves a partial file.
+    fn write_file(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> std::io::Result<()> {
+        self.check_disk_space()?;
+        crate::artifacts::write_atomic(path.as_ref(), contents)
+    }
+
+    /// Use `renderer` for the generated reports.
+    pub fn set_renderer(&mut self, renderer: ReportRenderer) {
+        self.renderer = renderer;
+    }
+
+    /// Replace `secret` with `placeholder` in everything written to the
+    /// transcript and event log, including its JSON-escaped form.
+    pub fn add_redaction(&mut self, secret: &str, placeholder: &str) {
+        if secret.is_empty() {
+            return;
+        }
+        self.redactions
+            .push((secret.to_string(), placeholder.to_string()));
+        if let Ok(escaped) = serde_json::to_string(secret) {
+            let escaped = escaped.trim_matches('"');
+            if escaped != secret {
+                self.redactions
+                    .push((escaped.to_string(), placeholder.to_string()));
+            }
+        }
+    }
+
+    fn apply_redactions(&self, text: &str) -> String {
+        self.redactions
+            .iter()
+            .fold(text.to_string(), |text, (secret, placeholder)| {
+                text.replace(secret, placeholder)
+            })
+    }
+
     pub fn write_raw(&self, content: &str) -> anyhow::Result<()> {
-        fs::write(self.base_dir.join("transcript.raw.txt"), content)?;
+        let content = self.apply_redactions(content);
+        self.write_file(self.base_dir.join("transcript.raw.txt"), &content)?;
         // Also generate human-readable version from the content
-        self.generate_human_transcript(content)?;
+        self.generate_human_transcript(&content)?;
+        Ok(())
+    }
+
+    /// Write output as the terminal received it to `transcript.terminal.txt`.
+    pub fn write_terminal(&self, content: &str) -> anyhow::Result<()> {
+        self.write_file(
+            self.base_dir.join("transcript.terminal.txt"),
+            self.apply_redactions(content),
+        )?;
+        Ok(())
+    }
+
+    /// Write separately captured streams to `transcript.stdout.txt` and
+    /// `transcript.stderr.txt`.
+    pub fn write_streams(&self, stdout: &str, stderr: &str) -> anyhow::Result<()> {
+        self.write_file(
+            self.base_dir.join("transcript.stdout.txt"),
+            self.apply_redactions(stdout),
+        )?;
+        self.write_file(
+            self.base_dir.join("transcript.stderr.txt"),
+            self.apply_redactions(stderr),
+        )?;
         Ok(())
     }
 
@@ -63,16 +151,22 @@ impl TranscriptWriter {
         }
 
         let human_content = human_lines.join("\n");
-        fs::write(self.base_dir.join("transcript.human.txt"), human_content)?;
+        self.write_file(self.base_dir.join("transcript.human.txt"), human_content)?;
         Ok(())
     }
 
     pub fn append_event(&self, event: &serde_json::Value) -> anyhow::Result<()> {
+        self.check_disk_space()?;
         let mut file = fs::OpenOptions::new()
             .create(true)
             .append(true)
             .open(self.base_dir.join("events.jsonl"))?;
-        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        writeln!(
+            file,
+            "{}",
+            self.apply_redactions(&serde_json::to_string(event)?)
+        )?;
+        crate::artifacts::sync_appended(&file)?;
         Ok(())
     }
 
@@ -119,12 +213,11 @@ impl TranscriptWriter {
 
     #[allow(dead_code)]
     pub fn read_events(&self) -> anyhow::Result<Vec<serde_json::Value>> {
-        let path = self.base_dir.join("events.jsonl");
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let content = fs::read_to_string(&path)?;
+        let content = match crate::artifacts::read_to_string(&self.base_dir.join("events.jsonl")) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
         let mut events = Vec::new();
         for line in content.lines() {
             if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
@@ -134,194 +227,54 @@ impl TranscriptWriter {
         Ok(events)
     }
 
-    pub fn write_run_metadata(&self, metadata: &RunMetadata) -> anyhow::Result<()> {
-        let json = serde_json::to_string_pretty(metadata)?;
-        fs::write(self.base_dir.join("run.json"), json)?;
+    /// Compress `transcript.raw.txt` and `events.jsonl` when they exceed
+    /// `threshold` bytes. Call once the run is finished; later appends would
+    /// start a fresh, uncompressed `events.jsonl`.
+    pub fn compress_large_artifacts(&self, threshold: u64) -> anyhow::Result<()> {
+        self.check_disk_space()?;
+        for name in ["transcript.raw.txt", "events.jsonl"] {
+            crate::artifacts::compress_if_larger(&self.base_dir.join(name), threshold)?;
+        }
         Ok(())
     }
 
-    fn write_report_header(&self, report: &RunReport, content: &mut String) {
-        content.push_str("# Test Run Report\n\n");
-        content.push_str("## Scenario\n\n");
-        content.push_str(&format!("- **ID**: {}\n", report.scenario_id));
-        content.push_str(&format!("- **Tool**: {}\n", report.tool));
-        content.push_str(&format!("- **Model**: {}\n", report.model));
-        content.push_str(&format!("- **Timestamp**: {}\n\n", report.timestamp));
-    }
-
-    fn write_execution_section(&self, report: &RunReport, content: &mut String) {
-        content.push_str("## Execution\n\n");
-        content.push_str(&format!("- **Duration**: {:.2}s\n", report.duration_secs));
-        if let Some(cost) = report.cost_usd {
-            content.push_str(&format!("- **Cost**: ${:.4}\n", cost));
-        }
-
-        if !report.setup_commands.is_empty() {
-            content.push_str(&format!(
-                "- **Setup**: {}\n",
-                if report.setup_success {
-                    "Success"
-                } else {
-                    "Failed"
-                }
-            ));
-            content.push_str("\n### Setup Commands\n\n");
-            for cmd_result in &report.setup_commands {
-                let status = if cmd_result.success { "✓" } else { "✗" };
-                let redacted_command = redact_sensitive(&cmd_result.command);
-                content.push_str(&format!("- {} `{}`\n", status, redacted_command));
-            }
-            content.push('\n');
-        }
-        if let Some(ref usage) = report.token_usage {
-            content.push_str(&format!(
-                "- **Token Usage**: {} input, {} output\n",
-                usage.input, usage.output
-            ));
-        }
-        content.push_str(&format!("- **Outcome**: {}\n\n", report.outcome));
+    pub fn write_run_metadata(&self, metadata: &RunMetadata) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(metadata)?;
+        self.write_file(self.base_dir.join("run.json"), json)?;
+        Ok(())
     }
 
-    fn write_evaluation_section(&self, report: &RunReport, content: &mut String) {
-        content.push_str("## Evaluation Metrics\n\n");
-        content.push_str(&format!(
-            "- **Gates Passed**: {}/{}\n",
-            report.gates_passed, report.gates_total
-        ));
-        if let Some(score) = report.composite_score {
-            content.push_str(&format!("- **Composite Score**: {:.2}\n", score));
-        }
-        content.push('\n');
-
-        if !report.gate_details.is_empty() {
-            content.push_str("### Gate Details\n\n");
-            for detail in &report.gate_details {
-                let status = if detail.passed { "✓" } else { "✗" };
-                let redacted_message = redact_sensitive(&detail.message);
-                content.push_str(&format!(
-                    "- {} {}: {}\n",
-                    status, detail.gate_type, redacted_message
-                ));
-            }
-            content.push('\n');
-        }
+    pub fn write_report(&self, report: &RunReport) -> anyhow::Result<()> {
+        let content = self.renderer.render(REPORT_TEMPLATE, "report", report)?;
+        self.write_file(self.results_dir.join("report.md"), content)?;
+        Ok(())
     }
 
-    fn write_efficiency_section(&self, report: &RunReport, content: &mut String) {
-        content.push_str("## Efficiency\n\n");
-        content.push_str(&format!(
-            "- **Total Commands**: {}\n",
-            report.efficiency.total_commands
-        ));
-        content.push_str(&format!(
-            "- **Unique Commands**: {}\n",
-            report.efficiency.unique_commands
-        ));
-        content.push_str(&format!(
-            "- **Error Count**: {}\n",
-            report.efficiency.error_count
-        ));
-        content.push_str(&format!(
-            "- **First Try Success Rate**: {:.1}%\n",
-            report.efficiency.first_try_success_rate * 100.0
-        ));
-        content.push_str(&format!(
-            "- **Iteration Ratio**: {:.2}\n\n",
-            report.efficiency.iteration_ratio
-        ));
+    pub fn write_score_breakdown(
+        &self,
+        breakdown: &crate::evaluation::ScoreBreakdown,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(breakdown)?;
+        self.write_file(self.results_dir.join("score_breakdown.json"), json)?;
+        Ok(())
     }
 
-    pub fn write_report(&self, report: &RunReport) -> anyhow::Result<()> {
-        let mut content = String::new();
-        self.write_report_header(report, &mut content);
-        self.write_execution_section(report, &mut content);
-        self.write_evaluation_section(report, &mut content);
-        self.write_efficiency_section(report, &mut content);
-
-        fs::write(self.results_dir.join("report.md"), content)?;
+    /// Write the judge's scores and feedback as `judge.json`, for reports
+    /// regenerated from the results directory.
+    pub fn write_judge_response(
+        &self,
+        response: &crate::judge::JudgeResponse,
+    ) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(response)?;
+        self.write_file(self.results_dir.join("judge.json"), json)?;
         Ok(())
     }
 
     pub fn write_evaluation(&self, evaluation: &EvaluationReport) -> anyhow::Result<()> {
-        let mut content = String::new();
-
-        content.push_str("# Evaluation\n\n");
-
-        content.push_str("## Summary\n\n");
-        content.push_str(&format!("- **Scenario**: {}\n", evaluation.scenario_id));
-        content.push_str(&format!("- **Tool**: {}\n", evaluation.tool));
-        content.push_str(&format!("- **Model**: {}\n", evaluation.model));
-        content.push_str(&format!("- **Outcome**: {}\n\n", evaluation.outcome));
-
-        if let Some(judge_score) = evaluation.judge_score_1_to_5 {
-            content.push_str("## Judge Score\n\n");
-            content.push_str(&format!("**{}** / 5\n\n", judge_score));
-        }
-
-        content.push_str("## Metrics\n\n");
-        content.push_str(&format!(
-            "- **Gates Passed**: {}/{}\n",
-            evaluation.gates_passed, evaluation.gates_total
-        ));
-        content.push_str(&format!(
-            "- **Duration**: {:.2}s\n",
-            evaluation.duration_secs
-        ));
-        if let Some(cost) = evaluation.cost_usd {
-            content.push_str(&format!("- **Cost**: ${:.4}\n", cost));
-        }
-        if let Some(composite_score) = evaluation.composite_score {
-            content.push_str(&format!(
-                "- **Composite Score**: {:.2}\n\n",
-                composite_score
-            ));
-        }
-
-        if !evaluation.judge_feedback.is_empty() {
-            content.push_str("## Judge Feedback\n\n");
-            for feedback in &evaluation.judge_feedback {
-                content.push_str(&format!("{}\n", feedback));
-            }
-            content.push('\n');
-        }
-
-        if !evaluation.evaluator_results.is_empty() {
-            content.push_str("## Custom Evaluator Results\n\n");
-            for result in &evaluation.evaluator_results {
-                if let Some(ref error) = result.error {
-                    content.push_str(&format!("**{}**: ❌ Failed - {}\n\n", result.name, error));
-                } else {
-                    let status = "✅";
-                    if let Some(score) = result.score {
-                        content.push_str(&format!(
-                            "**{}**: {} Score: {:.2}",
-                            result.name, status, score
-                        ));
-                    } else {
-                        content.push_str(&format!("**{}**: {}", result.name, status));
-                    }
-                    if let Some(ref summary) = result.summary {
-                        content.push_str(&format!(" - {}\n\n", summary));
-                    } else {
-                        content.push_str("\n\n");
-                    }
-                }
-            }
-        }
-
-        content.push_str("## Human Review\n\n");
-        content.push_str("<!--\n");
-        content.push_str("Human Score: __/5\n\n");
-        content.push_str("Further Human Notes:\n");
-        content.push_str("-->\n\n");
-
-        content.push_str("## Links\n\n");
-        content.push_str("- [Transcript](transcript.raw.txt)\n");
-        content.push_str("- [Metrics](metrics.json)\n");
-        content.push_str("- [Events](events.jsonl)\n");
-        content.push_str("- [Fixture](../fixture/)\n");
-
-        fs::write(self.results_dir.join("evaluation.md"), content)?;
+        let content = self
+            .renderer
+            .render(EVALUATION_TEMPLATE, "evaluation", evaluation)?;
+        self.write_file(self.results_dir.join("evaluation.md"), content)?;
         Ok(())
     }
 }
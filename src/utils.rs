@@ -11,3 +11,13 @@ pub fn resolve_fixtures_path(relative_path: &str) -> PathBuf {
         PathBuf::from(base_path).join(relative_path)
     }
 }
+
+/// Resolve `relative_path` like [`resolve_fixtures_path`], with `base` as the
+/// fixtures directory.
+pub fn resolve_against(base: &Path, relative_path: &str) -> PathBuf {
+    if Path::new(relative_path).is_absolute() || Path::new(relative_path).exists() {
+        PathBuf::from(relative_path)
+    } else {
+        base.join(relative_path)
+    }
+}
@@ -0,0 +1,249 @@
+//! Gate evaluators compiled to WebAssembly (WASI preview 1).
+//!
+//! A `wasm` gate loads a module (`.wasm`, or `.wat` text) and calls one of its
+//! exports with the signature `() -> i32`; `0` means the gate passed. The
+//! module sees the fixture directory preopened read-only as `.`, has no
+//! environment variables or network access, and is interrupted when the gate
+//! timeout expires. Anything it writes to stdout/stderr becomes the gate
+//! message.
+
+use std::path::Path;
+
+/// Default time limit for a single WASM check.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Outcome of calling a WASM check export.
+#[derive(Debug)]
+pub struct WasmCheck {
+    /// Value returned by the export (or passed to `proc_exit`)
+    pub code: i32,
+    /// Captured stdout followed by stderr, trimmed
+    pub output: String,
+}
+
+impl WasmCheck {
+    pub fn passed(&self) -> bool {
+        self.code == 0
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod sandbox {
+    use super::WasmCheck;
+    use anyhow::Context;
+    use std::path::Path;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+    use wasmtime_wasi::pipe::MemoryOutputPipe;
+    use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+    use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+    /// Linear memory a module may grow to.
+    const MAX_MEMORY_BYTES: usize = 256 << 20;
+    /// Bytes captured from each of stdout and stderr.
+    const MAX_OUTPUT_BYTES: usize = 64 << 10;
+
+    struct State {
+        wasi: WasiP1Ctx,
+        limits: StoreLimits,
+    }
+
+    pub fn run_check(
+        module_path: &Path,
+        export: &str,
+        fixture: &Path,
+        timeout_secs: u64,
+    ) -> anyhow::Result<WasmCheck> {
+        let mut config = Config::new();
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::from_file(&engine, module_path)
+            .with_context(|| format!("Failed to load WASM module {}", module_path.display()))?;
+
+        let stdout = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+        let stderr = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+        let program = module_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let wasi = WasiCtxBuilder::new()
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
+            .args(&[program.as_str(), export])
+            .preopened_dir(fixture, ".", DirPerms::READ, FilePerms::READ)
+            .with_context(|| format!("Failed to open fixture {}", fixture.display()))?
+            .build_p1();
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .build();
+        let mut store = Store::new(&engine, State { wasi, limits });
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(1);
+
+        let mut linker = Linker::new(&engine);
+        preview1::add_to_linker_sync(&mut linker, |state: &mut State| &mut state.wasi)?;
+        let instance = linker.instantiate(&mut store, &module)?;
+        let check = instance
+            .get_typed_func::<(), i32>(&mut store, export)
+            .with_context(|| format!("Export '{}' must be a function () -> i32", export))?;
+
+        // Bump the epoch once the deadline passes so running code traps
+        let (done, deadline) = mpsc::channel::<()>();
+        let timer_engine = engine.clone();
+        let timer = std::thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) =
+                deadline.recv_timeout(Duration::from_secs(timeout_secs))
+            {
+                timer_engine.increment_epoch();
+            }
+        });
+
+        let result = match instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+            Ok(init) => init
+                .call(&mut store, ())
+                .and_then(|_| check.call(&mut store, ())),
+            Err(_) => check.call(&mut store, ()),
+        };
+        drop(done);
+        let _ = timer.join();
+
+        let code = match result {
+            Ok(code) => code,
+            Err(e) => {
+                if let Some(exit) = e.downcast_ref::<I32Exit>() {
+                    exit.0
+                } else if e.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+                    anyhow::bail!("WASM check timed out after {} seconds", timeout_secs);
+                } else {
+                    return Err(e.context(format!("WASM check '{}' trapped", export)));
+                }
+            }
+        };
+
+        let mut output = String::from_utf8_lossy(&stdout.contents()).into_owned();
+        output.push_str(&String::from_utf8_lossy(&stderr.contents()));
+        Ok(WasmCheck {
+            code,
+            output: output.trim().to_string(),
+        })
+    }
+}
+
+/// Call `export` in the module at `module_path` against the `fixture` directory.
+#[cfg(feature = "wasm")]
+pub fn run_check(
+    module_path: &Path,
+    export: &str,
+    fixture: &Path,
+    timeout_secs: u64,
+) -> anyhow::Result<WasmCheck> {
+    sandbox::run_check(module_path, export, fixture, timeout_secs)
+}
+
+/// Call `export` in the module at `module_path` against the `fixture` directory.
+#[cfg(not(feature = "wasm"))]
+pub fn run_check(
+    _module_path: &Path,
+    _export: &str,
+    _fixture: &Path,
+    _timeout_secs: u64,
+) -> anyhow::Result<WasmCheck> {
+    anyhow::bail!("wasm gates require llm-tool-test built with the `wasm` feature")
+}
+
+#[cfg(all(test, feature = "wasm"))]
+mod tests {
+    use super::*;
+
+    /// Checks that read `README.md` from the fixture, try to write into it, or spin.
+    const CHECKS_WAT: &str = r#"
+(module
+  (import "wasi_snapshot_preview1" "path_open"
+    (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_read"
+    (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "README.md")
+  (data (i32.const 16) "readme ok\n")
+  (data (i32.const 32) "new.txt")
+
+  ;; Opens `len` bytes of path at `path` in the preopen (fd 3); stores fd at 60
+  (func $open (param $path i32) (param $len i32) (param $oflags i32) (param $rights i64) (result i32)
+    (call $path_open (i32.const 3) (i32.const 0) (local.get $path) (local.get $len)
+      (local.get $oflags) (local.get $rights) (i64.const 0) (i32.const 0) (i32.const 60)))
+
+  (func (export "check_readme") (result i32)
+    (if (call $open (i32.const 0) (i32.const 9) (i32.const 0) (i64.const 2))
+      (then (return (i32.const 2))))
+    (i32.store (i32.const 48) (i32.const 100))
+    (i32.store (i32.const 52) (i32.const 64))
+    (drop (call $fd_read (i32.load (i32.const 60)) (i32.const 48) (i32.const 1) (i32.const 56)))
+    (if (i32.ne (i32.load8_u (i32.const 100)) (i32.const 35))
+      (then (return (i32.const 1))))
+    (i32.store (i32.const 40) (i32.const 16))
+    (i32.store (i32.const 44) (i32.const 10))
+    (drop (call $fd_write (i32.const 1) (i32.const 40) (i32.const 1) (i32.const 64)))
+    (i32.const 0))
+
+  ;; O_CREAT with fd_write rights; must be refused by the read-only preopen
+  (func (export "create_file") (result i32)
+    (call $open (i32.const 32) (i32.const 7) (i32.const 1) (i64.const 64)))
+
+  (func (export "spin") (result i32)
+    (loop $forever (br $forever))
+    (i32.const 0))
+)
+"#;
+
+    fn setup(readme: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("fixture");
+        std::fs::create_dir(&fixture).unwrap();
+        std::fs::write(fixture.join("README.md"), readme).unwrap();
+        let module = dir.path().join("checks.wat");
+        std::fs::write(&module, CHECKS_WAT).unwrap();
+        (dir, module)
+    }
+
+    #[test]
+    fn passes_when_export_returns_zero() {
+        let (dir, module) = setup("# Title\n");
+        let check = run_check(&module, "check_readme", &dir.path().join("fixture"), 5).unwrap();
+        assert!(check.passed());
+        assert_eq!(check.output, "readme ok");
+    }
+
+    #[test]
+    fn fails_when_export_returns_nonzero() {
+        let (dir, module) = setup("no heading\n");
+        let check = run_check(&module, "check_readme", &dir.path().join("fixture"), 5).unwrap();
+        assert!(!check.passed());
+        assert_eq!(check.code, 1);
+    }
+
+    #[test]
+    fn fixture_is_read_only() {
+        let (dir, module) = setup("# Title\n");
+        let fixture = dir.path().join("fixture");
+        let check = run_check(&module, "create_file", &fixture, 5).unwrap();
+        assert!(!check.passed());
+        assert!(!fixture.join("new.txt").exists());
+    }
+
+    #[test]
+    fn interrupts_on_timeout() {
+        let (dir, module) = setup("# Title\n");
+        let err = run_check(&module, "spin", &dir.path().join("fixture"), 1).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn rejects_missing_export() {
+        let (dir, module) = setup("# Title\n");
+        let err = run_check(&module, "nope", &dir.path().join("fixture"), 5).unwrap_err();
+        assert!(err.to_string().contains("Export 'nope'"));
+    }
+}